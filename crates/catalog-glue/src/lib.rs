@@ -1,6 +1,9 @@
 //! Glue Data Catalog.
 //!
+use std::collections::HashMap;
+
 use aws_config::{BehaviorVersion, SdkConfig};
+use aws_sdk_glue::types::{StorageDescriptor, TableInput};
 use deltalake_core::data_catalog::{DataCatalog, DataCatalogError};
 
 #[derive(thiserror::Error, Debug)]
@@ -58,6 +61,13 @@ impl std::fmt::Debug for GlueDataCatalog {
 // Placeholder suffix created by Spark in the Glue Data Catalog Location
 const PLACEHOLDER_SUFFIX: &str = "-__PLACEHOLDER__";
 
+fn sdk_error<E>(err: E) -> DataCatalogError
+where
+    aws_sdk_glue::Error: From<E>,
+{
+    GlueError::AWSError { source: err.into() }.into()
+}
+
 #[async_trait::async_trait]
 impl DataCatalog for GlueDataCatalog {
     type Error = DataCatalogError;
@@ -113,4 +123,133 @@ impl DataCatalog for GlueDataCatalog {
             Err(err) => Err(err.into()),
         }
     }
+
+    /// List the databases in the Glue Data Catalog
+    async fn list_databases(
+        &self,
+        catalog_id: Option<String>,
+    ) -> Result<Vec<String>, DataCatalogError> {
+        let mut databases = Vec::new();
+        let mut next_token = None;
+        loop {
+            let response = self
+                .client
+                .get_databases()
+                .set_catalog_id(catalog_id.clone())
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(sdk_error)?;
+            databases.extend(
+                response
+                    .database_list()
+                    .iter()
+                    .map(|d| d.name().to_string()),
+            );
+            next_token = response.next_token;
+            if next_token.is_none() {
+                return Ok(databases);
+            }
+        }
+    }
+
+    /// List the tables of a database in the Glue Data Catalog
+    async fn list_tables(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+    ) -> Result<Vec<String>, DataCatalogError> {
+        let mut tables = Vec::new();
+        let mut next_token = None;
+        loop {
+            let response = self
+                .client
+                .get_tables()
+                .set_catalog_id(catalog_id.clone())
+                .database_name(database_name)
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(sdk_error)?;
+            tables.extend(response.table_list().iter().map(|t| t.name().to_string()));
+            next_token = response.next_token;
+            if next_token.is_none() {
+                return Ok(tables);
+            }
+        }
+    }
+
+    /// Register an external table in the Glue Data Catalog
+    async fn create_table(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+        storage_location: &str,
+        properties: HashMap<String, String>,
+    ) -> Result<(), DataCatalogError> {
+        let table_input = TableInput::builder()
+            .name(table_name)
+            .table_type("EXTERNAL_TABLE")
+            .set_parameters(Some(properties))
+            .storage_descriptor(
+                StorageDescriptor::builder()
+                    .location(storage_location)
+                    .build(),
+            )
+            .build()
+            .map_err(|err| DataCatalogError::Generic {
+                catalog: "glue",
+                source: Box::new(err),
+            })?;
+        self.client
+            .create_table()
+            .set_catalog_id(catalog_id)
+            .database_name(database_name)
+            .table_input(table_input)
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        Ok(())
+    }
+
+    /// Remove a table from the Glue Data Catalog, the table data is left untouched
+    async fn drop_table(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<(), DataCatalogError> {
+        self.client
+            .delete_table()
+            .set_catalog_id(catalog_id)
+            .database_name(database_name)
+            .name(table_name)
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        Ok(())
+    }
+
+    /// Get the parameters of a table in the Glue Data Catalog
+    async fn get_table_properties(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<HashMap<String, String>, DataCatalogError> {
+        let response = self
+            .client
+            .get_table()
+            .set_catalog_id(catalog_id)
+            .database_name(database_name)
+            .name(table_name)
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        let table = response.table.ok_or(GlueError::MissingMetadata {
+            metadata: "Table".to_string(),
+        })?;
+        Ok(table.parameters.unwrap_or_default())
+    }
 }
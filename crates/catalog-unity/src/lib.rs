@@ -26,9 +26,9 @@ use crate::credential::{
     AzureCliCredential, ClientSecretOAuthProvider, CredentialProvider, WorkspaceOAuthProvider,
 };
 use crate::models::{
-    ErrorResponse, GetSchemaResponse, GetTableResponse, ListCatalogsResponse, ListSchemasResponse,
-    ListTableSummariesResponse, Table, TableTempCredentialsResponse, TableType,
-    TemporaryTableCredentialsRequest, TokenErrorResponse,
+    CreateTableRequest, DataSourceFormat, ErrorResponse, GetSchemaResponse, GetTableResponse,
+    ListCatalogsResponse, ListSchemasResponse, ListTableSummariesResponse, Table,
+    TableTempCredentialsResponse, TableType, TemporaryTableCredentialsRequest, TokenErrorResponse,
 };
 
 use deltalake_core::data_catalog::DataCatalogResult;
@@ -861,6 +861,69 @@ impl UnityCatalog {
         Ok(table)
     }
 
+    /// Creates a new table in the metastore.
+    ///
+    /// The caller must be the owner of the parent schema or have the CREATE_TABLE and
+    /// USE_SCHEMA privilege on it, and the USE_CATALOG privilege on the parent catalog.
+    #[tracing::instrument(skip_all)]
+    pub async fn create_table(
+        &self,
+        request: &CreateTableRequest,
+    ) -> Result<GetTableResponse, UnityCatalogError> {
+        tracing::event!(
+            tracing::Level::DEBUG,
+            "Creating table: {}.{}.{}",
+            request.catalog_name,
+            request.schema_name,
+            request.name
+        );
+        let token = self.get_credential().await?;
+        let resp = self
+            .client
+            .post(format!("{}/tables", self.catalog_url()))
+            .header(AUTHORIZATION, token)
+            .json(request)
+            .send()
+            .await?;
+        Ok(resp.json().await?)
+    }
+
+    /// Deletes a table from the specified parent catalog and schema.
+    ///
+    /// The caller must be the owner of the table or of its parent schema or catalog. For
+    /// external tables only the metadata is removed, the table data is left untouched.
+    #[tracing::instrument(skip_all)]
+    pub async fn delete_table<S>(
+        &self,
+        catalog_id: S,
+        database_name: S,
+        table_name: S,
+    ) -> Result<(), UnityCatalogError>
+    where
+        S: Into<String> + Debug,
+    {
+        let full_path = format!(
+            "{}.{}.{}",
+            catalog_id.into(),
+            database_name.into(),
+            table_name.into()
+        );
+        tracing::event!(tracing::Level::DEBUG, "Deleting table: {}", full_path);
+        let token = self.get_credential().await?;
+        let resp = self
+            .client
+            .delete(format!("{}/tables/{}", self.catalog_url(), full_path))
+            .header(AUTHORIZATION, token)
+            .send()
+            .await?;
+        self.table_cache.remove(&full_path);
+        if resp.status().is_success() {
+            return Ok(());
+        }
+        let err: ErrorResponse = resp.json().await?;
+        Err(err.into())
+    }
+
     pub async fn get_temp_table_credentials<S>(
         &self,
         catalog_id: S,
@@ -1012,6 +1075,103 @@ impl DataCatalog for UnityCatalog {
             }),
         }
     }
+
+    /// List the schemas within the catalog
+    async fn list_databases(&self, catalog_id: Option<String>) -> DataCatalogResult<Vec<String>> {
+        match self
+            .list_schemas(catalog_id.unwrap_or("main".into()))
+            .await?
+        {
+            ListSchemasResponse::Success { schemas } => {
+                Ok(schemas.into_iter().map(|s| s.name).collect())
+            }
+            ListSchemasResponse::Error(err) => Err(UnityCatalogError::from(err).into()),
+        }
+    }
+
+    /// List the managed and external tables within a schema
+    async fn list_tables(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+    ) -> DataCatalogResult<Vec<String>> {
+        match self
+            .list_table_summaries(
+                catalog_id.unwrap_or("main".into()),
+                database_name.to_string(),
+            )
+            .await?
+        {
+            ListTableSummariesResponse::Success { tables, .. } => Ok(tables
+                .into_iter()
+                .filter(|t| {
+                    t.table_type == TableType::Managed || t.table_type == TableType::External
+                })
+                .filter_map(|t| t.full_name.split('.').next_back().map(|n| n.into()))
+                .collect()),
+            ListTableSummariesResponse::Error(err) => Err(UnityCatalogError::from(err).into()),
+        }
+    }
+
+    /// Register an external Delta table in the UnityCatalog
+    async fn create_table(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+        storage_location: &str,
+        properties: HashMap<String, String>,
+    ) -> DataCatalogResult<()> {
+        let request = CreateTableRequest {
+            name: table_name.to_string(),
+            catalog_name: catalog_id.unwrap_or("main".into()),
+            schema_name: database_name.to_string(),
+            table_type: TableType::External,
+            data_source_format: DataSourceFormat::Delta,
+            storage_location: storage_location.to_string(),
+            properties,
+        };
+        match UnityCatalog::create_table(self, &request).await? {
+            GetTableResponse::Success(_) => Ok(()),
+            GetTableResponse::Error(err) => Err(UnityCatalogError::from(err).into()),
+        }
+    }
+
+    /// Remove a table from the UnityCatalog
+    async fn drop_table(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> DataCatalogResult<()> {
+        Ok(self
+            .delete_table(
+                catalog_id.unwrap_or("main".into()),
+                database_name.to_string(),
+                table_name.to_string(),
+            )
+            .await?)
+    }
+
+    /// Get the properties of a table from the UnityCatalog
+    async fn get_table_properties(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> DataCatalogResult<HashMap<String, String>> {
+        match self
+            .get_table(
+                catalog_id.unwrap_or("main".into()),
+                database_name.to_string(),
+                table_name.to_string(),
+            )
+            .await?
+        {
+            GetTableResponse::Success(table) => Ok(table.properties),
+            GetTableResponse::Error(err) => Err(UnityCatalogError::from(err).into()),
+        }
+    }
 }
 
 impl std::fmt::Debug for UnityCatalog {
@@ -1024,7 +1184,9 @@ impl std::fmt::Debug for UnityCatalog {
 mod tests {
     use crate::UnityCatalogBuilder;
     use crate::client::ClientOptions;
-    use crate::models::tests::{GET_SCHEMA_RESPONSE, GET_TABLE_RESPONSE, LIST_SCHEMAS_RESPONSE};
+    use crate::models::tests::{
+        GET_SCHEMA_RESPONSE, GET_TABLE_RESPONSE, LIST_SCHEMAS_RESPONSE, LIST_TABLES,
+    };
     use crate::models::*;
     use deltalake_core::DataCatalog;
     use httpmock::prelude::*;
@@ -1098,6 +1260,114 @@ mod tests {
         assert!(storage_location.eq_ignore_ascii_case("string"));
     }
 
+    #[tokio::test]
+    async fn test_unity_data_catalog_listing() {
+        let server = MockServer::start_async().await;
+
+        let options = ClientOptions::builder().allow_http(true).build();
+
+        let client = UnityCatalogBuilder::builder()
+            .workspace_url(server.url(""))
+            .bearer_token("bearer_token")
+            .client_options(options)
+            .build()
+            .build()
+            .unwrap();
+
+        server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/schemas").method("GET");
+                then.body(LIST_SCHEMAS_RESPONSE);
+            })
+            .await;
+
+        server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/table-summaries")
+                    .method("GET");
+                then.body(LIST_TABLES);
+            })
+            .await;
+
+        server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/tables/catalog.schema.table_name")
+                    .method("GET");
+                then.body(GET_TABLE_RESPONSE);
+            })
+            .await;
+
+        let databases = client
+            .list_databases(Some("catalog".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(databases, vec!["string".to_string()]);
+
+        let tables = client
+            .list_tables(Some("catalog".to_string()), "schema")
+            .await
+            .unwrap();
+        assert_eq!(tables, vec!["table_name".to_string()]);
+
+        let properties = client
+            .get_table_properties(Some("catalog".to_string()), "schema", "table_name")
+            .await
+            .unwrap();
+        assert_eq!(properties.get("property1"), Some(&"string".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_unity_data_catalog_create_and_drop() {
+        let server = MockServer::start_async().await;
+
+        let options = ClientOptions::builder().allow_http(true).build();
+
+        let client = UnityCatalogBuilder::builder()
+            .workspace_url(server.url(""))
+            .bearer_token("bearer_token")
+            .client_options(options)
+            .build()
+            .build()
+            .unwrap();
+
+        let create = server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/tables")
+                    .method("POST")
+                    .body_includes(r#""table_type":"EXTERNAL""#)
+                    .body_includes(r#""data_source_format":"DELTA""#)
+                    .body_includes(r#""storage_location":"s3://bucket/table""#);
+                then.body(GET_TABLE_RESPONSE);
+            })
+            .await;
+
+        let delete = server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/tables/catalog.schema.table_name")
+                    .method("DELETE");
+                then.status(200).body("{}");
+            })
+            .await;
+
+        DataCatalog::create_table(
+            &client,
+            Some("catalog".to_string()),
+            "schema",
+            "table_name",
+            "s3://bucket/table",
+            HashMap::new(),
+        )
+        .await
+        .unwrap();
+        create.assert_async().await;
+
+        client
+            .drop_table(Some("catalog".to_string()), "schema", "table_name")
+            .await
+            .unwrap();
+        delete.assert_async().await;
+    }
+
     #[test]
     fn test_unitycatalogbuilder_with_storage_options() {
         let mut storage_options = HashMap::new();
@@ -266,7 +266,7 @@ pub struct Schema {
     pub metastore_id: String,
 }
 
-#[derive(Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[allow(missing_docs)]
 /// Possible data source formats for unity tables
@@ -299,7 +299,7 @@ pub enum DataSourceFormat {
     VectorIndexFormat,
 }
 
-#[derive(Deserialize, Default, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[allow(missing_docs)]
 /// Possible data source formats for unity tables
@@ -601,6 +601,24 @@ impl TemporaryTableCredentialsRequest {
     }
 }
 
+/// Request to register a table in a schema
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateTableRequest {
+    /// Name of table, relative to parent schema.
+    pub name: String,
+    /// Name of parent catalog.
+    pub catalog_name: String,
+    /// Name of parent schema relative to its parent catalog.
+    pub schema_name: String,
+    pub table_type: TableType,
+    pub data_source_format: DataSourceFormat,
+    /// Storage root URL for the table.
+    pub storage_location: String,
+    /// A map of key-value properties attached to the securable.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, String>,
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
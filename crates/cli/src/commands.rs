@@ -0,0 +1,395 @@
+//! Implementation of the subcommands
+//!
+//! Every command returns its result as [`Output`], which is rendered by [`crate::output`] as
+//! text or JSON. Field names follow the camelCase convention of the Delta log.
+
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+
+use anyhow::{Context, bail};
+use chrono::{DateTime, Utc};
+use deltalake::checkpoints::{cleanup_metadata, create_checkpoint};
+use deltalake::datafusion::prelude::SessionContext;
+use deltalake::kernel::Version;
+use deltalake::operations::convert_to_delta::ConvertToDeltaBuilder;
+use deltalake::operations::optimize::OptimizeType;
+use deltalake::operations::vacuum::VacuumMode;
+use deltalake::{
+    DataType, DeltaTable, DeltaTableBuilder, PartitionFilter, StructField, ensure_table_uri,
+};
+use futures::TryStreamExt;
+use serde_json::{Value, json};
+
+use crate::output::Output;
+
+/// Storage options passed to the object store of every table
+pub type StorageOptions = HashMap<String, String>;
+
+/// Load the table at `uri`, optionally at an older version
+pub async fn open_table(
+    uri: &str,
+    version: Option<Version>,
+    options: &StorageOptions,
+) -> anyhow::Result<DeltaTable> {
+    let url = ensure_table_uri(uri)?;
+    let mut builder = DeltaTableBuilder::from_url(url)?.with_storage_options(options.clone());
+    if let Some(version) = version {
+        builder = builder.with_version(version);
+    }
+    let table = builder
+        .load()
+        .await
+        .with_context(|| format!("failed to load table at {uri}"))?;
+    Ok(table)
+}
+
+/// Parse a partition filter like `year=2024`, `month>=6` or `region in eu,us`
+pub fn parse_partition_filter(filter: &str) -> anyhow::Result<PartitionFilter> {
+    for op in ["not in", "in"] {
+        if let Some((key, values)) = filter.split_once(&format!(" {op} ")) {
+            let values: Vec<&str> = values.split(',').map(str::trim).collect();
+            return Ok(PartitionFilter::try_from((
+                key.trim(),
+                op,
+                values.as_slice(),
+            ))?);
+        }
+    }
+    for op in ["!=", ">=", "<=", "=", ">", "<"] {
+        if let Some((key, value)) = filter.split_once(op) {
+            return Ok(PartitionFilter::try_from((key.trim(), op, value.trim()))?);
+        }
+    }
+    bail!("invalid partition filter '{filter}', expected e.g. 'year=2024' or 'region in eu,us'")
+}
+
+/// Parse a `KEY=VALUE` pair
+pub fn parse_key_value(pair: &str) -> anyhow::Result<(String, String)> {
+    match pair.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => bail!("invalid option '{pair}', expected KEY=VALUE"),
+    }
+}
+
+/// Parse a partition column like `year:integer` for `convert`
+pub fn parse_partition_column(column: &str) -> anyhow::Result<StructField> {
+    let (name, data_type) = column.split_once(':').unwrap_or((column, "string"));
+    let data_type: DataType = serde_json::from_value(Value::String(data_type.to_string()))
+        .with_context(|| format!("unsupported partition column type '{data_type}'"))?;
+    Ok(StructField::new(name, data_type, true))
+}
+
+/// Commits of the table, newest first
+pub async fn history(table: &DeltaTable, limit: Option<usize>) -> anyhow::Result<Output> {
+    let mut commits = Vec::new();
+    for (version, commit) in table.history_with_versions(limit).await? {
+        let mut commit = serde_json::to_value(commit)?;
+        if let Value::Object(fields) = &mut commit {
+            fields.insert("version".to_string(), json!(version));
+        }
+        commits.push(commit);
+    }
+    Ok(Output::Value(Value::Array(commits)))
+}
+
+/// Table-level details and schema of the table
+pub async fn describe(table: &DeltaTable) -> anyhow::Result<Output> {
+    let detail = table.describe_detail().await?;
+    let mut value = serde_json::to_value(detail)?;
+    value["schema"] = serde_json::to_value(table.snapshot()?.schema().as_ref())?;
+    Ok(Output::Value(value))
+}
+
+/// Active files of the table matching the partition filters
+pub async fn files(table: &DeltaTable, filters: &[PartitionFilter]) -> anyhow::Result<Output> {
+    let files: Vec<Value> = table
+        .get_active_add_actions_by_partitions(filters)
+        .map_ok(|file| {
+            json!({
+                "path": file.path(),
+                "size": file.size(),
+                "modificationTime": file.modification_time(),
+                "numRecords": file.num_records(),
+                "partitionValues": file.partition_values_map(),
+            })
+        })
+        .try_collect()
+        .await?;
+    Ok(Output::Value(Value::Array(files)))
+}
+
+/// Aggregated file statistics of the table
+pub fn stats(table: &DeltaTable) -> anyhow::Result<Output> {
+    let snapshot = table.snapshot()?;
+    let mut num_files = 0u64;
+    let mut size = 0i64;
+    let mut num_records = 0u64;
+    let mut files_without_stats = 0u64;
+    let mut min_size: Option<i64> = None;
+    let mut max_size: Option<i64> = None;
+    for file in snapshot.log_data().iter() {
+        num_files += 1;
+        size += file.size();
+        min_size = Some(min_size.map_or(file.size(), |min| min.min(file.size())));
+        max_size = Some(max_size.map_or(file.size(), |max| max.max(file.size())));
+        match file.num_records() {
+            Some(records) => num_records += records as u64,
+            None => files_without_stats += 1,
+        }
+    }
+    Ok(Output::Value(json!({
+        "version": snapshot.version(),
+        "numFiles": num_files,
+        "sizeInBytes": size,
+        "numRecords": num_records,
+        "filesWithoutStats": files_without_stats,
+        "minFileSize": min_size,
+        "maxFileSize": max_size,
+        "avgFileSize": (num_files > 0).then(|| size / num_files as i64),
+    })))
+}
+
+/// Options of the `vacuum` command
+#[derive(Debug)]
+pub struct VacuumOptions {
+    pub dry_run: bool,
+    pub retention_hours: Option<u64>,
+    pub enforce_retention_duration: bool,
+    pub full: bool,
+}
+
+/// Delete files no longer referenced by the table
+pub async fn vacuum(table: DeltaTable, options: VacuumOptions) -> anyhow::Result<Output> {
+    let mut builder = table
+        .vacuum()
+        .with_dry_run(options.dry_run)
+        .with_enforce_retention_duration(options.enforce_retention_duration)
+        .with_mode(if options.full {
+            VacuumMode::Full
+        } else {
+            VacuumMode::Lite
+        });
+    if let Some(hours) = options.retention_hours {
+        let hours = i64::try_from(hours).context("retention period is too large")?;
+        builder = builder.with_retention_period(chrono::Duration::hours(hours));
+    }
+    let (table, metrics) = builder.await?;
+    Ok(Output::Value(json!({
+        "version": table.version(),
+        "dryRun": metrics.dry_run,
+        "numFiles": metrics.files_deleted.len(),
+        "files": metrics.files_deleted,
+    })))
+}
+
+/// Compact small files, optionally Z-ordering them
+pub async fn optimize(
+    table: DeltaTable,
+    filters: &[PartitionFilter],
+    target_size: Option<NonZeroU64>,
+    zorder: Vec<String>,
+) -> anyhow::Result<Output> {
+    let mut builder = table.optimize().with_filters(filters);
+    if let Some(target_size) = target_size {
+        builder = builder.with_target_size(target_size);
+    }
+    if !zorder.is_empty() {
+        builder = builder.with_type(OptimizeType::ZOrder(zorder));
+    }
+    let (table, metrics) = builder.await?;
+    let mut output = serde_json::to_value(metrics)?;
+    if let Value::Object(fields) = &mut output {
+        fields.insert("version".to_string(), json!(table.version()));
+    }
+    Ok(Output::Value(output))
+}
+
+/// Write a checkpoint for the current version
+pub async fn checkpoint(table: &DeltaTable, cleanup: bool) -> anyhow::Result<Output> {
+    create_checkpoint(table, None).await?;
+    let removed = if cleanup {
+        Some(cleanup_metadata(table, None).await?)
+    } else {
+        None
+    };
+    Ok(Output::Value(json!({
+        "version": table.version(),
+        "numExpiredLogFilesRemoved": removed,
+    })))
+}
+
+/// Restore the table to an earlier version or point in time
+pub async fn restore(
+    table: DeltaTable,
+    version: Option<Version>,
+    timestamp: Option<DateTime<Utc>>,
+    ignore_missing_files: bool,
+    allow_protocol_downgrade: bool,
+) -> anyhow::Result<Output> {
+    let mut builder = table
+        .restore()
+        .with_ignore_missing_files(ignore_missing_files)
+        .with_protocol_downgrade_allowed(allow_protocol_downgrade);
+    match (version, timestamp) {
+        (Some(version), None) => builder = builder.with_version_to_restore(version),
+        (None, Some(timestamp)) => builder = builder.with_datetime_to_restore(timestamp),
+        _ => bail!("specify exactly one of --to-version or --to-timestamp"),
+    }
+    let (table, metrics) = builder.await?;
+    let mut output = serde_json::to_value(metrics)?;
+    if let Value::Object(fields) = &mut output {
+        fields.insert("version".to_string(), json!(table.version()));
+    }
+    Ok(Output::Value(output))
+}
+
+/// Remove log entries for files missing from storage
+pub async fn fsck(table: DeltaTable, dry_run: bool) -> anyhow::Result<Output> {
+    let (table, metrics) = table.filesystem_check().with_dry_run(dry_run).await?;
+    Ok(Output::Value(json!({
+        "version": table.version(),
+        "dryRun": metrics.dry_run,
+        "numFiles": metrics.files_removed.len(),
+        "files": metrics.files_removed,
+    })))
+}
+
+/// Check the active files for inconsistencies between the log and the data
+pub async fn validate(table: &DeltaTable) -> anyhow::Result<Output> {
+    let report = table.validate().await?;
+    Ok(Output::Value(serde_json::to_value(report)?))
+}
+
+/// Generate the symlink format manifest used by Presto, Trino and Athena
+pub async fn generate_manifest(table: DeltaTable) -> anyhow::Result<Output> {
+    let table = table.generate().await?;
+    Ok(Output::Value(json!({
+        "version": table.version(),
+        "manifest": "_symlink_format_manifest",
+    })))
+}
+
+/// Options of the `convert` command
+#[derive(Debug)]
+pub struct ConvertOptions {
+    pub location: String,
+    pub partition_columns: Vec<StructField>,
+    pub name: Option<String>,
+    pub comment: Option<String>,
+    pub properties: Vec<(String, String)>,
+}
+
+/// Convert a directory of Parquet files to a Delta table
+pub async fn convert(options: ConvertOptions, storage: &StorageOptions) -> anyhow::Result<Output> {
+    let location = ensure_table_uri(&options.location)?;
+    let mut builder = ConvertToDeltaBuilder::new()
+        .with_location(location.to_string())
+        .with_storage_options(storage.clone())
+        .with_partition_schema(options.partition_columns)
+        .with_configuration(
+            options
+                .properties
+                .into_iter()
+                .map(|(key, value)| (key, Some(value))),
+        );
+    if let Some(name) = options.name {
+        builder = builder.with_table_name(name);
+    }
+    if let Some(comment) = options.comment {
+        builder = builder.with_comment(comment);
+    }
+    let table = builder.await?;
+    Ok(Output::Value(json!({
+        "location": table.table_url().to_string(),
+        "version": table.version(),
+        "numFiles": table.snapshot()?.log_data().num_files(),
+    })))
+}
+
+/// Run a SQL query against the given tables
+pub async fn sql(
+    query: &str,
+    tables: Vec<(String, String)>,
+    options: &StorageOptions,
+) -> anyhow::Result<Output> {
+    let ctx = SessionContext::new();
+    for (name, uri) in tables {
+        let table = open_table(&uri, None, options).await?;
+        table.update_datafusion_session(&ctx.state())?;
+        ctx.register_table(name.as_str(), table.table_provider().await?)?;
+    }
+    let batches = ctx.sql(query).await?.collect().await?;
+    Ok(Output::Batches(batches))
+}
+
+#[cfg(test)]
+mod tests {
+    use deltalake::PartitionValue;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_history_of_older_version() {
+        let table = open_table(
+            "../test/tests/data/simple_table",
+            Some(2),
+            &StorageOptions::new(),
+        )
+        .await
+        .unwrap();
+        let Output::Value(Value::Array(commits)) = history(&table, None).await.unwrap() else {
+            panic!("expected a list of commits");
+        };
+        let versions: Vec<_> = commits.iter().map(|c| c["version"].clone()).collect();
+        assert_eq!(versions, vec![json!(2), json!(1), json!(0)]);
+        assert_eq!(commits[1]["operation"], json!("MERGE"));
+    }
+
+    #[test]
+    fn test_parse_partition_filter() {
+        let filter = parse_partition_filter("year=2024").unwrap();
+        assert_eq!(filter.key, "year");
+        assert_eq!(filter.value, PartitionValue::Equal("2024".into()));
+
+        let filter = parse_partition_filter("month >= 6").unwrap();
+        assert_eq!(filter.key, "month");
+        assert_eq!(filter.value, PartitionValue::GreaterThanOrEqual("6".into()));
+
+        let filter = parse_partition_filter("region != eu").unwrap();
+        assert_eq!(filter.value, PartitionValue::NotEqual("eu".into()));
+
+        let filter = parse_partition_filter("region in eu, us").unwrap();
+        assert_eq!(filter.key, "region");
+        assert_eq!(
+            filter.value,
+            PartitionValue::In(vec!["eu".into(), "us".into()])
+        );
+
+        let filter = parse_partition_filter("region not in eu").unwrap();
+        assert_eq!(filter.value, PartitionValue::NotIn(vec!["eu".into()]));
+
+        assert!(parse_partition_filter("region").is_err());
+        assert!(parse_partition_filter("=2024").is_err());
+    }
+
+    #[test]
+    fn test_parse_partition_column() {
+        let field = parse_partition_column("year:integer").unwrap();
+        assert_eq!(field.name(), "year");
+        assert_eq!(field.data_type(), &DataType::INTEGER);
+
+        let field = parse_partition_column("region").unwrap();
+        assert_eq!(field.data_type(), &DataType::STRING);
+
+        assert!(parse_partition_column("year:nope").is_err());
+    }
+
+    #[test]
+    fn test_parse_key_value() {
+        assert_eq!(
+            parse_key_value("AWS_REGION=eu-west-1").unwrap(),
+            ("AWS_REGION".to_string(), "eu-west-1".to_string())
+        );
+        assert!(parse_key_value("AWS_REGION").is_err());
+    }
+}
@@ -0,0 +1,391 @@
+//! `delta` - inspect and maintain Delta Lake tables from the command line
+//!
+//! Tables are addressed by URI or local path. Storage options are passed with `-o KEY=VALUE`
+//! and accept the same keys as `storage_options` in the Python and Rust APIs, in addition to
+//! the environment variables recognized by the storage backends.
+
+use std::num::NonZeroU64;
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
+use deltalake::kernel::Version;
+
+use crate::commands::{ConvertOptions, StorageOptions, VacuumOptions, open_table};
+use crate::output::Format;
+
+mod commands;
+mod output;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "delta",
+    version,
+    about = "Inspect and maintain Delta Lake tables"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Storage option passed to the object store, may be repeated
+    #[arg(
+        short = 'o',
+        long = "option",
+        value_name = "KEY=VALUE",
+        global = true,
+        value_parser = commands::parse_key_value
+    )]
+    options: Vec<(String, String)>,
+
+    /// Output format
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = Format::Text,
+        env = "DELTA_CLI_FORMAT",
+        global = true
+    )]
+    format: Format,
+}
+
+/// A table to read, optionally at an older version
+#[derive(Args, Debug)]
+struct TableArgs {
+    /// Table URI or local path
+    table: String,
+
+    /// Load the table at this version instead of the latest one
+    #[arg(long)]
+    at_version: Option<Version>,
+}
+
+/// A table to modify, always at the latest version
+#[derive(Args, Debug)]
+struct TargetArgs {
+    /// Table URI or local path
+    table: String,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Show the commit history, newest first
+    History {
+        #[command(flatten)]
+        table: TableArgs,
+
+        /// Only show the latest commits
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+
+    /// Show size, layout, protocol, properties and schema
+    Describe {
+        #[command(flatten)]
+        table: TableArgs,
+    },
+
+    /// List the active data files
+    Files {
+        #[command(flatten)]
+        table: TableArgs,
+
+        /// Partition filter like `year=2024`, `month>=6` or `region in eu,us`, may be repeated
+        #[arg(short = 'p', long = "partition", value_parser = commands::parse_partition_filter)]
+        filters: Vec<deltalake::PartitionFilter>,
+    },
+
+    /// Show file count, size and row statistics
+    Stats {
+        #[command(flatten)]
+        table: TableArgs,
+    },
+
+    /// Delete files that are no longer referenced by the table
+    Vacuum {
+        #[command(flatten)]
+        table: TargetArgs,
+
+        /// Only list the files that would be deleted
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Retention period in hours, defaults to the table's `delta.deletedFileRetentionDuration`
+        #[arg(long)]
+        retention_hours: Option<u64>,
+
+        /// Allow a retention period shorter than the table's retention duration
+        #[arg(long)]
+        no_enforce_retention: bool,
+
+        /// Also delete files never referenced in the log, instead of only removed files
+        #[arg(long)]
+        full: bool,
+    },
+
+    /// Compact small files, optionally Z-ordering them
+    Optimize {
+        #[command(flatten)]
+        table: TargetArgs,
+
+        /// Partition filter restricting the files to optimize, may be repeated
+        #[arg(short = 'p', long = "partition", value_parser = commands::parse_partition_filter)]
+        filters: Vec<deltalake::PartitionFilter>,
+
+        /// Target file size in bytes
+        #[arg(long)]
+        target_size: Option<NonZeroU64>,
+
+        /// Z-order the files by these columns
+        #[arg(long, value_delimiter = ',')]
+        zorder: Vec<String>,
+    },
+
+    /// Write a checkpoint for the latest version
+    Checkpoint {
+        #[command(flatten)]
+        table: TargetArgs,
+
+        /// Also delete log files older than the table's log retention duration
+        #[arg(long)]
+        cleanup: bool,
+    },
+
+    /// Restore the table to an earlier version or point in time
+    Restore {
+        #[command(flatten)]
+        table: TargetArgs,
+
+        /// Version to restore
+        #[arg(
+            long,
+            conflicts_with = "to_timestamp",
+            required_unless_present = "to_timestamp"
+        )]
+        to_version: Option<Version>,
+
+        /// Point in time to restore, as RFC 3339 timestamp
+        #[arg(long)]
+        to_timestamp: Option<DateTime<Utc>>,
+
+        /// Do not fail if files referenced by the restored version are missing
+        #[arg(long)]
+        ignore_missing_files: bool,
+
+        /// Allow restoring a version with a lower protocol version
+        #[arg(long)]
+        allow_protocol_downgrade: bool,
+    },
+
+    /// Remove log entries of data files missing from storage
+    Fsck {
+        #[command(flatten)]
+        table: TargetArgs,
+
+        /// Only list the files that would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Check the active files for inconsistencies between the log and the data
+    Validate {
+        #[command(flatten)]
+        table: TableArgs,
+    },
+
+    /// Generate files for other engines
+    #[command(subcommand)]
+    Generate(GenerateCommand),
+
+    /// Convert a directory of Parquet files to a Delta table in place
+    Convert {
+        /// URI or local path of the Parquet directory
+        location: String,
+
+        /// Partition column as `NAME[:TYPE]`, may be repeated. The type defaults to string.
+        #[arg(short = 'p', long = "partition", value_parser = commands::parse_partition_column)]
+        partition_columns: Vec<deltalake::StructField>,
+
+        /// Table name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Table description
+        #[arg(long)]
+        comment: Option<String>,
+
+        /// Table property, may be repeated
+        #[arg(
+            long = "property",
+            value_name = "KEY=VALUE",
+            value_parser = commands::parse_key_value
+        )]
+        properties: Vec<(String, String)>,
+    },
+
+    /// Run a SQL query against one or more tables
+    Sql {
+        /// The query to run
+        query: String,
+
+        /// Table to register as `NAME=URI`, may be repeated
+        #[arg(
+            short = 't',
+            long = "table",
+            value_name = "NAME=URI",
+            required = true,
+            value_parser = commands::parse_key_value
+        )]
+        tables: Vec<(String, String)>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum GenerateCommand {
+    /// Generate the symlink format manifest read by Presto, Trino and Athena
+    Manifest {
+        #[command(flatten)]
+        table: TargetArgs,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let options: StorageOptions = cli.options.into_iter().collect();
+    let output = run(cli.command, &options).await?;
+    output::print(output, cli.format)
+}
+
+async fn run(command: Command, options: &StorageOptions) -> anyhow::Result<output::Output> {
+    match command {
+        Command::History { table, limit } => {
+            let table = open_table(&table.table, table.at_version, options).await?;
+            commands::history(&table, limit).await
+        }
+        Command::Describe { table } => {
+            let table = open_table(&table.table, table.at_version, options).await?;
+            commands::describe(&table).await
+        }
+        Command::Files { table, filters } => {
+            let table = open_table(&table.table, table.at_version, options).await?;
+            commands::files(&table, &filters).await
+        }
+        Command::Stats { table } => {
+            let table = open_table(&table.table, table.at_version, options).await?;
+            commands::stats(&table)
+        }
+        Command::Vacuum {
+            table,
+            dry_run,
+            retention_hours,
+            no_enforce_retention,
+            full,
+        } => {
+            let table = open_table(&table.table, None, options).await?;
+            let vacuum = VacuumOptions {
+                dry_run,
+                retention_hours,
+                enforce_retention_duration: !no_enforce_retention,
+                full,
+            };
+            commands::vacuum(table, vacuum).await
+        }
+        Command::Optimize {
+            table,
+            filters,
+            target_size,
+            zorder,
+        } => {
+            let table = open_table(&table.table, None, options).await?;
+            commands::optimize(table, &filters, target_size, zorder).await
+        }
+        Command::Checkpoint { table, cleanup } => {
+            let table = open_table(&table.table, None, options).await?;
+            commands::checkpoint(&table, cleanup).await
+        }
+        Command::Restore {
+            table,
+            to_version,
+            to_timestamp,
+            ignore_missing_files,
+            allow_protocol_downgrade,
+        } => {
+            let table = open_table(&table.table, None, options).await?;
+            commands::restore(
+                table,
+                to_version,
+                to_timestamp,
+                ignore_missing_files,
+                allow_protocol_downgrade,
+            )
+            .await
+        }
+        Command::Fsck { table, dry_run } => {
+            let table = open_table(&table.table, None, options).await?;
+            commands::fsck(table, dry_run).await
+        }
+        Command::Validate { table } => {
+            let table = open_table(&table.table, table.at_version, options).await?;
+            commands::validate(&table).await
+        }
+        Command::Generate(GenerateCommand::Manifest { table }) => {
+            let table = open_table(&table.table, None, options).await?;
+            commands::generate_manifest(table).await
+        }
+        Command::Convert {
+            location,
+            partition_columns,
+            name,
+            comment,
+            properties,
+        } => {
+            let convert = ConvertOptions {
+                location,
+                partition_columns,
+                name,
+                comment,
+                properties,
+            };
+            commands::convert(convert, options).await
+        }
+        Command::Sql { query, tables } => commands::sql(&query, tables, options).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        let cli = Cli::try_parse_from([
+            "delta",
+            "files",
+            "s3://bucket/table",
+            "-p",
+            "year=2024",
+            "-o",
+            "AWS_REGION=eu-west-1",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(cli.format, Format::Json);
+        assert_eq!(
+            cli.options,
+            vec![("AWS_REGION".to_string(), "eu-west-1".to_string())]
+        );
+        assert!(matches!(cli.command, Command::Files { ref filters, .. } if filters.len() == 1));
+
+        assert!(Cli::try_parse_from(["delta", "restore", "table"]).is_err());
+        assert!(Cli::try_parse_from(["delta", "restore", "table", "--to-version", "1"]).is_ok());
+        assert!(Cli::try_parse_from(["delta", "generate", "manifest", "table"]).is_ok());
+    }
+}
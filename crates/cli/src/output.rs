@@ -0,0 +1,196 @@
+//! Rendering of command results as text or JSON
+
+use std::io::Write;
+
+use arrow::record_batch::RecordBatch;
+use arrow::util::pretty::pretty_format_batches;
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+/// Output format of the command results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human readable text, with lists of records rendered as tables
+    #[default]
+    Text,
+    /// A single JSON document, for use in scripts
+    Json,
+}
+
+/// Result of a command
+#[derive(Debug)]
+pub enum Output {
+    /// A JSON document
+    Value(Value),
+    /// Query results
+    Batches(Vec<RecordBatch>),
+}
+
+/// Write the result of a command to stdout in the given format
+pub fn print(output: Output, format: Format) -> anyhow::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    match (output, format) {
+        (Output::Value(value), Format::Json) => {
+            writeln!(stdout, "{}", serde_json::to_string_pretty(&value)?)?
+        }
+        (Output::Value(value), Format::Text) => write!(stdout, "{}", render_text(&value))?,
+        (Output::Batches(batches), Format::Json) => {
+            let value = batches_to_json(&batches)?;
+            writeln!(stdout, "{}", serde_json::to_string_pretty(&value)?)?
+        }
+        (Output::Batches(batches), Format::Text) => {
+            writeln!(stdout, "{}", pretty_format_batches(&batches)?)?
+        }
+    }
+    Ok(())
+}
+
+/// Convert record batches to a JSON array with an object per row
+fn batches_to_json(batches: &[RecordBatch]) -> anyhow::Result<Value> {
+    let mut writer = arrow_json::ArrayWriter::new(Vec::new());
+    writer.write_batches(&batches.iter().collect::<Vec<_>>())?;
+    writer.finish()?;
+    let buffer = writer.into_inner();
+    if buffer.is_empty() {
+        return Ok(Value::Array(Vec::new()));
+    }
+    Ok(serde_json::from_slice(&buffer)?)
+}
+
+/// Render a JSON value as text.
+///
+/// Objects are rendered as one `key: value` line per field, arrays of objects as tables with a
+/// column per field. Nested values are rendered as compact JSON.
+pub fn render_text(value: &Value) -> String {
+    match value {
+        Value::Object(fields) => render_fields(fields),
+        Value::Array(rows) if rows.iter().all(Value::is_object) && !rows.is_empty() => {
+            render_table(rows)
+        }
+        Value::Array(values) => values.iter().map(|v| format!("{}\n", cell(v))).collect(),
+        other => format!("{}\n", cell(other)),
+    }
+}
+
+fn render_fields(fields: &Map<String, Value>) -> String {
+    let width = fields.keys().map(|key| key.len()).max().unwrap_or(0);
+    fields
+        .iter()
+        .map(|(key, value)| format!("{key:<width$}  {}\n", cell(value)))
+        .collect()
+}
+
+fn render_table(rows: &[Value]) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for row in rows.iter().filter_map(Value::as_object) {
+        for key in row.keys() {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| row.get(*column).map(cell).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain([column.len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |values: &[&str]| -> String {
+        let line = values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        format!("{}\n", line.trim_end())
+    };
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    let mut out = line(&columns);
+    out.push_str(&line(
+        &separator.iter().map(String::as_str).collect::<Vec<_>>(),
+    ));
+    for row in &cells {
+        out.push_str(&line(&row.iter().map(String::as_str).collect::<Vec<_>>()));
+    }
+    out
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_batches_to_json() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("a"), None])),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            batches_to_json(&[batch]).unwrap(),
+            json!([{"id": 1, "name": "a"}, {"id": 2}])
+        );
+        assert_eq!(batches_to_json(&[]).unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_render_fields() {
+        let value = json!({"name": "events", "partitionColumns": ["date"], "version": 3});
+        assert_eq!(
+            render_text(&value),
+            "name              events\npartitionColumns  [\"date\"]\nversion           3\n"
+        );
+    }
+
+    #[test]
+    fn test_render_table() {
+        let value = json!([
+            {"path": "a.parquet", "size": 10},
+            {"path": "bb.parquet", "size": 200, "numRecords": null},
+        ]);
+        assert_eq!(
+            render_text(&value),
+            "path        size  numRecords\n\
+             ----------  ----  ----------\n\
+             a.parquet   10\n\
+             bb.parquet  200\n"
+        );
+    }
+}
@@ -1,10 +1,20 @@
 //! Catalog abstraction for Delta Table
+//!
+//! A [`DataCatalog`] organizes tables in a `catalog.database.table` hierarchy. With the
+//! `datafusion` feature enabled, any catalog can be exposed to DataFusion sessions through
+//! [`DataCatalogProvider`], so that tables resolve as `SELECT * FROM catalog.database.table`.
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 
+#[cfg(feature = "datafusion")]
+mod provider;
 #[cfg(feature = "datafusion")]
 pub mod storage;
 
+#[cfg(feature = "datafusion")]
+pub use provider::{DataCatalogProvider, DataCatalogSchemaProvider};
+
 /// A result type for data catalog implementations
 pub type DataCatalogResult<T> = Result<T, DataCatalogError>;
 
@@ -43,9 +53,24 @@ pub enum DataCatalogError {
         /// The underlying transport or service error that caused the request to fail.
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
+
+    /// The catalog does not implement the requested operation.
+    #[error("The data catalog does not support '{operation}'")]
+    NotSupported {
+        /// Name of the unsupported operation
+        operation: &'static str,
+    },
 }
 
 /// Abstractions for data catalog for the Delta table. To add support for new cloud, simply implement this trait.
+///
+/// Only [`get_table_storage_location`](Self::get_table_storage_location) is required. All other
+/// operations return [`DataCatalogError::NotSupported`] unless the catalog implements them, and
+/// report failures as [`DataCatalogError`] so that catalogs are free to choose their own
+/// [`Error`](Self::Error) type.
+///
+/// `catalog_id` selects the top level catalog for services that host more than one, and is
+/// `None` to use the service default.
 #[async_trait::async_trait]
 pub trait DataCatalog: Send + Sync + Debug {
     /// Error type returned by catalog operations.
@@ -58,4 +83,64 @@ pub trait DataCatalog: Send + Sync + Debug {
         database_name: &str,
         table_name: &str,
     ) -> Result<String, Self::Error>;
+
+    /// List the names of all databases (schemas) in the catalog.
+    async fn list_databases(&self, _catalog_id: Option<String>) -> DataCatalogResult<Vec<String>> {
+        Err(DataCatalogError::NotSupported {
+            operation: "list_databases",
+        })
+    }
+
+    /// List the names of all tables in a database.
+    async fn list_tables(
+        &self,
+        _catalog_id: Option<String>,
+        _database_name: &str,
+    ) -> DataCatalogResult<Vec<String>> {
+        Err(DataCatalogError::NotSupported {
+            operation: "list_tables",
+        })
+    }
+
+    /// Register a table stored at `storage_location` in the catalog.
+    ///
+    /// This only registers the table, the Delta table itself must be created separately.
+    async fn create_table(
+        &self,
+        _catalog_id: Option<String>,
+        _database_name: &str,
+        _table_name: &str,
+        _storage_location: &str,
+        _properties: HashMap<String, String>,
+    ) -> DataCatalogResult<()> {
+        Err(DataCatalogError::NotSupported {
+            operation: "create_table",
+        })
+    }
+
+    /// Remove a table from the catalog.
+    ///
+    /// Whether the table data is deleted as well depends on the catalog.
+    async fn drop_table(
+        &self,
+        _catalog_id: Option<String>,
+        _database_name: &str,
+        _table_name: &str,
+    ) -> DataCatalogResult<()> {
+        Err(DataCatalogError::NotSupported {
+            operation: "drop_table",
+        })
+    }
+
+    /// Get the properties the catalog stores for a table.
+    async fn get_table_properties(
+        &self,
+        _catalog_id: Option<String>,
+        _database_name: &str,
+        _table_name: &str,
+    ) -> DataCatalogResult<HashMap<String, String>> {
+        Err(DataCatalogError::NotSupported {
+            operation: "get_table_properties",
+        })
+    }
 }
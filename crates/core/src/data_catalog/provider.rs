@@ -0,0 +1,523 @@
+//! DataFusion [`CatalogProvider`] and [`SchemaProvider`] backed by any [`DataCatalog`].
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use datafusion::catalog::{CatalogProvider, SchemaProvider};
+use datafusion::common::DataFusionError;
+use datafusion::datasource::TableProvider;
+use parking_lot::RwLock;
+
+use super::{DataCatalog, DataCatalogError, DataCatalogResult};
+use crate::delta_datafusion::DeltaMetadataTable;
+use crate::delta_datafusion::metadata_tables::split_metadata_table_name;
+use crate::errors::DeltaResult;
+use crate::table::builder::ensure_table_uri;
+use crate::{DeltaTable, open_table_with_storage_options};
+
+/// How long listed table names are used before the catalog is asked again
+const DEFAULT_TABLE_NAMES_TTL: Duration = Duration::from_secs(60);
+
+/// A DataFusion [`CatalogProvider`] that exposes the databases of a [`DataCatalog`] as schemas.
+///
+/// Registering the provider under a name makes every table of the catalog queryable:
+///
+/// ```rust,ignore
+/// let catalog = DataCatalogProvider::try_new(Arc::new(unity), Some("main".into()), options).await?;
+/// ctx.register_catalog("uc", Arc::new(catalog));
+/// ctx.sql("SELECT * FROM uc.sales.orders").await?;
+/// ```
+///
+/// Only the databases are listed when the provider is created. The table names of a database
+/// are listed the first time a query resolves a table in it, and again once they are older
+/// than the configured TTL, tables themselves are opened when a query references them. Every
+/// table also exposes metadata tables such as `orders$history`, see
+/// [`crate::delta_datafusion::metadata_tables`].
+#[derive(Debug)]
+pub struct DataCatalogProvider<C: DataCatalog> {
+    catalog: Arc<C>,
+    catalog_id: Option<String>,
+    /// Options used to create underlying object stores
+    storage_options: HashMap<String, String>,
+    table_names_ttl: Duration,
+    schemas: DashMap<String, Arc<DataCatalogSchemaProvider<C>>>,
+}
+
+impl<C> DataCatalogProvider<C>
+where
+    C: DataCatalog + 'static,
+    C::Error: std::error::Error + Send + Sync + 'static,
+{
+    /// Create a new [`DataCatalogProvider`] listing the databases of `catalog_id`.
+    ///
+    /// `storage_options` are used to open the tables found in the catalog.
+    pub async fn try_new(
+        catalog: Arc<C>,
+        catalog_id: Option<String>,
+        storage_options: HashMap<String, String>,
+    ) -> DataCatalogResult<Self> {
+        let provider = Self {
+            catalog,
+            catalog_id,
+            storage_options,
+            table_names_ttl: DEFAULT_TABLE_NAMES_TTL,
+            schemas: DashMap::new(),
+        };
+        provider.refresh().await?;
+        Ok(provider)
+    }
+
+    /// Set how long the table names of a database are cached before they are listed again.
+    ///
+    /// Applies to databases discovered by later calls to [`refresh`](Self::refresh) as well.
+    pub fn with_table_names_ttl(mut self, ttl: Duration) -> Self {
+        self.table_names_ttl = ttl;
+        self.schemas = self
+            .schemas
+            .into_iter()
+            .map(|(name, schema)| {
+                let schema = Arc::unwrap_or_clone(schema).with_table_names_ttl(ttl);
+                (name, Arc::new(schema))
+            })
+            .collect();
+        self
+    }
+
+    /// Reload the databases from the catalog.
+    ///
+    /// Databases that still exist keep their cached table names, removed databases are dropped.
+    pub async fn refresh(&self) -> DataCatalogResult<()> {
+        let databases = self.catalog.list_databases(self.catalog_id.clone()).await?;
+        self.schemas.retain(|name, _| databases.contains(name));
+        for database in databases {
+            self.schemas.entry(database.clone()).or_insert_with(|| {
+                Arc::new(
+                    DataCatalogSchemaProvider::new(
+                        self.catalog.clone(),
+                        self.catalog_id.clone(),
+                        database,
+                        self.storage_options.clone(),
+                    )
+                    .with_table_names_ttl(self.table_names_ttl),
+                )
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<C> CatalogProvider for DataCatalogProvider<C>
+where
+    C: DataCatalog + 'static,
+    C::Error: std::error::Error + Send + Sync + 'static,
+{
+    fn schema_names(&self) -> Vec<String> {
+        self.schemas.iter().map(|s| s.key().clone()).collect()
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        self.schemas
+            .get(name)
+            .map(|s| s.value().clone() as Arc<dyn SchemaProvider>)
+    }
+}
+
+/// Table names listed from the catalog and when they were listed
+#[derive(Debug, Clone)]
+struct TableNames {
+    names: Vec<String>,
+    listed_at: Instant,
+}
+
+/// A DataFusion [`SchemaProvider`] that exposes the tables of one [`DataCatalog`] database.
+///
+/// The table names are listed lazily and cached for a TTL, see
+/// [`with_table_names_ttl`](Self::with_table_names_ttl). The synchronous [`SchemaProvider`]
+/// methods such as [`table_names`](SchemaProvider::table_names) only see the cached names, use
+/// [`refresh`](Self::refresh) to load them up front.
+#[derive(Debug)]
+pub struct DataCatalogSchemaProvider<C: DataCatalog> {
+    catalog: Arc<C>,
+    catalog_id: Option<String>,
+    database_name: String,
+    /// Table names as of the last refresh, `None` until first listed
+    table_names: RwLock<Option<TableNames>>,
+    table_names_ttl: Duration,
+    /// Options used to create underlying object stores
+    storage_options: HashMap<String, String>,
+}
+
+impl<C: DataCatalog> Clone for DataCatalogSchemaProvider<C> {
+    fn clone(&self) -> Self {
+        Self {
+            catalog: self.catalog.clone(),
+            catalog_id: self.catalog_id.clone(),
+            database_name: self.database_name.clone(),
+            table_names: RwLock::new(self.table_names.read().clone()),
+            table_names_ttl: self.table_names_ttl,
+            storage_options: self.storage_options.clone(),
+        }
+    }
+}
+
+impl<C> DataCatalogSchemaProvider<C>
+where
+    C: DataCatalog + 'static,
+    C::Error: std::error::Error + Send + Sync + 'static,
+{
+    /// Create a new [`DataCatalogSchemaProvider`] for `database_name`.
+    ///
+    /// No request is made to the catalog until a table is resolved or [`refresh`](Self::refresh)
+    /// is called.
+    pub fn new(
+        catalog: Arc<C>,
+        catalog_id: Option<String>,
+        database_name: impl Into<String>,
+        storage_options: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            catalog,
+            catalog_id,
+            database_name: database_name.into(),
+            table_names: RwLock::new(None),
+            table_names_ttl: DEFAULT_TABLE_NAMES_TTL,
+            storage_options,
+        }
+    }
+
+    /// Set how long the table names are cached before they are listed again.
+    pub fn with_table_names_ttl(mut self, ttl: Duration) -> Self {
+        self.table_names_ttl = ttl;
+        self
+    }
+
+    /// Reload the table names from the catalog
+    pub async fn refresh(&self) -> DataCatalogResult<()> {
+        let names = self
+            .catalog
+            .list_tables(self.catalog_id.clone(), &self.database_name)
+            .await?;
+        *self.table_names.write() = Some(TableNames {
+            names,
+            listed_at: Instant::now(),
+        });
+        Ok(())
+    }
+
+    /// Reload the table names if they were never listed or are older than the TTL
+    async fn refresh_if_expired(&self) -> DataCatalogResult<()> {
+        let expired = self
+            .table_names
+            .read()
+            .as_ref()
+            .is_none_or(|t| t.listed_at.elapsed() >= self.table_names_ttl);
+        if expired {
+            self.refresh().await?;
+        }
+        Ok(())
+    }
+
+    /// Register a Delta table stored at `storage_location` in the catalog database.
+    pub async fn create_table(
+        &self,
+        name: &str,
+        storage_location: &str,
+        properties: HashMap<String, String>,
+    ) -> DataCatalogResult<()> {
+        self.catalog
+            .create_table(
+                self.catalog_id.clone(),
+                &self.database_name,
+                name,
+                storage_location,
+                properties,
+            )
+            .await?;
+        self.refresh().await
+    }
+
+    /// Remove a table from the catalog database.
+    pub async fn drop_table(&self, name: &str) -> DataCatalogResult<()> {
+        self.catalog
+            .drop_table(self.catalog_id.clone(), &self.database_name, name)
+            .await?;
+        self.refresh().await
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.table_names
+            .read()
+            .as_ref()
+            .is_some_and(|t| t.names.iter().any(|n| n == name))
+    }
+
+    async fn open_table(&self, name: &str) -> DataCatalogResult<Option<DeltaTable>> {
+        if !self.contains(name) {
+            return Ok(None);
+        }
+        let location = self
+            .catalog
+            .get_table_storage_location(self.catalog_id.clone(), &self.database_name, name)
+            .await
+            .map_err(|err| DataCatalogError::RequestError {
+                source: Box::new(err),
+            })?;
+        let table = open_location(location, self.storage_options.clone())
+            .await
+            .map_err(|err| DataCatalogError::Generic {
+                catalog: "delta",
+                source: Box::new(err),
+            })?;
+        Ok(Some(table))
+    }
+}
+
+async fn open_location(
+    location: String,
+    storage_options: HashMap<String, String>,
+) -> DeltaResult<DeltaTable> {
+    open_table_with_storage_options(ensure_table_uri(location)?, storage_options).await
+}
+
+#[async_trait]
+impl<C> SchemaProvider for DataCatalogSchemaProvider<C>
+where
+    C: DataCatalog + 'static,
+    C::Error: std::error::Error + Send + Sync + 'static,
+{
+    fn table_names(&self) -> Vec<String> {
+        self.table_names
+            .read()
+            .as_ref()
+            .map(|t| t.names.clone())
+            .unwrap_or_default()
+    }
+
+    async fn table(
+        &self,
+        name: &str,
+    ) -> datafusion::common::Result<Option<Arc<dyn TableProvider>>> {
+        self.refresh_if_expired().await.map_err(external)?;
+        if let Some((base, kind)) = split_metadata_table_name(name)
+            && !self.contains(name)
+        {
+            let Some(table) = self.open_table(base).await.map_err(external)? else {
+                return Ok(None);
+            };
+            return Ok(Some(Arc::new(DeltaMetadataTable::try_new(&table, kind)?)));
+        }
+        let Some(table) = self.open_table(name).await.map_err(external)? else {
+            return Ok(None);
+        };
+        Ok(Some(table.table_provider().await?))
+    }
+
+    fn register_table(
+        &self,
+        _name: String,
+        _table: Arc<dyn TableProvider>,
+    ) -> datafusion::common::Result<Option<Arc<dyn TableProvider>>> {
+        Err(DataFusionError::Execution(
+            "schema provider does not support registering tables, use create_table".to_owned(),
+        ))
+    }
+
+    fn deregister_table(
+        &self,
+        _name: &str,
+    ) -> datafusion::common::Result<Option<Arc<dyn TableProvider>>> {
+        Err(DataFusionError::Execution(
+            "schema provider does not support deregistering tables, use drop_table".to_owned(),
+        ))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        self.contains(name)
+            || split_metadata_table_name(name).is_some_and(|(base, _)| self.contains(base))
+    }
+}
+
+fn external(err: DataCatalogError) -> DataFusionError {
+    DataFusionError::External(Box::new(err))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::execution::context::SessionContext;
+
+    use super::*;
+
+    /// Catalog with a single `tests` database over the test data directory.
+    #[derive(Debug, Default)]
+    struct TestCatalog {
+        tables: Mutex<HashMap<String, String>>,
+        list_tables_calls: AtomicUsize,
+    }
+
+    impl TestCatalog {
+        fn new() -> Self {
+            let root = std::fs::canonicalize("../test/tests/data").unwrap();
+            let tables = ["simple_table", "delta-0.8.0-partitioned"]
+                .into_iter()
+                .map(|name| {
+                    (
+                        name.replace(['-', '.'], "_"),
+                        root.join(name).to_str().unwrap().to_string(),
+                    )
+                })
+                .collect();
+            Self {
+                tables: Mutex::new(tables),
+                list_tables_calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl DataCatalog for TestCatalog {
+        type Error = DataCatalogError;
+
+        async fn get_table_storage_location(
+            &self,
+            _catalog_id: Option<String>,
+            _database_name: &str,
+            table_name: &str,
+        ) -> DataCatalogResult<String> {
+            self.tables
+                .lock()
+                .unwrap()
+                .get(table_name)
+                .cloned()
+                .ok_or_else(|| DataCatalogError::InvalidDataCatalog {
+                    data_catalog: table_name.to_string(),
+                })
+        }
+
+        async fn list_databases(
+            &self,
+            _catalog_id: Option<String>,
+        ) -> DataCatalogResult<Vec<String>> {
+            Ok(vec!["tests".to_string()])
+        }
+
+        async fn list_tables(
+            &self,
+            _catalog_id: Option<String>,
+            _database_name: &str,
+        ) -> DataCatalogResult<Vec<String>> {
+            self.list_tables_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self.tables.lock().unwrap().keys().cloned().collect())
+        }
+
+        async fn drop_table(
+            &self,
+            _catalog_id: Option<String>,
+            _database_name: &str,
+            table_name: &str,
+        ) -> DataCatalogResult<()> {
+            self.tables.lock().unwrap().remove(table_name);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_catalog_table() {
+        let catalog =
+            DataCatalogProvider::try_new(Arc::new(TestCatalog::new()), None, HashMap::new())
+                .await
+                .unwrap();
+        assert_eq!(catalog.schema_names(), vec!["tests".to_string()]);
+
+        let ctx = SessionContext::new();
+        ctx.register_catalog("uc", Arc::new(catalog));
+
+        let data = ctx
+            .sql("SELECT * FROM uc.tests.simple_table")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = vec![
+            "+----+", "| id |", "+----+", "| 5  |", "| 7  |", "| 9  |", "+----+",
+        ];
+        assert_batches_sorted_eq!(&expected, &data);
+
+        let data = ctx
+            .sql(r#"SELECT operation FROM uc.tests."simple_table$history" WHERE version = 0"#)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = vec![
+            "+-----------+",
+            "| operation |",
+            "+-----------+",
+            "| WRITE     |",
+            "+-----------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &data);
+    }
+
+    #[tokio::test]
+    async fn test_drop_and_unsupported_operations() {
+        let schema = DataCatalogSchemaProvider::new(
+            Arc::new(TestCatalog::new()),
+            None,
+            "tests",
+            HashMap::new(),
+        );
+        schema.refresh().await.unwrap();
+        assert!(schema.table_exist("delta_0_8_0_partitioned"));
+
+        schema.drop_table("delta_0_8_0_partitioned").await.unwrap();
+        assert!(!schema.table_exist("delta_0_8_0_partitioned"));
+        assert!(
+            schema
+                .table("delta_0_8_0_partitioned")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let err = schema
+            .create_table("other", "/tmp/other", HashMap::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DataCatalogError::NotSupported {
+                operation: "create_table"
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_table_names_listed_lazily() {
+        let catalog = Arc::new(TestCatalog::new());
+        let provider = DataCatalogProvider::try_new(catalog.clone(), None, HashMap::new())
+            .await
+            .unwrap();
+        assert_eq!(catalog.list_tables_calls.load(Ordering::SeqCst), 0);
+
+        let schema = provider.schema("tests").unwrap();
+        assert!(schema.table_names().is_empty());
+        assert!(schema.table("simple_table").await.unwrap().is_some());
+        assert!(schema.table("simple_table").await.unwrap().is_some());
+        assert_eq!(catalog.list_tables_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(schema.table_names().len(), 2);
+
+        let provider = provider.with_table_names_ttl(Duration::ZERO);
+        let schema = provider.schema("tests").unwrap();
+        assert!(schema.table("simple_table").await.unwrap().is_some());
+        assert!(schema.table("simple_table").await.unwrap().is_some());
+        assert_eq!(catalog.list_tables_calls.load(Ordering::SeqCst), 3);
+    }
+}
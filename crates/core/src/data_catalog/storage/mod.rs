@@ -11,6 +11,9 @@ use datafusion::datasource::TableProvider;
 use futures::TryStreamExt;
 use object_store::ObjectStore;
 
+use crate::DeltaTable;
+use crate::delta_datafusion::DeltaMetadataTable;
+use crate::delta_datafusion::metadata_tables::split_metadata_table_name;
 use crate::errors::DeltaResult;
 use crate::logstore::{StorageConfig, store_for};
 use crate::open_table_with_storage_options;
@@ -29,6 +32,9 @@ const DELTA_LOG_FOLDER: &str = "_delta_log";
 ///
 /// assuming it contains valid deltalake data, i.e a `_delta_log` folder:
 /// s3://host.example.com:3000/data/tpch/customer/_delta_log/
+///
+/// Every discovered table also exposes metadata tables such as `customer$history` or
+/// `customer$files`, see [`crate::delta_datafusion::metadata_tables`].
 #[derive(Debug)]
 pub struct ListingSchemaProvider {
     authority: String,
@@ -86,6 +92,20 @@ impl ListingSchemaProvider {
     }
 }
 
+impl ListingSchemaProvider {
+    async fn open_table(&self, name: &str) -> DeltaResult<Option<DeltaTable>> {
+        let Some(location) = self.tables.get(name).map(|t| t.clone()) else {
+            return Ok(None);
+        };
+        let table = open_table_with_storage_options(
+            ensure_table_uri(location)?,
+            self.storage_options.raw.clone(),
+        )
+        .await?;
+        Ok(Some(table))
+    }
+}
+
 // normalizes a path fragment to be a valida table name in datafusion
 // - removes some reserved characters (-, +, ., " ")
 // - lowercase ascii
@@ -109,14 +129,17 @@ impl SchemaProvider for ListingSchemaProvider {
         &self,
         name: &str,
     ) -> datafusion::common::Result<Option<Arc<dyn TableProvider>>> {
-        let Some(location) = self.tables.get(name).map(|t| t.clone()) else {
+        if let Some((base, kind)) = split_metadata_table_name(name)
+            && !self.tables.contains_key(name)
+        {
+            let Some(table) = self.open_table(base).await? else {
+                return Ok(None);
+            };
+            return Ok(Some(Arc::new(DeltaMetadataTable::try_new(&table, kind)?)));
+        }
+        let Some(table) = self.open_table(name).await? else {
             return Ok(None);
         };
-        let table = open_table_with_storage_options(
-            ensure_table_uri(location)?,
-            self.storage_options.raw.clone(),
-        )
-        .await?;
         Ok(Some(table.table_provider().await?))
     }
 
@@ -141,6 +164,8 @@ impl SchemaProvider for ListingSchemaProvider {
 
     fn table_exist(&self, name: &str) -> bool {
         self.tables.contains_key(name)
+            || split_metadata_table_name(name)
+                .is_some_and(|(base, _)| self.tables.contains_key(base))
     }
 }
 
@@ -197,4 +222,36 @@ mod tests {
 
         assert_batches_sorted_eq!(&expected, &data);
     }
+
+    #[tokio::test]
+    async fn test_query_metadata_table() {
+        let schema = Arc::new(ListingSchemaProvider::try_new("../test/tests/data/", None).unwrap());
+        schema.refresh().await.unwrap();
+        assert!(schema.table_exist("simple_table$history"));
+        assert!(!schema.table_exist("simple_table$unknown"));
+
+        let ctx = SessionContext::new();
+        let catalog = Arc::new(MemoryCatalogProvider::default());
+        catalog.register_schema("test", schema).unwrap();
+        ctx.register_catalog("delta", catalog);
+
+        let data = ctx
+            .sql(r#"SELECT version, operation FROM delta.test."simple_table$history" WHERE version < 2"#)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+
+        let expected = vec![
+            "+---------+-----------+",
+            "| version | operation |",
+            "+---------+-----------+",
+            "| 0       | WRITE     |",
+            "| 1       | MERGE     |",
+            "+---------+-----------+",
+        ];
+
+        assert_batches_sorted_eq!(&expected, &data);
+    }
 }
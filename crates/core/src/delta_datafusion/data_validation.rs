@@ -107,6 +107,10 @@ impl DataValidation {
         }
         .into())
     }
+
+    pub(crate) fn validations(&self) -> &[Expr] {
+        &self.validations
+    }
 }
 
 /// Visitor to extract non-null assertions in expressions
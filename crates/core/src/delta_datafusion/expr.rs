@@ -59,7 +59,8 @@ use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
 use tracing::log::*;
 
 use crate::delta_datafusion::session::DeltaParserOptions;
-use crate::table::GeneratedColumn;
+use crate::kernel::DataType as DeltaDataType;
+use crate::table::{ColumnDefault, GeneratedColumn};
 use crate::{DeltaResult, DeltaTableError};
 
 /// This struct is like Datafusion's MakeArray but ensures that `element` is used rather than `item
@@ -439,14 +440,42 @@ pub(crate) fn parse_generated_column_expression(
     generated_col: &GeneratedColumn,
     session: &dyn Session,
 ) -> DeltaResult<Expr> {
-    let mut sql = parse_sql_expr(generated_col.get_generation_expression())?;
+    parse_column_metadata_expression(
+        schema,
+        generated_col.get_generation_expression(),
+        &generated_col.data_type,
+        session,
+    )
+}
+
+/// Column defaults are Spark SQL as well and are parsed exactly like generation expressions.
+pub(crate) fn parse_column_default_expression(
+    schema: &DFSchema,
+    column_default: &ColumnDefault,
+    session: &dyn Session,
+) -> DeltaResult<Expr> {
+    parse_column_metadata_expression(
+        schema,
+        column_default.get_default_expression(),
+        &column_default.data_type,
+        session,
+    )
+}
+
+fn parse_column_metadata_expression(
+    schema: &DFSchema,
+    sql: &str,
+    data_type: &DeltaDataType,
+    session: &dyn Session,
+) -> DeltaResult<Expr> {
+    let mut sql = parse_sql_expr(sql)?;
     match sql.visit(&mut SparkGeneratedColumnExprRewrite) {
         ControlFlow::Continue(()) => {}
         ControlFlow::Break(err) => return Err(err),
     }
 
     let expr = sql_expr_to_df_expr(session, schema, sql)?;
-    let expected = (&generated_col.data_type).try_into_arrow()?;
+    let expected = data_type.try_into_arrow()?;
 
     Ok(expr.cast_to(&expected, schema)?)
 }
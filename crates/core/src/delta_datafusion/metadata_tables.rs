@@ -0,0 +1,832 @@
+//! Virtual tables exposing Delta log metadata to SQL.
+//!
+//! Every Delta table registered through a schema provider that understands metadata tables
+//! (see [`DeltaMetadataSchemaProvider`] and [`ListingSchemaProvider`]) gains a set of read-only
+//! companion tables addressed by a `$` suffix:
+//!
+//! - `<table>$history` - one row per commit, newest first.
+//! - `<table>$files` - one row per active data file.
+//! - `<table>$partitions` - active data files aggregated per partition.
+//! - `<table>$protocol` - the table protocol.
+//! - `<table>$properties` - the table configuration as key/value pairs.
+//! - `<table>$detail` - a single row of table-level details, see [`TableDetail`].
+//!
+//! ```sql
+//! SELECT partition, count(*), sum(size_bytes) FROM "orders$files" GROUP BY 1
+//! ```
+//!
+//! [`ListingSchemaProvider`]: crate::data_catalog::storage::ListingSchemaProvider
+//! [`TableDetail`]: crate::operations::describe::TableDetail
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{
+    ArrayRef, BooleanArray, Int32Array, Int64Array, ListBuilder, RecordBatch, StringArray,
+    StringBuilder, TimestampMillisecondArray,
+};
+use arrow::compute::filter_record_batch;
+use arrow_array::cast::AsArray;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use async_trait::async_trait;
+use datafusion::catalog::{SchemaProvider, Session};
+use datafusion::common::{DFSchema, DataFusionError, Result as DataFusionResult, ScalarValue};
+use datafusion::datasource::{MemTable, TableProvider, TableType};
+use datafusion::logical_expr::utils::conjunction;
+use datafusion::logical_expr::{BinaryExpr, Expr, Operator, TableProviderFilterPushDown};
+use datafusion::physical_plan::ExecutionPlan;
+use futures::TryStreamExt;
+
+use super::DeltaScanNext;
+use crate::kernel::{CommitInfo, Snapshot, Version};
+use crate::logstore::LogStoreRef;
+use crate::operations::describe::TableDetail;
+use crate::{DeltaResult, DeltaTable, DeltaTableError};
+
+/// Separator between a table name and the metadata table suffix, e.g. `orders$files`.
+pub const METADATA_TABLE_SEPARATOR: char = '$';
+
+/// Partition value rendered for `null` partition values, matching hive style layouts.
+const NULL_PARTITION_VALUE: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// The kind of metadata exposed by a [`DeltaMetadataTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeltaMetadataTableKind {
+    /// Commit history of the table.
+    History,
+    /// Active data files of the table.
+    Files,
+    /// Active data files aggregated by partition.
+    Partitions,
+    /// The table protocol.
+    Protocol,
+    /// The table configuration.
+    Properties,
+    /// Table-level details.
+    Detail,
+}
+
+impl DeltaMetadataTableKind {
+    /// All supported metadata table kinds.
+    pub const ALL: [DeltaMetadataTableKind; 6] = [
+        DeltaMetadataTableKind::History,
+        DeltaMetadataTableKind::Files,
+        DeltaMetadataTableKind::Partitions,
+        DeltaMetadataTableKind::Protocol,
+        DeltaMetadataTableKind::Properties,
+        DeltaMetadataTableKind::Detail,
+    ];
+
+    /// The suffix used to address this metadata table.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            DeltaMetadataTableKind::History => "history",
+            DeltaMetadataTableKind::Files => "files",
+            DeltaMetadataTableKind::Partitions => "partitions",
+            DeltaMetadataTableKind::Protocol => "protocol",
+            DeltaMetadataTableKind::Properties => "properties",
+            DeltaMetadataTableKind::Detail => "detail",
+        }
+    }
+
+    /// The arrow schema of this metadata table.
+    pub fn schema(&self) -> SchemaRef {
+        match self {
+            DeltaMetadataTableKind::History => HISTORY_SCHEMA.clone(),
+            DeltaMetadataTableKind::Files => FILES_SCHEMA.clone(),
+            DeltaMetadataTableKind::Partitions => PARTITIONS_SCHEMA.clone(),
+            DeltaMetadataTableKind::Protocol => PROTOCOL_SCHEMA.clone(),
+            DeltaMetadataTableKind::Properties => PROPERTIES_SCHEMA.clone(),
+            DeltaMetadataTableKind::Detail => TableDetail::arrow_schema(),
+        }
+    }
+}
+
+impl fmt::Display for DeltaMetadataTableKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+impl FromStr for DeltaMetadataTableKind {
+    type Err = DeltaTableError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.suffix().eq_ignore_ascii_case(s))
+            .ok_or_else(|| DeltaTableError::Generic(format!("Unknown metadata table: {s}")))
+    }
+}
+
+/// Split a table name like `orders$files` into the base table name and metadata table kind.
+///
+/// Returns `None` if the name does not address a known metadata table.
+pub fn split_metadata_table_name(name: &str) -> Option<(&str, DeltaMetadataTableKind)> {
+    let (base, suffix) = name.rsplit_once(METADATA_TABLE_SEPARATOR)?;
+    if base.is_empty() {
+        return None;
+    }
+    let kind = suffix.parse().ok()?;
+    Some((base, kind))
+}
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+}
+
+fn string_list_type() -> DataType {
+    DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+}
+
+static HISTORY_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("version", DataType::Int64, false),
+        Field::new("timestamp", timestamp_type(), true),
+        Field::new("operation", DataType::Utf8, true),
+        Field::new("operation_parameters", DataType::Utf8, true),
+        Field::new("operation_metrics", DataType::Utf8, true),
+        Field::new("read_version", DataType::Int64, true),
+        Field::new("isolation_level", DataType::Utf8, true),
+        Field::new("is_blind_append", DataType::Boolean, true),
+        Field::new("user_id", DataType::Utf8, true),
+        Field::new("user_name", DataType::Utf8, true),
+        Field::new("engine_info", DataType::Utf8, true),
+        Field::new("user_metadata", DataType::Utf8, true),
+    ]))
+});
+
+static FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("path", DataType::Utf8, false),
+        Field::new("partition", DataType::Utf8, true),
+        Field::new("size_bytes", DataType::Int64, false),
+        Field::new("modification_time", timestamp_type(), false),
+        Field::new("num_records", DataType::Int64, true),
+        Field::new("has_deletion_vector", DataType::Boolean, false),
+        Field::new("stats", DataType::Utf8, true),
+    ]))
+});
+
+static PARTITIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("partition", DataType::Utf8, true),
+        Field::new("num_files", DataType::Int64, false),
+        Field::new("size_bytes", DataType::Int64, false),
+        Field::new("num_records", DataType::Int64, true),
+        Field::new("min_file_size", DataType::Int64, false),
+        Field::new("max_file_size", DataType::Int64, false),
+    ]))
+});
+
+static PROTOCOL_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("min_reader_version", DataType::Int32, false),
+        Field::new("min_writer_version", DataType::Int32, false),
+        Field::new("reader_features", string_list_type(), true),
+        Field::new("writer_features", string_list_type(), true),
+    ]))
+});
+
+static PROPERTIES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("key", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, false),
+    ]))
+});
+
+/// A read-only [`TableProvider`] exposing one kind of metadata of a Delta table.
+///
+/// The provider is bound to a snapshot, so like [`DeltaScanNext`] it represents a static view
+/// of the table at a specific version. Filters are evaluated while the metadata is
+/// materialized, and filters on `version` of the history table bound the number of commit
+/// files read from storage.
+#[derive(Debug, Clone)]
+pub struct DeltaMetadataTable {
+    snapshot: Arc<Snapshot>,
+    log_store: LogStoreRef,
+    kind: DeltaMetadataTableKind,
+}
+
+impl DeltaMetadataTable {
+    /// Create a metadata table for a snapshot of a Delta table.
+    pub fn new(
+        snapshot: Arc<Snapshot>,
+        log_store: LogStoreRef,
+        kind: DeltaMetadataTableKind,
+    ) -> Self {
+        Self {
+            snapshot,
+            log_store,
+            kind,
+        }
+    }
+
+    /// Create a metadata table for the currently loaded state of `table`.
+    pub fn try_new(table: &DeltaTable, kind: DeltaMetadataTableKind) -> DeltaResult<Self> {
+        let snapshot = table.snapshot()?.snapshot().snapshot().clone();
+        Ok(Self::new(Arc::new(snapshot), table.log_store(), kind))
+    }
+
+    /// Create a metadata table for the snapshot backing a [`DeltaScanNext`] provider.
+    ///
+    /// Returns `None` if the provider was created without a log store.
+    pub fn try_from_scan(scan: &DeltaScanNext, kind: DeltaMetadataTableKind) -> Option<Self> {
+        let log_store = scan.log_store()?.clone();
+        let snapshot = Arc::new(scan.snapshot().snapshot().clone());
+        Some(Self::new(snapshot, log_store, kind))
+    }
+
+    /// The kind of metadata exposed by this table.
+    pub fn kind(&self) -> DeltaMetadataTableKind {
+        self.kind
+    }
+
+    /// Materialize the metadata into record batches.
+    pub async fn batches(&self, filters: &[Expr]) -> DeltaResult<Vec<RecordBatch>> {
+        let batch = match self.kind {
+            DeltaMetadataTableKind::History => self.history_batch(filters).await?,
+            DeltaMetadataTableKind::Files => self.files_batch().await?,
+            DeltaMetadataTableKind::Partitions => self.partitions_batch().await?,
+            DeltaMetadataTableKind::Protocol => self.protocol_batch()?,
+            DeltaMetadataTableKind::Properties => self.properties_batch()?,
+            DeltaMetadataTableKind::Detail => {
+                TableDetail::try_from_snapshot(&self.snapshot, self.log_store.as_ref())
+                    .await?
+                    .to_record_batch()?
+            }
+        };
+        Ok(vec![batch])
+    }
+
+    async fn history_batch(&self, filters: &[Expr]) -> DeltaResult<RecordBatch> {
+        let current_version = self.snapshot.version();
+        let limit = min_version_bound(filters).map(|min_version| {
+            current_version.saturating_sub(min_version.min(current_version)) as usize + 1
+        });
+        let infos: Vec<(Version, Option<CommitInfo>)> = self
+            .snapshot
+            .versioned_commit_infos(self.log_store.as_ref(), limit)
+            .await?
+            .try_collect()
+            .await?;
+
+        let rows: Vec<(Version, CommitInfo)> = infos
+            .into_iter()
+            .filter_map(|(version, info)| info.map(|info| (version, info)))
+            .collect();
+
+        let to_json = |value: Option<&serde_json::Value>| value.map(|v| v.to_string());
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from_iter_values(
+                rows.iter().map(|(version, _)| *version as i64),
+            )),
+            Arc::new(
+                TimestampMillisecondArray::from_iter(rows.iter().map(|(_, ci)| ci.timestamp))
+                    .with_timezone("UTC"),
+            ),
+            Arc::new(StringArray::from_iter(
+                rows.iter().map(|(_, ci)| ci.operation.clone()),
+            )),
+            Arc::new(StringArray::from_iter(rows.iter().map(|(_, ci)| {
+                ci.operation_parameters
+                    .as_ref()
+                    .and_then(|params| serde_json::to_string(params).ok())
+            }))),
+            Arc::new(StringArray::from_iter(
+                rows.iter()
+                    .map(|(_, ci)| to_json(ci.info.get("operationMetrics"))),
+            )),
+            Arc::new(Int64Array::from_iter(
+                rows.iter().map(|(_, ci)| ci.read_version.map(|v| v as i64)),
+            )),
+            Arc::new(StringArray::from_iter(rows.iter().map(|(_, ci)| {
+                ci.isolation_level
+                    .as_ref()
+                    .map(|level| level.as_ref().to_string())
+            }))),
+            Arc::new(BooleanArray::from_iter(
+                rows.iter().map(|(_, ci)| ci.is_blind_append),
+            )),
+            Arc::new(StringArray::from_iter(
+                rows.iter().map(|(_, ci)| ci.user_id.clone()),
+            )),
+            Arc::new(StringArray::from_iter(
+                rows.iter().map(|(_, ci)| ci.user_name.clone()),
+            )),
+            Arc::new(StringArray::from_iter(
+                rows.iter().map(|(_, ci)| ci.engine_info.clone()),
+            )),
+            Arc::new(StringArray::from_iter(
+                rows.iter().map(|(_, ci)| ci.user_metadata.clone()),
+            )),
+        ];
+        Ok(RecordBatch::try_new(HISTORY_SCHEMA.clone(), columns)?)
+    }
+
+    async fn files_batch(&self) -> DeltaResult<RecordBatch> {
+        let partition_columns = self.snapshot.metadata().partition_columns().clone();
+        let files: Vec<_> = self
+            .snapshot
+            .file_views(self.log_store.as_ref(), None)
+            .try_collect()
+            .await?;
+
+        let mut paths = Vec::with_capacity(files.len());
+        let mut partitions = Vec::with_capacity(files.len());
+        let mut sizes = Vec::with_capacity(files.len());
+        let mut modification_times = Vec::with_capacity(files.len());
+        let mut num_records = Vec::with_capacity(files.len());
+        let mut has_dv = Vec::with_capacity(files.len());
+        let mut stats = Vec::with_capacity(files.len());
+        for file in files.iter() {
+            paths.push(file.path().to_string());
+            partitions.push(partition_path(
+                &partition_columns,
+                &file.partition_values_map(),
+            ));
+            sizes.push(file.size());
+            modification_times.push(file.modification_time());
+            num_records.push(file.num_records().map(|n| n as i64));
+            has_dv.push(file.deletion_vector_descriptor().is_some());
+            stats.push(file.stats());
+        }
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(paths)),
+            Arc::new(StringArray::from(partitions)),
+            Arc::new(Int64Array::from(sizes)),
+            Arc::new(TimestampMillisecondArray::from(modification_times).with_timezone("UTC")),
+            Arc::new(Int64Array::from(num_records)),
+            Arc::new(BooleanArray::from(has_dv)),
+            Arc::new(StringArray::from(stats)),
+        ];
+        Ok(RecordBatch::try_new(FILES_SCHEMA.clone(), columns)?)
+    }
+
+    async fn partitions_batch(&self) -> DeltaResult<RecordBatch> {
+        #[derive(Default)]
+        struct PartitionAggregate {
+            num_files: i64,
+            size_bytes: i64,
+            num_records: Option<i64>,
+            min_file_size: i64,
+            max_file_size: i64,
+        }
+
+        let partition_columns = self.snapshot.metadata().partition_columns().clone();
+        let mut aggregates: BTreeMap<Option<String>, PartitionAggregate> = BTreeMap::new();
+        let mut files = self.snapshot.file_views(self.log_store.as_ref(), None);
+        while let Some(file) = files.try_next().await? {
+            let key = partition_path(&partition_columns, &file.partition_values_map());
+            let size = file.size();
+            let entry = aggregates.entry(key).or_insert_with(|| PartitionAggregate {
+                num_records: Some(0),
+                min_file_size: size,
+                max_file_size: size,
+                ..Default::default()
+            });
+            entry.num_files += 1;
+            entry.size_bytes += size;
+            entry.min_file_size = entry.min_file_size.min(size);
+            entry.max_file_size = entry.max_file_size.max(size);
+            // the record count is only meaningful if every file carries statistics
+            entry.num_records = entry
+                .num_records
+                .zip(file.num_records())
+                .map(|(total, n)| total + n as i64);
+        }
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter(aggregates.keys().cloned())),
+            Arc::new(Int64Array::from_iter_values(
+                aggregates.values().map(|agg| agg.num_files),
+            )),
+            Arc::new(Int64Array::from_iter_values(
+                aggregates.values().map(|agg| agg.size_bytes),
+            )),
+            Arc::new(Int64Array::from_iter(
+                aggregates.values().map(|agg| agg.num_records),
+            )),
+            Arc::new(Int64Array::from_iter_values(
+                aggregates.values().map(|agg| agg.min_file_size),
+            )),
+            Arc::new(Int64Array::from_iter_values(
+                aggregates.values().map(|agg| agg.max_file_size),
+            )),
+        ];
+        Ok(RecordBatch::try_new(PARTITIONS_SCHEMA.clone(), columns)?)
+    }
+
+    fn protocol_batch(&self) -> DeltaResult<RecordBatch> {
+        let protocol = self.snapshot.protocol();
+        let features_array = |features: Option<&[delta_kernel::table_features::TableFeature]>| {
+            let mut builder = ListBuilder::new(StringBuilder::new());
+            match features {
+                Some(features) => {
+                    for feature in features {
+                        builder.values().append_value(feature.to_string());
+                    }
+                    builder.append(true);
+                }
+                None => builder.append(false),
+            }
+            Arc::new(builder.finish()) as ArrayRef
+        };
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![protocol.min_reader_version()])),
+            Arc::new(Int32Array::from(vec![protocol.min_writer_version()])),
+            features_array(protocol.reader_features()),
+            features_array(protocol.writer_features()),
+        ];
+        Ok(RecordBatch::try_new(PROTOCOL_SCHEMA.clone(), columns)?)
+    }
+
+    fn properties_batch(&self) -> DeltaResult<RecordBatch> {
+        let properties: BTreeMap<_, _> = self.snapshot.metadata().configuration().iter().collect();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter_values(properties.keys())),
+            Arc::new(StringArray::from_iter_values(properties.values())),
+        ];
+        Ok(RecordBatch::try_new(PROPERTIES_SCHEMA.clone(), columns)?)
+    }
+}
+
+/// Render partition values in hive style (`a=1/b=2`) following the table's partition order.
+fn partition_path(
+    partition_columns: &[String],
+    values: &std::collections::HashMap<String, Option<String>>,
+) -> Option<String> {
+    if partition_columns.is_empty() {
+        return None;
+    }
+    let path = partition_columns
+        .iter()
+        .map(|col| {
+            let value = values
+                .get(col)
+                .cloned()
+                .flatten()
+                .unwrap_or_else(|| NULL_PARTITION_VALUE.to_string());
+            format!("{col}={value}")
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    Some(path)
+}
+
+/// Extract the smallest version a history query may return from `version >= x` style filters.
+fn min_version_bound(filters: &[Expr]) -> Option<Version> {
+    filters
+        .iter()
+        .filter_map(|filter| match filter {
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                match (left.as_ref(), right.as_ref()) {
+                    (Expr::Column(c), Expr::Literal(v, _)) if c.name == "version" => {
+                        lower_bound(*op, v)
+                    }
+                    (Expr::Literal(v, _), Expr::Column(c)) if c.name == "version" => {
+                        op.swap().and_then(|op| lower_bound(op, v))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .max()
+}
+
+fn lower_bound(op: Operator, value: &ScalarValue) -> Option<Version> {
+    let value = match value.cast_to(&DataType::Int64).ok()? {
+        ScalarValue::Int64(Some(v)) => Version::try_from(v.max(0)).ok()?,
+        _ => return None,
+    };
+    match op {
+        Operator::Eq | Operator::GtEq => Some(value),
+        Operator::Gt => Some(value + 1),
+        _ => None,
+    }
+}
+
+fn apply_filters(
+    session: &dyn Session,
+    schema: &SchemaRef,
+    batches: Vec<RecordBatch>,
+    filters: &[Expr],
+) -> DataFusionResult<Vec<RecordBatch>> {
+    let Some(predicate) = conjunction(filters.iter().cloned()) else {
+        return Ok(batches);
+    };
+    let df_schema: DFSchema = schema.clone().try_into()?;
+    let predicate = session.create_physical_expr(predicate, &df_schema)?;
+    batches
+        .into_iter()
+        .map(|batch| {
+            let mask = predicate.evaluate(&batch)?.into_array(batch.num_rows())?;
+            Ok(filter_record_batch(&batch, mask.as_boolean())?)
+        })
+        .collect()
+}
+
+#[async_trait]
+impl TableProvider for DeltaMetadataTable {
+    fn schema(&self) -> SchemaRef {
+        self.kind.schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    async fn scan(
+        &self,
+        session: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let schema = self.schema();
+        let batches = self.batches(filters).await?;
+        let batches = apply_filters(session, &schema, batches, filters)?;
+        MemTable::try_new(schema, vec![batches])?
+            .scan(session, projection, &[], limit)
+            .await
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filter: &[&Expr],
+    ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
+        Ok(filter
+            .iter()
+            .map(|_| TableProviderFilterPushDown::Exact)
+            .collect())
+    }
+}
+
+/// A [`SchemaProvider`] that adds metadata tables to the Delta tables of another provider.
+///
+/// Resolving `name$kind` looks up `name` in the wrapped provider. If that table is a Delta
+/// table, a [`DeltaMetadataTable`] of the requested kind is returned. All other names are
+/// passed through unchanged.
+#[derive(Debug)]
+pub struct DeltaMetadataSchemaProvider {
+    inner: Arc<dyn SchemaProvider>,
+}
+
+impl DeltaMetadataSchemaProvider {
+    /// Wrap an existing [`SchemaProvider`].
+    pub fn new(inner: Arc<dyn SchemaProvider>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl SchemaProvider for DeltaMetadataSchemaProvider {
+    fn table_names(&self) -> Vec<String> {
+        self.inner.table_names()
+    }
+
+    async fn table(&self, name: &str) -> DataFusionResult<Option<Arc<dyn TableProvider>>> {
+        if let Some(table) = self.inner.table(name).await? {
+            return Ok(Some(table));
+        }
+        let Some((base, kind)) = split_metadata_table_name(name) else {
+            return Ok(None);
+        };
+        let Some(table) = self.inner.table(base).await? else {
+            return Ok(None);
+        };
+        let scan = table.downcast_ref::<DeltaScanNext>().ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "Metadata table '{name}' requires '{base}' to be a Delta table"
+            ))
+        })?;
+        let metadata_table = DeltaMetadataTable::try_from_scan(scan, kind).ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "Metadata table '{name}' requires '{base}' to be registered with a log store"
+            ))
+        })?;
+        Ok(Some(Arc::new(metadata_table)))
+    }
+
+    fn register_table(
+        &self,
+        name: String,
+        table: Arc<dyn TableProvider>,
+    ) -> DataFusionResult<Option<Arc<dyn TableProvider>>> {
+        self.inner.register_table(name, table)
+    }
+
+    fn deregister_table(&self, name: &str) -> DataFusionResult<Option<Arc<dyn TableProvider>>> {
+        self.inner.deregister_table(name)
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        self.inner.table_exist(name)
+            || split_metadata_table_name(name).is_some_and(|(base, _)| self.inner.table_exist(base))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::catalog::MemorySchemaProvider;
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+    use crate::test_utils::open_fs_path;
+
+    #[test]
+    fn test_split_metadata_table_name() {
+        assert_eq!(
+            split_metadata_table_name("orders$files"),
+            Some(("orders", DeltaMetadataTableKind::Files))
+        );
+        assert_eq!(
+            split_metadata_table_name("a$b$HISTORY"),
+            Some(("a$b", DeltaMetadataTableKind::History))
+        );
+        assert_eq!(split_metadata_table_name("orders"), None);
+        assert_eq!(split_metadata_table_name("orders$unknown"), None);
+        assert_eq!(split_metadata_table_name("$files"), None);
+    }
+
+    #[test]
+    fn test_min_version_bound() {
+        use datafusion::prelude::{col, lit};
+        assert_eq!(
+            min_version_bound(&[col("version").gt_eq(lit(3_i64))]),
+            Some(3)
+        );
+        assert_eq!(min_version_bound(&[col("version").gt(lit(3_i64))]), Some(4));
+        assert_eq!(
+            min_version_bound(&[lit(2_i32).lt_eq(col("version"))]),
+            Some(2)
+        );
+        assert_eq!(min_version_bound(&[col("version").lt(lit(3_i64))]), None);
+        assert_eq!(
+            min_version_bound(&[col("operation").eq(lit("WRITE"))]),
+            None
+        );
+    }
+
+    async fn session_with_table(table: DeltaTable) -> SessionContext {
+        let ctx = SessionContext::new();
+        let schema = Arc::new(MemorySchemaProvider::new());
+        schema
+            .register_table("t".to_string(), table.table_provider().await.unwrap())
+            .unwrap();
+        ctx.catalog("datafusion")
+            .unwrap()
+            .register_schema("delta", Arc::new(DeltaMetadataSchemaProvider::new(schema)))
+            .unwrap();
+        ctx
+    }
+
+    #[tokio::test]
+    async fn test_history_metadata_table() {
+        let mut table = open_fs_path("../test/tests/data/simple_table");
+        table.load().await.unwrap();
+        let ctx = session_with_table(table).await;
+
+        let batches = ctx
+            .sql(r#"SELECT version, operation FROM delta."t$history" WHERE version >= 3"#)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = vec![
+            "+---------+-----------+",
+            "| version | operation |",
+            "+---------+-----------+",
+            "| 3       | UPDATE    |",
+            "| 4       | DELETE    |",
+            "+---------+-----------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &batches);
+    }
+
+    #[tokio::test]
+    async fn test_history_metadata_table_of_older_version() {
+        let mut table = open_fs_path("../test/tests/data/simple_table");
+        table.load_version(2).await.unwrap();
+        let ctx = session_with_table(table).await;
+
+        let batches = ctx
+            .sql(r#"SELECT version, operation FROM delta."t$history" WHERE version >= 1"#)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = vec![
+            "+---------+-----------+",
+            "| version | operation |",
+            "+---------+-----------+",
+            "| 1       | MERGE     |",
+            "| 2       | WRITE     |",
+            "+---------+-----------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &batches);
+    }
+
+    #[tokio::test]
+    async fn test_files_and_partitions_metadata_table() {
+        let mut table = open_fs_path("../test/tests/data/delta-0.8.0-partitioned");
+        table.load().await.unwrap();
+        let num_files = table.snapshot().unwrap().log_data().num_files();
+        let ctx = session_with_table(table).await;
+
+        let batches = ctx
+            .sql(r#"SELECT count(*) AS n FROM delta."t$files""#)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let count = batches[0]
+            .column(0)
+            .as_primitive::<arrow::datatypes::Int64Type>();
+        assert_eq!(count.value(0) as usize, num_files);
+
+        let batches = ctx
+            .sql(
+                r#"SELECT partition, num_files FROM delta."t$partitions"
+                   WHERE partition = 'year=2020/month=1/day=1'"#,
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = vec![
+            "+-------------------------+-----------+",
+            "| partition               | num_files |",
+            "+-------------------------+-----------+",
+            "| year=2020/month=1/day=1 | 1         |",
+            "+-------------------------+-----------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &batches);
+    }
+
+    #[tokio::test]
+    async fn test_protocol_and_properties_metadata_table() {
+        let mut table = open_fs_path("../test/tests/data/simple_table");
+        table.load().await.unwrap();
+        let ctx = session_with_table(table).await;
+
+        let batches = ctx
+            .sql(r#"SELECT min_reader_version, min_writer_version FROM delta."t$protocol""#)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = vec![
+            "+--------------------+--------------------+",
+            "| min_reader_version | min_writer_version |",
+            "+--------------------+--------------------+",
+            "| 1                  | 2                  |",
+            "+--------------------+--------------------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &batches);
+
+        let batches = ctx
+            .sql(r#"SELECT * FROM delta."t$properties""#)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_detail_metadata_table() {
+        let mut table = open_fs_path("../test/tests/data/simple_table");
+        table.load().await.unwrap();
+        let num_files = table.snapshot().unwrap().log_data().num_files();
+        let ctx = session_with_table(table).await;
+
+        let batches = ctx
+            .sql(r#"SELECT version, num_files FROM delta."t$detail""#)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+        let version = batches[0]
+            .column(0)
+            .as_primitive::<arrow::datatypes::Int64Type>();
+        assert_eq!(version.value(0), 4);
+        let count = batches[0]
+            .column(1)
+            .as_primitive::<arrow::datatypes::Int64Type>();
+        assert_eq!(count.value(0) as usize, num_files);
+    }
+}
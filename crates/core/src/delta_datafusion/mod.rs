@@ -44,15 +44,21 @@ use datafusion::execution::TaskContext;
 use datafusion::execution::context::SessionContext;
 use datafusion::logical_expr::logical_plan::CreateExternalTable;
 use datafusion::logical_expr::utils::conjunction;
-use datafusion::logical_expr::{Expr, Extension, LogicalPlan};
+use datafusion::logical_expr::{Expr, Extension, LogicalPlan, UserDefinedLogicalNode};
 use datafusion::physical_optimizer::pruning::PruningPredicate;
 use datafusion::physical_plan::ExecutionPlan;
+use datafusion_proto::bytes::Serializeable as _;
 use datafusion_proto::logical_plan::LogicalExtensionCodec;
 use datafusion_proto::physical_plan::PhysicalExtensionCodec;
 use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
 use either::Either;
+use itertools::Itertools as _;
+use serde::{Deserialize, Serialize};
 
+use crate::delta_datafusion::data_validation::DataValidation;
 use crate::delta_datafusion::expr::parse_predicate_expression;
+use crate::delta_datafusion::logical::MetricObserver;
+use crate::delta_datafusion::table_provider::next::DeltaScanExecWire;
 use crate::delta_datafusion::table_provider::{DeltaScan, DeltaScanWire};
 use crate::ensure_table_uri;
 use crate::errors::{DeltaResult, DeltaTableError};
@@ -74,6 +80,9 @@ pub(crate) use data_validation::{
     DataValidationExec, constraints_to_exprs, generated_columns_to_exprs, validation_predicates,
 };
 pub(crate) use find_files::*;
+pub use metadata_tables::{
+    DeltaMetadataSchemaProvider, DeltaMetadataTable, DeltaMetadataTableKind,
+};
 pub(crate) use table_provider::next::normalize_path_as_file_id;
 pub use table_provider::{
     DeltaScanConfig, DeltaScanConfigBuilder, TableProviderBuilder, next::DeltaScanExec,
@@ -95,6 +104,7 @@ pub mod expr;
 mod file_id;
 mod find_files;
 pub mod logical;
+pub mod metadata_tables;
 pub mod physical;
 pub mod planner;
 mod session;
@@ -444,17 +454,25 @@ pub(crate) fn to_correct_scalar_value(
     }
 }
 
-/// Legacy codec for serialized plans that still contain the retired physical
-/// [`DeltaScan`] wrapper.
-#[deprecated(
-    note = "DeltaPhysicalCodec only supports the retired physical DeltaScan wrapper. \
-            Use DeltaLogicalCodec for table-provider serialization until a DeltaScanExec \
-            physical codec is available."
-)]
+/// Serialized Delta execution plan nodes handled by [`DeltaPhysicalCodec`].
+#[derive(Serialize, Deserialize)]
+enum DeltaPhysicalNode {
+    ScanExec(Box<DeltaScanExecWire>),
+}
+
+/// Does serde on Delta execution plans.
+///
+/// Encodes [`DeltaScanExec`] nodes, including the snapshot they read, the scan config,
+/// deletion vector selections and file ids. The Parquet read plan below the scan is encoded
+/// by `datafusion-proto` itself. Object stores for the table must be registered with the
+/// runtime of the decoding session, as credentials are never part of the encoded plan.
+///
+/// Plans that still contain the retired physical [`DeltaScan`] wrapper can be decoded as well.
+/// Metadata-only scans, which answer queries like `COUNT(*)` without reading data files,
+/// are not supported.
 #[derive(Debug)]
 pub struct DeltaPhysicalCodec {}
 
-#[allow(deprecated)]
 impl PhysicalExtensionCodec for DeltaPhysicalCodec {
     fn try_decode(
         &self,
@@ -462,9 +480,24 @@ impl PhysicalExtensionCodec for DeltaPhysicalCodec {
         inputs: &[Arc<dyn ExecutionPlan>],
         _registry: &TaskContext,
     ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        let [input] = inputs else {
+            return Err(DataFusionError::Internal(format!(
+                "Delta scans expect exactly one input, got {}",
+                inputs.len()
+            )));
+        };
+
+        if let Ok(node) = serde_json::from_slice::<DeltaPhysicalNode>(buf) {
+            return match node {
+                DeltaPhysicalNode::ScanExec(wire) => {
+                    Ok(Arc::new(wire.try_into_exec(Arc::clone(input))?))
+                }
+            };
+        }
+
         let wire: DeltaScanWire = serde_json::from_reader(buf)
             .map_err(|_| DataFusionError::Internal("Unable to decode DeltaScan".to_string()))?;
-        let delta_scan = wire.into_delta_scan((*inputs)[0].clone());
+        let delta_scan = wire.into_delta_scan(Arc::clone(input));
         Ok(Arc::new(delta_scan))
     }
 
@@ -473,9 +506,20 @@ impl PhysicalExtensionCodec for DeltaPhysicalCodec {
         node: Arc<dyn ExecutionPlan>,
         buf: &mut Vec<u8>,
     ) -> Result<(), DataFusionError> {
-        let delta_scan = node
-            .downcast_ref::<DeltaScan>()
-            .ok_or_else(|| DataFusionError::Internal("Not a legacy delta scan!".to_string()))?;
+        if let Some(exec) = node.downcast_ref::<DeltaScanExec>() {
+            let wire =
+                DeltaPhysicalNode::ScanExec(Box::new(DeltaScanExecWire::try_from_exec(exec)?));
+            return serde_json::to_writer(buf, &wire).map_err(|err| {
+                DataFusionError::Internal(format!("Unable to encode DeltaScanExec: {err}"))
+            });
+        }
+
+        let delta_scan = node.downcast_ref::<DeltaScan>().ok_or_else(|| {
+            DataFusionError::NotImplemented(format!(
+                "DeltaPhysicalCodec cannot encode {}",
+                node.name()
+            ))
+        })?;
 
         let wire = DeltaScanWire::from(delta_scan);
         serde_json::to_writer(buf, &wire).map_err(|_| {
@@ -485,22 +529,88 @@ impl PhysicalExtensionCodec for DeltaPhysicalCodec {
     }
 }
 
+/// Serialized Delta logical extension nodes handled by [`DeltaLogicalCodec`].
+#[derive(Serialize, Deserialize)]
+enum DeltaLogicalNode {
+    MetricObserver {
+        id: String,
+        enable_pushdown: bool,
+    },
+    /// Validation expressions encoded with `datafusion-proto`.
+    DataValidation {
+        validations: Vec<Vec<u8>>,
+    },
+}
+
 /// Does serde on DeltaTables
+///
+/// Table scans are encoded as [`DeltaScanNext`] providers including their snapshot, scan
+/// config and file selection. The extension nodes delta-rs adds to logical plans for writes
+/// (metric observers and data validation) are supported as well.
 #[derive(Debug)]
 pub struct DeltaLogicalCodec {}
 
 impl LogicalExtensionCodec for DeltaLogicalCodec {
     fn try_decode(
         &self,
-        _buf: &[u8],
-        _inputs: &[LogicalPlan],
-        _ctx: &TaskContext,
+        buf: &[u8],
+        inputs: &[LogicalPlan],
+        ctx: &TaskContext,
     ) -> Result<Extension, DataFusionError> {
-        todo!("DeltaLogicalCodec")
+        let node: DeltaLogicalNode = serde_json::from_slice(buf).map_err(|err| {
+            DataFusionError::Internal(format!("Unable to decode delta plan node: {err}"))
+        })?;
+        let [input] = inputs else {
+            return Err(DataFusionError::Internal(format!(
+                "Delta plan nodes expect exactly one input, got {}",
+                inputs.len()
+            )));
+        };
+
+        let node: Arc<dyn UserDefinedLogicalNode> = match node {
+            DeltaLogicalNode::MetricObserver {
+                id,
+                enable_pushdown,
+            } => Arc::new(MetricObserver {
+                id,
+                input: input.clone(),
+                enable_pushdown,
+            }),
+            DeltaLogicalNode::DataValidation { validations } => {
+                let validations: Vec<_> = validations
+                    .iter()
+                    .map(|bytes| Expr::from_bytes_with_registry(bytes, ctx))
+                    .try_collect()?;
+                DataValidation::try_new(input.clone(), validations)?
+            }
+        };
+        Ok(Extension { node })
     }
 
-    fn try_encode(&self, _node: &Extension, _buf: &mut Vec<u8>) -> Result<(), DataFusionError> {
-        todo!("DeltaLogicalCodec")
+    fn try_encode(&self, node: &Extension, buf: &mut Vec<u8>) -> Result<(), DataFusionError> {
+        let any = node.node.as_any();
+        let wire = if let Some(observer) = any.downcast_ref::<MetricObserver>() {
+            DeltaLogicalNode::MetricObserver {
+                id: observer.id.clone(),
+                enable_pushdown: observer.enable_pushdown,
+            }
+        } else if let Some(validation) = any.downcast_ref::<DataValidation>() {
+            DeltaLogicalNode::DataValidation {
+                validations: validation
+                    .validations()
+                    .iter()
+                    .map(|expr| expr.to_bytes().map(|bytes| bytes.to_vec()))
+                    .try_collect()?,
+            }
+        } else {
+            return Err(DataFusionError::NotImplemented(format!(
+                "DeltaLogicalCodec cannot encode extension node {}",
+                node.node.name()
+            )));
+        };
+        serde_json::to_writer(buf, &wire).map_err(|err| {
+            DataFusionError::Internal(format!("Unable to encode delta plan node: {err}"))
+        })
     }
 
     fn try_decode_table_provider(
@@ -714,7 +824,6 @@ mod tests {
     }
 
     #[test]
-    #[allow(deprecated)]
     fn roundtrip_test_delta_exec_plan() {
         let ctx = SessionContext::new();
         let codec = DeltaPhysicalCodec {};
@@ -740,6 +849,108 @@ mod tests {
         assert_eq!(format!("{exec_plan:?}"), format!("{result_exec_plan:?}"));
     }
 
+    async fn physical_codec_roundtrip(path: &str, sql: &str) -> (String, String) {
+        let table = open_fs_path(path);
+        let provider = table.table_provider().await.unwrap();
+        let ctx = SessionContext::new();
+        ctx.register_table("test", provider).unwrap();
+        let plan = ctx
+            .sql(sql)
+            .await
+            .unwrap()
+            .create_physical_plan()
+            .await
+            .unwrap();
+
+        let codec = DeltaPhysicalCodec {};
+        let proto = protobuf::PhysicalPlanNode::try_from_physical_plan(plan.clone(), &codec)
+            .expect("to proto");
+
+        // decode in a fresh session that has never seen the table
+        let decoded_ctx = SessionContext::new();
+        let decoded = proto
+            .try_into_physical_plan(&decoded_ctx.task_ctx(), &codec)
+            .expect("from proto");
+
+        let expected = datafusion::physical_plan::collect(plan, ctx.task_ctx())
+            .await
+            .unwrap();
+        let actual = datafusion::physical_plan::collect(decoded, decoded_ctx.task_ctx())
+            .await
+            .unwrap();
+        let format = |batches: &[RecordBatch]| {
+            let mut lines: Vec<_> = arrow::util::pretty::pretty_format_batches(batches)
+                .unwrap()
+                .to_string()
+                .lines()
+                .map(str::to_string)
+                .collect();
+            lines.sort();
+            lines.join("\n")
+        };
+        (format(&expected), format(&actual))
+    }
+
+    #[tokio::test]
+    async fn roundtrip_test_delta_scan_exec_partitioned() {
+        let (expected, actual) = physical_codec_roundtrip(
+            "../test/tests/data/delta-0.8.0-partitioned",
+            "select value, year, month, day from test where year = '2021'",
+        )
+        .await;
+        assert_eq!(expected, actual);
+    }
+
+    #[tokio::test]
+    async fn roundtrip_test_delta_scan_exec_deletion_vectors() {
+        let (expected, actual) = physical_codec_roundtrip(
+            "../test/tests/data/table-with-dv-small",
+            "select * from test",
+        )
+        .await;
+        assert_eq!(expected, actual);
+    }
+
+    #[tokio::test]
+    async fn roundtrip_test_delta_logical_codec_extension_nodes() {
+        let table = open_fs_path("../test/tests/data/simple_table");
+        let ctx = SessionContext::new();
+        ctx.register_table("test", table.table_provider().await.unwrap())
+            .unwrap();
+        let input = ctx
+            .sql("select id from test")
+            .await
+            .unwrap()
+            .into_optimized_plan()
+            .unwrap();
+
+        let codec = DeltaLogicalCodec {};
+        let nodes: Vec<Arc<dyn UserDefinedLogicalNode>> = vec![
+            Arc::new(MetricObserver {
+                id: "observer".to_string(),
+                input: input.clone(),
+                enable_pushdown: true,
+            }),
+            DataValidation::try_new(
+                input.clone(),
+                [datafusion::prelude::col("id").is_not_null()],
+            )
+            .unwrap(),
+        ];
+        for node in nodes {
+            let extension = Extension { node };
+            let mut buf = Vec::new();
+            codec.try_encode(&extension, &mut buf).unwrap();
+            let decoded = codec
+                .try_decode(&buf, std::slice::from_ref(&input), &ctx.task_ctx())
+                .unwrap();
+            assert_eq!(
+                format!("{:?}", decoded.node),
+                format!("{:?}", extension.node)
+            );
+        }
+    }
+
     #[tokio::test]
     async fn roundtrip_test_delta_logical_codec_preserves_file_selection() {
         let log_store = crate::test_utils::TestTables::Simple
@@ -45,8 +45,8 @@ use url::Url;
 use uuid::Uuid;
 
 pub use self::scan::DeltaScanExec;
-pub(crate) use self::scan::KernelScanPlan;
 use self::scan::ProjectedScanContract;
+pub(crate) use self::scan::{DeltaScanExecWire, KernelScanPlan};
 use super::data_sink::DeltaDataSink;
 use crate::DeltaTableError;
 use crate::delta_datafusion::DeltaScanConfig;
@@ -596,6 +596,16 @@ impl DeltaScan {
         self
     }
 
+    /// The snapshot this scan reads from.
+    pub(crate) fn snapshot(&self) -> &SnapshotWrapper {
+        &self.snapshot
+    }
+
+    /// The runtime log store handle, if one was attached.
+    pub(crate) fn log_store(&self) -> Option<&LogStoreRef> {
+        self.log_store.as_ref()
+    }
+
     /// Scope runtime object store registration to a specific operation's temporary copy when
     /// the caller needs operation local reads.
     pub(crate) fn with_operation_id(mut self, operation_id: Uuid) -> Self {
@@ -0,0 +1,387 @@
+//! Serialization of [`DeltaScanExec`] for shipping physical plans to remote executors.
+//!
+//! The wire format carries everything the exec needs beyond its Parquet child, which is
+//! encoded by `datafusion-proto` itself:
+//!
+//! - the snapshot the scan was planned against (without materialized file state)
+//! - the scan config and the query scoped scan contract
+//! - deletion vector keep masks and public file ids keyed by compact scan file id
+//! - aggregated partition column statistics
+//!
+//! Kernel transform expressions are not serializable. The encoded plan instead records which
+//! files need a transform, and the decoded exec recomputes them on first execution via
+//! [`DeferredTransforms`].
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::StreamReader;
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{Field, Schema};
+use dashmap::DashMap;
+use datafusion::common::stats::Precision;
+use datafusion::common::{ColumnStatistics, HashMap, Result, internal_datafusion_err};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
+use datafusion::scalar::ScalarValue;
+use serde::{Deserialize, Serialize};
+
+use super::{DeferredTransforms, DeltaScanExec, KernelScanPlan, ProjectedScanContract};
+use crate::delta_datafusion::DeltaScanConfig;
+use crate::kernel::Snapshot;
+
+/// Serializable form of a [`DeltaScanExec`].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct DeltaScanExecWire {
+    snapshot: Snapshot,
+    config: DeltaScanConfig,
+    contract: ProjectedScanContractWire,
+    /// Compact scan file ids of files that need a kernel transform, keyed by file URL.
+    transform_file_ids: BTreeMap<String, String>,
+    /// Deletion vector keep masks keyed by compact scan file id.
+    selection_vectors: BTreeMap<String, SelectionVectorWire>,
+    /// Public file paths keyed by compact scan file id.
+    public_file_ids: BTreeMap<String, String>,
+    partition_stats: PartitionStatisticsWire,
+}
+
+impl DeltaScanExecWire {
+    pub(crate) fn try_from_exec(exec: &DeltaScanExec) -> Result<Self> {
+        let scan_plan = exec.scan_plan();
+
+        let transform_file_ids = match exec.deferred_transforms() {
+            Some(deferred) => deferred
+                .file_ids()
+                .iter()
+                .map(|(url, id)| (url.clone(), id.clone()))
+                .collect(),
+            None => exec
+                .transforms()
+                .keys()
+                .map(|file_id| {
+                    let url = super::public_file_id(exec.public_file_ids(), file_id)?;
+                    Ok((url.to_string(), file_id.clone()))
+                })
+                .collect::<Result<_>>()?,
+        };
+
+        let selection_vectors = exec
+            .selection_vectors()
+            .iter()
+            .map(|entry| (entry.key().clone(), SelectionVectorWire::new(entry.value())))
+            .collect();
+
+        Ok(Self {
+            snapshot: scan_plan.snapshot.without_materialized_files(),
+            config: scan_plan.config.clone(),
+            contract: ProjectedScanContractWire::from(&scan_plan.contract),
+            transform_file_ids,
+            selection_vectors,
+            public_file_ids: exec
+                .public_file_ids()
+                .iter()
+                .map(|(id, url)| (id.clone(), url.clone()))
+                .collect(),
+            partition_stats: PartitionStatisticsWire::try_new(exec.partition_stats())?,
+        })
+    }
+
+    /// Rebuild the exec on top of its decoded Parquet read plan.
+    pub(crate) fn try_into_exec(self, input: Arc<dyn ExecutionPlan>) -> Result<DeltaScanExec> {
+        // Predicates have already been applied to the file list and pushed into the
+        // Parquet child, so the scan is rebuilt without them.
+        let scan_plan = KernelScanPlan::try_new_with_contract(
+            &self.snapshot,
+            self.contract.into(),
+            &[],
+            &self.config,
+            None,
+        )?;
+
+        let selection_vectors: DashMap<_, _> = self
+            .selection_vectors
+            .into_iter()
+            .map(|(file_id, mask)| (file_id, mask.into_mask()))
+            .collect();
+
+        let exec = DeltaScanExec::new(
+            Arc::new(scan_plan),
+            input,
+            Arc::new(HashMap::new()),
+            Arc::new(selection_vectors),
+            Arc::new(self.public_file_ids.into_iter().collect()),
+            self.partition_stats.try_into_stats()?,
+            ExecutionPlanMetricsSet::new(),
+        );
+
+        if self.transform_file_ids.is_empty() {
+            Ok(exec)
+        } else {
+            Ok(
+                exec.with_deferred_transforms(Arc::new(DeferredTransforms::new(
+                    self.transform_file_ids.into_iter().collect(),
+                ))),
+            )
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProjectedScanContractWire {
+    result_schema: Schema,
+    scan_schema: Schema,
+    output_schema: Schema,
+    kernel_projection: Option<Vec<usize>>,
+    result_projection: Option<Vec<usize>>,
+    file_id_field: Field,
+    retain_file_id: bool,
+    row_index_field: Option<Field>,
+    retain_row_index: bool,
+}
+
+impl From<&ProjectedScanContract> for ProjectedScanContractWire {
+    fn from(contract: &ProjectedScanContract) -> Self {
+        Self {
+            result_schema: contract.result_schema.as_ref().clone(),
+            scan_schema: contract.scan_schema.as_ref().clone(),
+            output_schema: contract.output_schema.as_ref().clone(),
+            kernel_projection: contract.kernel_projection.clone(),
+            result_projection: contract.result_projection.clone(),
+            file_id_field: contract.file_id_field.as_ref().clone(),
+            retain_file_id: contract.retain_file_id,
+            row_index_field: contract.row_index_field.as_deref().cloned(),
+            retain_row_index: contract.retain_row_index,
+        }
+    }
+}
+
+impl From<ProjectedScanContractWire> for ProjectedScanContract {
+    fn from(wire: ProjectedScanContractWire) -> Self {
+        Self {
+            result_schema: Arc::new(wire.result_schema),
+            scan_schema: Arc::new(wire.scan_schema),
+            output_schema: Arc::new(wire.output_schema),
+            kernel_projection: wire.kernel_projection,
+            result_projection: wire.result_projection,
+            file_id_field: Arc::new(wire.file_id_field),
+            retain_file_id: wire.retain_file_id,
+            row_index_field: wire.row_index_field.map(Arc::new),
+            retain_row_index: wire.retain_row_index,
+        }
+    }
+}
+
+/// Deletion vector keep mask packed into a bitmap, one bit per row.
+#[derive(Serialize, Deserialize)]
+struct SelectionVectorWire {
+    len: usize,
+    bits: Vec<u8>,
+}
+
+impl SelectionVectorWire {
+    fn new(mask: &[bool]) -> Self {
+        let mut bits = vec![0u8; mask.len().div_ceil(8)];
+        for (idx, _) in mask.iter().enumerate().filter(|(_, keep)| **keep) {
+            bits[idx / 8] |= 1 << (idx % 8);
+        }
+        Self {
+            len: mask.len(),
+            bits,
+        }
+    }
+
+    fn into_mask(self) -> Vec<bool> {
+        (0..self.len)
+            .map(|idx| self.bits[idx / 8] & (1 << (idx % 8)) != 0)
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum PrecisionWire<T> {
+    Exact(T),
+    Inexact(T),
+    Absent,
+}
+
+impl<T: Clone> PrecisionWire<T> {
+    fn new<U: Clone + std::fmt::Debug + PartialEq + Eq + PartialOrd>(
+        precision: &Precision<U>,
+        mut f: impl FnMut(&U) -> T,
+    ) -> Self {
+        match precision {
+            Precision::Exact(value) => Self::Exact(f(value)),
+            Precision::Inexact(value) => Self::Inexact(f(value)),
+            Precision::Absent => Self::Absent,
+        }
+    }
+
+    fn into_precision<U: Clone + std::fmt::Debug + PartialEq + Eq + PartialOrd>(
+        self,
+        f: impl FnOnce(T) -> Result<U>,
+    ) -> Result<Precision<U>> {
+        Ok(match self {
+            Self::Exact(value) => Precision::Exact(f(value)?),
+            Self::Inexact(value) => Precision::Inexact(f(value)?),
+            Self::Absent => Precision::Absent,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ColumnStatisticsWire {
+    column: String,
+    null_count: PrecisionWire<usize>,
+    distinct_count: PrecisionWire<usize>,
+    byte_size: PrecisionWire<usize>,
+    /// Scalar statistics refer to a column of [`PartitionStatisticsWire::values`].
+    min_value: PrecisionWire<usize>,
+    max_value: PrecisionWire<usize>,
+    sum_value: PrecisionWire<usize>,
+}
+
+/// Partition column statistics with scalar values carried as a single row Arrow IPC batch.
+#[derive(Serialize, Deserialize)]
+struct PartitionStatisticsWire {
+    columns: Vec<ColumnStatisticsWire>,
+    values: Vec<u8>,
+}
+
+impl PartitionStatisticsWire {
+    fn try_new(stats: &HashMap<String, ColumnStatistics>) -> Result<Self> {
+        let mut values = Vec::new();
+        let mut push = |value: &ScalarValue| {
+            values.push(value.clone());
+            values.len() - 1
+        };
+        let columns = stats
+            .iter()
+            .map(|(column, stats)| ColumnStatisticsWire {
+                column: column.clone(),
+                null_count: PrecisionWire::new(&stats.null_count, |v| *v),
+                distinct_count: PrecisionWire::new(&stats.distinct_count, |v| *v),
+                byte_size: PrecisionWire::new(&stats.byte_size, |v| *v),
+                min_value: PrecisionWire::new(&stats.min_value, &mut push),
+                max_value: PrecisionWire::new(&stats.max_value, &mut push),
+                sum_value: PrecisionWire::new(&stats.sum_value, &mut push),
+            })
+            .collect();
+        Ok(Self {
+            columns,
+            values: encode_scalars(&values)?,
+        })
+    }
+
+    fn try_into_stats(self) -> Result<HashMap<String, ColumnStatistics>> {
+        let values = decode_scalars(&self.values)?;
+        let scalar = |idx: usize| {
+            values
+                .get(idx)
+                .cloned()
+                .ok_or_else(|| internal_datafusion_err!("missing partition statistic {idx}"))
+        };
+        self.columns
+            .into_iter()
+            .map(|column| {
+                let stats = ColumnStatistics {
+                    null_count: column.null_count.into_precision(Ok)?,
+                    distinct_count: column.distinct_count.into_precision(Ok)?,
+                    byte_size: column.byte_size.into_precision(Ok)?,
+                    min_value: column.min_value.into_precision(scalar)?,
+                    max_value: column.max_value.into_precision(scalar)?,
+                    sum_value: column.sum_value.into_precision(scalar)?,
+                };
+                Ok((column.column, stats))
+            })
+            .collect()
+    }
+}
+
+fn encode_scalars(values: &[ScalarValue]) -> Result<Vec<u8>> {
+    if values.is_empty() {
+        return Ok(vec![]);
+    }
+    let columns = values
+        .iter()
+        .map(ScalarValue::to_array)
+        .collect::<Result<Vec<_>>>()?;
+    let fields: Vec<_> = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| Field::new(idx.to_string(), column.data_type().clone(), true))
+        .collect();
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+
+    let mut buffer = vec![];
+    let mut writer = StreamWriter::try_new(&mut buffer, batch.schema_ref())?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    drop(writer);
+    Ok(buffer)
+}
+
+fn decode_scalars(buffer: &[u8]) -> Result<Vec<ScalarValue>> {
+    if buffer.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut reader = StreamReader::try_new(buffer, None)?;
+    let Some(batch) = reader.next().transpose()? else {
+        return Ok(vec![]);
+    };
+    batch
+        .columns()
+        .iter()
+        .map(|column| ScalarValue::try_from_array(column, 0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_vector_wire_roundtrip() {
+        for mask in [
+            vec![],
+            vec![true],
+            vec![false, true, true, false, true, false, false, true, false],
+            vec![false; 17],
+        ] {
+            assert_eq!(SelectionVectorWire::new(&mask).into_mask(), mask);
+        }
+    }
+
+    #[test]
+    fn test_partition_statistics_wire_roundtrip() {
+        let stats = HashMap::from([
+            (
+                "year".to_string(),
+                ColumnStatistics {
+                    null_count: Precision::Exact(0),
+                    min_value: Precision::Exact(ScalarValue::Utf8(Some("2020".into()))),
+                    max_value: Precision::Exact(ScalarValue::Utf8(Some("2021".into()))),
+                    distinct_count: Precision::Absent,
+                    sum_value: Precision::Absent,
+                    byte_size: Precision::Absent,
+                },
+            ),
+            (
+                "day".to_string(),
+                ColumnStatistics {
+                    null_count: Precision::Inexact(3),
+                    min_value: Precision::Exact(ScalarValue::Int32(Some(1))),
+                    max_value: Precision::Absent,
+                    distinct_count: Precision::Absent,
+                    sum_value: Precision::Absent,
+                    byte_size: Precision::Absent,
+                },
+            ),
+        ]);
+
+        let wire = PartitionStatisticsWire::try_new(&stats).unwrap();
+        let encoded = serde_json::to_vec(&wire).unwrap();
+        let decoded: PartitionStatisticsWire = serde_json::from_slice(&encoded).unwrap();
+        assert_eq!(decoded.try_into_stats().unwrap(), stats);
+    }
+}
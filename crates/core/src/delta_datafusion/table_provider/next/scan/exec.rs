@@ -26,6 +26,7 @@ use datafusion::physical_expr::{Distribution, EquivalenceProperties};
 use datafusion::physical_plan::execution_plan::{CardinalityEffect, PlanProperties};
 use datafusion::physical_plan::filter_pushdown::{FilterDescription, FilterPushdownPhase};
 use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
     DisplayAs, DisplayFormatType, ExecutionPlan, PhysicalExpr, Statistics,
 };
@@ -35,7 +36,7 @@ use datafusion_physical_expr_adapter::{
 use delta_kernel::schema::DataType as KernelDataType;
 use delta_kernel::table_features::TableFeature;
 use delta_kernel::{EvaluationHandler, ExpressionRef};
-use futures::stream::{Stream, StreamExt};
+use futures::stream::{Stream, StreamExt, TryStreamExt as _};
 
 use super::plan::KernelScanPlan;
 use crate::delta_datafusion::file_id::file_id_field;
@@ -128,6 +129,8 @@ pub struct DeltaScanExec {
     properties: Arc<PlanProperties>,
     /// Aggregated partition column statistics
     partition_stats: HashMap<String, ColumnStatistics>,
+    /// Transforms still to be recomputed, set when the exec was decoded from a serialized plan.
+    deferred_transforms: Option<Arc<super::DeferredTransforms>>,
 }
 
 impl DisplayAs for DeltaScanExec {
@@ -182,9 +185,69 @@ impl DeltaScanExec {
             input_file_id_column,
             file_id_column,
             properties,
+            deferred_transforms: None,
         }
     }
 
+    /// Recompute per-file transforms on first execution instead of using
+    /// [`transforms`](Self::transforms).
+    pub(crate) fn with_deferred_transforms(
+        mut self,
+        deferred_transforms: Arc<super::DeferredTransforms>,
+    ) -> Self {
+        self.deferred_transforms = Some(deferred_transforms);
+        self
+    }
+
+    pub(crate) fn scan_plan(&self) -> &Arc<KernelScanPlan> {
+        &self.scan_plan
+    }
+
+    pub(crate) fn transforms(&self) -> &HashMap<String, ExpressionRef> {
+        &self.transforms
+    }
+
+    pub(crate) fn deferred_transforms(&self) -> Option<&super::DeferredTransforms> {
+        self.deferred_transforms.as_deref()
+    }
+
+    pub(crate) fn selection_vectors(&self) -> &DashMap<String, Vec<bool>> {
+        &self.selection_vectors
+    }
+
+    pub(crate) fn public_file_ids(&self) -> &super::PublicFileIdMap {
+        &self.public_file_ids
+    }
+
+    pub(crate) fn partition_stats(&self) -> &HashMap<String, ColumnStatistics> {
+        &self.partition_stats
+    }
+
+    fn scan_stream(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+        transforms: Arc<HashMap<String, ExpressionRef>>,
+    ) -> Result<DeltaScanStream> {
+        Ok(DeltaScanStream {
+            scan_plan: Arc::clone(&self.scan_plan),
+            kernel_type: Arc::clone(self.scan_plan.scan.logical_schema()).into(),
+            input: self.input.execute(partition, context)?,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+            transforms,
+            selection_vectors: Arc::clone(&self.selection_vectors),
+            public_file_ids: Arc::clone(&self.public_file_ids),
+            input_file_id_column: self.input_file_id_column.clone(),
+            file_id_column: self.file_id_column.clone(),
+            row_index_field: self.scan_plan.contract.retained_row_index_field(),
+            row_index_by_file: HashMap::new(),
+            pending: VecDeque::new(),
+            schema_adapter: super::SchemaAdapter::new(Arc::clone(
+                &self.scan_plan.contract.result_schema,
+            )),
+        })
+    }
+
     /// Transform the statistics from the inner physical parquet read plan to the logical
     /// schema we expose via the table provider. We do not attempt to provide meaningful
     /// statistics for metadata columns as we do not expect these to be useful in planning.
@@ -308,7 +371,7 @@ impl ExecutionPlan for DeltaScanExec {
         if children.len() != 1 {
             return plan_err!("DeltaScan: wrong number of children {}", children.len());
         }
-        Ok(Arc::new(Self::new(
+        let mut exec = Self::new(
             self.scan_plan.clone(),
             children[0].clone(),
             self.transforms.clone(),
@@ -316,7 +379,9 @@ impl ExecutionPlan for DeltaScanExec {
             self.public_file_ids.clone(),
             self.partition_stats.clone(),
             self.metrics.clone(),
-        )))
+        );
+        exec.deferred_transforms = self.deferred_transforms.clone();
+        Ok(Arc::new(exec))
     }
 
     fn repartitioned(
@@ -356,23 +421,26 @@ impl ExecutionPlan for DeltaScanExec {
             }
         }
 
-        Ok(Box::pin(DeltaScanStream {
-            scan_plan: Arc::clone(&self.scan_plan),
-            kernel_type: Arc::clone(self.scan_plan.scan.logical_schema()).into(),
-            input: self.input.execute(partition, context)?,
-            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
-            transforms: Arc::clone(&self.transforms),
-            selection_vectors: Arc::clone(&self.selection_vectors),
-            public_file_ids: Arc::clone(&self.public_file_ids),
-            input_file_id_column: self.input_file_id_column.clone(),
-            file_id_column: self.file_id_column.clone(),
-            row_index_field: self.scan_plan.contract.retained_row_index_field(),
-            row_index_by_file: HashMap::new(),
-            pending: VecDeque::new(),
-            schema_adapter: super::SchemaAdapter::new(Arc::clone(
-                &self.scan_plan.contract.result_schema,
-            )),
-        }))
+        let Some(deferred_transforms) = self.deferred_transforms.clone() else {
+            return Ok(Box::pin(self.scan_stream(
+                partition,
+                context,
+                Arc::clone(&self.transforms),
+            )?));
+        };
+
+        let exec = self.clone();
+        let stream = futures::stream::once(async move {
+            let transforms = deferred_transforms
+                .resolve(&exec.scan_plan, Arc::clone(&context))
+                .await?;
+            exec.scan_stream(partition, context, transforms)
+        })
+        .try_flatten();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&self.scan_plan.contract.output_schema),
+            stream,
+        )))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -37,7 +37,7 @@ use datafusion::{
     config::TableParquetOptions,
     datasource::physical_plan::{ParquetSource, parquet::CachedParquetFileReaderFactory},
     error::DataFusionError,
-    execution::object_store::ObjectStoreUrl,
+    execution::{TaskContext, object_store::ObjectStoreUrl},
     physical_plan::{
         ExecutionPlan,
         empty::EmptyExec,
@@ -64,6 +64,7 @@ use object_store::{ObjectMeta, path::Path};
 use tracing::debug;
 use url::Url;
 
+pub(crate) use self::codec::DeltaScanExecWire;
 pub use self::exec::DeltaScanExec;
 use self::exec_meta::DeltaScanMetaExec;
 pub(crate) use self::plan::{KernelScanPlan, ProjectedScanContract, supports_filters_pushdown};
@@ -73,13 +74,14 @@ use crate::{
     DeltaTableError,
     delta_datafusion::{
         DeltaScanConfig,
-        engine::{AsObjectStoreUrl as _, to_datafusion_scalar},
+        engine::{AsObjectStoreUrl as _, DataFusionEngine, to_datafusion_scalar},
         file_id::wrap_file_id_value,
         table_provider::next::DeletionVectorSelection,
     },
     kernel::LogicalFileView,
 };
 
+mod codec;
 mod exec;
 mod exec_meta;
 mod plan;
@@ -318,9 +320,11 @@ async fn replay_files(
         files.extend(file);
     }
 
+    // Keep file paths for projected file ids, and for files with a kernel transform so that a
+    // serialized scan can recompute the transform on the executor (see `DeferredTransforms`).
     let mut public_file_ids = PublicFileIdMap::default();
-    if scan_plan.contract.retain_file_id {
-        for (file_index, file) in files.iter().enumerate() {
+    for (file_index, file) in files.iter().enumerate() {
+        if scan_plan.contract.retain_file_id || file.transform.is_some() {
             public_file_ids.insert(
                 compact_internal_file_id(file_index),
                 file.file_url.to_string(),
@@ -359,6 +363,71 @@ async fn replay_files(
     })
 }
 
+/// Per-file kernel transforms of a [`DeltaScanExec`] decoded from a serialized plan.
+///
+/// Kernel transform expressions cannot be serialized. Instead the encoded plan carries the
+/// file paths of every file that needs a transform and the transforms are recomputed by
+/// replaying the scan metadata for exactly those files on first execution. The result is
+/// shared by all partitions of the exec.
+#[derive(Debug)]
+pub(crate) struct DeferredTransforms {
+    /// Compact scan file ids keyed by file URL.
+    file_ids: HashMap<String, String>,
+    resolved: tokio::sync::OnceCell<Arc<HashMap<String, Arc<Expression>>>>,
+}
+
+impl DeferredTransforms {
+    pub(crate) fn new(file_ids: HashMap<String, String>) -> Self {
+        Self {
+            file_ids,
+            resolved: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    /// File URLs with a pending transform, keyed to their compact scan file id.
+    pub(crate) fn file_ids(&self) -> &HashMap<String, String> {
+        &self.file_ids
+    }
+
+    pub(crate) async fn resolve(
+        &self,
+        scan_plan: &KernelScanPlan,
+        context: Arc<TaskContext>,
+    ) -> Result<Arc<HashMap<String, Arc<Expression>>>> {
+        self.resolved
+            .get_or_try_init(|| async {
+                let engine: Arc<dyn Engine> = DataFusionEngine::new_from_context(context);
+                let selection: HashSet<String> = self.file_ids.keys().cloned().collect();
+                let mut stream = ScanFileStream::new(
+                    engine.clone(),
+                    &scan_plan.scan,
+                    scan_plan.config.clone(),
+                    Some(&selection),
+                    scan_plan.scan.scan_metadata(engine),
+                );
+                let mut transforms = HashMap::new();
+                while let Some(files) = stream.try_next().await? {
+                    for file in files {
+                        let file_id = self.file_ids.get(file.file_url.as_str());
+                        if let (Some(file_id), Some(transform)) = (file_id, file.transform) {
+                            transforms.insert(file_id.clone(), transform);
+                        }
+                    }
+                }
+                if transforms.len() != self.file_ids.len() {
+                    return Err(internal_datafusion_err!(
+                        "expected {} file transforms when replaying a decoded scan, found {}",
+                        self.file_ids.len(),
+                        transforms.len()
+                    ));
+                }
+                Ok(Arc::new(transforms))
+            })
+            .await
+            .cloned()
+    }
+}
+
 /// Normalize a DV keep mask for `deletion_vectors()`.
 ///
 /// Kernel returns a sparse mask (up to the highest deleted row index). For API output we need one
@@ -221,6 +221,10 @@ impl ProjectedScanContract {
 /// be pushed to kernel file skipping vs. Parquet readers.
 #[derive(Clone, Debug)]
 pub(crate) struct KernelScanPlan {
+    /// Snapshot the scan was planned against.
+    pub(crate) snapshot: Snapshot,
+    /// Scan configuration used to derive the read schemas.
+    pub(crate) config: DeltaScanConfig,
     /// Wrapped kernel scan to produce logical file stream
     pub(crate) scan: Arc<Scan>,
     /// Query scoped contract shared across planning and execution.
@@ -315,6 +319,8 @@ impl KernelScanPlan {
         let parquet_predicate_schema =
             build_parquet_predicate_schema(&parquet_read_schema, &contract.file_id_field);
         Ok(Self {
+            snapshot: snapshot.clone(),
+            config: config.clone(),
             scan,
             contract,
             parquet_read_schema,
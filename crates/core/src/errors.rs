@@ -230,6 +230,14 @@ pub enum DeltaTableError {
     #[error("No starting version or timestamp provided for CDC")]
     NoStartingVersionOrTimestamp,
 
+    #[error(
+        "Table schema changed in version {version} while streaming the change data feed, restart the stream from this version to read with the new schema"
+    )]
+    ChangeDataSchemaChanged { version: Version },
+
+    #[error("Change data file {path} of version {version} not found, it may have been vacuumed")]
+    ChangeDataFileNotFound { version: Version, path: String },
+
     /// Error returned when an operation is attempted on a column-mapped table that does not
     /// yet support column mapping.
     #[error("Column mapping is not supported for {mode} operation '{operation}' yet")]
@@ -0,0 +1,598 @@
+//! Translation of an Iceberg snapshot into Delta schema, partitioning and add actions
+
+use std::collections::HashMap;
+
+use apache_avro::types::Value;
+use chrono::DateTime;
+use delta_kernel::expressions::Scalar;
+use delta_kernel::schema::{
+    ColumnMetadataKey, DataType, MetadataValue, PrimitiveType as DeltaPrimitive, StructField,
+    StructType,
+};
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{ObjectStore, ObjectStoreExt as _};
+use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+use percent_encoding::percent_decode_str;
+use tracing::debug;
+
+use super::manifest::{
+    DataContent, ManifestContent, ManifestEntry, ManifestFile, ManifestStatus, read_manifest,
+    read_manifest_list, unwrap_union,
+};
+use super::schema::{delta_schema, leaf_fields, mapped_field, physical_name, physical_names};
+use super::spec::{PartitionSpec, TableMetadata, Transform};
+use super::stats::delta_stats;
+use super::{IcebergError, IcebergResult};
+use crate::ObjectStoreError;
+use crate::kernel::{Add, scalars::ScalarExt};
+use crate::logstore::LogStoreRef;
+
+/// Number of manifests read concurrently
+pub(super) const MANIFEST_CONCURRENCY: usize = 10;
+
+/// File holding the latest metadata version of Hadoop catalog tables
+pub(super) const VERSION_HINT_PATH: &str = "metadata/version-hint.text";
+
+/// The Delta view of the current snapshot of an Iceberg table
+#[derive(Debug)]
+pub(crate) struct IcebergSnapshot {
+    /// Id of the converted snapshot, `None` for tables without snapshots
+    pub snapshot_id: Option<i64>,
+    /// Schema with column mapping metadata, including columns derived by partition transforms
+    pub schema: StructType,
+    /// Logical names of the partition columns
+    pub partition_columns: Vec<String>,
+    /// Highest column mapping id in the schema
+    pub max_column_id: i64,
+    /// The live data files of the snapshot
+    pub files: Vec<Add>,
+}
+
+/// A Delta partition column computed from an Iceberg partition field
+#[derive(Debug)]
+struct PartitionColumn {
+    /// Name of the partition field in the partition records of data files
+    partition_field: String,
+    transform: Transform,
+    name: String,
+    physical_name: String,
+    data_type: DataType,
+}
+
+/// Read the current snapshot of the Iceberg table at the root of the log store.
+///
+/// `existing` is the schema of an earlier conversion of the same table, whose physical column
+/// names and ids are kept so the table can be converted again after new Iceberg commits.
+pub(crate) async fn read_iceberg_snapshot(
+    log_store: &LogStoreRef,
+    existing: Option<&StructType>,
+) -> IcebergResult<IcebergSnapshot> {
+    let store = log_store.object_store(None);
+    let root = percent_decode_str(log_store.root_url().as_str())
+        .decode_utf8_lossy()
+        .to_string();
+    let metadata = load_table_metadata(store.as_ref()).await?;
+    if metadata.format_version > 2 {
+        return Err(IcebergError::Unsupported(format!(
+            "format version {}",
+            metadata.format_version
+        )));
+    }
+    let roots = [root.as_str(), metadata.location.as_str()];
+
+    let names = existing.map(physical_names).unwrap_or_default();
+    let schema = delta_schema(&metadata.current_schema()?.fields, &names)?;
+    let spec = metadata.default_partition_spec()?;
+    let mut max_column_id =
+        (metadata.last_column_id as i64).max(names.keys().copied().max().unwrap_or(0));
+    let (schema, partition_columns) =
+        partition_columns(&spec, schema, existing, &mut max_column_id)?;
+
+    let Some(snapshot) = metadata.current_snapshot()? else {
+        return Ok(IcebergSnapshot {
+            snapshot_id: None,
+            schema,
+            partition_columns: partition_columns.into_iter().map(|c| c.name).collect(),
+            max_column_id,
+            files: vec![],
+        });
+    };
+    debug!(
+        "Converting Iceberg snapshot {} of {}",
+        snapshot.snapshot_id, metadata.location
+    );
+
+    let manifests = match (&snapshot.manifest_list, &snapshot.manifests) {
+        (Some(manifest_list), _) => {
+            read_manifest_list(&read_file(store.as_ref(), &roots, manifest_list).await?)?
+        }
+        (None, Some(manifests)) => manifests
+            .iter()
+            .map(|path| ManifestFile {
+                manifest_path: path.clone(),
+                partition_spec_id: spec.spec_id,
+                content: ManifestContent::Data,
+                ..Default::default()
+            })
+            .collect(),
+        (None, None) => vec![],
+    };
+
+    let entries: Vec<(ManifestFile, Vec<ManifestEntry>)> =
+        futures::stream::iter(manifests.into_iter().map(|manifest| {
+            let store = store.clone();
+            let roots = &roots;
+            async move {
+                let bytes = read_file(store.as_ref(), roots, &manifest.manifest_path).await?;
+                let entries = read_manifest(&bytes)?
+                    .into_iter()
+                    .filter(|e| e.status != ManifestStatus::Deleted)
+                    .collect::<Vec<_>>();
+                Ok::<_, IcebergError>((manifest, entries))
+            }
+        }))
+        .buffer_unordered(MANIFEST_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    let timestamps: HashMap<i64, i64> = metadata
+        .snapshots
+        .iter()
+        .map(|s| (s.snapshot_id, s.timestamp_ms))
+        .collect();
+    let leaves = leaf_fields(&schema);
+    let mut files = Vec::new();
+    for (manifest, entries) in entries {
+        if entries.is_empty() {
+            continue;
+        }
+        if manifest.content == ManifestContent::Deletes {
+            return Err(IcebergError::Unsupported(format!(
+                "snapshot {} has delete files, compact the table before converting it",
+                snapshot.snapshot_id
+            )));
+        }
+        let manifest_spec = match metadata.partition_spec(manifest.partition_spec_id) {
+            Ok(manifest_spec) => manifest_spec,
+            Err(_) if metadata.partition_specs.is_empty() => &spec,
+            Err(e) => return Err(e),
+        };
+        if !manifest_spec.is_compatible_with(&spec) {
+            return Err(IcebergError::Unsupported(format!(
+                "data files written with partition spec {} differ from the current partition spec {}",
+                manifest_spec.spec_id, spec.spec_id
+            )));
+        }
+
+        for entry in entries {
+            let data_file = entry.data_file;
+            if data_file.content != DataContent::Data {
+                return Err(IcebergError::InvalidMetadata(format!(
+                    "delete file {} listed in a data manifest",
+                    data_file.file_path
+                )));
+            }
+            if !data_file.file_format.eq_ignore_ascii_case("parquet") {
+                return Err(IcebergError::Unsupported(format!(
+                    "{} data file {}, only Parquet can be read by Delta",
+                    data_file.file_format, data_file.file_path
+                )));
+            }
+
+            let stats = delta_stats(&data_file, &leaves);
+            let mut partition: HashMap<String, Value> = data_file.partition.into_iter().collect();
+            let partition_values = partition_columns
+                .iter()
+                .map(|column| {
+                    let value = partition
+                        .remove(&column.partition_field)
+                        .map(|value| partition_value(value, column))
+                        .transpose()?
+                        .flatten();
+                    Ok((column.physical_name.clone(), value))
+                })
+                .collect::<IcebergResult<HashMap<_, _>>>()?;
+
+            files.push(Add {
+                path: relative_path(&roots, &data_file.file_path)
+                    .unwrap_or_else(|| data_file.file_path.clone()),
+                partition_values,
+                size: data_file.file_size_in_bytes,
+                modification_time: entry
+                    .snapshot_id
+                    .and_then(|id| timestamps.get(&id).copied())
+                    .unwrap_or(snapshot.timestamp_ms),
+                data_change: true,
+                stats: Some(stats.to_string()),
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(IcebergSnapshot {
+        snapshot_id: Some(snapshot.snapshot_id),
+        schema,
+        partition_columns: partition_columns.into_iter().map(|c| c.name).collect(),
+        max_column_id,
+        files,
+    })
+}
+
+/// Whether every top-level column of the `files` carries a Parquet field id.
+///
+/// Iceberg writers always write field ids, but files imported by migration procedures like
+/// `add_files` may lack them, and the `id` column mapping mode would read their columns as null.
+/// Only the footers are read. Files outside of the table location cannot be read and are
+/// assumed to lack field ids.
+pub(crate) async fn files_have_field_ids(
+    log_store: &LogStoreRef,
+    files: &[Add],
+) -> IcebergResult<bool> {
+    let store = log_store.object_store(None);
+    let with_ids: Vec<bool> = futures::stream::iter(files.iter().map(|file| {
+        let store = store.clone();
+        async move {
+            if strip_scheme(&file.path) != file.path {
+                return Ok(false);
+            }
+            let reader = ParquetObjectReader::new(store, Path::from(file.path.as_str()))
+                .with_file_size(file.size as u64);
+            let builder = ParquetRecordBatchStreamBuilder::new(reader).await?;
+            let with_ids = builder
+                .metadata()
+                .file_metadata()
+                .schema_descr()
+                .root_schema()
+                .get_fields()
+                .iter()
+                .all(|field| field.get_basic_info().has_id());
+            if !with_ids {
+                debug!("Data file {} has no Parquet field ids", file.path);
+            }
+            Ok::<_, IcebergError>(with_ids)
+        }
+    }))
+    .buffer_unordered(MANIFEST_CONCURRENCY)
+    .try_collect()
+    .await?;
+    Ok(with_ids.into_iter().all(|with_ids| with_ids))
+}
+
+/// Map the fields of the partition spec onto Delta partition columns.
+///
+/// Identity partitions use the source column. Other transforms add a column named after the
+/// partition field holding the transformed value, e.g. the date of a `day(ts)` partition.
+fn partition_columns(
+    spec: &PartitionSpec,
+    schema: StructType,
+    existing: Option<&StructType>,
+    max_column_id: &mut i64,
+) -> IcebergResult<(StructType, Vec<PartitionColumn>)> {
+    let mut fields: Vec<StructField> = schema.fields().cloned().collect();
+    let mut columns = Vec::new();
+    let ids: HashMap<i64, &StructField> = fields
+        .iter()
+        .filter_map(|f| {
+            match f
+                .metadata()
+                .get(ColumnMetadataKey::ColumnMappingId.as_ref())
+            {
+                Some(MetadataValue::Number(id)) => Some((*id, f)),
+                _ => None,
+            }
+        })
+        .collect();
+
+    let mut derived = Vec::new();
+    for partition_field in spec
+        .fields
+        .iter()
+        .filter(|f| f.transform != Transform::Void)
+    {
+        let Some(source) = ids.get(&(partition_field.source_id as i64)) else {
+            return Err(IcebergError::Unsupported(format!(
+                "partition field {} is derived from a nested column, Delta can only partition by top-level columns",
+                partition_field.name
+            )));
+        };
+        if partition_field.transform == Transform::Identity {
+            columns.push(PartitionColumn {
+                partition_field: partition_field.name.clone(),
+                transform: Transform::Identity,
+                name: source.name().clone(),
+                physical_name: physical_name(source).to_string(),
+                data_type: source.data_type().clone(),
+            });
+            continue;
+        }
+
+        let data_type = match partition_field.transform {
+            Transform::Year | Transform::Bucket(_) => DataType::INTEGER,
+            Transform::Month | Transform::Hour => DataType::STRING,
+            Transform::Day => DataType::DATE,
+            _ => source.data_type().clone(),
+        };
+        if ids.values().any(|f| f.name() == &partition_field.name) {
+            return Err(IcebergError::Unsupported(format!(
+                "partition field {} has the name of a column",
+                partition_field.name
+            )));
+        }
+        let field = match existing.and_then(|s| s.field(&partition_field.name)) {
+            Some(field) => field.clone(),
+            None => {
+                *max_column_id += 1;
+                mapped_field(
+                    partition_field.name.clone(),
+                    data_type.clone(),
+                    true,
+                    *max_column_id,
+                    partition_field.name.clone(),
+                )
+            }
+        };
+        columns.push(PartitionColumn {
+            partition_field: partition_field.name.clone(),
+            transform: partition_field.transform,
+            name: field.name().clone(),
+            physical_name: physical_name(&field).to_string(),
+            data_type,
+        });
+        derived.push(field);
+    }
+    fields.extend(derived);
+
+    Ok((StructType::try_new(fields)?, columns))
+}
+
+/// The serialized Delta partition value of an Iceberg partition value
+fn partition_value(value: Value, column: &PartitionColumn) -> IcebergResult<Option<String>> {
+    let scalar = match (column.transform, unwrap_union(value)) {
+        (_, Value::Null) => return Ok(None),
+        (Transform::Year, Value::Int(years)) => Scalar::Integer(1970 + years),
+        (Transform::Month, Value::Int(months)) => Scalar::String(format!(
+            "{:04}-{:02}",
+            1970 + months.div_euclid(12),
+            months.rem_euclid(12) + 1
+        )),
+        (Transform::Day, Value::Date(days) | Value::Int(days)) => Scalar::Date(days),
+        (Transform::Hour, Value::Int(hours)) => {
+            let hour = DateTime::from_timestamp(hours as i64 * 3600, 0).ok_or_else(|| {
+                IcebergError::InvalidMetadata(format!("invalid hour partition value {hours}"))
+            })?;
+            Scalar::String(hour.format("%Y-%m-%d-%H").to_string())
+        }
+        (Transform::Bucket(_), Value::Int(bucket)) => Scalar::Integer(bucket),
+        (_, value) => source_scalar(value, &column.data_type)?,
+    };
+    Ok(Some(scalar.serialize()))
+}
+
+fn source_scalar(value: Value, data_type: &DataType) -> IcebergResult<Scalar> {
+    let DataType::Primitive(primitive) = data_type else {
+        return Err(IcebergError::Unsupported(format!(
+            "partition column of type {data_type:?}"
+        )));
+    };
+    Ok(match (primitive, value) {
+        (DeltaPrimitive::Boolean, Value::Boolean(v)) => Scalar::Boolean(v),
+        (DeltaPrimitive::Integer, Value::Int(v)) => Scalar::Integer(v),
+        (DeltaPrimitive::Long, Value::Long(v)) => Scalar::Long(v),
+        (DeltaPrimitive::Long, Value::Int(v)) => Scalar::Long(v as i64),
+        (DeltaPrimitive::Float, Value::Float(v)) => Scalar::Float(v),
+        (DeltaPrimitive::Double, Value::Double(v)) => Scalar::Double(v),
+        (DeltaPrimitive::Double, Value::Float(v)) => Scalar::Double(v as f64),
+        (DeltaPrimitive::Date, Value::Date(v) | Value::Int(v)) => Scalar::Date(v),
+        (DeltaPrimitive::Timestamp, Value::TimestampMicros(v) | Value::Long(v)) => {
+            Scalar::Timestamp(v)
+        }
+        (
+            DeltaPrimitive::TimestampNtz,
+            Value::LocalTimestampMicros(v) | Value::TimestampMicros(v) | Value::Long(v),
+        ) => Scalar::TimestampNtz(v),
+        (DeltaPrimitive::String, Value::String(v)) => Scalar::String(v),
+        (DeltaPrimitive::Binary, Value::Bytes(v) | Value::Fixed(_, v)) => Scalar::Binary(v),
+        (_, value) => {
+            return Err(IcebergError::Unsupported(format!(
+                "partition value {value:?} of type {primitive}"
+            )));
+        }
+    })
+}
+
+/// Read the metadata file of the latest table version, failing if there is none.
+async fn load_table_metadata(store: &dyn ObjectStore) -> IcebergResult<TableMetadata> {
+    latest_table_metadata(store)
+        .await?
+        .map(|(_, metadata)| metadata)
+        .ok_or_else(|| IcebergError::MetadataNotFound("metadata/".to_string()))
+}
+
+/// Find the metadata file of the latest table version and its version number.
+///
+/// Hadoop catalog tables point to it with `metadata/version-hint.text`, other catalogs name metadata
+/// files `<version>-<uuid>.metadata.json`.
+pub(super) async fn latest_table_metadata(
+    store: &dyn ObjectStore,
+) -> IcebergResult<Option<(u64, TableMetadata)>> {
+    let (version, location) = match store.get(&Path::from(VERSION_HINT_PATH)).await {
+        Ok(hint) => {
+            let hint = String::from_utf8_lossy(&hint.bytes().await?)
+                .trim()
+                .to_string();
+            let mut version = hint.parse().map_err(|_| {
+                IcebergError::InvalidMetadata(format!("invalid version hint {hint}"))
+            })?;
+            // the hint is written after the metadata and may lag behind it, like the Hadoop
+            // catalog newer versions are probed
+            loop {
+                match store.head(&metadata_path(version + 1)).await {
+                    Ok(_) => version += 1,
+                    Err(ObjectStoreError::NotFound { .. }) => break,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            (version, metadata_path(version))
+        }
+        Err(ObjectStoreError::NotFound { .. }) => {
+            let latest = store
+                .list(Some(&Path::from("metadata")))
+                .try_filter_map(|meta| async move {
+                    Ok(meta
+                        .location
+                        .filename()
+                        .and_then(metadata_version)
+                        .map(|version| (version, meta.location.clone())))
+                })
+                .try_fold(None::<(u64, Path)>, |latest, candidate| async move {
+                    Ok(match latest {
+                        Some(latest) if latest.0 >= candidate.0 => Some(latest),
+                        _ => Some(candidate),
+                    })
+                })
+                .await?;
+            match latest {
+                Some(latest) => latest,
+                None => return Ok(None),
+            }
+        }
+        Err(e) => return Err(e.into()),
+    };
+    debug!("Reading Iceberg metadata {location}");
+    let bytes = store.get(&location).await?.bytes().await?;
+    Ok(Some((version, serde_json::from_slice(&bytes)?)))
+}
+
+/// Path of the metadata file of `version` in the layout of the Hadoop catalog
+pub(super) fn metadata_path(version: u64) -> Path {
+    Path::from(format!("metadata/v{version}.metadata.json"))
+}
+
+/// Version of `v3.metadata.json` or `00003-<uuid>.metadata.json`
+fn metadata_version(name: &str) -> Option<u64> {
+    let stem = name.strip_suffix(".metadata.json")?;
+    let stem = stem.strip_prefix('v').unwrap_or(stem);
+    stem.split('-').next()?.parse().ok()
+}
+
+pub(super) async fn read_file(
+    store: &dyn ObjectStore,
+    roots: &[&str],
+    path: &str,
+) -> IcebergResult<bytes::Bytes> {
+    let relative = relative_path(roots, path).ok_or_else(|| {
+        IcebergError::Unsupported(format!("{path} is outside of the table location"))
+    })?;
+    Ok(store.get(&Path::from(relative)).await?.bytes().await?)
+}
+
+/// The path of a file relative to the first of `roots` containing it.
+///
+/// Schemes are ignored, as the same location may be written as `s3a://` by one engine and as
+/// `s3://` by another, or as `file:/` and `file:///`.
+fn relative_path(roots: &[&str], path: &str) -> Option<String> {
+    let path = strip_scheme(path);
+    roots.iter().find_map(|root| {
+        path.strip_prefix(strip_scheme(root).trim_end_matches('/'))?
+            .strip_prefix('/')
+            .map(str::to_string)
+    })
+}
+
+fn strip_scheme(uri: &str) -> &str {
+    let rest = match uri.split_once(':') {
+        Some((scheme, rest))
+            if !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) =>
+        {
+            rest
+        }
+        _ => uri,
+    };
+    rest.trim_start_matches('/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path() {
+        let roots = ["file:///tmp/table/", "file:/tmp/table"];
+        assert_eq!(
+            relative_path(&roots, "file:/tmp/table/data/a.parquet"),
+            Some("data/a.parquet".to_string())
+        );
+        assert_eq!(
+            relative_path(
+                &["s3://bucket/warehouse/t"],
+                "s3a://bucket/warehouse/t/data/b.parquet"
+            ),
+            Some("data/b.parquet".to_string())
+        );
+        assert_eq!(
+            relative_path(&roots, "file:/tmp/table2/data/a.parquet"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_metadata_version() {
+        assert_eq!(metadata_version("v12.metadata.json"), Some(12));
+        assert_eq!(
+            metadata_version("00003-6f3f2fd8-3b8f-4bde-8c3d-1f1a5c3f0e1c.metadata.json"),
+            Some(3)
+        );
+        assert_eq!(metadata_version("snap-1.avro"), None);
+    }
+
+    #[test]
+    fn test_partition_values_of_transforms() {
+        let column = |transform, data_type| PartitionColumn {
+            partition_field: "p".to_string(),
+            transform,
+            name: "p".to_string(),
+            physical_name: "p".to_string(),
+            data_type,
+        };
+        let value = |transform, data_type, value| {
+            partition_value(value, &column(transform, data_type)).unwrap()
+        };
+
+        assert_eq!(
+            value(Transform::Year, DataType::INTEGER, Value::Int(51)),
+            Some("2021".to_string())
+        );
+        assert_eq!(
+            value(Transform::Month, DataType::STRING, Value::Int(613)),
+            Some("2021-02".to_string())
+        );
+        assert_eq!(
+            value(Transform::Day, DataType::DATE, Value::Date(18628)),
+            Some("2021-01-01".to_string())
+        );
+        assert_eq!(
+            value(Transform::Hour, DataType::STRING, Value::Int(447082)),
+            Some("2021-01-01-10".to_string())
+        );
+        assert_eq!(
+            value(
+                Transform::Identity,
+                DataType::STRING,
+                Value::Union(1, Box::new(Value::String("a".to_string())))
+            ),
+            Some("a".to_string())
+        );
+        assert_eq!(
+            value(
+                Transform::Identity,
+                DataType::STRING,
+                Value::Union(0, Box::new(Value::Null))
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,1067 @@
+//! Generation of Iceberg metadata for Delta tables
+//!
+//! Tables with [icebergCompatV2] that list `iceberg` in `delta.universalFormat.enabledFormats`
+//! get Iceberg metadata next to the Delta log after every commit, in the layout of the Hadoop
+//! catalog: `metadata/v<N>.metadata.json` and `metadata/version-hint.text`. Delta version `v`
+//! becomes the Iceberg snapshot with id `v + 1`, which records `v` in its summary so the metadata
+//! can be continued incrementally from the actions of the next commit.
+//!
+//! [icebergCompatV2]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#iceberg-compatibility-v2
+
+use std::collections::{HashMap, HashSet};
+
+use apache_avro::types::Value;
+use chrono::Utc;
+use delta_kernel::expressions::Scalar;
+use delta_kernel::schema::{
+    ColumnMetadataKey, DataType, MetadataValue, PrimitiveType as DeltaPrimitive, StructType,
+};
+use delta_kernel::table_features::TableFeature;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{ObjectStore, ObjectStoreExt as _, PutMode, PutOptions};
+use percent_encoding::percent_decode_str;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use super::convert::{
+    MANIFEST_CONCURRENCY, VERSION_HINT_PATH, latest_table_metadata, metadata_path, read_file,
+};
+use super::manifest::{
+    DataFile, ManifestContent, ManifestEntry, ManifestFile, ManifestStatus, read_manifest,
+    read_manifest_list, write_manifest, write_manifest_list,
+};
+use super::schema::{iceberg_primitive, iceberg_schema, leaf_fields, name_mapping, physical_name};
+use super::spec::{
+    MetadataLogEntry, PartitionField, PartitionSpec, PrimitiveType, Schema, Snapshot,
+    SnapshotLogEntry, SnapshotReference, SortOrder, TableMetadata, Transform,
+};
+use super::stats::iceberg_data_file;
+use super::{IcebergError, IcebergResult};
+use crate::ObjectStoreError;
+use crate::kernel::{Action, EagerSnapshot};
+use crate::logstore::{LogStoreRef, ObjectStoreRef};
+use crate::table::config::TableProperty;
+
+/// Snapshot summary key holding the Delta version the snapshot was generated from
+const DELTA_VERSION_KEY: &str = "delta-version";
+
+/// Table property mapping physical column names to field ids
+const NAME_MAPPING_KEY: &str = "schema.name-mapping.default";
+
+/// Partition field ids start after the ids of the fields of data files
+const PARTITION_FIELD_ID_START: i32 = 1000;
+
+/// Table property limiting the number of previous metadata files tracked in the metadata log
+const PREVIOUS_VERSIONS_MAX_KEY: &str = "write.metadata.previous-versions-max";
+const PREVIOUS_VERSIONS_MAX_DEFAULT: usize = 100;
+
+/// Table property holding the age after which snapshots are expired
+const MAX_SNAPSHOT_AGE_MS_KEY: &str = "history.expire.max-snapshot-age-ms";
+const MAX_SNAPSHOT_AGE_MS_DEFAULT: i64 = 5 * 24 * 60 * 60 * 1000;
+
+/// Table property holding the number of most recent snapshots that are never expired
+const MIN_SNAPSHOTS_TO_KEEP_KEY: &str = "history.expire.min-snapshots-to-keep";
+const MIN_SNAPSHOTS_TO_KEEP_DEFAULT: usize = 1;
+
+/// Number of attempts to write the metadata when other writers write the same metadata version
+const METADATA_COMMIT_ATTEMPTS: usize = 5;
+
+/// Whether the table asks for Iceberg metadata after every commit
+pub(crate) fn is_enabled(snapshot: &EagerSnapshot) -> bool {
+    let configuration = snapshot.metadata().configuration();
+    configuration
+        .get(TableProperty::UniversalFormatEnabledFormats.as_ref())
+        .is_some_and(|formats| {
+            formats
+                .split(',')
+                .any(|format| format.trim().eq_ignore_ascii_case("iceberg"))
+        })
+}
+
+/// Check that Iceberg metadata can be generated for the table, which must enable icebergCompatV2
+pub(crate) fn check_iceberg_compat(snapshot: &EagerSnapshot) -> IcebergResult<()> {
+    let enabled = snapshot
+        .metadata()
+        .configuration()
+        .get(TableProperty::EnableIcebergCompatV2.as_ref())
+        .is_some_and(|value| value.eq_ignore_ascii_case("true"));
+    let supported = snapshot
+        .protocol()
+        .writer_features()
+        .is_some_and(|features| features.contains(&TableFeature::IcebergCompatV2));
+    if !enabled || !supported {
+        return Err(IcebergError::Unsupported(format!(
+            "Iceberg metadata requires the icebergCompatV2 table feature and {}=true",
+            TableProperty::EnableIcebergCompatV2.as_ref()
+        )));
+    }
+    Ok(())
+}
+
+/// Write the Iceberg metadata of the version of `snapshot`.
+///
+/// `actions` are the actions committed in that version. If the latest Iceberg metadata was
+/// generated from the previous version, the new snapshot keeps its manifests and only adds and
+/// rewrites the ones touched by the actions. Otherwise the manifests are written for all files of
+/// the snapshot. Nothing is written if the metadata is already up to date.
+///
+/// Concurrent writers race for the next metadata version. The losers continue from the metadata
+/// of the winner, which may have been generated from an older Delta version.
+pub(crate) async fn write_iceberg_metadata(
+    log_store: &LogStoreRef,
+    snapshot: &EagerSnapshot,
+    actions: Option<&[Action]>,
+) -> IcebergResult<()> {
+    check_iceberg_compat(snapshot)?;
+    let mut attempt = 1;
+    loop {
+        match try_write_iceberg_metadata(log_store, snapshot, actions).await {
+            Err(IcebergError::ObjectStore(ObjectStoreError::AlreadyExists { path, .. }))
+                if attempt < METADATA_COMMIT_ATTEMPTS =>
+            {
+                debug!("Iceberg metadata {path} was written concurrently, retrying");
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn try_write_iceberg_metadata(
+    log_store: &LogStoreRef,
+    snapshot: &EagerSnapshot,
+    actions: Option<&[Action]>,
+) -> IcebergResult<()> {
+    let store = log_store.object_store(None);
+    let root = percent_decode_str(log_store.root_url().as_str())
+        .decode_utf8_lossy()
+        .trim_end_matches('/')
+        .to_string();
+    let roots = [root.as_str()];
+    let version = snapshot.version();
+    let delta_metadata = snapshot.metadata();
+
+    // metadata written by Iceberg itself, e.g. of a converted table, is replaced but not continued
+    let previous = latest_table_metadata(store.as_ref()).await?;
+    let generated = previous.as_ref().and_then(|(_, metadata)| {
+        let delta_version = generated_version(metadata)?;
+        (metadata.table_uuid.as_deref() == Some(delta_metadata.id())).then_some(delta_version)
+    });
+    if generated.is_some_and(|generated| generated >= version) {
+        debug!("Iceberg metadata of version {version} is up to date");
+        return Ok(());
+    }
+    let metadata_version = previous.as_ref().map_or(0, |(v, _)| *v) + 1;
+    let previous = previous
+        .filter(|_| generated.is_some())
+        .map(|(_, metadata)| metadata);
+    let continued = previous.is_some();
+
+    let delta_schema = snapshot.schema();
+    let max_column_id = match delta_metadata
+        .configuration()
+        .get("delta.columnMapping.maxColumnId")
+    {
+        Some(id) => id.parse().map_err(|_| {
+            IcebergError::InvalidMetadata(format!("invalid maximum column id {id}"))
+        })?,
+        None => leaf_fields(&delta_schema)
+            .keys()
+            .copied()
+            .max()
+            .unwrap_or(0),
+    };
+    let (fields, last_column_id) = iceberg_schema(&delta_schema, max_column_id)?;
+    let schemas = previous
+        .as_ref()
+        .map(|m| m.schemas.clone())
+        .unwrap_or_default();
+    let schema = match schemas.iter().find(|s| s.fields == fields) {
+        Some(schema) => schema.clone(),
+        None => Schema {
+            schema_id: schemas.iter().map(|s| s.schema_id + 1).max().unwrap_or(0),
+            fields,
+        },
+    };
+
+    let layout = Layout::try_new(
+        &root,
+        &delta_schema,
+        delta_metadata.partition_columns(),
+        previous
+            .as_ref()
+            .map(|m| m.partition_specs.as_slice())
+            .unwrap_or_default(),
+    )?;
+    let sequence_number = previous.as_ref().map_or(0, |m| m.last_sequence_number) + 1;
+    let snapshot_id = version as i64 + 1;
+    let context = ManifestContext {
+        store: &store,
+        root: &root,
+        schema: &schema,
+        layout: &layout,
+        snapshot_id,
+        sequence_number,
+    };
+
+    let parent = previous
+        .as_ref()
+        .map(|m| m.current_snapshot())
+        .transpose()?
+        .flatten();
+    let parent_id = parent.map(|p| p.snapshot_id);
+    let incremental = match (parent, actions) {
+        (Some(parent), Some(actions))
+            if generated == version.checked_sub(1)
+                && previous.as_ref().and_then(|m| m.default_spec_id)
+                    == Some(layout.spec.spec_id) =>
+        {
+            parent.manifest_list.clone().map(|list| (list, actions))
+        }
+        _ => None,
+    };
+    let (manifests, operation) = match incremental {
+        Some((manifest_list, actions)) => {
+            let manifests =
+                read_manifest_list(&read_file(store.as_ref(), &roots, &manifest_list).await?)?;
+            context.apply_actions(manifests, actions).await?
+        }
+        None => {
+            let files = snapshot
+                .file_views(log_store.as_ref(), None)
+                .map(|file| {
+                    let file = file?;
+                    layout.data_file(
+                        &file.path(),
+                        file.size(),
+                        &file.partition_values_map(),
+                        file.stats().as_deref(),
+                    )
+                })
+                .try_collect::<Vec<_>>()
+                .await?;
+            let entries = files
+                .into_iter()
+                .map(|data_file| ManifestEntry {
+                    status: ManifestStatus::Added,
+                    snapshot_id: Some(snapshot_id),
+                    sequence_number: None,
+                    file_sequence_number: None,
+                    data_file,
+                })
+                .collect::<Vec<_>>();
+            let manifests = if entries.is_empty() {
+                vec![]
+            } else {
+                vec![context.write(entries).await?]
+            };
+            let operation = match parent_id {
+                Some(_) => "overwrite",
+                None => "append",
+            };
+            (manifests, operation)
+        }
+    };
+
+    // manifests carried over from the parent snapshot are referenced by the previous metadata
+    let new_manifests: Vec<Path> = manifests
+        .iter()
+        .filter(|m| m.added_snapshot_id == snapshot_id)
+        .filter_map(|m| table_path(&root, &m.manifest_path))
+        .collect();
+    let manifest_list_path = format!("metadata/snap-{snapshot_id}-{}.avro", Uuid::new_v4());
+    let manifest_list = write_manifest_list(snapshot_id, parent_id, sequence_number, manifests)?;
+    store
+        .put(
+            &Path::from(manifest_list_path.as_str()),
+            manifest_list.into(),
+        )
+        .await?;
+
+    let now = Utc::now().timestamp_millis();
+    let timestamp_ms = snapshot.version_timestamp(version).unwrap_or(now);
+    let mut metadata = previous.unwrap_or_else(|| TableMetadata {
+        format_version: 2,
+        table_uuid: Some(delta_metadata.id().to_string()),
+        location: root.clone(),
+        last_sequence_number: 0,
+        last_updated_ms: now,
+        last_column_id: 0,
+        schemas: vec![],
+        current_schema_id: None,
+        schema: None,
+        partition_specs: vec![],
+        default_spec_id: None,
+        partition_spec: None,
+        properties: HashMap::new(),
+        last_partition_id: None,
+        sort_orders: vec![SortOrder::unsorted()],
+        default_sort_order_id: Some(0),
+        current_snapshot_id: None,
+        snapshots: vec![],
+        refs: HashMap::new(),
+        snapshot_log: vec![],
+        metadata_log: vec![],
+    });
+    if continued {
+        metadata.metadata_log.push(MetadataLogEntry {
+            metadata_file: format!("{root}/{}", metadata_path(metadata_version - 1)),
+            timestamp_ms: metadata.last_updated_ms,
+        });
+    }
+    let previous_versions_max = table_property(
+        snapshot,
+        &metadata,
+        PREVIOUS_VERSIONS_MAX_KEY,
+        PREVIOUS_VERSIONS_MAX_DEFAULT,
+    )?;
+    let expired_logs = metadata
+        .metadata_log
+        .len()
+        .saturating_sub(previous_versions_max);
+    metadata.metadata_log.drain(..expired_logs);
+    metadata.last_sequence_number = sequence_number;
+    metadata.last_updated_ms = now;
+    metadata.last_column_id = metadata.last_column_id.max(last_column_id);
+    if !metadata.schemas.contains(&schema) {
+        metadata.schemas.push(schema.clone());
+    }
+    metadata.current_schema_id = Some(schema.schema_id);
+    if !metadata.partition_specs.contains(&layout.spec) {
+        metadata.partition_specs.push(layout.spec.clone());
+    }
+    metadata.default_spec_id = Some(layout.spec.spec_id);
+    metadata.last_partition_id = Some(
+        metadata
+            .partition_specs
+            .iter()
+            .flat_map(|spec| spec.fields.iter().filter_map(|f| f.field_id))
+            .max()
+            .unwrap_or(PARTITION_FIELD_ID_START - 1),
+    );
+    metadata.properties.insert(
+        NAME_MAPPING_KEY.to_string(),
+        name_mapping(&schema.fields, &delta_schema).to_string(),
+    );
+    metadata.snapshots.push(Snapshot {
+        snapshot_id,
+        parent_snapshot_id: parent_id,
+        sequence_number,
+        timestamp_ms,
+        manifest_list: Some(format!("{root}/{manifest_list_path}")),
+        manifests: None,
+        summary: HashMap::from([
+            ("operation".to_string(), operation.to_string()),
+            (DELTA_VERSION_KEY.to_string(), version.to_string()),
+        ]),
+        schema_id: Some(schema.schema_id),
+    });
+    metadata.current_snapshot_id = Some(snapshot_id);
+    metadata.refs.insert(
+        "main".to_string(),
+        SnapshotReference {
+            snapshot_id,
+            reference_type: "branch".to_string(),
+        },
+    );
+    metadata.snapshot_log.push(SnapshotLogEntry {
+        snapshot_id,
+        timestamp_ms,
+    });
+    let expired_manifest_lists = expire_snapshots(snapshot, &mut metadata, now)?;
+
+    // concurrent writers of the same metadata version lose and retry from the metadata of the
+    // winner, the files written for the lost attempt are never referenced
+    let location = metadata_path(metadata_version);
+    let result = store
+        .put_opts(
+            &location,
+            serde_json::to_vec(&metadata)?.into(),
+            PutOptions {
+                mode: PutMode::Create,
+                ..Default::default()
+            },
+        )
+        .await;
+    if let Err(ObjectStoreError::AlreadyExists { .. }) = &result {
+        let mut unreferenced = new_manifests;
+        unreferenced.push(Path::from(manifest_list_path.as_str()));
+        delete_files(store.as_ref(), &unreferenced).await;
+    }
+    result?;
+    write_version_hint(store.as_ref(), metadata_version).await?;
+    debug!("Wrote Iceberg metadata {location} for version {version}");
+
+    let expired: Vec<Path> = expired_manifest_lists
+        .iter()
+        .filter_map(|list| table_path(&root, list))
+        .collect();
+    delete_files(store.as_ref(), &expired).await;
+    Ok(())
+}
+
+/// The path in the object store of a file of the table at `root`
+fn table_path(root: &str, location: &str) -> Option<Path> {
+    location
+        .strip_prefix(root)?
+        .strip_prefix('/')
+        .map(Path::from)
+}
+
+/// Delete metadata files that are no longer referenced. Failures only leave the files behind.
+async fn delete_files(store: &dyn ObjectStore, paths: &[Path]) {
+    for path in paths {
+        match store.delete(path).await {
+            Ok(()) | Err(ObjectStoreError::NotFound { .. }) => {}
+            Err(err) => warn!("Failed to delete unreferenced Iceberg metadata {path}: {err}"),
+        }
+    }
+}
+
+/// Point the version hint to `metadata_version`, unless a concurrent writer already pointed it to
+/// a newer version.
+///
+/// Another writer may still overwrite the hint with an older version in between, in which case
+/// readers find the newer metadata by probing past the hint.
+async fn write_version_hint(store: &dyn ObjectStore, metadata_version: u64) -> IcebergResult<()> {
+    let hint = Path::from(VERSION_HINT_PATH);
+    let current = match store.get(&hint).await {
+        Ok(current) => String::from_utf8_lossy(&current.bytes().await?)
+            .trim()
+            .parse::<u64>()
+            .ok(),
+        Err(ObjectStoreError::NotFound { .. }) => None,
+        Err(e) => return Err(e.into()),
+    };
+    if current.is_some_and(|current| current >= metadata_version) {
+        debug!("Version hint already points to a newer version than {metadata_version}");
+        return Ok(());
+    }
+    store
+        .put(&hint, metadata_version.to_string().into_bytes().into())
+        .await?;
+    Ok(())
+}
+
+/// Expire the snapshots older than `history.expire.max-snapshot-age-ms`, but keep the
+/// `history.expire.min-snapshots-to-keep` most recent ones.
+///
+/// Returns the manifest lists of the expired snapshots, which are deleted once the metadata is
+/// written. Their manifests are kept, as they may be shared with the retained snapshots.
+fn expire_snapshots(
+    snapshot: &EagerSnapshot,
+    metadata: &mut TableMetadata,
+    now: i64,
+) -> IcebergResult<Vec<String>> {
+    let max_age_ms = table_property(
+        snapshot,
+        metadata,
+        MAX_SNAPSHOT_AGE_MS_KEY,
+        MAX_SNAPSHOT_AGE_MS_DEFAULT,
+    )?;
+    let min_to_keep = table_property(
+        snapshot,
+        metadata,
+        MIN_SNAPSHOTS_TO_KEEP_KEY,
+        MIN_SNAPSHOTS_TO_KEEP_DEFAULT,
+    )?
+    .max(1);
+    let keep_from = metadata.snapshots.len().saturating_sub(min_to_keep);
+    let cutoff_ms = now.saturating_sub(max_age_ms);
+    let mut index = 0;
+    let (retained, expired): (Vec<_>, Vec<_>) = std::mem::take(&mut metadata.snapshots)
+        .into_iter()
+        .partition(|s| {
+            index += 1;
+            index > keep_from || s.timestamp_ms >= cutoff_ms
+        });
+    metadata.snapshots = retained;
+    let retained: HashSet<i64> = metadata.snapshots.iter().map(|s| s.snapshot_id).collect();
+    metadata
+        .snapshot_log
+        .retain(|entry| retained.contains(&entry.snapshot_id));
+    Ok(expired
+        .into_iter()
+        .filter_map(|s| s.manifest_list)
+        .collect())
+}
+
+/// A retention property of the Delta table configuration or of the Iceberg metadata
+fn table_property<T: std::str::FromStr>(
+    snapshot: &EagerSnapshot,
+    metadata: &TableMetadata,
+    key: &str,
+    default: T,
+) -> IcebergResult<T> {
+    match snapshot
+        .metadata()
+        .configuration()
+        .get(key)
+        .or_else(|| metadata.properties.get(key))
+    {
+        Some(value) => value
+            .parse()
+            .map_err(|_| IcebergError::InvalidMetadata(format!("invalid value {value} of {key}"))),
+        None => Ok(default),
+    }
+}
+
+/// The Delta version the current snapshot of generated metadata was generated from
+fn generated_version(metadata: &TableMetadata) -> Option<u64> {
+    metadata
+        .current_snapshot()
+        .ok()??
+        .summary
+        .get(DELTA_VERSION_KEY)?
+        .parse()
+        .ok()
+}
+
+/// How the files of the Delta table are described in manifests
+struct Layout {
+    root: String,
+    spec: PartitionSpec,
+    /// Physical name, logical name and type of the source column of every partition field
+    partition_columns: Vec<(String, String, DeltaPrimitive)>,
+    partition_types: Vec<PrimitiveType>,
+    leaves: HashMap<i64, (Vec<String>, DeltaPrimitive)>,
+}
+
+impl Layout {
+    /// Identity partition fields for the partition columns, keeping the spec id of an existing
+    /// spec with the same fields
+    fn try_new(
+        root: &str,
+        schema: &StructType,
+        partition_columns: &[String],
+        specs: &[PartitionSpec],
+    ) -> IcebergResult<Self> {
+        let mut fields = Vec::new();
+        let mut columns = Vec::new();
+        let mut partition_types = Vec::new();
+        for (i, name) in partition_columns.iter().enumerate() {
+            let field = schema.field(name).ok_or_else(|| {
+                IcebergError::InvalidMetadata(format!("partition column {name} not found"))
+            })?;
+            let Some(MetadataValue::Number(id)) = field
+                .metadata()
+                .get(ColumnMetadataKey::ColumnMappingId.as_ref())
+            else {
+                return Err(IcebergError::Unsupported(format!(
+                    "partition column {name} has no column mapping id"
+                )));
+            };
+            let DataType::Primitive(primitive) = field.data_type() else {
+                return Err(IcebergError::Unsupported(format!(
+                    "partition column {name} of type {:?}",
+                    field.data_type()
+                )));
+            };
+            fields.push(PartitionField {
+                source_id: *id as i32,
+                field_id: Some(PARTITION_FIELD_ID_START + i as i32),
+                name: avro_name(name),
+                transform: Transform::Identity,
+            });
+            columns.push((
+                physical_name(field).to_string(),
+                name.clone(),
+                primitive.clone(),
+            ));
+            partition_types.push(iceberg_primitive(primitive)?);
+        }
+        let spec = match specs.iter().find(|s| s.fields == fields) {
+            Some(spec) => spec.clone(),
+            None => PartitionSpec {
+                spec_id: specs.iter().map(|s| s.spec_id + 1).max().unwrap_or(0),
+                fields,
+            },
+        };
+
+        Ok(Self {
+            root: root.to_string(),
+            spec,
+            partition_columns: columns,
+            partition_types,
+            leaves: leaf_fields(schema),
+        })
+    }
+
+    /// The Iceberg location of a Delta file path, which is relative unless it is a URI
+    fn location(&self, path: &str) -> String {
+        if path.contains("://") {
+            path.to_string()
+        } else {
+            format!("{}/{path}", self.root)
+        }
+    }
+
+    fn data_file(
+        &self,
+        path: &str,
+        size: i64,
+        partition_values: &HashMap<String, Option<String>>,
+        stats: Option<&str>,
+    ) -> IcebergResult<DataFile> {
+        let partition = self
+            .spec
+            .fields
+            .iter()
+            .zip(&self.partition_columns)
+            .map(|(field, (physical, logical, data_type))| {
+                let raw = partition_values
+                    .get(physical)
+                    .or_else(|| partition_values.get(logical))
+                    .cloned()
+                    .flatten()
+                    .filter(|raw| !raw.is_empty());
+                let value = match raw {
+                    Some(raw) => partition_value(&data_type.parse_scalar(&raw)?)?,
+                    None => Value::Null,
+                };
+                Ok((field.name.clone(), value))
+            })
+            .collect::<IcebergResult<Vec<_>>>()?;
+        let stats = stats
+            .map(serde_json::from_str::<serde_json::Value>)
+            .transpose()?
+            .unwrap_or_default();
+        iceberg_data_file(self.location(path), size, partition, &stats, &self.leaves)
+    }
+}
+
+/// Where and for which snapshot manifests are written
+struct ManifestContext<'a> {
+    store: &'a ObjectStoreRef,
+    root: &'a str,
+    schema: &'a Schema,
+    layout: &'a Layout,
+    snapshot_id: i64,
+    sequence_number: i64,
+}
+
+impl ManifestContext<'_> {
+    /// The manifests of the previous snapshot updated with the committed actions. Files added
+    /// by the actions go into a new manifest, manifests with removed files are rewritten.
+    async fn apply_actions(
+        &self,
+        manifests: Vec<ManifestFile>,
+        actions: &[Action],
+    ) -> IcebergResult<(Vec<ManifestFile>, &'static str)> {
+        let mut added = Vec::new();
+        let mut removed = HashSet::new();
+        let mut has_removes = false;
+        for action in actions {
+            match action {
+                Action::Add(add) => {
+                    // re-added files, e.g. with new statistics, replace the existing entry
+                    removed.insert(self.layout.location(&add.path));
+                    added.push(self.layout.data_file(
+                        &add.path,
+                        add.size,
+                        &add.partition_values,
+                        add.stats.as_deref(),
+                    )?);
+                }
+                Action::Remove(remove) => {
+                    has_removes = true;
+                    removed.insert(self.layout.location(&remove.path));
+                }
+                _ => {}
+            }
+        }
+        let operation = match (added.is_empty(), has_removes) {
+            (false, false) => "append",
+            (true, _) => "delete",
+            (false, true) => "overwrite",
+        };
+
+        let roots = [self.root];
+        let removed = &removed;
+        let mut manifests: Vec<ManifestFile> = futures::stream::iter(manifests)
+            .map(|manifest| async move {
+                if removed.is_empty() || manifest.content != ManifestContent::Data {
+                    return Ok::<_, IcebergError>(manifest);
+                }
+                let bytes = read_file(self.store.as_ref(), &roots, &manifest.manifest_path).await?;
+                let entries = read_manifest(&bytes)?;
+                if !entries.iter().any(|e| {
+                    e.status != ManifestStatus::Deleted && removed.contains(&e.data_file.file_path)
+                }) {
+                    return Ok(manifest);
+                }
+                let entries = entries
+                    .into_iter()
+                    .filter(|e| e.status != ManifestStatus::Deleted)
+                    .map(|entry| {
+                        let mut entry = entry.inherit(&manifest);
+                        if removed.contains(&entry.data_file.file_path) {
+                            entry.status = ManifestStatus::Deleted;
+                            entry.snapshot_id = Some(self.snapshot_id);
+                        } else {
+                            entry.status = ManifestStatus::Existing;
+                        }
+                        entry
+                    })
+                    .collect::<Vec<_>>();
+                self.write(entries).await
+            })
+            .buffered(MANIFEST_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        if !added.is_empty() {
+            let entries = added
+                .into_iter()
+                .map(|data_file| ManifestEntry {
+                    status: ManifestStatus::Added,
+                    snapshot_id: Some(self.snapshot_id),
+                    sequence_number: None,
+                    file_sequence_number: None,
+                    data_file,
+                })
+                .collect();
+            manifests.push(self.write(entries).await?);
+        }
+        Ok((manifests, operation))
+    }
+
+    /// Write a manifest of the snapshot and describe it for the manifest list
+    async fn write(&self, entries: Vec<ManifestEntry>) -> IcebergResult<ManifestFile> {
+        let mut manifest = ManifestFile {
+            partition_spec_id: self.layout.spec.spec_id,
+            content: ManifestContent::Data,
+            sequence_number: self.sequence_number,
+            min_sequence_number: self.sequence_number,
+            added_snapshot_id: self.snapshot_id,
+            ..Default::default()
+        };
+        for entry in &entries {
+            let rows = entry.data_file.record_count;
+            match entry.status {
+                ManifestStatus::Added => {
+                    manifest.added_files_count += 1;
+                    manifest.added_rows_count += rows;
+                }
+                ManifestStatus::Existing => {
+                    manifest.existing_files_count += 1;
+                    manifest.existing_rows_count += rows;
+                    if let Some(sequence_number) = entry.sequence_number {
+                        manifest.min_sequence_number =
+                            manifest.min_sequence_number.min(sequence_number);
+                    }
+                }
+                ManifestStatus::Deleted => {
+                    manifest.deleted_files_count += 1;
+                    manifest.deleted_rows_count += rows;
+                }
+            }
+        }
+
+        let bytes = write_manifest(
+            self.schema,
+            &self.layout.spec,
+            &self.layout.partition_types,
+            entries,
+        )?;
+        let path = format!("metadata/{}-m0.avro", Uuid::new_v4());
+        manifest.manifest_length = bytes.len() as i64;
+        manifest.manifest_path = format!("{}/{path}", self.root);
+        self.store.put(&Path::from(path), bytes.into()).await?;
+        Ok(manifest)
+    }
+}
+
+/// The Avro value of a partition value
+fn partition_value(scalar: &Scalar) -> IcebergResult<Value> {
+    Ok(match scalar {
+        Scalar::Null(_) => Value::Null,
+        Scalar::Boolean(v) => Value::Boolean(*v),
+        Scalar::Byte(v) => Value::Int(*v as i32),
+        Scalar::Short(v) => Value::Int(*v as i32),
+        Scalar::Integer(v) => Value::Int(*v),
+        Scalar::Long(v) => Value::Long(*v),
+        Scalar::Float(v) => Value::Float(*v),
+        Scalar::Double(v) => Value::Double(*v),
+        Scalar::Date(v) => Value::Date(*v),
+        Scalar::Timestamp(v) | Scalar::TimestampNtz(v) => Value::TimestampMicros(*v),
+        Scalar::String(v) => Value::String(v.clone()),
+        Scalar::Binary(v) => Value::Bytes(v.clone()),
+        other => {
+            return Err(IcebergError::Unsupported(format!(
+                "partition value {other} in Iceberg metadata"
+            )));
+        }
+    })
+}
+
+/// Avro names only allow letters, digits and underscores and cannot start with a digit. Other
+/// characters are replaced by `_x` and their hex code, as Iceberg does.
+fn avro_name(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len());
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_alphabetic() || c == '_' || (i > 0 && c.is_ascii_digit()) {
+            sanitized.push(c);
+        } else if i == 0 && c.is_ascii_digit() {
+            sanitized.push('_');
+            sanitized.push(c);
+        } else {
+            sanitized.push_str(&format!("_x{:X}", c as u32));
+        }
+    }
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use delta_kernel::schema::DataType;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::iceberg::convert::read_iceberg_snapshot;
+    use crate::iceberg::schema::mapped_field;
+    use crate::kernel::transaction::{CommitBuilder, TableReference};
+    use crate::kernel::{Add, ProtocolInner, Remove};
+    use crate::operations::create::CreateBuilder;
+    use crate::operations::generate::{GenerateBuilder, GenerateMode};
+    use crate::protocol::{DeltaOperation, SaveMode};
+    use crate::{DeltaResult, DeltaTable, ensure_table_uri};
+
+    fn add(name: &str, region: &str, stats: Option<serde_json::Value>) -> Add {
+        Add {
+            path: format!("region={region}/{name}.parquet"),
+            partition_values: HashMap::from([("col-2".to_string(), Some(region.to_string()))]),
+            size: 100,
+            modification_time: 1_700_000_000_000,
+            data_change: true,
+            stats: stats.map(|stats| stats.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn stats(records: i64) -> Option<serde_json::Value> {
+        Some(json!({
+            "numRecords": records,
+            "minValues": {"col-1": 1},
+            "maxValues": {"col-1": records},
+            "nullCount": {"col-1": 0},
+        }))
+    }
+
+    async fn commit(table: &mut DeltaTable, actions: Vec<Action>) -> DeltaResult<()> {
+        CommitBuilder::default()
+            .with_actions(actions)
+            .build(
+                table
+                    .state
+                    .as_ref()
+                    .map(|state| state as &dyn TableReference),
+                table.log_store(),
+                DeltaOperation::Write {
+                    mode: SaveMode::Append,
+                    partition_by: None,
+                    predicate: None,
+                },
+            )
+            .await?;
+        table.update_state().await
+    }
+
+    /// Create a table with Iceberg metadata and the file `a` in the `eu` partition
+    async fn create_table(
+        log_store: &LogStoreRef,
+        configuration: &[(&str, &str)],
+    ) -> DeltaResult<DeltaTable> {
+        let protocol = ProtocolInner::default()
+            .append_reader_features([TableFeature::ColumnMapping])
+            .append_writer_features([TableFeature::ColumnMapping, TableFeature::IcebergCompatV2])
+            .as_kernel();
+        CreateBuilder::new()
+            .with_log_store(log_store.clone())
+            .with_columns([
+                mapped_field("id", DataType::LONG, false, 1, "col-1"),
+                mapped_field("region", DataType::STRING, true, 2, "col-2"),
+            ])
+            .with_partition_columns(["region"])
+            .with_configuration(
+                [
+                    ("delta.columnMapping.mode", "name"),
+                    ("delta.columnMapping.maxColumnId", "2"),
+                    ("delta.enableIcebergCompatV2", "true"),
+                    ("delta.universalFormat.enabledFormats", "iceberg"),
+                ]
+                .iter()
+                .chain(configuration)
+                .map(|(key, value)| (*key, Some(*value))),
+            )
+            // Iceberg table properties are not Delta table properties
+            .with_raise_if_key_not_exists(false)
+            .with_actions([
+                Action::Protocol(protocol),
+                Action::Add(add("a", "eu", stats(3))),
+            ])
+            .with_column_mapping()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_metadata_after_commits() -> DeltaResult<()> {
+        let root = tempdir().expect("Failed to create a temp directory");
+        let location = ensure_table_uri(root.path().to_str().unwrap())?;
+        let log_store =
+            crate::logstore::logstore_for(&location, crate::logstore::StorageConfig::default())?;
+        let mut table = create_table(&log_store, &[]).await?;
+
+        let store = log_store.object_store(None);
+        let hint = store
+            .get(&Path::from(VERSION_HINT_PATH))
+            .await?
+            .bytes()
+            .await?;
+        assert_eq!(hint.as_ref(), b"1");
+        let schema = table.snapshot()?.schema();
+        let iceberg = read_iceberg_snapshot(&log_store, Some(schema.as_ref())).await?;
+        assert_eq!(iceberg.snapshot_id, Some(1));
+        assert_eq!(iceberg.partition_columns, vec!["region".to_string()]);
+        assert_eq!(iceberg.schema, *schema.as_ref());
+        assert_eq!(iceberg.files.len(), 1);
+        assert_eq!(iceberg.files[0].path, "region=eu/a.parquet");
+        assert_eq!(
+            iceberg.files[0].partition_values,
+            HashMap::from([("col-2".to_string(), Some("eu".to_string()))])
+        );
+        let file_stats: serde_json::Value =
+            serde_json::from_str(iceberg.files[0].stats.as_ref().unwrap()).unwrap();
+        assert_eq!(file_stats["numRecords"], json!(3));
+        assert_eq!(file_stats["maxValues"]["col-1"], json!(3));
+
+        commit(
+            &mut table,
+            vec![
+                Action::Add(add("b", "us", stats(5))),
+                Action::Remove(Remove {
+                    path: "region=eu/a.parquet".to_string(),
+                    data_change: true,
+                    deletion_timestamp: Some(1_700_000_000_000),
+                    ..Default::default()
+                }),
+            ],
+        )
+        .await?;
+        let (version, metadata) = latest_table_metadata(store.as_ref()).await?.unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(metadata.snapshots.len(), 2);
+        assert_eq!(metadata.metadata_log.len(), 1);
+        let current = metadata.current_snapshot()?.unwrap();
+        assert_eq!(current.parent_snapshot_id, Some(1));
+        assert_eq!(current.summary.get("operation").unwrap(), "overwrite");
+        assert_eq!(current.summary.get(DELTA_VERSION_KEY).unwrap(), "1");
+        let iceberg = read_iceberg_snapshot(&log_store, Some(schema.as_ref())).await?;
+        let paths: Vec<_> = iceberg.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["region=us/b.parquet"]);
+
+        // files without record counts cannot be described in Iceberg manifests
+        assert!(
+            commit(&mut table, vec![Action::Add(add("c", "eu", None))])
+                .await
+                .is_err()
+        );
+
+        // the metadata is up to date, generating it again writes nothing
+        GenerateBuilder::new(table.log_store(), table.state.map(|s| s.snapshot))
+            .with_mode(GenerateMode::IcebergMetadata)
+            .await?;
+        assert_eq!(latest_table_metadata(store.as_ref()).await?.unwrap().0, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_metadata_retention() -> DeltaResult<()> {
+        let root = tempdir().expect("Failed to create a temp directory");
+        let location = ensure_table_uri(root.path().to_str().unwrap())?;
+        let log_store =
+            crate::logstore::logstore_for(&location, crate::logstore::StorageConfig::default())?;
+        let mut table = create_table(
+            &log_store,
+            &[
+                (PREVIOUS_VERSIONS_MAX_KEY, "1"),
+                (MAX_SNAPSHOT_AGE_MS_KEY, "0"),
+                (MIN_SNAPSHOTS_TO_KEEP_KEY, "2"),
+            ],
+        )
+        .await?;
+        for name in ["b", "c", "d"] {
+            commit(&mut table, vec![Action::Add(add(name, "us", stats(1)))]).await?;
+        }
+
+        let store = log_store.object_store(None);
+        let (version, metadata) = latest_table_metadata(store.as_ref()).await?.unwrap();
+        assert_eq!(version, 4);
+        assert_eq!(metadata.metadata_log.len(), 1);
+        assert!(
+            metadata.metadata_log[0]
+                .metadata_file
+                .ends_with("v3.metadata.json")
+        );
+        let snapshot_ids: Vec<_> = metadata.snapshots.iter().map(|s| s.snapshot_id).collect();
+        assert_eq!(snapshot_ids, vec![3, 4]);
+        let logged_ids: Vec<_> = metadata
+            .snapshot_log
+            .iter()
+            .map(|s| s.snapshot_id)
+            .collect();
+        assert_eq!(logged_ids, vec![3, 4]);
+
+        // only the manifest lists of the retained snapshots are kept
+        let mut manifest_lists: Vec<_> = store
+            .list(Some(&Path::from("metadata")))
+            .map_ok(|meta| meta.location.filename().unwrap_or_default().to_string())
+            .try_filter(|name| futures::future::ready(name.starts_with("snap-")))
+            .try_collect()
+            .await?;
+        manifest_lists.sort();
+        let mut expected: Vec<_> = metadata
+            .snapshots
+            .iter()
+            .map(|s| {
+                s.manifest_list
+                    .as_deref()
+                    .and_then(|list| list.rsplit('/').next())
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        expected.sort();
+        assert_eq!(manifest_lists, expected);
+
+        // a stale version hint does not hide newer metadata, nor is it moved backwards
+        store
+            .put(&Path::from(VERSION_HINT_PATH), "2".into())
+            .await?;
+        assert_eq!(latest_table_metadata(store.as_ref()).await?.unwrap().0, 4);
+        write_version_hint(store.as_ref(), 3).await?;
+        let hint = store
+            .get(&Path::from(VERSION_HINT_PATH))
+            .await?
+            .bytes()
+            .await?;
+        assert_eq!(hint.as_ref(), b"3");
+        write_version_hint(store.as_ref(), 1).await?;
+        let hint = store
+            .get(&Path::from(VERSION_HINT_PATH))
+            .await?
+            .bytes()
+            .await?;
+        assert_eq!(hint.as_ref(), b"3");
+        Ok(())
+    }
+
+    #[test]
+    fn test_avro_name() {
+        assert_eq!(avro_name("region"), "region");
+        assert_eq!(avro_name("1st col"), "_1st_x20col");
+        assert_eq!(avro_name("a.b"), "a_x2Eb");
+    }
+}
@@ -0,0 +1,696 @@
+//! Avro manifest lists and manifests, see <https://iceberg.apache.org/spec/#manifests>
+//!
+//! Records are read and written field by field as generic Avro values rather than through serde,
+//! since the partition record of a data file is shaped by the partition spec of the manifest.
+
+use std::collections::HashMap;
+
+use apache_avro::types::Value;
+use apache_avro::{Reader, Schema as AvroSchema, Writer};
+use serde_json::json;
+
+use super::spec::{PartitionSpec, PrimitiveType, Schema};
+use super::{IcebergError, IcebergResult};
+
+/// Whether a manifest tracks data files or delete files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ManifestContent {
+    #[default]
+    Data,
+    Deletes,
+}
+
+/// An entry of a manifest list
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ManifestFile {
+    pub manifest_path: String,
+    pub manifest_length: i64,
+    pub partition_spec_id: i32,
+    pub content: ManifestContent,
+    /// Sequence number of the snapshot that added the manifest
+    pub sequence_number: i64,
+    /// Lowest data sequence number of the live files in the manifest
+    pub min_sequence_number: i64,
+    pub added_snapshot_id: i64,
+    pub added_files_count: i32,
+    pub existing_files_count: i32,
+    pub deleted_files_count: i32,
+    pub added_rows_count: i64,
+    pub existing_rows_count: i64,
+    pub deleted_rows_count: i64,
+}
+
+/// Whether a manifest entry was added, kept or deleted by the snapshot that wrote it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ManifestStatus {
+    Existing,
+    Added,
+    Deleted,
+}
+
+/// What a data file of a manifest contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DataContent {
+    Data,
+    PositionDeletes,
+    EqualityDeletes,
+}
+
+/// An entry of a manifest
+#[derive(Debug, Clone)]
+pub(crate) struct ManifestEntry {
+    pub status: ManifestStatus,
+    /// Snapshot that added or deleted the file, inherited from the manifest if missing
+    pub snapshot_id: Option<i64>,
+    /// Data sequence number of the file, inherited from the manifest if missing
+    pub sequence_number: Option<i64>,
+    pub file_sequence_number: Option<i64>,
+    pub data_file: DataFile,
+}
+
+impl ManifestEntry {
+    /// Replace inherited values by the ones of the manifest, so the entry can be written to
+    /// another manifest
+    pub fn inherit(mut self, manifest: &ManifestFile) -> Self {
+        self.snapshot_id = self.snapshot_id.or(Some(manifest.added_snapshot_id));
+        self.sequence_number = self.sequence_number.or(Some(manifest.sequence_number));
+        self.file_sequence_number = self.file_sequence_number.or(Some(manifest.sequence_number));
+        self
+    }
+}
+
+/// A data or delete file with its partition and column metrics
+#[derive(Debug, Clone)]
+pub(crate) struct DataFile {
+    pub content: DataContent,
+    pub file_path: String,
+    pub file_format: String,
+    /// Partition values by partition field name, in the order of the partition spec
+    pub partition: Vec<(String, Value)>,
+    pub record_count: i64,
+    pub file_size_in_bytes: i64,
+    pub null_value_counts: HashMap<i32, i64>,
+    pub lower_bounds: HashMap<i32, Vec<u8>>,
+    pub upper_bounds: HashMap<i32, Vec<u8>>,
+}
+
+/// Read the manifests listed in a manifest list file
+pub(crate) fn read_manifest_list(bytes: &[u8]) -> IcebergResult<Vec<ManifestFile>> {
+    Reader::new(bytes)?
+        .map(|value| {
+            let mut record = Record::try_from(value?)?;
+            // format version 1 manifest lists name the counts after data files
+            let mut count = |name: &str, v1_name: &str| -> IcebergResult<i32> {
+                Ok(record
+                    .optional_int(name)?
+                    .or(record.optional_int(v1_name)?)
+                    .unwrap_or_default())
+            };
+            let added_files_count = count("added_files_count", "added_data_files_count")?;
+            let existing_files_count = count("existing_files_count", "existing_data_files_count")?;
+            let deleted_files_count = count("deleted_files_count", "deleted_data_files_count")?;
+            Ok(ManifestFile {
+                manifest_path: record.string("manifest_path")?,
+                manifest_length: record.optional_long("manifest_length")?.unwrap_or_default(),
+                partition_spec_id: record.int("partition_spec_id")?,
+                // format version 1 manifest lists only track data manifests
+                content: match record.optional_int("content")?.unwrap_or(0) {
+                    0 => ManifestContent::Data,
+                    _ => ManifestContent::Deletes,
+                },
+                sequence_number: record.optional_long("sequence_number")?.unwrap_or_default(),
+                min_sequence_number: record
+                    .optional_long("min_sequence_number")?
+                    .unwrap_or_default(),
+                added_snapshot_id: record
+                    .optional_long("added_snapshot_id")?
+                    .unwrap_or_default(),
+                added_files_count,
+                existing_files_count,
+                deleted_files_count,
+                added_rows_count: record
+                    .optional_long("added_rows_count")?
+                    .unwrap_or_default(),
+                existing_rows_count: record
+                    .optional_long("existing_rows_count")?
+                    .unwrap_or_default(),
+                deleted_rows_count: record
+                    .optional_long("deleted_rows_count")?
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Read the entries of a manifest file
+pub(crate) fn read_manifest(bytes: &[u8]) -> IcebergResult<Vec<ManifestEntry>> {
+    Reader::new(bytes)?
+        .map(|value| {
+            let mut record = Record::try_from(value?)?;
+            let status = match record.int("status")? {
+                0 => ManifestStatus::Existing,
+                1 => ManifestStatus::Added,
+                2 => ManifestStatus::Deleted,
+                status => {
+                    return Err(IcebergError::InvalidMetadata(format!(
+                        "unknown manifest entry status {status}"
+                    )));
+                }
+            };
+            let snapshot_id = record.optional_long("snapshot_id")?;
+            let sequence_number = record.optional_long("sequence_number")?;
+            let file_sequence_number = record.optional_long("file_sequence_number")?;
+            let mut data_file = Record::try_from(record.take("data_file")?)?;
+            let content = match data_file.optional_int("content")?.unwrap_or(0) {
+                0 => DataContent::Data,
+                1 => DataContent::PositionDeletes,
+                _ => DataContent::EqualityDeletes,
+            };
+            let partition = match data_file.take("partition")? {
+                Value::Record(fields) => fields,
+                value => {
+                    return Err(IcebergError::InvalidMetadata(format!(
+                        "expected a partition record, found {value:?}"
+                    )));
+                }
+            };
+
+            Ok(ManifestEntry {
+                status,
+                snapshot_id,
+                sequence_number,
+                file_sequence_number,
+                data_file: DataFile {
+                    content,
+                    file_path: data_file.string("file_path")?,
+                    file_format: data_file.string("file_format")?,
+                    partition,
+                    record_count: data_file.long("record_count")?,
+                    file_size_in_bytes: data_file.long("file_size_in_bytes")?,
+                    null_value_counts: data_file.int_map(
+                        "null_value_counts",
+                        |value| match value {
+                            Value::Long(v) => Some(v),
+                            Value::Int(v) => Some(v as i64),
+                            _ => None,
+                        },
+                    )?,
+                    lower_bounds: data_file.int_map("lower_bounds", bytes)?,
+                    upper_bounds: data_file.int_map("upper_bounds", bytes)?,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Avro schema of format version 2 manifest lists
+const MANIFEST_LIST_SCHEMA: &str = r#"{"type": "record", "name": "manifest_file", "fields": [
+    {"name": "manifest_path", "type": "string", "field-id": 500},
+    {"name": "manifest_length", "type": "long", "field-id": 501},
+    {"name": "partition_spec_id", "type": "int", "field-id": 502},
+    {"name": "content", "type": "int", "field-id": 517},
+    {"name": "sequence_number", "type": "long", "field-id": 515},
+    {"name": "min_sequence_number", "type": "long", "field-id": 516},
+    {"name": "added_snapshot_id", "type": "long", "field-id": 503},
+    {"name": "added_files_count", "type": "int", "field-id": 504},
+    {"name": "existing_files_count", "type": "int", "field-id": 505},
+    {"name": "deleted_files_count", "type": "int", "field-id": 506},
+    {"name": "added_rows_count", "type": "long", "field-id": 512},
+    {"name": "existing_rows_count", "type": "long", "field-id": 513},
+    {"name": "deleted_rows_count", "type": "long", "field-id": 514}
+]}"#;
+
+/// Write a format version 2 manifest list of a snapshot
+pub(crate) fn write_manifest_list(
+    snapshot_id: i64,
+    parent_snapshot_id: Option<i64>,
+    sequence_number: i64,
+    manifests: Vec<ManifestFile>,
+) -> IcebergResult<Vec<u8>> {
+    let schema = AvroSchema::parse_str(MANIFEST_LIST_SCHEMA)?;
+    let mut writer = Writer::new(&schema, Vec::new());
+    writer.add_user_metadata("snapshot-id".to_string(), snapshot_id.to_string())?;
+    writer.add_user_metadata(
+        "parent-snapshot-id".to_string(),
+        parent_snapshot_id.map_or_else(|| "null".to_string(), |id| id.to_string()),
+    )?;
+    writer.add_user_metadata("sequence-number".to_string(), sequence_number.to_string())?;
+    writer.add_user_metadata("format-version".to_string(), "2")?;
+    for manifest in manifests {
+        writer.append(Value::Record(vec![
+            (
+                "manifest_path".into(),
+                Value::String(manifest.manifest_path),
+            ),
+            (
+                "manifest_length".into(),
+                Value::Long(manifest.manifest_length),
+            ),
+            (
+                "partition_spec_id".into(),
+                Value::Int(manifest.partition_spec_id),
+            ),
+            (
+                "content".into(),
+                Value::Int(match manifest.content {
+                    ManifestContent::Data => 0,
+                    ManifestContent::Deletes => 1,
+                }),
+            ),
+            (
+                "sequence_number".into(),
+                Value::Long(manifest.sequence_number),
+            ),
+            (
+                "min_sequence_number".into(),
+                Value::Long(manifest.min_sequence_number),
+            ),
+            (
+                "added_snapshot_id".into(),
+                Value::Long(manifest.added_snapshot_id),
+            ),
+            (
+                "added_files_count".into(),
+                Value::Int(manifest.added_files_count),
+            ),
+            (
+                "existing_files_count".into(),
+                Value::Int(manifest.existing_files_count),
+            ),
+            (
+                "deleted_files_count".into(),
+                Value::Int(manifest.deleted_files_count),
+            ),
+            (
+                "added_rows_count".into(),
+                Value::Long(manifest.added_rows_count),
+            ),
+            (
+                "existing_rows_count".into(),
+                Value::Long(manifest.existing_rows_count),
+            ),
+            (
+                "deleted_rows_count".into(),
+                Value::Long(manifest.deleted_rows_count),
+            ),
+        ]))?;
+    }
+    Ok(writer.into_inner()?)
+}
+
+/// Write a format version 2 data manifest.
+///
+/// `partition_types` are the source column types of the identity partition fields of `spec`.
+/// The partition values of the entries hold one value per field in the same order.
+pub(crate) fn write_manifest(
+    schema: &Schema,
+    spec: &PartitionSpec,
+    partition_types: &[PrimitiveType],
+    entries: Vec<ManifestEntry>,
+) -> IcebergResult<Vec<u8>> {
+    let partition_fields = spec
+        .fields
+        .iter()
+        .zip(partition_types)
+        .map(|(field, data_type)| {
+            Ok(json!({
+                "name": field.name,
+                "type": ["null", avro_type(data_type)?],
+                "default": null,
+                "field-id": field.field_id,
+            }))
+        })
+        .collect::<IcebergResult<Vec<_>>>()?;
+    let avro_schema = AvroSchema::parse(&manifest_entry_schema(partition_fields))?;
+
+    let mut writer = Writer::new(&avro_schema, Vec::new());
+    writer.add_user_metadata("schema".to_string(), serde_json::to_string(schema)?)?;
+    writer.add_user_metadata("schema-id".to_string(), schema.schema_id.to_string())?;
+    writer.add_user_metadata(
+        "partition-spec".to_string(),
+        serde_json::to_string(&spec.fields)?,
+    )?;
+    writer.add_user_metadata("partition-spec-id".to_string(), spec.spec_id.to_string())?;
+    writer.add_user_metadata("format-version".to_string(), "2")?;
+    writer.add_user_metadata("content".to_string(), "data")?;
+    for entry in entries {
+        writer.append(entry_value(entry))?;
+    }
+    Ok(writer.into_inner()?)
+}
+
+fn manifest_entry_schema(partition_fields: Vec<serde_json::Value>) -> serde_json::Value {
+    let int_map = |name: &str, record: &str, ids: [i32; 3], value_type: &str| {
+        json!({
+            "name": name,
+            "type": ["null", {"type": "array", "logicalType": "map", "items": {
+                "type": "record",
+                "name": record,
+                "fields": [
+                    {"name": "key", "type": "int", "field-id": ids[1]},
+                    {"name": "value", "type": value_type, "field-id": ids[2]}
+                ]
+            }}],
+            "default": null,
+            "field-id": ids[0],
+        })
+    };
+    json!({"type": "record", "name": "manifest_entry", "fields": [
+        {"name": "status", "type": "int", "field-id": 0},
+        {"name": "snapshot_id", "type": ["null", "long"], "default": null, "field-id": 1},
+        {"name": "sequence_number", "type": ["null", "long"], "default": null, "field-id": 3},
+        {"name": "file_sequence_number", "type": ["null", "long"], "default": null, "field-id": 4},
+        {"name": "data_file", "field-id": 2, "type": {"type": "record", "name": "r2", "fields": [
+            {"name": "content", "type": "int", "field-id": 134},
+            {"name": "file_path", "type": "string", "field-id": 100},
+            {"name": "file_format", "type": "string", "field-id": 101},
+            {"name": "partition", "field-id": 102, "type": {
+                "type": "record", "name": "r102", "fields": partition_fields
+            }},
+            {"name": "record_count", "type": "long", "field-id": 103},
+            {"name": "file_size_in_bytes", "type": "long", "field-id": 104},
+            int_map("null_value_counts", "k121_v122", [110, 121, 122], "long"),
+            int_map("lower_bounds", "k126_v127", [125, 126, 127], "bytes"),
+            int_map("upper_bounds", "k129_v130", [128, 129, 130], "bytes"),
+        ]}}
+    ]})
+}
+
+/// Avro type of a partition value
+fn avro_type(data_type: &PrimitiveType) -> IcebergResult<serde_json::Value> {
+    Ok(match data_type {
+        PrimitiveType::Boolean => json!("boolean"),
+        PrimitiveType::Int => json!("int"),
+        PrimitiveType::Long => json!("long"),
+        PrimitiveType::Float => json!("float"),
+        PrimitiveType::Double => json!("double"),
+        PrimitiveType::Date => json!({"type": "int", "logicalType": "date"}),
+        PrimitiveType::Timestamp => {
+            json!({"type": "long", "logicalType": "timestamp-micros", "adjust-to-utc": false})
+        }
+        PrimitiveType::Timestamptz => {
+            json!({"type": "long", "logicalType": "timestamp-micros", "adjust-to-utc": true})
+        }
+        PrimitiveType::String => json!("string"),
+        PrimitiveType::Binary => json!("bytes"),
+        other => {
+            return Err(IcebergError::Unsupported(format!(
+                "partition field of type {other}"
+            )));
+        }
+    })
+}
+
+fn entry_value(entry: ManifestEntry) -> Value {
+    let data_file = entry.data_file;
+    let optional_long = |value: Option<i64>| optional(value.map(Value::Long));
+    let int_map = |map: HashMap<i32, Value>| {
+        if map.is_empty() {
+            return optional(None);
+        }
+        let mut entries: Vec<_> = map.into_iter().collect();
+        entries.sort_by_key(|(key, _)| *key);
+        optional(Some(Value::Array(
+            entries
+                .into_iter()
+                .map(|(key, value)| {
+                    Value::Record(vec![
+                        ("key".into(), Value::Int(key)),
+                        ("value".into(), value),
+                    ])
+                })
+                .collect(),
+        )))
+    };
+    let bounds = |map: HashMap<i32, Vec<u8>>| {
+        int_map(map.into_iter().map(|(k, v)| (k, Value::Bytes(v))).collect())
+    };
+
+    Value::Record(vec![
+        (
+            "status".into(),
+            Value::Int(match entry.status {
+                ManifestStatus::Existing => 0,
+                ManifestStatus::Added => 1,
+                ManifestStatus::Deleted => 2,
+            }),
+        ),
+        ("snapshot_id".into(), optional_long(entry.snapshot_id)),
+        (
+            "sequence_number".into(),
+            optional_long(entry.sequence_number),
+        ),
+        (
+            "file_sequence_number".into(),
+            optional_long(entry.file_sequence_number),
+        ),
+        (
+            "data_file".into(),
+            Value::Record(vec![
+                (
+                    "content".into(),
+                    Value::Int(match data_file.content {
+                        DataContent::Data => 0,
+                        DataContent::PositionDeletes => 1,
+                        DataContent::EqualityDeletes => 2,
+                    }),
+                ),
+                ("file_path".into(), Value::String(data_file.file_path)),
+                ("file_format".into(), Value::String(data_file.file_format)),
+                (
+                    "partition".into(),
+                    Value::Record(
+                        data_file
+                            .partition
+                            .into_iter()
+                            .map(|(name, value)| {
+                                let value = match unwrap_union(value) {
+                                    Value::Null => None,
+                                    value => Some(value),
+                                };
+                                (name, optional(value))
+                            })
+                            .collect(),
+                    ),
+                ),
+                ("record_count".into(), Value::Long(data_file.record_count)),
+                (
+                    "file_size_in_bytes".into(),
+                    Value::Long(data_file.file_size_in_bytes),
+                ),
+                (
+                    "null_value_counts".into(),
+                    int_map(
+                        data_file
+                            .null_value_counts
+                            .into_iter()
+                            .map(|(k, v)| (k, Value::Long(v)))
+                            .collect(),
+                    ),
+                ),
+                ("lower_bounds".into(), bounds(data_file.lower_bounds)),
+                ("upper_bounds".into(), bounds(data_file.upper_bounds)),
+            ]),
+        ),
+    ])
+}
+
+/// A value of an optional field, whose union has the null branch first
+fn optional(value: Option<Value>) -> Value {
+    match value {
+        Some(value) => Value::Union(1, Box::new(value)),
+        None => Value::Union(0, Box::new(Value::Null)),
+    }
+}
+
+/// Remove the null branch of an optional value
+pub(crate) fn unwrap_union(value: Value) -> Value {
+    match value {
+        Value::Union(_, value) => unwrap_union(*value),
+        value => value,
+    }
+}
+
+fn bytes(value: Value) -> Option<Vec<u8>> {
+    match value {
+        Value::Bytes(b) | Value::Fixed(_, b) => Some(b),
+        _ => None,
+    }
+}
+
+/// The fields of an Avro record, by name
+struct Record(HashMap<String, Value>);
+
+impl TryFrom<Value> for Record {
+    type Error = IcebergError;
+
+    fn try_from(value: Value) -> IcebergResult<Self> {
+        match unwrap_union(value) {
+            Value::Record(fields) => Ok(Self(fields.into_iter().collect())),
+            value => Err(IcebergError::InvalidMetadata(format!(
+                "expected a record, found {value:?}"
+            ))),
+        }
+    }
+}
+
+impl Record {
+    fn take(&mut self, name: &str) -> IcebergResult<Value> {
+        self.0
+            .remove(name)
+            .map(unwrap_union)
+            .ok_or_else(|| IcebergError::InvalidMetadata(format!("missing field {name}")))
+    }
+
+    fn take_optional(&mut self, name: &str) -> Option<Value> {
+        self.0
+            .remove(name)
+            .map(unwrap_union)
+            .filter(|v| *v != Value::Null)
+    }
+
+    fn string(&mut self, name: &str) -> IcebergResult<String> {
+        match self.take(name)? {
+            Value::String(s) => Ok(s),
+            value => Err(invalid_field(name, value)),
+        }
+    }
+
+    fn int(&mut self, name: &str) -> IcebergResult<i32> {
+        match self.take(name)? {
+            Value::Int(v) => Ok(v),
+            value => Err(invalid_field(name, value)),
+        }
+    }
+
+    fn optional_int(&mut self, name: &str) -> IcebergResult<Option<i32>> {
+        match self.take_optional(name) {
+            None => Ok(None),
+            Some(Value::Int(v)) => Ok(Some(v)),
+            Some(value) => Err(invalid_field(name, value)),
+        }
+    }
+
+    fn long(&mut self, name: &str) -> IcebergResult<i64> {
+        match self.take(name)? {
+            Value::Long(v) => Ok(v),
+            Value::Int(v) => Ok(v as i64),
+            value => Err(invalid_field(name, value)),
+        }
+    }
+
+    fn optional_long(&mut self, name: &str) -> IcebergResult<Option<i64>> {
+        match self.take_optional(name) {
+            None => Ok(None),
+            Some(Value::Long(v)) => Ok(Some(v)),
+            Some(Value::Int(v)) => Ok(Some(v as i64)),
+            Some(value) => Err(invalid_field(name, value)),
+        }
+    }
+
+    /// Maps keyed by field id are stored as arrays of `key`/`value` records
+    fn int_map<T>(
+        &mut self,
+        name: &str,
+        convert: impl Fn(Value) -> Option<T>,
+    ) -> IcebergResult<HashMap<i32, T>> {
+        let entries = match self.take_optional(name) {
+            None => return Ok(HashMap::new()),
+            Some(Value::Array(entries)) => entries,
+            Some(value) => return Err(invalid_field(name, value)),
+        };
+        let mut map = HashMap::with_capacity(entries.len());
+        for entry in entries {
+            let mut entry = Record::try_from(entry)?;
+            let key = entry.int("key")?;
+            if let Some(value) = entry.take_optional("value").and_then(&convert) {
+                map.insert(key, value);
+            }
+        }
+        Ok(map)
+    }
+}
+
+fn invalid_field(name: &str, value: Value) -> IcebergError {
+    IcebergError::InvalidMetadata(format!("unexpected value for field {name}: {value:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iceberg::spec::{PartitionField, StructType, Transform};
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let schema = Schema {
+            schema_id: 0,
+            fields: StructType { fields: vec![] },
+        };
+        let spec = PartitionSpec {
+            spec_id: 0,
+            fields: vec![PartitionField {
+                source_id: 2,
+                field_id: Some(1000),
+                name: "region".to_string(),
+                transform: Transform::Identity,
+            }],
+        };
+        let entry = ManifestEntry {
+            status: ManifestStatus::Added,
+            snapshot_id: Some(7),
+            sequence_number: None,
+            file_sequence_number: None,
+            data_file: DataFile {
+                content: DataContent::Data,
+                file_path: "s3://bucket/table/a.parquet".to_string(),
+                file_format: "PARQUET".to_string(),
+                partition: vec![("region".to_string(), Value::String("eu".to_string()))],
+                record_count: 10,
+                file_size_in_bytes: 1024,
+                null_value_counts: HashMap::from([(1, 0)]),
+                lower_bounds: HashMap::from([(1, 3i64.to_le_bytes().to_vec())]),
+                upper_bounds: HashMap::new(),
+            },
+        };
+
+        let bytes = write_manifest(&schema, &spec, &[PrimitiveType::String], vec![entry]).unwrap();
+        let entries = read_manifest(&bytes).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].snapshot_id, Some(7));
+        assert_eq!(entries[0].sequence_number, None);
+        let data_file = &entries[0].data_file;
+        assert_eq!(data_file.file_path, "s3://bucket/table/a.parquet");
+        assert_eq!(data_file.record_count, 10);
+        assert_eq!(
+            unwrap_union(data_file.partition[0].1.clone()),
+            Value::String("eu".to_string())
+        );
+        assert_eq!(
+            data_file.lower_bounds.get(&1),
+            Some(&3i64.to_le_bytes().to_vec())
+        );
+        assert!(data_file.upper_bounds.is_empty());
+
+        let list = write_manifest_list(
+            7,
+            None,
+            1,
+            vec![ManifestFile {
+                manifest_path: "s3://bucket/table/metadata/m0.avro".to_string(),
+                manifest_length: bytes.len() as i64,
+                sequence_number: 1,
+                min_sequence_number: 1,
+                added_snapshot_id: 7,
+                added_files_count: 1,
+                added_rows_count: 10,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        let manifests = read_manifest_list(&list).unwrap();
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].added_snapshot_id, 7);
+        assert_eq!(manifests[0].added_files_count, 1);
+        assert_eq!(manifests[0].content, ManifestContent::Data);
+    }
+}
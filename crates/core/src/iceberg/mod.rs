@@ -0,0 +1,57 @@
+//! Apache Iceberg table metadata
+//!
+//! Iceberg tables keep their state in a metadata JSON file that points to a manifest list per
+//! snapshot, which in turn lists the Avro manifests describing the data files. This module models
+//! the parts of the [Iceberg spec](https://iceberg.apache.org/spec/) needed to translate an Iceberg
+//! table into Delta actions without copying any data, and to generate Iceberg metadata for Delta
+//! tables so Iceberg readers can read them.
+
+use crate::{DeltaTableError, ObjectStoreError};
+
+pub(crate) mod convert;
+pub(crate) mod generate;
+mod manifest;
+mod schema;
+mod spec;
+mod stats;
+
+/// Error reading Iceberg table metadata
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum IcebergError {
+    #[error("Avro error: {0}")]
+    Avro(#[from] apache_avro::Error),
+    #[error("Invalid Iceberg metadata JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] ObjectStoreError),
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("DeltaTable error: {0}")]
+    DeltaTable(#[from] DeltaTableError),
+    #[error("No Iceberg metadata file found in {0}")]
+    MetadataNotFound(String),
+    #[error("Invalid Iceberg metadata: {0}")]
+    InvalidMetadata(String),
+    #[error("Unsupported Iceberg table: {0}")]
+    Unsupported(String),
+}
+
+impl From<IcebergError> for DeltaTableError {
+    fn from(err: IcebergError) -> Self {
+        match err {
+            IcebergError::ObjectStore(e) => DeltaTableError::ObjectStore { source: e },
+            IcebergError::DeltaTable(e) => e,
+            _ => DeltaTableError::GenericError {
+                source: Box::new(err),
+            },
+        }
+    }
+}
+
+impl From<delta_kernel::Error> for IcebergError {
+    fn from(err: delta_kernel::Error) -> Self {
+        IcebergError::DeltaTable(err.into())
+    }
+}
+
+pub(crate) type IcebergResult<T> = Result<T, IcebergError>;
@@ -0,0 +1,490 @@
+//! Translation between Iceberg schemas and Delta schemas with column mapping
+//!
+//! Iceberg resolves columns in data files by field id, which maps onto Delta's `id` column
+//! mapping mode: every field keeps its Iceberg id as `delta.columnMapping.id`.
+
+use std::collections::HashMap;
+
+use delta_kernel::schema::{
+    ArrayType, ColumnMetadataKey, DataType, MapType, MetadataValue,
+    PrimitiveType as DeltaPrimitive, StructField, StructType,
+};
+use serde_json::{Value, json};
+
+use super::spec::{self, ListType, NestedField, PrimitiveType, Type};
+use super::{IcebergError, IcebergResult};
+
+/// Field metadata key holding the comment of a column
+const COMMENT_KEY: &str = "comment";
+
+/// Field metadata key holding the ids of list elements and map keys and values nested in a
+/// column, by paths like `<physical name>.element`
+const NESTED_IDS_KEY: &str = "delta.columnMapping.nested.ids";
+
+/// Translate an Iceberg schema into a Delta schema.
+///
+/// `physical_names` holds the physical names of fields by id from an earlier conversion, which
+/// must be kept when columns are renamed in Iceberg. New fields use their name as physical name.
+pub(crate) fn delta_schema(
+    schema: &spec::StructType,
+    physical_names: &HashMap<i64, String>,
+) -> IcebergResult<StructType> {
+    Ok(StructType::try_new(
+        schema
+            .fields
+            .iter()
+            .map(|field| delta_field(field, physical_names))
+            .collect::<IcebergResult<Vec<_>>>()?,
+    )?)
+}
+
+/// A Delta field carrying column mapping metadata
+pub(crate) fn mapped_field(
+    name: impl Into<String>,
+    data_type: DataType,
+    nullable: bool,
+    id: i64,
+    physical_name: impl Into<String>,
+) -> StructField {
+    StructField::new(name, data_type, nullable).with_metadata([
+        (
+            ColumnMetadataKey::ColumnMappingId.as_ref(),
+            MetadataValue::Number(id),
+        ),
+        (
+            ColumnMetadataKey::ColumnMappingPhysicalName.as_ref(),
+            MetadataValue::String(physical_name.into()),
+        ),
+    ])
+}
+
+fn delta_field(
+    field: &NestedField,
+    physical_names: &HashMap<i64, String>,
+) -> IcebergResult<StructField> {
+    let id = field.id as i64;
+    let physical_name = physical_names
+        .get(&id)
+        .cloned()
+        .unwrap_or_else(|| field.name.clone());
+    let mut delta_field = mapped_field(
+        field.name.clone(),
+        delta_type(&field.field_type, physical_names)?,
+        !field.required,
+        id,
+        physical_name,
+    );
+    if let Some(doc) = &field.doc {
+        delta_field
+            .metadata
+            .insert(COMMENT_KEY.to_string(), MetadataValue::String(doc.clone()));
+    }
+    Ok(delta_field)
+}
+
+fn delta_type(data_type: &Type, physical_names: &HashMap<i64, String>) -> IcebergResult<DataType> {
+    Ok(match data_type {
+        Type::Primitive(primitive) => delta_primitive(primitive)?,
+        Type::Struct(fields) => DataType::Struct(Box::new(delta_schema(fields, physical_names)?)),
+        Type::List(list) => DataType::Array(Box::new(ArrayType::new(
+            delta_type(&list.element, physical_names)?,
+            !list.element_required,
+        ))),
+        Type::Map(map) => DataType::Map(Box::new(MapType::new(
+            delta_type(&map.key, physical_names)?,
+            delta_type(&map.value, physical_names)?,
+            !map.value_required,
+        ))),
+    })
+}
+
+pub(crate) fn delta_primitive(primitive: &PrimitiveType) -> IcebergResult<DataType> {
+    Ok(match primitive {
+        PrimitiveType::Boolean => DataType::BOOLEAN,
+        PrimitiveType::Int => DataType::INTEGER,
+        PrimitiveType::Long => DataType::LONG,
+        PrimitiveType::Float => DataType::FLOAT,
+        PrimitiveType::Double => DataType::DOUBLE,
+        PrimitiveType::Decimal { precision, scale } => {
+            DataType::Primitive(DeltaPrimitive::decimal(*precision, *scale)?)
+        }
+        PrimitiveType::Date => DataType::DATE,
+        PrimitiveType::Timestamp => DataType::TIMESTAMP_NTZ,
+        PrimitiveType::Timestamptz => DataType::TIMESTAMP,
+        PrimitiveType::String => DataType::STRING,
+        PrimitiveType::Binary => DataType::BINARY,
+        // uuid and fixed are stored as fixed length binary, which Delta cannot read as binary
+        PrimitiveType::Time | PrimitiveType::Uuid | PrimitiveType::Fixed(_) => {
+            return Err(IcebergError::Unsupported(format!(
+                "type {primitive} has no Delta equivalent"
+            )));
+        }
+    })
+}
+
+/// Translate a column mapped Delta schema into an Iceberg schema.
+///
+/// Fields keep their column mapping ids. Lists and maps take the ids of their elements, keys and
+/// values from the nested ids of the column, or get ids above `max_column_id` in schema order.
+/// Returns the schema and the highest id in it.
+pub(crate) fn iceberg_schema(
+    schema: &StructType,
+    max_column_id: i64,
+) -> IcebergResult<(spec::StructType, i32)> {
+    let mut ids = FieldIds {
+        next: max_column_id as i32,
+        max: max_column_id as i32,
+    };
+    let fields = iceberg_struct(schema, &mut ids)?;
+    Ok((fields, ids.max))
+}
+
+/// Ids of the fields of an Iceberg schema
+struct FieldIds {
+    next: i32,
+    max: i32,
+}
+
+impl FieldIds {
+    fn seen(&mut self, id: i32) -> i32 {
+        self.max = self.max.max(id);
+        id
+    }
+
+    /// The nested id at `path` of the column, or a new one
+    fn nested(&mut self, nested_ids: Option<&Value>, path: &str) -> i32 {
+        match nested_ids
+            .and_then(|ids| ids.get(path))
+            .and_then(Value::as_i64)
+        {
+            Some(id) => self.seen(id as i32),
+            None => {
+                self.next = self.next.max(self.max) + 1;
+                self.seen(self.next)
+            }
+        }
+    }
+}
+
+fn iceberg_struct(fields: &StructType, ids: &mut FieldIds) -> IcebergResult<spec::StructType> {
+    let fields = fields
+        .fields()
+        .map(|field| {
+            let Some(MetadataValue::Number(id)) = field
+                .metadata()
+                .get(ColumnMetadataKey::ColumnMappingId.as_ref())
+            else {
+                return Err(IcebergError::Unsupported(format!(
+                    "column {} has no column mapping id",
+                    field.name()
+                )));
+            };
+            let nested_ids = match field.metadata().get(NESTED_IDS_KEY) {
+                Some(MetadataValue::Other(ids)) => Some(ids),
+                _ => None,
+            };
+            let id = ids.seen(*id as i32);
+            Ok(NestedField {
+                id,
+                name: field.name().clone(),
+                required: !field.is_nullable(),
+                field_type: iceberg_type(field.data_type(), physical_name(field), nested_ids, ids)?,
+                doc: match field.metadata().get(COMMENT_KEY) {
+                    Some(MetadataValue::String(doc)) => Some(doc.clone()),
+                    _ => None,
+                },
+            })
+        })
+        .collect::<IcebergResult<_>>()?;
+    Ok(spec::StructType { fields })
+}
+
+fn iceberg_type(
+    data_type: &DataType,
+    path: &str,
+    nested_ids: Option<&Value>,
+    ids: &mut FieldIds,
+) -> IcebergResult<Type> {
+    Ok(match data_type {
+        DataType::Primitive(primitive) => Type::Primitive(iceberg_primitive(primitive)?),
+        DataType::Struct(fields) => Type::Struct(iceberg_struct(fields, ids)?),
+        DataType::Array(array) => {
+            let path = format!("{path}.element");
+            let element_id = ids.nested(nested_ids, &path);
+            Type::List(ListType {
+                element_id,
+                element: Box::new(iceberg_type(array.element_type(), &path, nested_ids, ids)?),
+                element_required: !array.contains_null(),
+            })
+        }
+        DataType::Map(map) => {
+            let key_path = format!("{path}.key");
+            let value_path = format!("{path}.value");
+            let key_id = ids.nested(nested_ids, &key_path);
+            let value_id = ids.nested(nested_ids, &value_path);
+            Type::Map(spec::MapType {
+                key_id,
+                key: Box::new(iceberg_type(map.key_type(), &key_path, nested_ids, ids)?),
+                value_id,
+                value: Box::new(iceberg_type(
+                    map.value_type(),
+                    &value_path,
+                    nested_ids,
+                    ids,
+                )?),
+                value_required: !map.value_contains_null(),
+            })
+        }
+        DataType::Variant(_) => {
+            return Err(IcebergError::Unsupported(
+                "variant columns require Iceberg format version 3".to_string(),
+            ));
+        }
+    })
+}
+
+pub(crate) fn iceberg_primitive(primitive: &DeltaPrimitive) -> IcebergResult<PrimitiveType> {
+    Ok(match primitive {
+        DeltaPrimitive::Boolean => PrimitiveType::Boolean,
+        // Iceberg has no narrower integer types, readers widen the values
+        DeltaPrimitive::Byte | DeltaPrimitive::Short | DeltaPrimitive::Integer => {
+            PrimitiveType::Int
+        }
+        DeltaPrimitive::Long => PrimitiveType::Long,
+        DeltaPrimitive::Float => PrimitiveType::Float,
+        DeltaPrimitive::Double => PrimitiveType::Double,
+        DeltaPrimitive::Decimal(decimal) => PrimitiveType::Decimal {
+            precision: decimal.precision(),
+            scale: decimal.scale(),
+        },
+        DeltaPrimitive::Date => PrimitiveType::Date,
+        DeltaPrimitive::Timestamp => PrimitiveType::Timestamptz,
+        DeltaPrimitive::TimestampNtz => PrimitiveType::Timestamp,
+        DeltaPrimitive::String => PrimitiveType::String,
+        DeltaPrimitive::Binary => PrimitiveType::Binary,
+        #[cfg(feature = "nanosecond-timestamps")]
+        DeltaPrimitive::TimestampNanos => {
+            return Err(IcebergError::Unsupported(
+                "nanosecond timestamps require Iceberg format version 3".to_string(),
+            ));
+        }
+    })
+}
+
+/// The `schema.name-mapping.default` table property, which lets Iceberg readers resolve columns
+/// of data files written without field ids by their physical names
+pub(crate) fn name_mapping(schema: &spec::StructType, delta: &StructType) -> Value {
+    fn mapping(fields: &spec::StructType, names: &HashMap<i64, String>) -> Value {
+        Value::Array(
+            fields
+                .fields
+                .iter()
+                .map(|field| {
+                    let name = names.get(&(field.id as i64)).unwrap_or(&field.name);
+                    nested_mapping(field.id, name, &field.field_type, names)
+                })
+                .collect(),
+        )
+    }
+    fn nested_mapping(
+        id: i32,
+        name: &str,
+        data_type: &Type,
+        names: &HashMap<i64, String>,
+    ) -> Value {
+        let mut entry = json!({"field-id": id, "names": [name]});
+        let fields = match data_type {
+            Type::Primitive(_) => return entry,
+            Type::Struct(fields) => mapping(fields, names),
+            Type::List(list) => json!([nested_mapping(
+                list.element_id,
+                "element",
+                &list.element,
+                names
+            )]),
+            Type::Map(map) => json!([
+                nested_mapping(map.key_id, "key", &map.key, names),
+                nested_mapping(map.value_id, "value", &map.value, names),
+            ]),
+        };
+        entry["fields"] = fields;
+        entry
+    }
+
+    mapping(schema, &physical_names(delta))
+}
+
+/// The physical path and type of every primitive field that is not nested in a list or map, by
+/// column mapping id. Data file metrics are keyed by these ids.
+pub(crate) fn leaf_fields(schema: &StructType) -> HashMap<i64, (Vec<String>, DeltaPrimitive)> {
+    fn collect(
+        fields: &StructType,
+        parent: &[String],
+        leaves: &mut HashMap<i64, (Vec<String>, DeltaPrimitive)>,
+    ) {
+        for field in fields.fields() {
+            let Some(MetadataValue::Number(id)) = field
+                .metadata()
+                .get(ColumnMetadataKey::ColumnMappingId.as_ref())
+            else {
+                continue;
+            };
+            let mut path = parent.to_vec();
+            path.push(physical_name(field).to_string());
+            match field.data_type() {
+                DataType::Primitive(primitive) => {
+                    leaves.insert(*id, (path, primitive.clone()));
+                }
+                DataType::Struct(inner) => collect(inner, &path, leaves),
+                _ => {}
+            }
+        }
+    }
+
+    let mut leaves = HashMap::new();
+    collect(schema, &[], &mut leaves);
+    leaves
+}
+
+/// The physical names of all fields of a column mapped schema by id
+pub(crate) fn physical_names(schema: &StructType) -> HashMap<i64, String> {
+    fn collect(data_type: &DataType, names: &mut HashMap<i64, String>) {
+        match data_type {
+            DataType::Struct(fields) => {
+                for field in fields.fields() {
+                    if let Some(MetadataValue::Number(id)) = field
+                        .metadata()
+                        .get(ColumnMetadataKey::ColumnMappingId.as_ref())
+                    {
+                        names.insert(*id, physical_name(field).to_string());
+                    }
+                    collect(field.data_type(), names);
+                }
+            }
+            DataType::Array(array) => collect(array.element_type(), names),
+            DataType::Map(map) => {
+                collect(map.key_type(), names);
+                collect(map.value_type(), names);
+            }
+            _ => {}
+        }
+    }
+
+    let mut names = HashMap::new();
+    collect(&DataType::Struct(Box::new(schema.clone())), &mut names);
+    names
+}
+
+/// The physical name of a field, its name if it has no column mapping metadata
+pub(crate) fn physical_name(field: &StructField) -> &str {
+    match field
+        .metadata()
+        .get(ColumnMetadataKey::ColumnMappingPhysicalName.as_ref())
+    {
+        Some(MetadataValue::String(name)) => name,
+        _ => field.name(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delta_schema_keeps_field_ids_and_physical_names() {
+        let schema: spec::StructType = serde_json::from_value(serde_json::json!({
+            "type": "struct",
+            "fields": [
+                {"id": 1, "name": "id", "required": true, "type": "long"},
+                {"id": 2, "name": "location", "required": false, "type": {
+                    "type": "struct",
+                    "fields": [{"id": 3, "name": "lat", "required": false, "type": "double"}]
+                }},
+                {"id": 4, "name": "ts", "required": false, "type": "timestamptz", "doc": "event time"}
+            ]
+        }))
+        .unwrap();
+        let renamed = HashMap::from([(1, "event_id".to_string())]);
+
+        let delta = delta_schema(&schema, &renamed).unwrap();
+        let id = delta.field("id").unwrap();
+        assert!(!id.is_nullable());
+        assert_eq!(physical_name(id), "event_id");
+        assert_eq!(delta.field("ts").unwrap().data_type(), &DataType::TIMESTAMP);
+
+        let leaves = leaf_fields(&delta);
+        assert_eq!(
+            leaves.get(&3),
+            Some(&(
+                vec!["location".to_string(), "lat".to_string()],
+                DeltaPrimitive::Double
+            ))
+        );
+        assert_eq!(leaves.get(&1).unwrap().0, vec!["event_id".to_string()]);
+        assert!(!leaves.contains_key(&2));
+        assert_eq!(physical_names(&delta).len(), 4);
+    }
+
+    #[test]
+    fn test_unsupported_types() {
+        assert!(delta_primitive(&PrimitiveType::Uuid).is_err());
+        assert!(delta_primitive(&PrimitiveType::Time).is_err());
+    }
+
+    #[test]
+    fn test_iceberg_schema_of_delta_schema() {
+        let mut tags = mapped_field(
+            "tags",
+            DataType::Array(Box::new(ArrayType::new(DataType::STRING, true))),
+            true,
+            2,
+            "col-2",
+        );
+        tags.metadata.insert(
+            NESTED_IDS_KEY.to_string(),
+            MetadataValue::Other(json!({"col-2.element": 3})),
+        );
+        let delta = StructType::try_new([
+            mapped_field("id", DataType::BYTE, false, 1, "col-1"),
+            tags,
+            mapped_field(
+                "scores",
+                DataType::Map(Box::new(MapType::new(
+                    DataType::STRING,
+                    DataType::TIMESTAMP,
+                    true,
+                ))),
+                true,
+                4,
+                "col-4",
+            ),
+        ])
+        .unwrap();
+
+        let (schema, last_column_id) = iceberg_schema(&delta, 4).unwrap();
+        assert_eq!(last_column_id, 6);
+        assert!(schema.fields[0].required);
+        assert_eq!(
+            schema.fields[0].field_type,
+            Type::Primitive(PrimitiveType::Int)
+        );
+        let Type::List(list) = &schema.fields[1].field_type else {
+            panic!("expected a list");
+        };
+        assert_eq!(list.element_id, 3);
+        let Type::Map(map) = &schema.fields[2].field_type else {
+            panic!("expected a map");
+        };
+        assert_eq!((map.key_id, map.value_id), (5, 6));
+        assert_eq!(*map.value, Type::Primitive(PrimitiveType::Timestamptz));
+
+        let mapping = name_mapping(&schema, &delta);
+        assert_eq!(mapping[0], json!({"field-id": 1, "names": ["col-1"]}));
+        assert_eq!(
+            mapping[1],
+            json!({"field-id": 2, "names": ["col-2"], "fields": [
+                {"field-id": 3, "names": ["element"]}
+            ]})
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Iceberg table metadata JSON, see <https://iceberg.apache.org/spec/#table-metadata-fields>
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::{IcebergError, IcebergResult};
+
+/// Contents of a `metadata.json` file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct TableMetadata {
+    pub format_version: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_uuid: Option<String>,
+    pub location: String,
+    #[serde(default)]
+    pub last_sequence_number: i64,
+    pub last_updated_ms: i64,
+    pub last_column_id: i32,
+    #[serde(default)]
+    pub schemas: Vec<Schema>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_schema_id: Option<i32>,
+    /// The only schema of format version 1 tables written before `schemas` existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Schema>,
+    #[serde(default)]
+    pub partition_specs: Vec<PartitionSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_spec_id: Option<i32>,
+    /// The only partition spec of format version 1 tables written before `partition-specs` existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition_spec: Option<Vec<PartitionField>>,
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_partition_id: Option<i32>,
+    #[serde(default)]
+    pub sort_orders: Vec<SortOrder>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_sort_order_id: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_snapshot_id: Option<i64>,
+    #[serde(default)]
+    pub snapshots: Vec<Snapshot>,
+    #[serde(default)]
+    pub refs: HashMap<String, SnapshotReference>,
+    #[serde(default)]
+    pub snapshot_log: Vec<SnapshotLogEntry>,
+    #[serde(default)]
+    pub metadata_log: Vec<MetadataLogEntry>,
+}
+
+impl TableMetadata {
+    /// The schema the current snapshot is read with
+    pub fn current_schema(&self) -> IcebergResult<&Schema> {
+        match self.current_schema_id {
+            Some(id) => self.schemas.iter().find(|s| s.schema_id == id),
+            None => self.schema.as_ref().or(self.schemas.last()),
+        }
+        .ok_or_else(|| IcebergError::InvalidMetadata("current schema not found".to_string()))
+    }
+
+    /// The partition spec new data files are written with
+    pub fn default_partition_spec(&self) -> IcebergResult<PartitionSpec> {
+        match (self.default_spec_id, &self.partition_spec) {
+            (Some(id), _) => self.partition_spec(id).cloned(),
+            (None, Some(fields)) => Ok(PartitionSpec {
+                spec_id: 0,
+                fields: fields.clone(),
+            }),
+            (None, None) => Ok(PartitionSpec::default()),
+        }
+    }
+
+    /// The partition spec with the given id
+    pub fn partition_spec(&self, spec_id: i32) -> IcebergResult<&PartitionSpec> {
+        self.partition_specs
+            .iter()
+            .find(|s| s.spec_id == spec_id)
+            .ok_or_else(|| {
+                IcebergError::InvalidMetadata(format!("partition spec {spec_id} not found"))
+            })
+    }
+
+    /// The current snapshot, `None` for tables without data
+    pub fn current_snapshot(&self) -> IcebergResult<Option<&Snapshot>> {
+        // format version 1 uses -1 for tables without snapshots
+        let Some(id) = self.current_snapshot_id.filter(|id| *id >= 0) else {
+            return Ok(None);
+        };
+        self.snapshots
+            .iter()
+            .find(|s| s.snapshot_id == id)
+            .map(Some)
+            .ok_or_else(|| IcebergError::InvalidMetadata(format!("snapshot {id} not found")))
+    }
+}
+
+/// A table schema with the id it is referenced by
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Schema {
+    #[serde(default)]
+    pub schema_id: i32,
+    #[serde(flatten)]
+    pub fields: StructType,
+}
+
+/// A field of a struct, or the table schema, with its unique field id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct NestedField {
+    pub id: i32,
+    pub name: String,
+    pub required: bool,
+    #[serde(rename = "type")]
+    pub field_type: Type,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+/// Any Iceberg type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Type {
+    Primitive(PrimitiveType),
+    Struct(StructType),
+    List(ListType),
+    Map(MapType),
+}
+
+/// A struct type, `{"type": "struct", "fields": [..]}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "struct")]
+pub(crate) struct StructType {
+    pub fields: Vec<NestedField>,
+}
+
+/// A list type, `{"type": "list", "element-id": .., "element": .., "element-required": ..}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "list", rename_all = "kebab-case")]
+pub(crate) struct ListType {
+    pub element_id: i32,
+    pub element: Box<Type>,
+    pub element_required: bool,
+}
+
+/// A map type, `{"type": "map", "key-id": .., "key": .., "value-id": .., "value": .., ..}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "map", rename_all = "kebab-case")]
+pub(crate) struct MapType {
+    pub key_id: i32,
+    pub key: Box<Type>,
+    pub value_id: i32,
+    pub value: Box<Type>,
+    pub value_required: bool,
+}
+
+/// Primitive types, serialized as strings like `"long"` or `"decimal(10, 2)"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) enum PrimitiveType {
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Decimal { precision: u8, scale: u8 },
+    Date,
+    Time,
+    Timestamp,
+    Timestamptz,
+    String,
+    Uuid,
+    Fixed(u64),
+    Binary,
+}
+
+impl FromStr for PrimitiveType {
+    type Err = IcebergError;
+
+    fn from_str(s: &str) -> IcebergResult<Self> {
+        let invalid = || IcebergError::InvalidMetadata(format!("unknown type {s}"));
+        Ok(match s {
+            "boolean" => Self::Boolean,
+            "int" => Self::Int,
+            "long" => Self::Long,
+            "float" => Self::Float,
+            "double" => Self::Double,
+            "date" => Self::Date,
+            "time" => Self::Time,
+            "timestamp" => Self::Timestamp,
+            "timestamptz" => Self::Timestamptz,
+            "string" => Self::String,
+            "uuid" => Self::Uuid,
+            "binary" => Self::Binary,
+            _ => {
+                if let Some(args) = s.strip_prefix("decimal(").and_then(|s| s.strip_suffix(')')) {
+                    let (precision, scale) = args.split_once(',').ok_or_else(invalid)?;
+                    Self::Decimal {
+                        precision: precision.trim().parse().map_err(|_| invalid())?,
+                        scale: scale.trim().parse().map_err(|_| invalid())?,
+                    }
+                } else if let Some(length) =
+                    s.strip_prefix("fixed[").and_then(|s| s.strip_suffix(']'))
+                {
+                    Self::Fixed(length.trim().parse().map_err(|_| invalid())?)
+                } else {
+                    return Err(invalid());
+                }
+            }
+        })
+    }
+}
+
+impl TryFrom<String> for PrimitiveType {
+    type Error = IcebergError;
+
+    fn try_from(value: String) -> IcebergResult<Self> {
+        value.parse()
+    }
+}
+
+impl From<PrimitiveType> for String {
+    fn from(value: PrimitiveType) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for PrimitiveType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Boolean => write!(f, "boolean"),
+            Self::Int => write!(f, "int"),
+            Self::Long => write!(f, "long"),
+            Self::Float => write!(f, "float"),
+            Self::Double => write!(f, "double"),
+            Self::Decimal { precision, scale } => write!(f, "decimal({precision}, {scale})"),
+            Self::Date => write!(f, "date"),
+            Self::Time => write!(f, "time"),
+            Self::Timestamp => write!(f, "timestamp"),
+            Self::Timestamptz => write!(f, "timestamptz"),
+            Self::String => write!(f, "string"),
+            Self::Uuid => write!(f, "uuid"),
+            Self::Fixed(length) => write!(f, "fixed[{length}]"),
+            Self::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+/// How data files are partitioned
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PartitionSpec {
+    pub spec_id: i32,
+    pub fields: Vec<PartitionField>,
+}
+
+impl PartitionSpec {
+    /// Whether both specs partition the same source columns with the same transforms
+    pub fn is_compatible_with(&self, other: &PartitionSpec) -> bool {
+        let fields = |spec: &PartitionSpec| {
+            spec.fields
+                .iter()
+                .filter(|f| f.transform != Transform::Void)
+                .map(|f| (f.source_id, f.transform))
+                .collect::<Vec<_>>()
+        };
+        fields(self) == fields(other)
+    }
+}
+
+/// A partition value derived from a source column with a transform
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PartitionField {
+    pub source_id: i32,
+    /// Missing in format version 1, where ids are assigned from 1000 in order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_id: Option<i32>,
+    pub name: String,
+    pub transform: Transform,
+}
+
+/// Partition transforms, see <https://iceberg.apache.org/spec/#partition-transforms>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) enum Transform {
+    Identity,
+    Bucket(u32),
+    Truncate(u32),
+    Year,
+    Month,
+    Day,
+    Hour,
+    Void,
+}
+
+impl FromStr for Transform {
+    type Err = IcebergError;
+
+    fn from_str(s: &str) -> IcebergResult<Self> {
+        let invalid = || IcebergError::InvalidMetadata(format!("unknown transform {s}"));
+        let argument = |prefix: &str| {
+            s.strip_prefix(prefix)
+                .and_then(|s| s.strip_suffix(']'))
+                .map(|n| n.parse::<u32>().map_err(|_| invalid()))
+        };
+        Ok(match s {
+            "identity" => Self::Identity,
+            "year" => Self::Year,
+            "month" => Self::Month,
+            "day" => Self::Day,
+            "hour" => Self::Hour,
+            "void" => Self::Void,
+            _ => {
+                if let Some(n) = argument("bucket[") {
+                    Self::Bucket(n?)
+                } else if let Some(width) = argument("truncate[") {
+                    Self::Truncate(width?)
+                } else {
+                    return Err(invalid());
+                }
+            }
+        })
+    }
+}
+
+impl TryFrom<String> for Transform {
+    type Error = IcebergError;
+
+    fn try_from(value: String) -> IcebergResult<Self> {
+        value.parse()
+    }
+}
+
+impl From<Transform> for String {
+    fn from(value: Transform) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identity => write!(f, "identity"),
+            Self::Bucket(n) => write!(f, "bucket[{n}]"),
+            Self::Truncate(width) => write!(f, "truncate[{width}]"),
+            Self::Year => write!(f, "year"),
+            Self::Month => write!(f, "month"),
+            Self::Day => write!(f, "day"),
+            Self::Hour => write!(f, "hour"),
+            Self::Void => write!(f, "void"),
+        }
+    }
+}
+
+/// The state of the table at some point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Snapshot {
+    pub snapshot_id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_snapshot_id: Option<i64>,
+    #[serde(default)]
+    pub sequence_number: i64,
+    pub timestamp_ms: i64,
+    /// Avro file listing the manifests of the snapshot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_list: Option<String>,
+    /// Manifests of format version 1 snapshots written without a manifest list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifests: Option<Vec<String>>,
+    #[serde(default)]
+    pub summary: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_id: Option<i32>,
+}
+
+/// The order of rows in data files, Delta tables are always written unsorted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct SortOrder {
+    pub order_id: i32,
+    pub fields: Vec<serde_json::Value>,
+}
+
+impl SortOrder {
+    /// The order of unsorted tables, which has id 0
+    pub fn unsorted() -> Self {
+        Self {
+            order_id: 0,
+            fields: vec![],
+        }
+    }
+}
+
+/// A named branch or tag pointing at a snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct SnapshotReference {
+    pub snapshot_id: i64,
+    /// `branch` or `tag`
+    #[serde(rename = "type")]
+    pub reference_type: String,
+}
+
+/// When a snapshot became the current snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct SnapshotLogEntry {
+    pub snapshot_id: i64,
+    pub timestamp_ms: i64,
+}
+
+/// A previous metadata file of the table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct MetadataLogEntry {
+    pub metadata_file: String,
+    pub timestamp_ms: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table_metadata() {
+        let metadata: TableMetadata = serde_json::from_value(serde_json::json!({
+            "format-version": 2,
+            "table-uuid": "9c12d441-03fe-4693-9a96-a0705ddf69c1",
+            "location": "s3://bucket/warehouse/db/events",
+            "last-sequence-number": 1,
+            "last-updated-ms": 1602638573590i64,
+            "last-column-id": 5,
+            "current-schema-id": 0,
+            "schemas": [{
+                "type": "struct",
+                "schema-id": 0,
+                "fields": [
+                    {"id": 1, "name": "id", "required": true, "type": "long"},
+                    {"id": 2, "name": "ts", "required": false, "type": "timestamptz"},
+                    {"id": 3, "name": "price", "required": false, "type": "decimal(10, 2)"},
+                    {"id": 4, "name": "tags", "required": false, "type": {
+                        "type": "list", "element-id": 5, "element": "string", "element-required": false
+                    }}
+                ]
+            }],
+            "default-spec-id": 0,
+            "partition-specs": [{
+                "spec-id": 0,
+                "fields": [{"source-id": 2, "field-id": 1000, "name": "ts_day", "transform": "day"}]
+            }],
+            "last-partition-id": 1000,
+            "properties": {},
+            "current-snapshot-id": 3051729675574597004i64,
+            "snapshots": [{
+                "snapshot-id": 3051729675574597004i64,
+                "sequence-number": 1,
+                "timestamp-ms": 1515100955770i64,
+                "summary": {"operation": "append"},
+                "manifest-list": "s3://bucket/warehouse/db/events/metadata/snap-3051729675574597004.avro",
+                "schema-id": 0
+            }]
+        }))
+        .unwrap();
+
+        let schema = metadata.current_schema().unwrap();
+        assert_eq!(schema.fields.fields.len(), 4);
+        assert_eq!(
+            schema.fields.fields[2].field_type,
+            Type::Primitive(PrimitiveType::Decimal {
+                precision: 10,
+                scale: 2
+            })
+        );
+        assert!(matches!(schema.fields.fields[3].field_type, Type::List(_)));
+
+        let spec = metadata.default_partition_spec().unwrap();
+        assert_eq!(spec.fields[0].transform, Transform::Day);
+
+        let snapshot = metadata.current_snapshot().unwrap().unwrap();
+        assert!(snapshot.manifest_list.is_some());
+    }
+
+    #[test]
+    fn test_transform_round_trip() {
+        for transform in ["identity", "bucket[16]", "truncate[4]", "year", "void"] {
+            assert_eq!(
+                transform.parse::<Transform>().unwrap().to_string(),
+                transform
+            );
+        }
+        assert!("bucket[x]".parse::<Transform>().is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! Translation between Iceberg column metrics and Delta file statistics
+//!
+//! Iceberg stores lower and upper bounds in the single-value binary serialization of the column
+//! type, see <https://iceberg.apache.org/spec/#binary-single-value-serialization>.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime};
+use delta_kernel::schema::PrimitiveType;
+use serde_json::{Map, Value, json};
+
+use super::manifest::{DataContent, DataFile};
+use super::{IcebergError, IcebergResult};
+
+/// Days between 0001-01-01 and the unix epoch
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Delta statistics JSON of a data file.
+///
+/// `leaves` maps field ids to the physical path and type of the column, see
+/// [`leaf_fields`](super::schema::leaf_fields). Bounds of types that cannot be decoded without
+/// loss, like decimals, are left out.
+pub(crate) fn delta_stats(
+    data_file: &DataFile,
+    leaves: &HashMap<i64, (Vec<String>, PrimitiveType)>,
+) -> Value {
+    let mut min_values = Map::new();
+    let mut max_values = Map::new();
+    let mut null_count = Map::new();
+
+    for (id, (path, data_type)) in leaves {
+        let id = *id as i32;
+        if let Some(value) = data_file
+            .lower_bounds
+            .get(&id)
+            .and_then(|bytes| decode_bound(bytes, data_type))
+        {
+            insert_nested(&mut min_values, path, value);
+        }
+        if let Some(value) = data_file
+            .upper_bounds
+            .get(&id)
+            .and_then(|bytes| decode_bound(bytes, data_type))
+        {
+            insert_nested(&mut max_values, path, value);
+        }
+        if let Some(count) = data_file.null_value_counts.get(&id) {
+            insert_nested(&mut null_count, path, json!(count));
+        }
+    }
+
+    json!({
+        "numRecords": data_file.record_count,
+        "minValues": min_values,
+        "maxValues": max_values,
+        "nullCount": null_count,
+    })
+}
+
+/// A data file with the metrics of its Delta statistics JSON.
+///
+/// Delta statistics may be truncated: strings are cut to a prefix and timestamps written by Spark
+/// to milliseconds. Truncated lower bounds stay valid, upper bounds of strings are left out and
+/// those of timestamps extended to the end of the millisecond.
+pub(crate) fn iceberg_data_file(
+    file_path: String,
+    file_size_in_bytes: i64,
+    partition: Vec<(String, apache_avro::types::Value)>,
+    stats: &Value,
+    leaves: &HashMap<i64, (Vec<String>, PrimitiveType)>,
+) -> IcebergResult<DataFile> {
+    let record_count = stats
+        .get("numRecords")
+        .and_then(Value::as_i64)
+        .ok_or_else(|| {
+            IcebergError::InvalidMetadata(format!("statistics of {file_path} have no record count"))
+        })?;
+    let mut null_value_counts = HashMap::new();
+    let mut lower_bounds = HashMap::new();
+    let mut upper_bounds = HashMap::new();
+    for (id, (path, data_type)) in leaves {
+        let id = *id as i32;
+        if let Some(count) = get_nested(stats, "nullCount", path).and_then(Value::as_i64) {
+            null_value_counts.insert(id, count);
+        }
+        if let Some(bound) = get_nested(stats, "minValues", path)
+            .and_then(|value| encode_bound(value, data_type, false))
+        {
+            lower_bounds.insert(id, bound);
+        }
+        if let Some(bound) = get_nested(stats, "maxValues", path)
+            .and_then(|value| encode_bound(value, data_type, true))
+        {
+            upper_bounds.insert(id, bound);
+        }
+    }
+
+    Ok(DataFile {
+        content: DataContent::Data,
+        file_path,
+        file_format: "PARQUET".to_string(),
+        partition,
+        record_count,
+        file_size_in_bytes,
+        null_value_counts,
+        lower_bounds,
+        upper_bounds,
+    })
+}
+
+fn get_nested<'a>(stats: &'a Value, key: &str, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(stats.get(key)?, |value, name| value.get(name))
+}
+
+/// Encode a Delta statistics value as a bound, `None` if it cannot be used as one
+fn encode_bound(value: &Value, data_type: &PrimitiveType, upper: bool) -> Option<Vec<u8>> {
+    let micros = |ts: NaiveDateTime| {
+        let micros = ts.and_utc().timestamp_micros();
+        if upper && micros % 1000 == 0 {
+            micros + 999
+        } else {
+            micros
+        }
+    };
+    match data_type {
+        PrimitiveType::Byte | PrimitiveType::Short | PrimitiveType::Integer => {
+            Some(i32::try_from(value.as_i64()?).ok()?.to_le_bytes().to_vec())
+        }
+        PrimitiveType::Long => Some(value.as_i64()?.to_le_bytes().to_vec()),
+        PrimitiveType::Float => Some((value.as_f64()? as f32).to_le_bytes().to_vec()),
+        PrimitiveType::Double => Some(value.as_f64()?.to_le_bytes().to_vec()),
+        PrimitiveType::Date => {
+            let date = NaiveDate::parse_from_str(value.as_str()?, "%Y-%m-%d").ok()?;
+            let days = date.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE;
+            Some(days.to_le_bytes().to_vec())
+        }
+        PrimitiveType::Timestamp => {
+            let ts = DateTime::parse_from_rfc3339(value.as_str()?)
+                .ok()?
+                .naive_utc();
+            Some(micros(ts).to_le_bytes().to_vec())
+        }
+        PrimitiveType::TimestampNtz => {
+            let value = value.as_str()?;
+            let ts = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+                .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f"))
+                .ok()?;
+            Some(micros(ts).to_le_bytes().to_vec())
+        }
+        PrimitiveType::String if !upper => Some(value.as_str()?.as_bytes().to_vec()),
+        _ => None,
+    }
+}
+
+fn insert_nested(map: &mut Map<String, Value>, path: &[String], value: Value) {
+    match path {
+        [] => {}
+        [name] => {
+            map.insert(name.clone(), value);
+        }
+        [name, rest @ ..] => {
+            if let Value::Object(inner) = map
+                .entry(name.clone())
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                insert_nested(inner, rest, value);
+            }
+        }
+    }
+}
+
+/// Decode a bound into its Delta statistics value. Timestamps keep their microsecond precision,
+/// so upper bounds stay valid without rounding.
+fn decode_bound(bytes: &[u8], data_type: &PrimitiveType) -> Option<Value> {
+    let int = || Some(i32::from_le_bytes(bytes.try_into().ok()?));
+    let long = || Some(i64::from_le_bytes(bytes.try_into().ok()?));
+    match data_type {
+        PrimitiveType::Byte | PrimitiveType::Short | PrimitiveType::Integer => {
+            int().map(Value::from)
+        }
+        PrimitiveType::Long => long().map(Value::from),
+        PrimitiveType::Float => {
+            let v = f32::from_le_bytes(bytes.try_into().ok()?);
+            v.is_finite().then(|| Value::from(v))
+        }
+        PrimitiveType::Double => {
+            let v = f64::from_le_bytes(bytes.try_into().ok()?);
+            v.is_finite().then(|| Value::from(v))
+        }
+        PrimitiveType::Date => {
+            let date = NaiveDate::from_num_days_from_ce_opt(int()? + UNIX_EPOCH_DAYS_FROM_CE)?;
+            Some(Value::from(date.format("%Y-%m-%d").to_string()))
+        }
+        PrimitiveType::Timestamp => {
+            let ts = DateTime::from_timestamp_micros(long()?)?.naive_utc();
+            Some(Value::from(ts.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string()))
+        }
+        PrimitiveType::TimestampNtz => {
+            let ts = DateTime::from_timestamp_micros(long()?)?.naive_utc();
+            Some(Value::from(ts.format("%Y-%m-%d %H:%M:%S%.f").to_string()))
+        }
+        PrimitiveType::String => std::str::from_utf8(bytes).ok().map(Value::from),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iceberg::manifest::DataContent;
+
+    #[test]
+    fn test_delta_stats_from_bounds() {
+        let data_file = DataFile {
+            content: DataContent::Data,
+            file_path: "data/a.parquet".to_string(),
+            file_format: "PARQUET".to_string(),
+            partition: vec![],
+            record_count: 3,
+            file_size_in_bytes: 100,
+            null_value_counts: HashMap::from([(1, 0), (2, 1)]),
+            lower_bounds: HashMap::from([
+                (1, 5i64.to_le_bytes().to_vec()),
+                (2, b"apple".to_vec()),
+                (3, 18628i32.to_le_bytes().to_vec()),
+            ]),
+            upper_bounds: HashMap::from([
+                (1, 42i64.to_le_bytes().to_vec()),
+                (2, b"pear".to_vec()),
+                (3, 18632i32.to_le_bytes().to_vec()),
+            ]),
+        };
+        let leaves = HashMap::from([
+            (1, (vec!["id".to_string()], PrimitiveType::Long)),
+            (
+                2,
+                (
+                    vec!["fruit".to_string(), "name".to_string()],
+                    PrimitiveType::String,
+                ),
+            ),
+            (3, (vec!["day".to_string()], PrimitiveType::Date)),
+        ]);
+
+        assert_eq!(
+            delta_stats(&data_file, &leaves),
+            json!({
+                "numRecords": 3,
+                "minValues": {"id": 5, "fruit": {"name": "apple"}, "day": "2021-01-01"},
+                "maxValues": {"id": 42, "fruit": {"name": "pear"}, "day": "2021-01-05"},
+                "nullCount": {"id": 0, "fruit": {"name": 1}},
+            })
+        );
+    }
+
+    #[test]
+    fn test_iceberg_data_file_from_stats() {
+        let stats = json!({
+            "numRecords": 3,
+            "minValues": {"id": 5, "fruit": {"name": "apple"}, "ts": "2021-01-01T10:00:00Z"},
+            "maxValues": {"id": 42, "fruit": {"name": "pear"}, "ts": "2021-01-01T11:00:00Z"},
+            "nullCount": {"id": 0, "fruit": {"name": 1}, "ts": 0},
+        });
+        let leaves = HashMap::from([
+            (1, (vec!["id".to_string()], PrimitiveType::Long)),
+            (
+                2,
+                (
+                    vec!["fruit".to_string(), "name".to_string()],
+                    PrimitiveType::String,
+                ),
+            ),
+            (3, (vec!["ts".to_string()], PrimitiveType::Timestamp)),
+        ]);
+
+        let data_file =
+            iceberg_data_file("data/a.parquet".to_string(), 100, vec![], &stats, &leaves).unwrap();
+        assert_eq!(data_file.record_count, 3);
+        assert_eq!(
+            data_file.null_value_counts,
+            HashMap::from([(1, 0), (2, 1), (3, 0)])
+        );
+        assert_eq!(data_file.lower_bounds.get(&2), Some(&b"apple".to_vec()));
+        // the upper bound of a possibly truncated string is unknown
+        assert!(!data_file.upper_bounds.contains_key(&2));
+        assert_eq!(
+            data_file.upper_bounds.get(&3),
+            Some(&1_609_498_800_000_999i64.to_le_bytes().to_vec())
+        );
+
+        let round_trip = delta_stats(&data_file, &leaves);
+        assert_eq!(round_trip["minValues"]["id"], json!(5));
+        assert_eq!(round_trip["maxValues"]["id"], json!(42));
+        assert_eq!(round_trip["minValues"]["ts"], stats["minValues"]["ts"]);
+
+        assert!(
+            iceberg_data_file("b.parquet".to_string(), 1, vec![], &json!({}), &leaves).is_err()
+        );
+    }
+}
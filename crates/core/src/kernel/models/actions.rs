@@ -396,6 +396,9 @@ impl ProtocolInner {
                     "delta.enableRowTracking" if parse_bool(value) => {
                         Some(TableFeature::RowTracking)
                     }
+                    "delta.enableIcebergCompatV2" if parse_bool(value) => {
+                        Some(TableFeature::IcebergCompatV2)
+                    }
                     "delta.checkpointPolicy" if value == "v2" => Some(TableFeature::V2Checkpoint),
                     _ => None,
                 })
@@ -442,6 +445,7 @@ impl ProtocolInner {
     /// features
     pub fn apply_column_metadata_to_protocol(mut self, schema: &StructType) -> DeltaResult<Self> {
         let generated_cols = schema.get_generated_columns()?;
+        let column_defaults = schema.get_column_defaults()?;
         let invariants = schema.get_invariants()?;
         let contains_timestamp_ntz = self.contains_timestampntz(schema.fields());
         #[cfg(feature = "nanosecond-timestamps")]
@@ -463,6 +467,10 @@ impl ProtocolInner {
             self = self.enable_variant_type()
         }
 
+        if !column_defaults.is_empty() {
+            self = self.enable_column_defaults()
+        }
+
         if !generated_cols.is_empty() {
             self = self.enable_generated_columns()
         }
@@ -601,6 +609,31 @@ impl ProtocolInner {
                 }
             }
         }
+
+        if let Some(enable_iceberg_compat) =
+            parsed_properties.get(&TableProperty::EnableIcebergCompatV2)
+        {
+            match enable_iceberg_compat.to_ascii_lowercase().parse::<bool>() {
+                // Upgrading older protocols would drop their implicit features like column mapping
+                Ok(true) if self.min_writer_version < 7 => {
+                    return Err(Error::Generic(
+                        "delta.enableIcebergCompatV2 requires table features, add the icebergCompatV2 feature to the table instead"
+                            .to_string(),
+                    ));
+                }
+                Ok(true) => {
+                    self.writer_features
+                        .get_or_insert_with(HashSet::new)
+                        .insert(TableFeature::IcebergCompatV2);
+                }
+                Ok(false) => {}
+                _ => {
+                    return Err(Error::Generic(format!(
+                        "delta.enableIcebergCompatV2 = '{enable_iceberg_compat}' is invalid, valid values are ['true', 'false']"
+                    )));
+                }
+            }
+        }
         Ok(self)
     }
 
@@ -653,6 +686,14 @@ impl ProtocolInner {
         self
     }
 
+    /// Enable column defaults, a writer only table feature
+    fn enable_column_defaults(self) -> Self {
+        match TableFeature::try_from(&TableFeatures::AllowColumnDefaults) {
+            Ok(feature) => self.append_writer_features([feature]),
+            Err(_) => self,
+        }
+    }
+
     /// Enabled generated columns
     fn enable_invariants(mut self) -> Self {
         if self.min_writer_version >= 7 {
@@ -697,6 +738,8 @@ pub enum TableFeatures {
     DomainMetadata,
     /// Iceberg compatibility support
     IcebergCompatV1,
+    /// Iceberg compatibility support, required to generate Iceberg metadata
+    IcebergCompatV2,
     /// Variant type support
     VariantType,
     /// Preview variant type support
@@ -705,6 +748,10 @@ pub enum TableFeatures {
     VariantShreddingPreview,
     /// Support for materializing partition column values into data files.
     MaterializePartitionColumns,
+    /// Columns with a default value
+    AllowColumnDefaults,
+    /// Commits are ratified by a catalog, see [`crate::logstore::CommitCoordinatorLogStore`]
+    CatalogManaged,
 }
 
 impl FromStr for TableFeatures {
@@ -727,10 +774,13 @@ impl FromStr for TableFeatures {
             "rowTracking" => Ok(TableFeatures::RowTracking),
             "domainMetadata" => Ok(TableFeatures::DomainMetadata),
             "icebergCompatV1" => Ok(TableFeatures::IcebergCompatV1),
+            "icebergCompatV2" => Ok(TableFeatures::IcebergCompatV2),
             "variantType" => Ok(TableFeatures::VariantType),
             "variantType-preview" => Ok(TableFeatures::VariantTypePreview),
             "variantShredding-preview" => Ok(TableFeatures::VariantShreddingPreview),
             "materializePartitionColumns" => Ok(TableFeatures::MaterializePartitionColumns),
+            "allowColumnDefaults" => Ok(TableFeatures::AllowColumnDefaults),
+            "catalogManaged" => Ok(TableFeatures::CatalogManaged),
             _ => Err(()),
         }
     }
@@ -754,10 +804,13 @@ impl AsRef<str> for TableFeatures {
             TableFeatures::RowTracking => "rowTracking",
             TableFeatures::DomainMetadata => "domainMetadata",
             TableFeatures::IcebergCompatV1 => "icebergCompatV1",
+            TableFeatures::IcebergCompatV2 => "icebergCompatV2",
             TableFeatures::VariantType => "variantType",
             TableFeatures::VariantTypePreview => "variantType-preview",
             TableFeatures::VariantShreddingPreview => "variantShredding-preview",
             TableFeatures::MaterializePartitionColumns => "materializePartitionColumns",
+            TableFeatures::AllowColumnDefaults => "allowColumnDefaults",
+            TableFeatures::CatalogManaged => "catalogManaged",
         }
     }
 }
@@ -780,6 +833,11 @@ impl TableFeatures {
     /// Convert table feature to respective reader or/and write feature
     pub fn to_reader_writer_features(&self) -> (Option<TableFeature>, Option<TableFeature>) {
         let feature = TableFeature::try_from(self).ok();
+        // Column defaults are not modelled by the kernel and would otherwise be ignored as an
+        // unknown feature.
+        if matches!(self, TableFeatures::AllowColumnDefaults) {
+            return (None, feature);
+        }
         match feature {
             Some(feature) => {
                 // Classify features based on their type
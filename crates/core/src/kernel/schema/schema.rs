@@ -11,13 +11,18 @@ use serde_json::Value;
 
 use crate::kernel::error::Error;
 use crate::schema::DataCheck;
-use crate::table::GeneratedColumn;
+use crate::table::{ColumnDefault, GeneratedColumn};
 
 /// Type alias for a top level schema
 pub type Schema = StructType;
 /// Schema reference type
 pub type SchemaRef = Arc<StructType>;
 
+/// Field metadata key holding the SQL expression of a column's default value.
+///
+/// Requires the `allowColumnDefaults` writer feature.
+pub const COLUMN_DEFAULT_KEY: &str = "CURRENT_DEFAULT";
+
 /// An invariant for a column that is enforced on all writes to a Delta table.
 #[derive(Eq, PartialEq, Debug, Default, Clone)]
 pub struct Invariant {
@@ -58,6 +63,9 @@ pub trait StructTypeExt {
 
     /// Get all generated column expressions
     fn get_generated_columns(&self) -> Result<Vec<GeneratedColumn>, Error>;
+
+    /// Get all column default expressions
+    fn get_column_defaults(&self) -> Result<Vec<ColumnDefault>, Error>;
 }
 
 impl StructTypeExt for StructType {
@@ -84,6 +92,53 @@ impl StructTypeExt for StructType {
         Ok(generated_cols)
     }
 
+    /// Get all columns with a `CURRENT_DEFAULT` expression in the schema
+    ///
+    /// Only top level columns can have a default value, nested fields with a default are an
+    /// error as writers cannot fill them in.
+    fn get_column_defaults(&self) -> Result<Vec<ColumnDefault>, Error> {
+        let mut remaining_fields: Vec<(String, &DataType)> = self
+            .fields()
+            .map(|field| (field.name.clone(), field.data_type()))
+            .collect();
+        while let Some((field_path, data_type)) = remaining_fields.pop() {
+            match data_type {
+                DataType::Struct(inner) => {
+                    for field in inner.fields() {
+                        let nested_path = format!("{field_path}.{}", field.name);
+                        if field.metadata.contains_key(COLUMN_DEFAULT_KEY) {
+                            return Err(Error::Schema(format!(
+                                "Default values are only supported for top level columns, \
+                                 found one for nested field {nested_path}"
+                            )));
+                        }
+                        remaining_fields.push((nested_path, field.data_type()));
+                    }
+                }
+                DataType::Array(inner) => {
+                    remaining_fields.push((format!("{field_path}.element"), &inner.element_type));
+                }
+                DataType::Map(inner) => {
+                    remaining_fields.push((format!("{field_path}.key"), &inner.key_type));
+                    remaining_fields.push((format!("{field_path}.value"), &inner.value_type));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(self
+            .fields()
+            .filter_map(|field| match field.metadata.get(COLUMN_DEFAULT_KEY) {
+                Some(MetadataValue::String(default_expr)) => Some(ColumnDefault::new(
+                    &field.name,
+                    default_expr,
+                    field.data_type(),
+                )),
+                _ => None,
+            })
+            .collect())
+    }
+
     /// Get all invariants in the schemas
     fn get_invariants(&self) -> Result<Vec<Invariant>, Error> {
         let mut remaining_fields: Vec<(String, StructField)> = self
@@ -202,6 +257,43 @@ mod tests {
         assert_eq!(cols.len(), 2);
     }
 
+    #[test]
+    fn test_get_column_defaults() {
+        let schema: StructType = serde_json::from_value(json!(
+            {
+                "type":"struct",
+                "fields":[
+                    {"name":"id","type":"integer","nullable":true,"metadata":{}},
+                    {"name":"status","type":"string","nullable":false,"metadata":{"CURRENT_DEFAULT":"'active'"}},
+                    {"name":"gc","type":"integer","nullable":true,"metadata":{"delta.generationExpression":"5"}}]
+            }
+        )).unwrap();
+        let defaults = schema.get_column_defaults().unwrap();
+        assert_eq!(defaults.len(), 1);
+        assert_eq!(defaults[0].name, "status");
+        assert_eq!(defaults[0].default_expr, "'active'");
+        assert_eq!(defaults[0].data_type, DataType::STRING);
+
+        let schema: StructType = serde_json::from_value(json!(
+            {
+                "type":"struct",
+                "fields":[
+                    {"name":"id","type":"integer","nullable":true,"metadata":{}},
+                    {"name":"items","type":{"type":"array","containsNull":true,"elementType":{
+                        "type":"struct",
+                        "fields":[
+                            {"name":"status","type":"string","nullable":true,"metadata":{"CURRENT_DEFAULT":"'active'"}}]
+                    }},"nullable":true,"metadata":{}}]
+            }
+        )).unwrap();
+        let err = schema.get_column_defaults().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("nested field items.element.status"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_get_invariants() {
         let schema: StructType = serde_json::from_value(json!({
@@ -23,6 +23,7 @@ use arrow::datatypes::SchemaRef;
 use delta_kernel::actions::{Remove, Sidecar};
 use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
 use delta_kernel::engine::arrow_data::ArrowEngineData;
+use delta_kernel::log_segment::LogSegment;
 use delta_kernel::path::{LogPathFileType, ParsedLogPath};
 use delta_kernel::scan::scan_row_schema;
 use delta_kernel::schema::derive_macro_utils::ToDataType;
@@ -46,6 +47,7 @@ use crate::checkpoints::parse_last_checkpoint_hint;
 use crate::kernel::arrow::engine_ext::{ExpressionEvaluatorExt, rb_from_scan_meta};
 use crate::kernel::{ARROW_HANDLER, StructType, spawn_blocking_with_span};
 use crate::logstore::{LogStore, LogStoreExt};
+use crate::protocol::checksum::read_version_checksum;
 use crate::{DeltaResult, DeltaTableConfig, DeltaTableError, PartitionFilter, to_kernel_predicate};
 
 pub use self::log_data::*;
@@ -151,6 +153,16 @@ impl<'a> ConflictReadSet<'a> {
     }
 }
 
+/// Error of the kernel while loading a snapshot
+fn snapshot_error(e: delta_kernel::Error) -> DeltaTableError {
+    // TODO: we should have more handling-friendly errors upstream in kernel.
+    if e.to_string().contains("No files in log segment") {
+        DeltaTableError::NotATable(e.to_string())
+    } else {
+        e.into()
+    }
+}
+
 /// A snapshot of a Delta table
 #[derive(Debug, Clone, PartialEq)]
 pub struct Snapshot {
@@ -184,14 +196,7 @@ impl Snapshot {
         .map_err(|e| DeltaTableError::Generic(e.to_string()))?
         {
             Ok(snapshot) => snapshot,
-            Err(e) => {
-                // TODO: we should have more handling-friendly errors upstream in kernel.
-                if e.to_string().contains("No files in log segment") {
-                    return Err(DeltaTableError::NotATable(e.to_string()));
-                } else {
-                    return Err(e.into());
-                }
-            }
+            Err(e) => return Err(snapshot_error(e)),
         };
 
         Ok(Self {
@@ -218,7 +223,79 @@ impl Snapshot {
             table_root.set_path(&format!("{}/", table_root.path()));
         }
 
-        Self::try_new_with_engine(engine, table_root, config, version).await
+        // The log is listed once. The protocol and metadata are taken from the version checksum
+        // if there is one for the snapshot version, otherwise they are replayed from the log.
+        let storage = engine.storage_handler();
+        let log_root = log_store.log_root_url();
+        let log_segment = spawn_blocking_with_span(move || {
+            LogSegment::for_snapshot(storage.as_ref(), log_root, Vec::new(), version)
+        })
+        .await
+        .map_err(|e| DeltaTableError::Generic(e.to_string()))?
+        .map_err(snapshot_error)?;
+
+        let snapshot =
+            match Self::table_configuration_from_checksum(log_store, &table_root, &log_segment)
+                .await
+            {
+                Some(table_configuration) => KernelSnapshot::new(log_segment, table_configuration),
+                None => spawn_blocking_with_span(move || {
+                    KernelSnapshot::try_new_from_log_segment(
+                        table_root,
+                        log_segment,
+                        engine.as_ref(),
+                    )
+                })
+                .await
+                .map_err(|e| DeltaTableError::Generic(e.to_string()))?
+                .map_err(snapshot_error)?,
+            };
+        Ok(Self {
+            inner: Arc::new(snapshot),
+            config,
+            materialized_files: None,
+        })
+    }
+
+    /// The table configuration recorded in the version checksum of the end version of the
+    /// log segment, so the protocol and metadata do not have to be replayed from the log.
+    ///
+    /// Returns `None` if the version has no usable checksum.
+    async fn table_configuration_from_checksum(
+        log_store: &dyn LogStore,
+        table_root: &Url,
+        log_segment: &LogSegment,
+    ) -> Option<TableConfiguration> {
+        let version = log_segment.end_version;
+        if log_segment
+            .listed
+            .latest_crc_file
+            .as_ref()
+            .is_none_or(|crc| crc.version != version)
+        {
+            return None;
+        }
+
+        let checksum = match read_version_checksum(log_store, version).await {
+            Ok(checksum) => checksum?,
+            Err(err) => {
+                tracing::warn!(version, "ignoring unreadable version checksum: {err}");
+                return None;
+            }
+        };
+        let table_configuration = TableConfiguration::try_new(
+            checksum.metadata,
+            checksum.protocol,
+            table_root.clone(),
+            version,
+        )
+        .inspect_err(|err| tracing::warn!(version, "ignoring invalid version checksum: {err}"))
+        .ok()?;
+        tracing::debug!(
+            version,
+            "loaded protocol and metadata from version checksum"
+        );
+        Some(table_configuration)
     }
 
     /// Create a [`ScanBuilder`] borrowing this snapshot to configure a read of the table.
@@ -346,6 +423,17 @@ impl Snapshot {
         self.inner.log_segment().checkpoint_version
     }
 
+    /// Get the timestamp of the given version, if its commit file is part of the log segment.
+    pub(crate) fn version_timestamp(&self, version: Version) -> Option<i64> {
+        self.inner
+            .log_segment()
+            .listed
+            .ascending_commit_files
+            .iter()
+            .find(|path| path.version == version)
+            .map(|path| path.location.last_modified)
+    }
+
     /// Get the logical table schema of the snapshot
     pub fn schema(&self) -> KernelSchemaRef {
         self.inner.table_configuration().logical_schema()
@@ -382,6 +470,18 @@ impl Snapshot {
         self.materialized_files.as_ref()
     }
 
+    /// A copy of this snapshot without materialized file state.
+    ///
+    /// Useful when the snapshot is serialized as part of a plan and only the log segment
+    /// and table configuration need to travel.
+    pub(crate) fn without_materialized_files(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            config: self.config.clone(),
+            materialized_files: None,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn has_materialized_files_for_test(&self) -> bool {
         self.materialized_files.is_some()
@@ -897,6 +997,20 @@ impl Snapshot {
         log_store: &dyn LogStore,
         limit: Option<usize>,
     ) -> DeltaResult<BoxStream<'_, DeltaResult<Option<CommitInfo>>>> {
+        Ok(self
+            .versioned_commit_infos(log_store, limit)
+            .await?
+            .map_ok(|(_, commit_info)| commit_info)
+            .boxed())
+    }
+
+    /// Get the commit infos in the snapshot together with the version of their commit, newest
+    /// first. Commits newer than the snapshot are not listed.
+    pub(crate) async fn versioned_commit_infos(
+        &self,
+        log_store: &dyn LogStore,
+        limit: Option<usize>,
+    ) -> DeltaResult<BoxStream<'_, DeltaResult<(Version, Option<CommitInfo>)>>> {
         let store = log_store.root_object_store(None);
 
         let log_root = self.table_root_path()?.join("_delta_log");
@@ -923,13 +1037,14 @@ impl Snapshot {
                 .map_err(|err| DeltaTableError::InvalidTableLocation(err.to_string()))?;
             if let Some(parsed_path) = ParsedLogPath::try_from(dummy_path)?
                 && matches!(parsed_path.file_type, LogPathFileType::Commit)
+                && parsed_path.version <= self.version()
             {
-                commit_files.push(meta);
+                commit_files.push((parsed_path.version, meta));
             }
         }
-        commit_files.sort_unstable_by(|a, b| b.location.cmp(&a.location));
+        commit_files.sort_unstable_by(|a, b| b.0.cmp(&a.0));
         Ok(futures::stream::iter(commit_files)
-            .map(move |meta| {
+            .map(move |(version, meta)| {
                 let store = store.clone();
                 async move {
                     let commit_log_bytes = store.get(&meta.location).await?.bytes().await?;
@@ -938,10 +1053,10 @@ impl Snapshot {
                     {
                         let action = result?;
                         if let Action::CommitInfo(commit_info) = action {
-                            return Ok::<_, DeltaTableError>(Some(commit_info));
+                            return Ok::<_, DeltaTableError>((version, Some(commit_info)));
                         }
                     }
-                    Ok(None)
+                    Ok((version, None))
                 }
             })
             .buffered(self.config.log_buffer_size)
@@ -1293,18 +1408,7 @@ impl EagerSnapshot {
 
     /// Get the timestamp of the given version
     pub fn version_timestamp(&self, version: Version) -> Option<i64> {
-        for path in &self
-            .snapshot
-            .inner
-            .log_segment()
-            .listed
-            .ascending_commit_files
-        {
-            if path.version == version {
-                return Some(path.location.last_modified);
-            }
-        }
-        None
+        self.snapshot.version_timestamp(version)
     }
 
     /// Get the table schema of the snapshot
@@ -349,6 +349,21 @@ impl WinningCommitSummary {
         }
     }
 
+    /// Paths of files which are re-added with a new deletion vector, i.e. only had rows deleted
+    pub fn deletion_vector_updated_files(&self) -> HashSet<String> {
+        let removed: HashSet<String> = self
+            .removed_files()
+            .into_iter()
+            .filter(|r| r.data_change)
+            .map(|r| r.path)
+            .collect();
+        self.added_files()
+            .into_iter()
+            .filter(|a| a.deletion_vector.is_some() && removed.contains(&a.path))
+            .map(|a| a.path)
+            .collect()
+    }
+
     pub fn is_blind_append(&self) -> Option<bool> {
         self.commit_info
             .as_ref()
@@ -364,6 +379,8 @@ pub(crate) struct ConflictChecker<'a> {
     winning_commit_summary: WinningCommitSummary,
     /// Isolation level for the current transaction
     isolation_level: IsolationLevel,
+    /// Files where both transactions only deleted distinct rows via deletion vectors
+    resolved_deletion_vectors: HashSet<String>,
 }
 
 impl<'a> ConflictChecker<'a> {
@@ -400,9 +417,19 @@ impl<'a> ConflictChecker<'a> {
             txn_info: transaction_info,
             winning_commit_summary,
             isolation_level,
+            resolved_deletion_vectors: HashSet::new(),
         }
     }
 
+    /// Files which no longer conflict since the transactions deleted distinct rows from them.
+    ///
+    /// The current transaction must be rebased to combine both deletion vectors before it is
+    /// committed.
+    pub fn with_resolved_deletion_vectors(mut self, paths: HashSet<String>) -> Self {
+        self.resolved_deletion_vectors = paths;
+        self
+    }
+
     /// This function checks conflict of the `initial_current_transaction_info` against the
     /// `winning_commit_version` and returns an updated [`TransactionInfo`] that represents
     /// the transaction as if it had started while reading the `winning_commit_version`.
@@ -477,7 +504,7 @@ impl<'a> ConflictChecker<'a> {
         }
 
         // Fail if new files have been added that the txn should have read.
-        let added_files_to_check = match self.isolation_level {
+        let mut added_files_to_check = match self.isolation_level {
             IsolationLevel::WriteSerializable if !self.txn_info.metadata_changed() => {
                 // don't conflict with blind appends
                 self.winning_commit_summary.changed_data_added_files()
@@ -489,6 +516,10 @@ impl<'a> ConflictChecker<'a> {
             }
             IsolationLevel::SnapshotIsolation => vec![],
         };
+        // Files which only had rows deleted add no new data, removing rows the current
+        // transaction read is checked against its read files.
+        let dv_updated_files = self.winning_commit_summary.deletion_vector_updated_files();
+        added_files_to_check.retain(|f| !dv_updated_files.contains(&f.path));
 
         // Here we need to check if the current transaction would have read the
         // added files. for this we need to be able to evaluate predicates. Err on the safe side is
@@ -557,7 +588,7 @@ impl<'a> ConflictChecker<'a> {
             .winning_commit_summary
             .removed_files()
             .into_iter()
-            .filter(|r| r.data_change)
+            .filter(|r| r.data_change && !self.resolved_deletion_vectors.contains(&r.path))
             .collect();
 
         let deleted_read_overlap = removed_files_with_data_change
@@ -598,6 +629,7 @@ impl<'a> ConflictChecker<'a> {
             .collect();
         let intersection: HashSet<&String> = txn_deleted_files
             .intersection(&winning_deleted_files)
+            .filter(|path| !self.resolved_deletion_vectors.contains(*path))
             .collect();
 
         if !intersection.is_empty() {
@@ -732,6 +764,26 @@ mod tests {
         concurrent: Vec<Action>,
         actions: Vec<Action>,
         read_whole_table: bool,
+    ) -> Result<(), CommitConflictError> {
+        execute_test_with_resolved_deletion_vectors(
+            setup,
+            reads,
+            concurrent,
+            actions,
+            read_whole_table,
+            HashSet::new(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "datafusion")]
+    async fn execute_test_with_resolved_deletion_vectors(
+        setup: Option<Vec<Action>>,
+        reads: Option<Expr>,
+        concurrent: Vec<Action>,
+        actions: Vec<Action>,
+        read_whole_table: bool,
+        resolved_deletion_vectors: HashSet<String>,
     ) -> Result<(), CommitConflictError> {
         use crate::table::state::DeltaTableState;
         use object_store::path::Path;
@@ -746,10 +798,23 @@ mod tests {
             actions: concurrent,
             commit_info: None,
         };
-        let checker = ConflictChecker::new(transaction_info, summary, None);
+        let checker = ConflictChecker::new(transaction_info, summary, None)
+            .with_resolved_deletion_vectors(resolved_deletion_vectors);
         checker.check_conflicts()
     }
 
+    fn deletion_vector_update(add: &Add, cardinality: i64) -> Vec<Action> {
+        let mut updated = add.clone();
+        updated.deletion_vector = Some(crate::kernel::DeletionVectorDescriptor {
+            storage_type: crate::kernel::StorageType::Inline,
+            path_or_inline_dv: "dv".into(),
+            offset: None,
+            size_in_bytes: 0,
+            cardinality,
+        });
+        vec![ActionFactory::remove(add, true).into(), updated.into()]
+    }
+
     // tests adopted from https://github.com/delta-io/delta/blob/24c025128612a4ae02d0ad958621f928cda9a3ec/core/src/test/scala/org/apache/spark/sql/delta/OptimisticTransactionSuite.scala#L40-L94
     #[tokio::test]
     #[cfg(feature = "datafusion")]
@@ -1276,4 +1341,39 @@ mod tests {
             "Disjoint replaceWhere-style transactions with empty reads should succeed"
         );
     }
+
+    #[tokio::test]
+    #[cfg(feature = "datafusion")]
+    async fn test_concurrent_deletion_vector_updates() {
+        // both transactions delete rows from the same file
+        let file = simple_add(true, "1", "10");
+        let mut setup_actions = init_table_actions();
+        setup_actions.push(file.clone().into());
+        let read = Some(col("value").lt_eq(lit::<i32>(10)));
+
+        let result = execute_test(
+            Some(setup_actions.clone()),
+            read.clone(),
+            deletion_vector_update(&file, 1),
+            deletion_vector_update(&file, 1),
+            false,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(CommitConflictError::ConcurrentDeleteRead)
+        ));
+
+        // distinct rows were deleted, the re-added file is not a concurrent append
+        let result = execute_test_with_resolved_deletion_vectors(
+            Some(setup_actions),
+            read,
+            deletion_vector_update(&file, 1),
+            deletion_vector_update(&file, 1),
+            false,
+            HashSet::from([file.path.clone()]),
+        )
+        .await;
+        assert!(result.is_ok());
+    }
 }
@@ -0,0 +1,423 @@
+//! Row level conflict resolution for concurrent deletion vector updates.
+//!
+//! When two transactions only mark rows of the same file as deleted, they only conflict if they
+//! delete the same rows. Otherwise the current transaction can be rebased onto the winning
+//! commit by combining both deletion vectors.
+//!
+//! Deletion vectors are read and written in the `RoaringBitmapArray` format described in the
+//! [protocol] using the `roaring` crate. Small combined deletion vectors are stored inline in
+//! the log, larger ones are written to a `deletion_vector_<uuid>.bin` file in the table root.
+//!
+//! [protocol]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#deletion-vector-format
+
+use std::collections::HashMap;
+
+use object_store::ObjectStoreExt as _;
+use object_store::path::Path;
+use roaring::RoaringTreemap;
+use url::Url;
+use uuid::Uuid;
+
+use crate::kernel::{Action, DeletionVectorDescriptor, StorageType};
+use crate::logstore::{LogStore, object_store_path};
+use crate::{DeltaResult, DeltaTableError};
+
+const MAGIC_NUMBER: u32 = 1681511377;
+/// Version byte at the start of a deletion vector file
+const FILE_FORMAT_VERSION: u8 = 1;
+/// Deletion vectors up to this serialized size are stored inline in the log
+const MAX_INLINE_SIZE: usize = 256;
+
+/// A file whose deletion vector was updated by the winning and the current transaction.
+#[derive(Debug, Clone)]
+pub(crate) struct ConcurrentDeletionVectorUpdate {
+    /// Deletion vector of the file after the winning commit
+    pub winning: DeletionVectorDescriptor,
+    /// Deletion vector with the rows deleted by both transactions, if they deleted distinct rows
+    pub merged: Option<DeletionVectorDescriptor>,
+}
+
+/// Files where both `winning` and `current` only changed the deletion vector, starting from the
+/// same deletion vector.
+pub(crate) async fn concurrent_deletion_vector_updates(
+    log_store: &dyn LogStore,
+    winning: &[Action],
+    current: &[Action],
+) -> DeltaResult<HashMap<String, ConcurrentDeletionVectorUpdate>> {
+    let winning_updates = deletion_vector_updates(winning);
+    if winning_updates.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut updates = HashMap::new();
+    for (path, (current_base, current_dv)) in deletion_vector_updates(current) {
+        let Some((winning_base, winning_dv)) = winning_updates.get(&path) else {
+            continue;
+        };
+        if winning_base != &current_base {
+            continue;
+        }
+
+        let base = match &current_base {
+            Some(dv) => read_deletion_vector(log_store, dv).await?,
+            None => RoaringTreemap::new(),
+        };
+        let winning_rows = read_deletion_vector(log_store, winning_dv).await?;
+        let current_rows = read_deletion_vector(log_store, current_dv).await?;
+
+        let overlap = !(&winning_rows - &base).is_disjoint(&current_rows);
+        let merged = if overlap {
+            None
+        } else {
+            Some(store_deletion_vector(log_store, &(winning_rows | current_rows)).await?)
+        };
+        updates.insert(
+            path,
+            ConcurrentDeletionVectorUpdate {
+                winning: (*winning_dv).clone(),
+                merged,
+            },
+        );
+    }
+    Ok(updates)
+}
+
+/// Rebase `actions` onto the winning commit for the given files.
+///
+/// Removes now refer to the deletion vector written by the winning commit, adds carry the
+/// merged deletion vector.
+pub(crate) fn rebase_deletion_vector_updates(
+    actions: &mut [Action],
+    updates: &HashMap<String, ConcurrentDeletionVectorUpdate>,
+) {
+    for action in actions.iter_mut() {
+        match action {
+            Action::Remove(remove) => {
+                if let Some(update) = updates.get(&remove.path)
+                    && update.merged.is_some()
+                {
+                    remove.deletion_vector = Some(update.winning.clone());
+                }
+            }
+            Action::Add(add) => {
+                if let Some(merged) = updates.get(&add.path).and_then(|u| u.merged.as_ref()) {
+                    add.deletion_vector = Some(merged.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Files whose deletion vector is replaced by the actions, keyed by path, with the previous and
+/// new deletion vector.
+fn deletion_vector_updates(
+    actions: &[Action],
+) -> HashMap<String, (Option<DeletionVectorDescriptor>, &DeletionVectorDescriptor)> {
+    let removes: HashMap<&str, Option<&DeletionVectorDescriptor>> = actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::Remove(remove) if remove.data_change => {
+                Some((remove.path.as_str(), remove.deletion_vector.as_ref()))
+            }
+            _ => None,
+        })
+        .collect();
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::Add(add) => {
+                let dv = add.deletion_vector.as_ref()?;
+                let base = removes.get(add.path.as_str())?;
+                Some((add.path.clone(), ((*base).cloned(), dv)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Read the row indexes marked as deleted by a deletion vector.
+pub(crate) async fn read_deletion_vector(
+    log_store: &dyn LogStore,
+    dv: &DeletionVectorDescriptor,
+) -> DeltaResult<RoaringTreemap> {
+    let (store, path) = match dv.storage_type {
+        StorageType::Inline => {
+            let bytes = z85_decode(&dv.path_or_inline_dv)?;
+            return deserialize_bitmap_array(&bytes);
+        }
+        StorageType::UuidRelativePath => (
+            log_store.object_store(None),
+            uuid_relative_path(&dv.path_or_inline_dv)?,
+        ),
+        StorageType::AbsolutePath => {
+            let url = Url::parse(&dv.path_or_inline_dv)
+                .map_err(|e| DeltaTableError::Generic(e.to_string()))?;
+            (log_store.root_object_store(None), object_store_path(&url)?)
+        }
+    };
+
+    // Each deletion vector in a file is prefixed with its size and followed by a checksum
+    let offset = dv.offset.unwrap_or(1) as u64;
+    let size = dv.size_in_bytes as u64;
+    let bytes = store
+        .get_range(&path, offset..offset + 4 + size + 4)
+        .await?;
+    let stored_size = u32::from_be_bytes(read_array(&bytes, 0)?) as u64;
+    if stored_size != size {
+        return Err(invalid_dv(format!(
+            "size {stored_size} does not match descriptor size {size}"
+        )));
+    }
+    let data = &bytes[4..4 + size as usize];
+    let checksum = u32::from_be_bytes(read_array(&bytes, 4 + size as usize)?);
+    if crc32fast::hash(data) != checksum {
+        return Err(invalid_dv(format!("checksum mismatch in '{path}'")));
+    }
+    deserialize_bitmap_array(data)
+}
+
+/// Store a deletion vector marking `rows`, inline if it is small and in a new deletion vector
+/// file in the table root otherwise.
+pub(crate) async fn store_deletion_vector(
+    log_store: &dyn LogStore,
+    rows: &RoaringTreemap,
+) -> DeltaResult<DeletionVectorDescriptor> {
+    let data = serialize_bitmap_array(rows);
+    if data.len() <= MAX_INLINE_SIZE {
+        return Ok(inline_descriptor(rows, &data));
+    }
+
+    let id = Uuid::new_v4();
+    let mut file = Vec::with_capacity(1 + 4 + data.len() + 4);
+    file.push(FILE_FORMAT_VERSION);
+    file.extend((data.len() as u32).to_be_bytes());
+    file.extend(&data);
+    file.extend(crc32fast::hash(&data).to_be_bytes());
+    let path = Path::from(format!("deletion_vector_{id}.bin"));
+    log_store.object_store(None).put(&path, file.into()).await?;
+
+    Ok(DeletionVectorDescriptor {
+        storage_type: StorageType::UuidRelativePath,
+        path_or_inline_dv: z85_encode(id.as_bytes()),
+        offset: Some(1),
+        size_in_bytes: data.len() as i32,
+        cardinality: rows.len() as i64,
+    })
+}
+
+/// Deletion vector storing `rows` inline in the log
+#[cfg(test)]
+pub(crate) fn inline_deletion_vector(rows: &RoaringTreemap) -> DeletionVectorDescriptor {
+    inline_descriptor(rows, &serialize_bitmap_array(rows))
+}
+
+fn inline_descriptor(rows: &RoaringTreemap, data: &[u8]) -> DeletionVectorDescriptor {
+    DeletionVectorDescriptor {
+        storage_type: StorageType::Inline,
+        path_or_inline_dv: z85_encode(data),
+        offset: None,
+        size_in_bytes: data.len() as i32,
+        cardinality: rows.len() as i64,
+    }
+}
+
+fn uuid_relative_path(path_or_inline_dv: &str) -> DeltaResult<Path> {
+    if path_or_inline_dv.len() < 20 {
+        return Err(invalid_dv(format!("invalid path '{path_or_inline_dv}'")));
+    }
+    let (prefix, encoded) = path_or_inline_dv.split_at(path_or_inline_dv.len() - 20);
+    let bytes: [u8; 16] = z85_decode(encoded)?
+        .try_into()
+        .map_err(|_| invalid_dv(format!("invalid uuid '{encoded}'")))?;
+    let file_name = format!("deletion_vector_{}.bin", Uuid::from_bytes(bytes));
+    Ok(if prefix.is_empty() {
+        Path::from(file_name)
+    } else {
+        Path::from_iter([prefix, &file_name])
+    })
+}
+
+fn invalid_dv(msg: String) -> DeltaTableError {
+    DeltaTableError::Generic(format!("Invalid deletion vector: {msg}"))
+}
+
+fn read_array<const N: usize>(bytes: &[u8], pos: usize) -> DeltaResult<[u8; N]> {
+    bytes
+        .get(pos..pos + N)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| invalid_dv("unexpected end of data".into()))
+}
+
+/// The magic number followed by the portable serialization of a [`RoaringTreemap`]
+fn deserialize_bitmap_array(bytes: &[u8]) -> DeltaResult<RoaringTreemap> {
+    if u32::from_le_bytes(read_array(bytes, 0)?) != MAGIC_NUMBER {
+        return Err(invalid_dv("unexpected magic number".into()));
+    }
+    RoaringTreemap::deserialize_from(&bytes[4..]).map_err(|err| invalid_dv(err.to_string()))
+}
+
+fn serialize_bitmap_array(rows: &RoaringTreemap) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(4 + rows.serialized_size());
+    bytes.extend(MAGIC_NUMBER.to_le_bytes());
+    rows.serialize_into(&mut bytes)
+        .expect("serializing into a Vec does not fail");
+    bytes
+}
+
+/// Z85 encoding, padding the input with zeros to a multiple of four bytes as the protocol does
+fn z85_encode(bytes: &[u8]) -> String {
+    let mut padded = bytes.to_vec();
+    padded.resize(bytes.len().div_ceil(4) * 4, 0);
+    z85::encode(padded)
+}
+
+fn z85_decode(encoded: &str) -> DeltaResult<Vec<u8>> {
+    z85::decode(encoded).map_err(|err| invalid_dv(format!("invalid z85 data: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt as _;
+
+    use super::*;
+
+    #[test]
+    fn test_z85_roundtrip() {
+        // Example from the Z85 specification
+        let bytes = [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B];
+        assert_eq!(z85_encode(&bytes), "HelloWorld");
+        assert_eq!(z85_decode("HelloWorld").unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_uuid_relative_path() {
+        let path = uuid_relative_path("ab^-aqEH.-t@S}K{vb[*k^").unwrap();
+        assert_eq!(
+            path.as_ref(),
+            "ab/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin"
+        );
+    }
+
+    fn rows(rows: &[u64]) -> RoaringTreemap {
+        rows.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_bitmap_array_roundtrip() {
+        let rows: RoaringTreemap = [0, 3, 65_536, 1 << 33]
+            .into_iter()
+            .chain(100_000..110_000)
+            .collect();
+        let dv = inline_deletion_vector(&rows);
+        assert_eq!(dv.cardinality, rows.len() as i64);
+
+        let bytes = z85_decode(&dv.path_or_inline_dv).unwrap();
+        assert_eq!(deserialize_bitmap_array(&bytes).unwrap(), rows);
+    }
+
+    #[tokio::test]
+    async fn test_read_stored_deletion_vector() {
+        let log_store = crate::DeltaTable::new_in_memory().log_store();
+        let rows = rows(&[2, 7, 1 << 20]);
+        let data = serialize_bitmap_array(&rows);
+        let mut file = vec![1u8];
+        file.extend((data.len() as u32).to_be_bytes());
+        file.extend(&data);
+        file.extend(crc32fast::hash(&data).to_be_bytes());
+
+        let dv = DeletionVectorDescriptor {
+            storage_type: StorageType::UuidRelativePath,
+            path_or_inline_dv: "ab^-aqEH.-t@S}K{vb[*k^".to_string(),
+            offset: Some(1),
+            size_in_bytes: data.len() as i32,
+            cardinality: rows.len() as i64,
+        };
+        let path = uuid_relative_path(&dv.path_or_inline_dv).unwrap();
+        let store = log_store.object_store(None);
+        store.put(&path, file.clone().into()).await.unwrap();
+        assert_eq!(
+            read_deletion_vector(log_store.as_ref(), &dv).await.unwrap(),
+            rows
+        );
+
+        let last = file.len() - 1;
+        file[last] ^= 0xFF;
+        store.put(&path, file.into()).await.unwrap();
+        let err = read_deletion_vector(log_store.as_ref(), &dv)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+    }
+
+    fn dv_update(path: &str, base: Option<&RoaringTreemap>, rows: &RoaringTreemap) -> Vec<Action> {
+        let remove = crate::kernel::Remove {
+            path: path.into(),
+            data_change: true,
+            deletion_vector: base.map(inline_deletion_vector),
+            ..Default::default()
+        };
+        let add = crate::kernel::Add {
+            path: path.into(),
+            data_change: true,
+            deletion_vector: Some(inline_deletion_vector(rows)),
+            ..Default::default()
+        };
+        vec![Action::Remove(remove), Action::Add(add)]
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_deletion_vector_updates() {
+        let log_store = crate::DeltaTable::new_in_memory().log_store();
+        let base = rows(&[1]);
+        let winning = dv_update("a.parquet", Some(&base), &rows(&[1, 2]));
+        let disjoint = dv_update("a.parquet", Some(&base), &rows(&[1, 3]));
+        let overlapping = dv_update("a.parquet", Some(&base), &rows(&[1, 2, 4]));
+
+        let mut actions = disjoint.clone();
+        let updates = concurrent_deletion_vector_updates(&log_store, &winning, &actions)
+            .await
+            .unwrap();
+        let merged = updates["a.parquet"].merged.clone().unwrap();
+        assert_eq!(merged.cardinality, 3);
+
+        rebase_deletion_vector_updates(&mut actions, &updates);
+        let Action::Remove(remove) = &actions[0] else {
+            panic!("expected remove")
+        };
+        assert_eq!(remove.deletion_vector.as_ref().unwrap().cardinality, 2);
+        let Action::Add(add) = &actions[1] else {
+            panic!("expected add")
+        };
+        assert_eq!(add.deletion_vector, Some(merged));
+
+        let updates = concurrent_deletion_vector_updates(&log_store, &winning, &overlapping)
+            .await
+            .unwrap();
+        assert!(updates["a.parquet"].merged.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_large_deletion_vector_is_stored_in_file() {
+        let log_store = crate::DeltaTable::new_in_memory().log_store();
+        let small = rows(&[1, 5]);
+        let dv = store_deletion_vector(log_store.as_ref(), &small)
+            .await
+            .unwrap();
+        assert_eq!(dv.storage_type, StorageType::Inline);
+
+        // every other row, so that the bitmap does not compress into a few runs
+        let large: RoaringTreemap = (0..10_000u64).step_by(2).collect();
+        let dv = store_deletion_vector(log_store.as_ref(), &large)
+            .await
+            .unwrap();
+        assert_eq!(dv.storage_type, StorageType::UuidRelativePath);
+        assert_eq!(dv.cardinality, 5_000);
+        let path = uuid_relative_path(&dv.path_or_inline_dv).unwrap();
+        assert!(path.as_ref().starts_with("deletion_vector_"));
+        assert_eq!(
+            read_deletion_vector(log_store.as_ref(), &dv).await.unwrap(),
+            large
+        );
+    }
+}
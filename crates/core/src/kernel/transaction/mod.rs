@@ -73,7 +73,7 @@
 //!       │                               │
 //!       └───────────────────────────────┘
 //!</pre>
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -93,6 +93,7 @@ use delta_kernel::table_features::TableFeature;
 use serde::{Deserialize, Serialize};
 
 use self::conflict_checker::{TransactionInfo, WinningCommitSummary};
+use self::deletion_vector::{concurrent_deletion_vector_updates, rebase_deletion_vector_updates};
 use crate::errors::DeltaTableError;
 use crate::kernel::{
     Action, CommitInfo, EagerSnapshot, IsolationLevel, Metadata, Protocol, Transaction, Version,
@@ -100,6 +101,7 @@ use crate::kernel::{
 use crate::logstore::ObjectStoreRef;
 use crate::logstore::{CommitOrBytes, LogStoreRef};
 use crate::operations::CustomExecuteHandler;
+use crate::protocol::checksum::{VersionChecksum, read_version_checksum, write_version_checksum};
 use crate::protocol::{DeltaOperation, operation_parameter_value};
 use crate::protocol::{cleanup_expired_logs_for, create_checkpoint_for};
 use crate::table::config::TablePropertiesExt as _;
@@ -112,6 +114,7 @@ pub use self::protocol::INSTANCE as PROTOCOL;
 #[cfg(test)]
 pub(crate) mod application;
 mod conflict_checker;
+pub(crate) mod deletion_vector;
 mod protocol;
 #[cfg(feature = "datafusion")]
 mod state;
@@ -211,6 +214,16 @@ pub enum TransactionError {
     #[error("Table features must be specified, please specify: {0:?}")]
     TableFeaturesRequired(TableFeature),
 
+    /// The transaction does not meet the requirements of the icebergCompatV2 table feature
+    #[error("The transaction violates the requirements of icebergCompatV2: {0}")]
+    IcebergCompatViolation(String),
+
+    /// The table is catalog managed, but the log store does not commit through a coordinator
+    #[error(
+        "Commits to catalog managed tables must be ratified by a commit coordinator, the {0} cannot commit to this table"
+    )]
+    CatalogManagedCommit(String),
+
     /// The transaction failed to commit due to an error in an implementation-specific layer.
     /// Currently used by DynamoDb-backed S3 log store when database operations fail.
     #[error("Transaction failed: {msg}")]
@@ -504,6 +517,7 @@ impl CommitData {
 /// Properties for post commit hook.
 pub struct PostCommitHookProperties {
     create_checkpoint: bool,
+    create_checksum: bool,
     /// Override the EnableExpiredLogCleanUp setting, if None config setting is used
     cleanup_expired_logs: Option<bool>,
 }
@@ -516,6 +530,7 @@ pub struct CommitProperties {
     pub(crate) app_transaction: Vec<Transaction>,
     max_retries: usize,
     create_checkpoint: bool,
+    create_checksum: bool,
     cleanup_expired_logs: Option<bool>,
 }
 
@@ -526,6 +541,7 @@ impl Default for CommitProperties {
             app_transaction: Vec::new(),
             max_retries: DEFAULT_RETRIES,
             create_checkpoint: true,
+            create_checksum: true,
             cleanup_expired_logs: None,
         }
     }
@@ -553,6 +569,16 @@ impl CommitProperties {
         self
     }
 
+    /// Specify if it should write the version checksum (`.crc`) file of the commit.
+    ///
+    /// Enabled by default. The checksum costs one more write per commit, plus a read of the
+    /// previous checksum if the table state does not track files. It is written after the post
+    /// commit hooks and failures to write it are only logged.
+    pub fn with_create_checksum(mut self, create_checksum: bool) -> Self {
+        self.create_checksum = create_checksum;
+        self
+    }
+
     /// Add an additional application transaction to the commit
     pub fn with_application_transaction(mut self, txn: Transaction) -> Self {
         self.app_transaction.push(txn);
@@ -579,6 +605,7 @@ impl From<CommitProperties> for CommitBuilder {
             app_metadata: value.app_metadata,
             post_commit_hook: Some(PostCommitHookProperties {
                 create_checkpoint: value.create_checkpoint,
+                create_checksum: value.create_checksum,
                 cleanup_expired_logs: value.cleanup_expired_logs,
             }),
             app_transaction: value.app_transaction,
@@ -718,12 +745,35 @@ impl<'a> PreCommit<'a> {
             if let Some(table_reference) = this.table_data {
                 PROTOCOL.can_commit(table_reference, &this.data.actions, &this.data.operation)?;
             }
+            // Catalog managed tables must not be committed to with a plain put-if-absent
+            let catalog_managed = this
+                .table_data
+                .map(|table| table.protocol())
+                .into_iter()
+                .chain(this.data.actions.iter().filter_map(|action| match action {
+                    Action::Protocol(protocol) => Some(protocol),
+                    _ => None,
+                }))
+                .any(|protocol| {
+                    protocol
+                        .writer_features()
+                        .is_some_and(|features| features.contains(&TableFeature::CatalogManaged))
+                });
+            if catalog_managed && this.log_store.name() != "CommitCoordinatorLogStore" {
+                return Err(TransactionError::CatalogManagedCommit(this.log_store.name()).into());
+            }
             let log_entry = this.data.get_bytes()?;
 
-            // With the DefaultLogStore & LakeFSLogstore, we just pass the bytes around, since we use conditionalPuts
+            // With the DefaultLogStore, LakeFSLogstore, CommitCoordinatorLogStore & LockingLogStore, we just
+            // pass the bytes around, since we use conditionalPuts, staged commits or a commit lock.
             // Other stores will use tmp_commits
-            let commit_or_bytes = if ["LakeFSLogStore", "DefaultLogStore"]
-                .contains(&this.log_store.name().as_str())
+            let commit_or_bytes = if [
+                "LakeFSLogStore",
+                "DefaultLogStore",
+                "CommitCoordinatorLogStore",
+                "LockingLogStore",
+            ]
+            .contains(&this.log_store.name().as_str())
             {
                 CommitOrBytes::LogBytes(log_entry)
             } else {
@@ -772,10 +822,10 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
     type IntoFuture = BoxFuture<'a, Self::Output>;
 
     fn into_future(self) -> Self::IntoFuture {
-        let this = self;
+        let mut this = self;
 
         Box::pin(async move {
-            let commit_or_bytes = this.commit_or_bytes;
+            let mut commit_or_bytes = this.commit_or_bytes;
 
             let mut attempt_number: usize = 1;
 
@@ -794,6 +844,10 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                             version: 0,
                             data: this.data,
                             create_checkpoint: false,
+                            create_checksum: this
+                                .post_commit_hook
+                                .map(|v| v.create_checksum)
+                                .unwrap_or_default(),
                             cleanup_expired_logs: None,
                             log_store: this.log_store,
                             table_data: None,
@@ -860,6 +914,7 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                         );
                         let mut steps = latest_version - read_snapshot.version();
                         let mut conflicts_checked = 0;
+                        let mut rebased = false;
 
                         // Need to check for conflicts with each version between the read_snapshot and
                         // the latest!
@@ -871,6 +926,19 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                                 (latest_version - steps) + 1,
                             )
                             .await?;
+                            // Files where both transactions only deleted rows via deletion vectors
+                            // do not conflict if the deleted rows are distinct.
+                            let dv_updates = concurrent_deletion_vector_updates(
+                                this.log_store.as_ref(),
+                                &summary.actions,
+                                &this.data.actions,
+                            )
+                            .await?;
+                            let resolved_deletion_vectors = dv_updates
+                                .iter()
+                                .filter(|(_, update)| update.merged.is_some())
+                                .map(|(path, _)| path.clone())
+                                .collect::<HashSet<_>>();
                             let conflict_read_set = read_snapshot
                                 .snapshot()
                                 .conflict_read_set(this.log_store.as_ref())
@@ -885,7 +953,8 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                                 transaction_info,
                                 summary,
                                 Some(&this.data.operation),
-                            );
+                            )
+                            .with_resolved_deletion_vectors(resolved_deletion_vectors.clone());
 
                             match conflict_checker.check_conflicts() {
                                 Ok(_) => {}
@@ -898,8 +967,29 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                                     return Err(TransactionError::CommitConflict(err).into());
                                 }
                             }
+                            if !resolved_deletion_vectors.is_empty() {
+                                debug!(
+                                    files = resolved_deletion_vectors.len(),
+                                    "rebasing deletion vectors onto winning commit"
+                                );
+                                rebase_deletion_vector_updates(&mut this.data.actions, &dv_updates);
+                                rebased = true;
+                            }
                             steps -= 1;
                         }
+                        if rebased {
+                            let log_entry = this.data.get_bytes()?;
+                            commit_or_bytes = match commit_or_bytes {
+                                CommitOrBytes::LogBytes(_) => CommitOrBytes::LogBytes(log_entry),
+                                CommitOrBytes::TmpCommit(tmp_commit) => {
+                                    this.log_store
+                                        .object_store(Some(this.operation_id))
+                                        .put(&tmp_commit, log_entry.into())
+                                        .await?;
+                                    CommitOrBytes::TmpCommit(tmp_commit)
+                                }
+                            };
+                        }
                         Span::current().record("conflicts_checked", conflicts_checked);
                         debug!(
                             conflicts_checked = conflicts_checked,
@@ -931,6 +1021,10 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                                     .post_commit
                                     .map(|v| v.create_checkpoint)
                                     .unwrap_or_default(),
+                                create_checksum: this
+                                    .post_commit
+                                    .map(|v| v.create_checksum)
+                                    .unwrap_or_default(),
                                 cleanup_expired_logs: this
                                     .post_commit
                                     .map(|v| v.cleanup_expired_logs)
@@ -986,6 +1080,7 @@ pub struct PostCommit {
     /// The data that was committed to the log store
     pub data: CommitData,
     create_checkpoint: bool,
+    create_checksum: bool,
     cleanup_expired_logs: Option<bool>,
     log_store: LogStoreRef,
     table_data: Option<Box<dyn TableReference>>,
@@ -1004,6 +1099,8 @@ impl PostCommit {
             }
 
             let mut state = DeltaTableState { snapshot };
+            #[cfg(feature = "iceberg")]
+            self.write_iceberg_metadata(&state).await;
 
             let cleanup_logs = if let Some(cleanup_logs) = self.cleanup_expired_logs {
                 cleanup_logs
@@ -1066,6 +1163,11 @@ impl PostCommit {
                     )
                     .await?
             }
+
+            // the checksum is optional, its write stays out of the way of the commit hooks
+            if self.create_checksum {
+                self.write_checksum(&state).await;
+            }
             Ok((
                 state,
                 PostCommitMetrics {
@@ -1077,6 +1179,11 @@ impl PostCommit {
             let state =
                 DeltaTableState::try_new(&self.log_store, Default::default(), Some(self.version))
                     .await?;
+            if self.create_checksum {
+                self.write_checksum(&state).await;
+            }
+            #[cfg(feature = "iceberg")]
+            self.write_iceberg_metadata(&state).await;
             Ok((
                 state,
                 PostCommitMetrics {
@@ -1086,6 +1193,72 @@ impl PostCommit {
             ))
         }
     }
+
+    /// Write the version checksum of the commit. Failures are logged, as the checksum is optional.
+    async fn write_checksum(&self, table_state: &DeltaTableState) {
+        if let Err(err) = self.try_write_checksum(table_state).await {
+            warn!(version = self.version, error = %err, "failed to write version checksum");
+        }
+    }
+
+    /// Generate the Iceberg metadata of the commit if the table asks for it. Failures are logged,
+    /// as Iceberg readers keep reading the metadata of an earlier version.
+    #[cfg(feature = "iceberg")]
+    async fn write_iceberg_metadata(&self, table_state: &DeltaTableState) {
+        use crate::iceberg::generate::{is_enabled, write_iceberg_metadata};
+
+        let snapshot = table_state.snapshot();
+        if !is_enabled(snapshot) {
+            return;
+        }
+        if !table_state.load_config().require_files {
+            debug!(
+                version = self.version,
+                "Iceberg metadata skipped due to table being initialized without files"
+            );
+            return;
+        }
+        if let Err(err) = write_iceberg_metadata(
+            &self.log_store,
+            snapshot,
+            Some(self.data.actions.as_slice()),
+        )
+        .await
+        {
+            warn!(version = self.version, error = %err, "failed to write Iceberg metadata");
+        }
+    }
+
+    /// Compute the version checksum from the files of the post-commit state if they are loaded,
+    /// otherwise derive it from the checksum of the previous version.
+    async fn try_write_checksum(&self, table_state: &DeltaTableState) -> DeltaResult<()> {
+        let log_store = self.log_store.as_ref();
+        let checksum = if table_state.load_config().require_files {
+            Some(VersionChecksum::from_log_data(
+                table_state.log_data(),
+                &self.data.actions,
+            ))
+        } else {
+            let previous = match self.version.checked_sub(1) {
+                Some(previous) => read_version_checksum(log_store, previous).await?,
+                None => None,
+            };
+            if previous.is_some() || self.version == 0 {
+                VersionChecksum::try_apply(previous.as_ref(), &self.data.actions)
+            } else {
+                None
+            }
+        };
+        let Some(checksum) = checksum else {
+            debug!(
+                version = self.version,
+                "version checksum skipped due to table being initialized without files"
+            );
+            return Ok(());
+        };
+        write_version_checksum(log_store, self.version, &checksum).await
+    }
+
     async fn create_checkpoint(
         &self,
         table_state: &DeltaTableState,
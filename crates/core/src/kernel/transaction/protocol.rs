@@ -4,6 +4,8 @@ use std::sync::LazyLock;
 use delta_kernel::table_features::TableFeature;
 
 use super::{TableReference, TransactionError};
+#[cfg(feature = "datafusion")]
+use crate::kernel::TableFeatures;
 #[cfg(feature = "nanosecond-timestamps")]
 use crate::kernel::contains_timestamp_nanos;
 use crate::kernel::{
@@ -11,7 +13,7 @@ use crate::kernel::{
     contains_variant,
 };
 use crate::protocol::DeltaOperation;
-use crate::table::config::TablePropertiesExt as _;
+use crate::table::config::{TablePropertiesExt as _, TableProperty};
 
 use tracing::log::*;
 
@@ -282,6 +284,63 @@ impl ProtocolChecker {
             }
         }
 
+        self.check_iceberg_compat_v2(snapshot, actions)?;
+
+        Ok(())
+    }
+
+    /// Check the requirements of [icebergCompatV2] if the table enables it. Metadata committed
+    /// with the actions takes precedence over the metadata of the snapshot.
+    ///
+    /// [icebergCompatV2]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#iceberg-compatibility-v2
+    pub fn check_iceberg_compat_v2(
+        &self,
+        snapshot: &dyn TableReference,
+        actions: &[Action],
+    ) -> Result<(), TransactionError> {
+        let metadata = actions
+            .iter()
+            .rev()
+            .find_map(|action| match action {
+                Action::Metadata(metadata) => Some(metadata),
+                _ => None,
+            })
+            .unwrap_or_else(|| snapshot.metadata());
+        let configuration = metadata.configuration();
+        let is_true = |property: TableProperty| {
+            configuration
+                .get(property.as_ref())
+                .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+        };
+        if !is_true(TableProperty::EnableIcebergCompatV2) {
+            return Ok(());
+        }
+
+        let violation = |msg: &str| Err(TransactionError::IcebergCompatViolation(msg.to_string()));
+        if !configuration
+            .get(TableProperty::ColumnMappingMode.as_ref())
+            .is_some_and(|mode| mode == "id" || mode == "name")
+        {
+            return violation("column mapping must be enabled");
+        }
+        if is_true(TableProperty::EnableDeletionVectors) {
+            return violation("deletion vectors must be disabled");
+        }
+        for action in actions {
+            if let Action::Add(add) = action {
+                if add.deletion_vector.is_some() {
+                    return violation("data files cannot have deletion vectors");
+                }
+                let has_num_records = add
+                    .stats
+                    .as_deref()
+                    .and_then(|stats| serde_json::from_str::<serde_json::Value>(stats).ok())
+                    .is_some_and(|stats| stats.get("numRecords").is_some());
+                if !has_num_records {
+                    return violation("data files must have statistics with numRecords");
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -305,6 +364,8 @@ pub static INSTANCE: LazyLock<ProtocolChecker> = LazyLock::new(|| {
     {
         reader_features.insert(TableFeature::ColumnMapping);
     }
+    // Unpublished commits are read through the `CommitCoordinatorLogStore`
+    reader_features.insert(TableFeature::CatalogManaged);
 
     let mut writer_features = HashSet::new();
     writer_features.insert(TableFeature::AppendOnly);
@@ -320,8 +381,16 @@ pub static INSTANCE: LazyLock<ProtocolChecker> = LazyLock::new(|| {
         writer_features.insert(TableFeature::CheckConstraints);
         writer_features.insert(TableFeature::GeneratedColumns);
         writer_features.insert(TableFeature::ColumnMapping);
+        if let Ok(feature) = TableFeature::try_from(&TableFeatures::AllowColumnDefaults) {
+            writer_features.insert(feature);
+        }
+        // Iceberg metadata of compatible tables is generated after every commit
+        #[cfg(feature = "iceberg")]
+        writer_features.insert(TableFeature::IcebergCompatV2);
     }
     writer_features.insert(TableFeature::DeletionVectors);
+    // Commits through other log stores are rejected when the transaction is prepared
+    writer_features.insert(TableFeature::CatalogManaged);
     // writer_features.insert(TableFeature::IdentityColumns);
 
     ProtocolChecker::new(reader_features, writer_features)
@@ -50,6 +50,8 @@
 //! - `datafusion` - enable the `datafusion::datasource::TableProvider` trait implementation
 //!   for Delta Tables, allowing them to be queried using [DataFusion](https://github.com/apache/arrow-datafusion).
 //! - `datafusion-ext` - DEPRECATED: alias for `datafusion` feature.
+//! - `iceberg` - enable converting Apache Iceberg tables to Delta tables in place, and generating
+//!   Iceberg metadata for Delta tables with `delta.universalFormat.enabledFormats=iceberg`.
 //!
 //! # Querying Delta Tables with Datafusion
 //!
@@ -80,6 +82,8 @@
 #![allow(clippy::nonminimal_bool)]
 pub mod data_catalog;
 pub mod errors;
+#[cfg(feature = "iceberg")]
+pub(crate) mod iceberg;
 pub mod kernel;
 pub mod logstore;
 pub mod operations;
@@ -0,0 +1,785 @@
+//! Catalog-managed commits via a pluggable [`CommitCoordinator`].
+//!
+//! With coordinated commits a writer does not create `_delta_log/<version>.json` directly.
+//! Instead the commit is written as a staged commit to
+//! `_delta_log/_staged_commits/<version>.<uuid>.json` and the [`CommitCoordinator`] is asked to
+//! ratify it. Once ratified, the commit is part of the table, even before it is backfilled
+//! (published) to `_delta_log/<version>.json`.
+//!
+//! The [`CommitCoordinatorLogStore`] wraps any other [`LogStore`] and merges ratified but
+//! unpublished commits into the listing of the delta log, so snapshots loaded through it see
+//! the latest ratified version.
+//!
+//! Tables with the `catalogManaged` table feature can only be committed to through a
+//! [`CommitCoordinatorLogStore`], commits through any other log store are rejected.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, Error as ObjectStoreError, GetOptions, GetResult, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, ObjectStoreExt as _, PutMode, PutMultipartOptions, PutOptions,
+    PutPayload, PutResult, RenameOptions,
+};
+use parking_lot::Mutex;
+use tracing::*;
+use url::Url;
+use uuid::Uuid;
+
+use super::{
+    CommitOrBytes, LogStore, LogStoreConfig, LogStoreRef, ObjectStoreRef, commit_uri_from_version,
+    get_engine, object_store_path,
+};
+use crate::kernel::Version;
+use crate::kernel::transaction::TransactionError;
+use crate::{DeltaResult, DeltaTableError};
+
+const STAGED_COMMITS_FOLDER: &str = "_staged_commits";
+
+/// A commit staged in `_delta_log/_staged_commits`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedCommit {
+    /// Table version of the commit
+    pub version: Version,
+    /// Location of the staged commit file relative to the table root
+    pub path: Path,
+}
+
+impl StagedCommit {
+    /// Create a staged commit for `version` with a unique file name
+    pub fn new(version: Version, id: Uuid) -> Self {
+        let path = Path::from_iter([
+            "_delta_log",
+            STAGED_COMMITS_FOLDER,
+            &format!("{version:020}.{id}.json"),
+        ]);
+        Self { version, path }
+    }
+}
+
+/// Coordinator deciding which staged commit becomes a table version.
+///
+/// Implementations are typically backed by a catalog. Tables are identified by their normalized
+/// root url.
+#[async_trait::async_trait]
+pub trait CommitCoordinator: Send + Sync + Debug {
+    /// Ratify a staged commit as the next version of the table.
+    ///
+    /// Must fail with [`TransactionError::VersionAlreadyExists`] if a commit for the version was
+    /// already ratified. Any other error leaves it open whether the commit was ratified, so the
+    /// staged commit is kept.
+    async fn ratify_commit(
+        &self,
+        table: &Url,
+        commit: StagedCommit,
+    ) -> Result<(), TransactionError>;
+
+    /// Ratified commits which have not been published yet, ordered by version.
+    async fn unpublished_commits(&self, table: &Url) -> DeltaResult<Vec<StagedCommit>>;
+
+    /// Record that all commits up to and including `version` have been published.
+    async fn mark_published(&self, table: &Url, version: Version) -> DeltaResult<()>;
+}
+
+#[derive(Debug, Default)]
+struct CoordinatedTable {
+    latest_version: Option<Version>,
+    unpublished: BTreeMap<Version, StagedCommit>,
+}
+
+/// Reference [`CommitCoordinator`] keeping its state in memory.
+///
+/// The first commit ratified for a table may have any version, all following commits must be
+/// contiguous.
+#[derive(Debug, Default)]
+pub struct InMemoryCommitCoordinator {
+    tables: Mutex<HashMap<Url, CoordinatedTable>>,
+}
+
+impl InMemoryCommitCoordinator {
+    /// Create a new coordinator without any tables
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl CommitCoordinator for InMemoryCommitCoordinator {
+    async fn ratify_commit(
+        &self,
+        table: &Url,
+        commit: StagedCommit,
+    ) -> Result<(), TransactionError> {
+        let mut tables = self.tables.lock();
+        let state = tables.entry(table.clone()).or_default();
+        match state.latest_version {
+            Some(latest) if commit.version <= latest => {
+                return Err(TransactionError::VersionAlreadyExists(commit.version));
+            }
+            Some(latest) if commit.version > latest + 1 => {
+                return Err(TransactionError::LogStoreError {
+                    msg: format!(
+                        "cannot ratify version {} for {table}, latest ratified version is {latest}",
+                        commit.version
+                    ),
+                    source: "non-contiguous commit version".into(),
+                });
+            }
+            _ => {}
+        }
+        state.latest_version = Some(commit.version);
+        state.unpublished.insert(commit.version, commit);
+        Ok(())
+    }
+
+    async fn unpublished_commits(&self, table: &Url) -> DeltaResult<Vec<StagedCommit>> {
+        Ok(self
+            .tables
+            .lock()
+            .get(table)
+            .map(|state| state.unpublished.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn mark_published(&self, table: &Url, version: Version) -> DeltaResult<()> {
+        if let Some(state) = self.tables.lock().get_mut(table) {
+            state.unpublished = state.unpublished.split_off(&(version + 1));
+        }
+        Ok(())
+    }
+}
+
+/// [`LogStore`] committing through a [`CommitCoordinator`].
+///
+/// Commits are staged and ratified by the coordinator, then backfilled to the delta log. Reads
+/// through this log store include ratified commits which are not yet published.
+#[derive(Debug)]
+pub struct CommitCoordinatorLogStore {
+    inner: LogStoreRef,
+    coordinator: Arc<dyn CommitCoordinator>,
+    auto_backfill: bool,
+}
+
+impl CommitCoordinatorLogStore {
+    /// Create a log store staging its commits in the object store of `inner`
+    pub fn new(inner: LogStoreRef, coordinator: Arc<dyn CommitCoordinator>) -> Self {
+        Self {
+            inner,
+            coordinator,
+            auto_backfill: true,
+        }
+    }
+
+    /// Whether ratified commits are published right after each commit, defaults to `true`.
+    ///
+    /// When disabled, commits are only published by calling [`backfill`](Self::backfill).
+    pub fn with_auto_backfill(mut self, auto_backfill: bool) -> Self {
+        self.auto_backfill = auto_backfill;
+        self
+    }
+
+    /// The coordinator ratifying commits for this log store
+    pub fn coordinator(&self) -> &Arc<dyn CommitCoordinator> {
+        &self.coordinator
+    }
+
+    /// Publish all ratified commits to the delta log, returning the published versions.
+    ///
+    /// A version which was already published, e.g. by a concurrent backfill, must hold the
+    /// ratified commit, otherwise the backfill fails.
+    pub async fn backfill(&self) -> DeltaResult<Vec<Version>> {
+        let table = self.root_url();
+        let store = self.inner.object_store(None);
+        let mut published = Vec::new();
+        for commit in self.coordinator.unpublished_commits(table).await? {
+            let bytes = store.get(&commit.path).await?.bytes().await?;
+            let published_path = commit_uri_from_version(Some(commit.version));
+            match store
+                .put_opts(
+                    &published_path,
+                    bytes.clone().into(),
+                    PutMode::Create.into(),
+                )
+                .await
+            {
+                Ok(_) => {}
+                // Another writer may have published the commit already
+                Err(ObjectStoreError::AlreadyExists { .. }) => {
+                    let published = store.get(&published_path).await?.bytes().await?;
+                    if published != bytes {
+                        return Err(DeltaTableError::Generic(format!(
+                            "version {} was published with a different commit than the ratified \
+                             commit {}",
+                            commit.version, commit.path
+                        )));
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            }
+            self.coordinator
+                .mark_published(table, commit.version)
+                .await?;
+            debug!(version = commit.version, "published ratified commit");
+            published.push(commit.version);
+        }
+        Ok(published)
+    }
+
+    fn with_unpublished_commits(
+        &self,
+        store: ObjectStoreRef,
+        table_path: Path,
+    ) -> Arc<dyn ObjectStore> {
+        Arc::new(UnpublishedCommitStore {
+            inner: store,
+            coordinator: self.coordinator.clone(),
+            table: self.root_url().clone(),
+            log_path: join_path(&table_path, &Path::from("_delta_log")),
+            table_path,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl LogStore for CommitCoordinatorLogStore {
+    fn name(&self) -> String {
+        "CommitCoordinatorLogStore".into()
+    }
+
+    async fn refresh(&self) -> DeltaResult<()> {
+        self.inner.refresh().await
+    }
+
+    async fn read_commit_entry(&self, version: Version) -> DeltaResult<Option<Bytes>> {
+        if let Some(bytes) = self.inner.read_commit_entry(version).await? {
+            return Ok(Some(bytes));
+        }
+        let unpublished = self
+            .coordinator
+            .unpublished_commits(self.root_url())
+            .await?;
+        match unpublished.into_iter().find(|c| c.version == version) {
+            Some(commit) => {
+                let store = self.inner.object_store(None);
+                Ok(Some(store.get(&commit.path).await?.bytes().await?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn write_commit_entry(
+        &self,
+        version: Version,
+        commit_or_bytes: CommitOrBytes,
+        _operation_id: Uuid,
+    ) -> Result<(), TransactionError> {
+        let store = self.inner.object_store(None);
+        match store.head(&commit_uri_from_version(Some(version))).await {
+            Ok(_) => return Err(TransactionError::VersionAlreadyExists(version)),
+            Err(ObjectStoreError::NotFound { .. }) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        // The check above only avoids staging commits which lost already, ratification decides
+        // which commit becomes the version.
+        let staged = StagedCommit::new(version, Uuid::new_v4());
+        match commit_or_bytes {
+            CommitOrBytes::LogBytes(bytes) => {
+                store
+                    .put_opts(&staged.path, bytes.into(), PutMode::Create.into())
+                    .await?;
+            }
+            CommitOrBytes::TmpCommit(tmp_commit) => {
+                store
+                    .rename_if_not_exists(&tmp_commit, &staged.path)
+                    .await?;
+            }
+        }
+
+        match self
+            .coordinator
+            .ratify_commit(self.root_url(), staged.clone())
+            .await
+        {
+            Ok(()) => {}
+            Err(err @ TransactionError::VersionAlreadyExists(_)) => {
+                if let Err(delete_err) = store.delete(&staged.path).await {
+                    warn!(error = %delete_err, path = %staged.path, "failed to remove rejected staged commit");
+                }
+                return Err(err);
+            }
+            Err(err) => {
+                // e.g. a timeout, the coordinator may have ratified the commit nevertheless
+                warn!(error = %err, version, path = %staged.path, "ratification outcome unknown, keeping staged commit");
+                return Err(err);
+            }
+        }
+        debug!(version, path = %staged.path, "staged commit ratified");
+
+        // The commit is durable once ratified, a failed backfill is retried with the next commit
+        if self.auto_backfill
+            && let Err(err) = self.backfill().await
+        {
+            warn!(error = %err, version, "failed to backfill ratified commits");
+        }
+        Ok(())
+    }
+
+    async fn abort_commit_entry(
+        &self,
+        _version: Version,
+        commit_or_bytes: CommitOrBytes,
+        _operation_id: Uuid,
+    ) -> Result<(), TransactionError> {
+        match &commit_or_bytes {
+            CommitOrBytes::LogBytes(_) => Ok(()),
+            // The temporary commit was moved if it has been staged
+            CommitOrBytes::TmpCommit(tmp_commit) => {
+                match self.inner.object_store(None).delete(tmp_commit).await {
+                    Ok(_) | Err(ObjectStoreError::NotFound { .. }) => Ok(()),
+                    Err(err) => Err(err.into()),
+                }
+            }
+        }
+    }
+
+    async fn get_latest_version(&self, current_version: Version) -> DeltaResult<Version> {
+        super::get_latest_version(self, current_version).await
+    }
+
+    fn object_store(&self, operation_id: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.with_unpublished_commits(self.inner.object_store(operation_id), Path::default())
+    }
+
+    fn root_object_store(&self, operation_id: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        let table_path = object_store_path(self.root_url()).unwrap_or_default();
+        self.with_unpublished_commits(self.inner.root_object_store(operation_id), table_path)
+    }
+
+    fn engine(&self, operation_id: Option<Uuid>) -> Arc<dyn delta_kernel::Engine> {
+        get_engine(self.root_object_store(operation_id))
+    }
+
+    fn transaction_url(&self, operation_id: Option<Uuid>) -> DeltaResult<Url> {
+        self.inner.transaction_url(operation_id)
+    }
+
+    fn config(&self) -> &LogStoreConfig {
+        self.inner.config()
+    }
+}
+
+fn join_path(base: &Path, relative: &Path) -> Path {
+    Path::from_iter(base.parts().chain(relative.parts()))
+}
+
+fn coordinator_error(err: DeltaTableError) -> ObjectStoreError {
+    ObjectStoreError::Generic {
+        store: "CommitCoordinator",
+        source: Box::new(err),
+    }
+}
+
+/// Object store presenting ratified but unpublished commits as regular delta log commits.
+///
+/// Staged commits are hidden from the delta log listing, so readers only see each version once.
+#[derive(Debug)]
+struct UnpublishedCommitStore {
+    inner: ObjectStoreRef,
+    coordinator: Arc<dyn CommitCoordinator>,
+    table: Url,
+    table_path: Path,
+    log_path: Path,
+}
+
+impl Display for UnpublishedCommitStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "UnpublishedCommitStore({})", self.inner)
+    }
+}
+
+impl UnpublishedCommitStore {
+    fn list_log(
+        &self,
+        offset: Option<Path>,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        let inner = self.inner.clone();
+        let coordinator = self.coordinator.clone();
+        let table = self.table.clone();
+        let table_path = self.table_path.clone();
+        let log_path = self.log_path.clone();
+        let staged_path = join_path(&log_path, &Path::from(STAGED_COMMITS_FOLDER));
+
+        futures::stream::once(async move {
+            let commits = coordinator
+                .unpublished_commits(&table)
+                .await
+                .map_err(coordinator_error)?;
+            let listed = match &offset {
+                Some(offset) => inner.list_with_offset(Some(&log_path), offset),
+                None => inner.list(Some(&log_path)),
+            };
+            let mut listed: Vec<ObjectMeta> = listed
+                .try_filter(|meta| {
+                    futures::future::ready(!meta.location.prefix_matches(&staged_path))
+                })
+                .try_collect()
+                .await?;
+
+            for commit in commits {
+                let location =
+                    join_path(&table_path, &commit_uri_from_version(Some(commit.version)));
+                if offset.as_ref().is_some_and(|offset| &location <= offset)
+                    || listed.iter().any(|meta| meta.location == location)
+                {
+                    continue;
+                }
+                let meta = inner.head(&join_path(&table_path, &commit.path)).await?;
+                listed.push(ObjectMeta { location, ..meta });
+            }
+            listed.sort_by(|a, b| a.location.cmp(&b.location));
+            Ok::<_, ObjectStoreError>(futures::stream::iter(listed.into_iter().map(Ok)))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    /// The staged commit backing `location`, if it is an unpublished commit
+    async fn staged_commit(&self, location: &Path) -> object_store::Result<Option<Path>> {
+        if !location.prefix_matches(&self.log_path) || location.extension() != Some("json") {
+            return Ok(None);
+        }
+        let commits = self
+            .coordinator
+            .unpublished_commits(&self.table)
+            .await
+            .map_err(coordinator_error)?;
+        Ok(commits.into_iter().find_map(|commit| {
+            let published = join_path(
+                &self.table_path,
+                &commit_uri_from_version(Some(commit.version)),
+            );
+            (&published == location).then(|| join_path(&self.table_path, &commit.path))
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for UnpublishedCommitStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        match self.inner.get_opts(location, options.clone()).await {
+            Err(ObjectStoreError::NotFound { path, source }) => {
+                match self.staged_commit(location).await? {
+                    Some(staged) => {
+                        let mut result = self.inner.get_opts(&staged, options).await?;
+                        result.meta.location = location.clone();
+                        Ok(result)
+                    }
+                    None => Err(ObjectStoreError::NotFound { path, source }),
+                }
+            }
+            result => result,
+        }
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, object_store::Result<Path>>,
+    ) -> BoxStream<'static, object_store::Result<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        if prefix == Some(&self.log_path) {
+            return self.list_log(None);
+        }
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        if prefix == Some(&self.log_path) {
+            return self.list_log(Some(offset.clone()));
+        }
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> object_store::Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> object_store::Result<()> {
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> object_store::Result<()> {
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use delta_kernel::table_features::TableFeature;
+
+    use super::*;
+    use crate::DeltaTable;
+    use crate::kernel::{Action, ProtocolInner};
+    use crate::logstore::{StorageConfig, logstore_for};
+    use crate::operations::create::CreateBuilder;
+    use crate::writer::test_utils::{get_delta_schema, get_record_batch};
+    use crate::writer::{DeltaWriter, RecordBatchWriter};
+
+    fn coordinated_log_store(auto_backfill: bool) -> (LogStoreRef, Arc<CommitCoordinatorLogStore>) {
+        let location = Url::parse("memory:///table").unwrap();
+        let inner = logstore_for(&location, StorageConfig::default()).unwrap();
+        let coordinator = Arc::new(InMemoryCommitCoordinator::new());
+        let log_store = Arc::new(
+            CommitCoordinatorLogStore::new(inner.clone(), coordinator)
+                .with_auto_backfill(auto_backfill),
+        );
+        (inner, log_store)
+    }
+
+    async fn create_and_write(log_store: LogStoreRef) -> DeltaTable {
+        let mut table = CreateBuilder::new()
+            .with_log_store(log_store)
+            .with_columns(get_delta_schema().fields().cloned())
+            .await
+            .unwrap();
+        let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+        writer.write(get_record_batch(None, false)).await.unwrap();
+        writer.flush_and_commit(&mut table).await.unwrap();
+        table
+    }
+
+    /// Coordinator ratifying commits but failing as if the response was lost
+    #[derive(Debug, Default)]
+    struct LostResponseCoordinator(InMemoryCommitCoordinator);
+
+    #[async_trait::async_trait]
+    impl CommitCoordinator for LostResponseCoordinator {
+        async fn ratify_commit(
+            &self,
+            table: &Url,
+            commit: StagedCommit,
+        ) -> Result<(), TransactionError> {
+            self.0.ratify_commit(table, commit).await?;
+            Err(TransactionError::LogStoreError {
+                msg: "request timed out".into(),
+                source: "timeout".into(),
+            })
+        }
+
+        async fn unpublished_commits(&self, table: &Url) -> DeltaResult<Vec<StagedCommit>> {
+            self.0.unpublished_commits(table).await
+        }
+
+        async fn mark_published(&self, table: &Url, version: Version) -> DeltaResult<()> {
+            self.0.mark_published(table, version).await
+        }
+    }
+
+    async fn staged_commits(inner: &LogStoreRef) -> usize {
+        crate::logstore::tests::flatten_list_stream(
+            inner.object_store(None).as_ref(),
+            Some(&Path::from("_delta_log/_staged_commits")),
+        )
+        .await
+        .unwrap()
+        .len()
+    }
+
+    #[tokio::test]
+    async fn test_staged_commit_is_kept_unless_rejected() {
+        let location = Url::parse("memory:///table").unwrap();
+        let inner = logstore_for(&location, StorageConfig::default()).unwrap();
+        let log_store = CommitCoordinatorLogStore::new(
+            inner.clone(),
+            Arc::new(LostResponseCoordinator::default()),
+        );
+
+        // the outcome is unknown, the staged commit may be part of the table
+        let result = log_store
+            .write_commit_entry(
+                0,
+                CommitOrBytes::LogBytes(Bytes::from("{}")),
+                Uuid::new_v4(),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::LogStoreError { .. })
+        ));
+        assert_eq!(staged_commits(&inner).await, 1);
+        assert!(log_store.read_commit_entry(0).await.unwrap().is_some());
+
+        // a commit rejected for an existing version is removed
+        let result = log_store
+            .write_commit_entry(
+                0,
+                CommitOrBytes::LogBytes(Bytes::from("{}")),
+                Uuid::new_v4(),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::VersionAlreadyExists(0))
+        ));
+        assert_eq!(staged_commits(&inner).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_ratified_commits_are_backfilled() {
+        let (inner, log_store) = coordinated_log_store(true);
+        let table = create_and_write(log_store.clone()).await;
+        assert_eq!(table.version(), Some(1));
+
+        assert!(inner.read_commit_entry(0).await.unwrap().is_some());
+        assert!(inner.read_commit_entry(1).await.unwrap().is_some());
+        let unpublished = log_store
+            .coordinator()
+            .unpublished_commits(log_store.root_url())
+            .await
+            .unwrap();
+        assert!(unpublished.is_empty());
+
+        let staged = crate::logstore::tests::flatten_list_stream(
+            inner.object_store(None).as_ref(),
+            Some(&Path::from("_delta_log/_staged_commits")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(staged.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_includes_unpublished_commits() {
+        let (inner, log_store) = coordinated_log_store(false);
+        create_and_write(log_store.clone()).await;
+        assert!(inner.read_commit_entry(0).await.unwrap().is_none());
+        assert!(log_store.read_commit_entry(1).await.unwrap().is_some());
+
+        let mut table = DeltaTable::new(log_store.clone(), Default::default());
+        table.load().await.unwrap();
+        assert_eq!(table.version(), Some(1));
+        assert_eq!(table.snapshot().unwrap().log_data().num_files(), 1);
+
+        assert_eq!(log_store.backfill().await.unwrap(), vec![0, 1]);
+        let mut table = DeltaTable::new(inner, Default::default());
+        table.load().await.unwrap();
+        assert_eq!(table.version(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_backfill_rejects_different_published_commit() {
+        let (inner, log_store) = coordinated_log_store(false);
+        create_and_write(log_store.clone()).await;
+        inner
+            .object_store(None)
+            .put(&commit_uri_from_version(Some(0)), Bytes::from("{}").into())
+            .await
+            .unwrap();
+        assert!(log_store.backfill().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_catalog_managed_table_requires_coordinator() {
+        let protocol = ProtocolInner {
+            min_reader_version: 3,
+            min_writer_version: 7,
+            reader_features: Some([TableFeature::CatalogManaged].into_iter().collect()),
+            writer_features: Some([TableFeature::CatalogManaged].into_iter().collect()),
+        }
+        .as_kernel();
+        let (inner, _) = coordinated_log_store(true);
+        let result = CreateBuilder::new()
+            .with_log_store(inner)
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_actions([Action::Protocol(protocol)])
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeltaTableError::Transaction {
+                source: TransactionError::CatalogManagedCommit(_)
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_coordinator_ratification() {
+        let table = Url::parse("memory:///table/").unwrap();
+        let coordinator = InMemoryCommitCoordinator::new();
+        coordinator
+            .ratify_commit(&table, StagedCommit::new(0, Uuid::new_v4()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            coordinator
+                .ratify_commit(&table, StagedCommit::new(0, Uuid::new_v4()))
+                .await,
+            Err(TransactionError::VersionAlreadyExists(0))
+        ));
+        assert!(
+            coordinator
+                .ratify_commit(&table, StagedCommit::new(2, Uuid::new_v4()))
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            coordinator.unpublished_commits(&table).await.unwrap().len(),
+            1
+        );
+
+        coordinator.mark_published(&table, 0).await.unwrap();
+        assert!(
+            coordinator
+                .unpublished_commits(&table)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        coordinator
+            .ratify_commit(&table, StagedCommit::new(1, Uuid::new_v4()))
+            .await
+            .unwrap();
+    }
+}
@@ -8,10 +8,13 @@
 //! defines how to update internal fields based on key-value pairs.
 #[cfg(feature = "cloud")]
 use ::object_store::RetryConfig;
-use object_store::{ObjectStore, path::Path, prefix::PrefixStore};
+use object_store::{ObjectStore, limit::LimitStore, path::Path, prefix::PrefixStore};
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use super::storage::{CertificateConfig, LimitConfig};
+#[cfg(feature = "delta-cache")]
+use super::storage::{CacheConfig, CachingObjectStore};
+use super::storage::{CertificateConfig, LimitConfig, ObjectStoreMetrics, RateLimitStore};
 use super::{IORuntime, storage::runtime::RuntimeConfig};
 use crate::{DeltaResult, DeltaTableError};
 
@@ -112,7 +115,8 @@ pub struct StorageConfig {
 
     /// Limit configuration.
     ///
-    /// Configuration to limit the number of concurrent requests to the object store.
+    /// Configuration to limit the number of concurrent requests and the request rate to the
+    /// object store.
     pub limit: Option<LimitConfig>,
 
     /// Certificate configuration.
@@ -120,6 +124,12 @@ pub struct StorageConfig {
     /// Configuration for custom TLS root certificates.
     pub certificate: Option<CertificateConfig>,
 
+    /// Cache configuration.
+    ///
+    /// Configuration for caching immutable objects on local memory and disk.
+    #[cfg(feature = "delta-cache")]
+    pub cache: Option<CacheConfig>,
+
     /// Properties that are not recognized by the storage configuration.
     ///
     /// These properties are ignored by the storage configuration and can be used for custom purposes.
@@ -130,21 +140,64 @@ pub struct StorageConfig {
     /// Since we remove properties during processing, but downstream integrations may
     /// use them for their own purposes, we keep a copy of the original properties.
     pub raw: HashMap<String, String>,
+
+    /// Request metrics of the object stores decorated with this configuration.
+    ///
+    /// Shared between clones of the configuration, so all stores of a log store report into
+    /// the same metrics.
+    pub io_metrics: Arc<ObjectStoreMetrics>,
 }
 
 impl StorageConfig {
     /// Wrap an object store with additional layers of functionality.
     ///
-    /// Depending on the configuration, the following layers may be added:
-    /// - Retry layer: Adds retry logic to the object store.
-    /// - Limit layer: Limits the number of concurrent requests to the object store.
+    /// See [`decorate_layers`](Self::decorate_layers) for the layers added. The resulting
+    /// store is scoped to `table_root`.
     pub fn decorate_store<T: ObjectStore + Clone>(
         &self,
         store: T,
         table_root: &url::Url,
     ) -> DeltaResult<Box<dyn ObjectStore>> {
-        let inner = Self::decorate_prefix(store, table_root)?;
-        Ok(inner)
+        let inner = self.decorate_layers(Arc::new(store), table_root);
+        Self::decorate_prefix(inner, table_root)
+    }
+
+    /// Wrap an object store with the layers implied by this configuration.
+    ///
+    /// Depending on the configuration, the following layers are added, innermost first:
+    /// - Limit layer: Limits the number of concurrent requests to the object store.
+    /// - Rate limit layer: Limits the number of requests per second to the object store.
+    /// - Metrics layer: Records the requests issued in [`io_metrics`](Self::io_metrics).
+    /// - Cache layer: Serves immutable objects from a local cache (`delta-cache` feature).
+    ///
+    /// `table_root` is only used to tell apart the objects of different buckets or accounts
+    /// in a shared cache, the returned store is not scoped to it.
+    pub fn decorate_layers(
+        &self,
+        store: Arc<dyn ObjectStore>,
+        #[cfg_attr(not(feature = "delta-cache"), allow(unused_variables))] table_root: &url::Url,
+    ) -> Arc<dyn ObjectStore> {
+        let mut store = store;
+        if let Some(limit) = &self.limit {
+            if let Some(max_concurrency) = limit.max_concurrency {
+                store = Arc::new(LimitStore::new(store, max_concurrency));
+            }
+            // Invalid rates are rejected by `parse_options`, configs built through
+            // `FromIterator` skip them instead of panicking in the rate limiter
+            if let Some(rate) = limit.max_requests_per_second
+                && rate.is_finite()
+                && rate > 0.0
+            {
+                let burst = limit.rate_limit_burst.unwrap_or(rate.ceil() as usize);
+                store = Arc::new(RateLimitStore::new(store, rate, burst));
+            }
+        }
+        let store = self.io_metrics.instrument(store);
+        #[cfg(feature = "delta-cache")]
+        if let Some(cache) = self.cache.as_ref().filter(|cache| cache.is_enabled()) {
+            return Arc::new(CachingObjectStore::new(store, cache, table_root));
+        }
+        store
     }
 
     pub(crate) fn decorate_prefix<T: ObjectStore>(
@@ -187,6 +240,13 @@ where
 
         let remainder = result.unparsed;
 
+        #[cfg(feature = "delta-cache")]
+        let remainder = {
+            let result = ParseResult::<CacheConfig>::from_iter(remainder);
+            config.cache = (!result.is_default).then_some(result.config);
+            result.unparsed
+        };
+
         #[cfg(feature = "cloud")]
         let remainder = {
             let result = ParseResult::<RetryConfig>::from_iter(remainder);
@@ -236,6 +296,7 @@ impl StorageConfig {
 
         let result = ParseResult::<LimitConfig>::from_iter(remainder);
         result.raise_errors()?;
+        result.config.validate()?;
         props.limit = (!result.is_default).then_some(result.config);
 
         let result = ParseResult::<CertificateConfig>::from_iter(result.unparsed);
@@ -243,6 +304,14 @@ impl StorageConfig {
         props.certificate = (!result.is_default).then_some(result.config);
         let remainder = result.unparsed;
 
+        #[cfg(feature = "delta-cache")]
+        let remainder = {
+            let result = ParseResult::<CacheConfig>::from_iter(remainder);
+            result.raise_errors()?;
+            props.cache = (!result.is_default).then_some(result.config);
+            result.unparsed
+        };
+
         #[cfg(feature = "cloud")]
         let remainder = {
             let (retry, remainder): (RetryConfig, _) = try_parse_impl(remainder)?;
@@ -408,6 +477,17 @@ mod tests {
         assert!(config.unknown_properties.contains_key("unknown_prop"));
     }
 
+    #[test]
+    fn test_storage_config_rejects_invalid_rate_limit() {
+        for rate in ["0", "-1", "NaN", "inf"] {
+            let options = HashMap::from([("rate_limit".to_string(), rate.to_string())]);
+            assert!(StorageConfig::parse_options(options).is_err(), "{rate}");
+        }
+        let options = HashMap::from([("rate_limit".to_string(), "2.5".to_string())]);
+        let config = StorageConfig::parse_options(options).unwrap();
+        assert_eq!(config.limit.unwrap().max_requests_per_second, Some(2.5));
+    }
+
     // Test utility parsing functions
     #[test]
     #[allow(clippy::approx_constant)]
@@ -455,4 +535,56 @@ mod tests {
             StorageConfig::default().with_io_runtime(IORuntime::Config(RuntimeConfig::default()));
         assert!(config.runtime.is_some());
     }
+
+    #[cfg(feature = "delta-cache")]
+    #[test]
+    fn test_cache_config_parsing() {
+        let config = StorageConfig::parse_options([
+            ("delta_cache_dir", "/tmp/delta-cache"),
+            ("cache_data_files", "true"),
+        ])
+        .unwrap();
+        let cache = config.cache.unwrap();
+        assert!(cache.is_enabled());
+        assert_eq!(cache.cache_dir.as_deref(), Some("/tmp/delta-cache"));
+        assert_eq!(cache.cache_data_files, Some(true));
+        assert!(StorageConfig::parse_options([("cache_max_object_size", "x")]).is_err());
+
+        let config = StorageConfig::parse_options([("cache_enabled", "false")]).unwrap();
+        assert!(!config.cache.unwrap().is_enabled());
+        assert!(StorageConfig::default().cache.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_decorate_store_records_metrics() {
+        use object_store::ObjectStoreExt as _;
+        use object_store::memory::InMemory;
+
+        let config =
+            StorageConfig::parse_options([("concurrency_limit", "4"), ("rate_limit", "1000")])
+                .unwrap();
+        let limit = config.limit.as_ref().unwrap();
+        assert_eq!(limit.max_concurrency, Some(4));
+        assert_eq!(limit.max_requests_per_second, Some(1000.0));
+
+        let table_root = url::Url::parse("memory:///table/").unwrap();
+        let root_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let store = config
+            .clone()
+            .decorate_store(root_store.clone(), &table_root)
+            .unwrap();
+        store
+            .put(&Path::from("part-0.parquet"), vec![0u8; 42].into())
+            .await
+            .unwrap();
+        root_store
+            .head(&Path::from("table/part-0.parquet"))
+            .await
+            .unwrap();
+
+        let stats = config.io_metrics.snapshot();
+        assert_eq!(stats.put.requests, 1);
+        assert_eq!(stats.put.bytes, 42);
+        assert_eq!(stats.head.requests, 0);
+    }
 }
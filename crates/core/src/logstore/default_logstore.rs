@@ -112,12 +112,18 @@ impl LogStore for DefaultLogStore {
         super::get_latest_version(self, current_version).await
     }
 
-    fn object_store(&self, _: Option<Uuid>) -> Arc<dyn ObjectStore> {
-        self.prefixed_store.clone()
+    fn object_store(&self, operation_id: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.config
+            .options()
+            .io_metrics
+            .scope(self.prefixed_store.clone(), operation_id)
     }
 
-    fn root_object_store(&self, _: Option<Uuid>) -> Arc<dyn ObjectStore> {
-        self.root_store.clone()
+    fn root_object_store(&self, operation_id: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.config
+            .options()
+            .io_metrics
+            .scope(self.root_store.clone(), operation_id)
     }
 
     fn config(&self) -> &LogStoreConfig {
@@ -0,0 +1,337 @@
+//! Mutual exclusion for commits on storage backends without conditional writes.
+//!
+//! The [`LogStore`] contract requires that only one writer is able to create the commit for a
+//! given version. Most backends guarantee this with an atomic put-if-absent or
+//! rename-if-not-exists. Network and FUSE mounts (NFS, SMB, ...) often do not, so two writers
+//! may silently overwrite each other's commit.
+//!
+//! The [`LockingLogStore`] wraps any other [`LogStore`] and serializes commits through a
+//! [`CommitLock`]. While holding the lock it checks that the target version does not exist yet
+//! and only then writes the commit, which gives the same guarantee as a conditional write as
+//! long as every writer of the table uses the same lock.
+//!
+//! [`FileCommitLock`] takes an advisory lock (`flock` on unix, `LockFileEx` on windows) on a
+//! lock file next to the delta log. The lock is released by the operating system if the
+//! process dies, so it can never become stale. Note that NFS only forwards `flock` to the
+//! server on Linux 2.6.12 and later, older clients lock locally only.
+
+use std::fmt::{self, Debug, Formatter};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use object_store::{Error as ObjectStoreError, ObjectStore, ObjectStoreExt as _};
+use tokio::time::Instant;
+use tracing::*;
+use url::Url;
+use uuid::Uuid;
+
+use super::{CommitOrBytes, LogStore, LogStoreConfig, LogStoreRef, commit_uri_from_version};
+use crate::kernel::transaction::TransactionError;
+use crate::kernel::{Version, spawn_blocking_with_span};
+use crate::{DeltaResult, DeltaTableError};
+
+const LOCK_FILE_NAME: &str = "_commit.lock";
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A lock serializing the commits to a single table
+#[async_trait::async_trait]
+pub trait CommitLock: Send + Sync + Debug {
+    /// Wait until the lock is acquired
+    async fn acquire(&self) -> DeltaResult<Box<dyn CommitLockGuard>>;
+}
+
+/// A held [`CommitLock`]
+#[async_trait::async_trait]
+pub trait CommitLockGuard: Send + Sync {
+    /// Release the lock
+    async fn release(self: Box<Self>) -> DeltaResult<()>;
+}
+
+/// Advisory lock on a file in the local (or mounted) filesystem
+#[derive(Debug, Clone)]
+pub struct FileCommitLock {
+    path: PathBuf,
+    timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl FileCommitLock {
+    /// Create a lock on the file at `path`. The file is created if it does not exist.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            timeout: DEFAULT_LOCK_TIMEOUT,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Create a lock on `_delta_log/_commit.lock` of the table at the given `file://` url
+    pub fn try_new_for_table(location: &Url) -> DeltaResult<Self> {
+        let root = location
+            .to_file_path()
+            .map_err(|_| DeltaTableError::InvalidTableLocation(location.to_string()))?;
+        Ok(Self::new(root.join("_delta_log").join(LOCK_FILE_NAME)))
+    }
+
+    /// Maximum time to wait for the lock
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Interval in which a held lock is polled
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    fn try_lock(path: PathBuf) -> DeltaResult<Option<File>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(file)),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(err)) => Err(err.into()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CommitLock for FileCommitLock {
+    async fn acquire(&self) -> DeltaResult<Box<dyn CommitLockGuard>> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let path = self.path.clone();
+            let file = spawn_blocking_with_span(move || Self::try_lock(path))
+                .await
+                .map_err(|err| DeltaTableError::GenericError {
+                    source: Box::new(err),
+                })??;
+            if let Some(file) = file {
+                debug!(path = %self.path.display(), "acquired commit lock");
+                return Ok(Box::new(FileCommitLockGuard { file }));
+            }
+            if Instant::now() >= deadline {
+                return Err(lock_timeout(self.path.display(), self.timeout));
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+struct FileCommitLockGuard {
+    file: File,
+}
+
+#[async_trait::async_trait]
+impl CommitLockGuard for FileCommitLockGuard {
+    async fn release(self: Box<Self>) -> DeltaResult<()> {
+        // The lock file itself is kept, removing it would race with writers waiting on it
+        Ok(self.file.unlock()?)
+    }
+}
+
+fn lock_timeout(path: impl fmt::Display, timeout: Duration) -> DeltaTableError {
+    DeltaTableError::Generic(format!(
+        "Timed out after {timeout:?} waiting for commit lock {path}"
+    ))
+}
+
+/// [`LogStore`] serializing commits of the wrapped store through a [`CommitLock`]
+///
+/// Commits are written with a plain put while holding the lock, so the wrapped store's object
+/// store does not need to support conditional writes.
+pub struct LockingLogStore {
+    inner: LogStoreRef,
+    lock: Arc<dyn CommitLock>,
+}
+
+impl Debug for LockingLogStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockingLogStore")
+            .field("inner", &self.inner.name())
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+impl LockingLogStore {
+    /// Create a new [`LockingLogStore`]
+    pub fn new(inner: LogStoreRef, lock: Arc<dyn CommitLock>) -> Self {
+        Self { inner, lock }
+    }
+
+    /// The lock used to serialize commits
+    pub fn lock(&self) -> &Arc<dyn CommitLock> {
+        &self.lock
+    }
+
+    async fn write_locked(
+        &self,
+        version: Version,
+        commit_or_bytes: CommitOrBytes,
+    ) -> Result<(), TransactionError> {
+        let store = self.inner.object_store(None);
+        let commit_path = commit_uri_from_version(Some(version));
+        match store.head(&commit_path).await {
+            Ok(_) => return Err(TransactionError::VersionAlreadyExists(version)),
+            Err(ObjectStoreError::NotFound { .. }) => {}
+            Err(err) => return Err(err.into()),
+        }
+        match commit_or_bytes {
+            CommitOrBytes::LogBytes(bytes) => {
+                store.put(&commit_path, bytes.into()).await?;
+            }
+            CommitOrBytes::TmpCommit(tmp_commit) => {
+                store.rename(&tmp_commit, &commit_path).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn lock_error(err: DeltaTableError) -> TransactionError {
+    TransactionError::LogStoreError {
+        msg: "failed to acquire commit lock".to_string(),
+        source: Box::new(err),
+    }
+}
+
+#[async_trait::async_trait]
+impl LogStore for LockingLogStore {
+    fn name(&self) -> String {
+        "LockingLogStore".into()
+    }
+
+    async fn refresh(&self) -> DeltaResult<()> {
+        self.inner.refresh().await
+    }
+
+    async fn read_commit_entry(&self, version: Version) -> DeltaResult<Option<Bytes>> {
+        self.inner.read_commit_entry(version).await
+    }
+
+    async fn write_commit_entry(
+        &self,
+        version: Version,
+        commit_or_bytes: CommitOrBytes,
+        _operation_id: Uuid,
+    ) -> Result<(), TransactionError> {
+        let guard = self.lock.acquire().await.map_err(lock_error)?;
+        let result = self.write_locked(version, commit_or_bytes).await;
+        if let Err(err) = guard.release().await {
+            warn!(error = %err, version, "failed to release commit lock");
+        }
+        result
+    }
+
+    async fn abort_commit_entry(
+        &self,
+        _version: Version,
+        commit_or_bytes: CommitOrBytes,
+        _operation_id: Uuid,
+    ) -> Result<(), TransactionError> {
+        match &commit_or_bytes {
+            CommitOrBytes::LogBytes(_) => Ok(()),
+            CommitOrBytes::TmpCommit(tmp_commit) => {
+                match self.inner.object_store(None).delete(tmp_commit).await {
+                    Ok(_) | Err(ObjectStoreError::NotFound { .. }) => Ok(()),
+                    Err(err) => Err(err.into()),
+                }
+            }
+        }
+    }
+
+    async fn get_latest_version(&self, current_version: Version) -> DeltaResult<Version> {
+        self.inner.get_latest_version(current_version).await
+    }
+
+    fn object_store(&self, operation_id: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.inner.object_store(operation_id)
+    }
+
+    fn root_object_store(&self, operation_id: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.inner.root_object_store(operation_id)
+    }
+
+    fn engine(&self, operation_id: Option<Uuid>) -> Arc<dyn delta_kernel::Engine> {
+        self.inner.engine(operation_id)
+    }
+
+    fn transaction_url(&self, operation_id: Option<Uuid>) -> DeltaResult<Url> {
+        self.inner.transaction_url(operation_id)
+    }
+
+    fn config(&self) -> &LogStoreConfig {
+        self.inner.config()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future::join_all;
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::logstore::{StorageConfig, default_logstore};
+
+    fn memory_log_store() -> LogStoreRef {
+        let store = Arc::new(InMemory::new());
+        let location = Url::parse("memory:///table").unwrap();
+        default_logstore(store.clone(), store, &location, &StorageConfig::default())
+    }
+
+    #[tokio::test]
+    async fn test_file_lock_is_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = FileCommitLock::new(dir.path().join(LOCK_FILE_NAME))
+            .with_timeout(Duration::from_millis(100))
+            .with_poll_interval(Duration::from_millis(10));
+
+        let guard = lock.acquire().await.unwrap();
+        assert!(lock.acquire().await.is_err());
+        guard.release().await.unwrap();
+        lock.acquire().await.unwrap().release().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_locking_log_store_single_winner() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner = memory_log_store();
+        let lock = Arc::new(FileCommitLock::new(dir.path().join(LOCK_FILE_NAME)));
+        let log_store = Arc::new(LockingLogStore::new(inner, lock));
+
+        let results = join_all((0..4).map(|i| {
+            let log_store = log_store.clone();
+            async move {
+                log_store
+                    .write_commit_entry(
+                        0,
+                        CommitOrBytes::LogBytes(Bytes::from(format!("{{\"writer\":{i}}}"))),
+                        Uuid::new_v4(),
+                    )
+                    .await
+            }
+        }))
+        .await;
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(
+            results
+                .iter()
+                .filter_map(|r| r.as_ref().err())
+                .all(|err| matches!(err, TransactionError::VersionAlreadyExists(0)))
+        );
+        assert!(log_store.read_commit_entry(0).await.unwrap().is_some());
+    }
+}
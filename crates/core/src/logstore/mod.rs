@@ -78,22 +78,32 @@ use crate::kernel::{Action, Version, spawn_blocking_with_span};
 use crate::table::normalize_table_url;
 use crate::{DeltaResult, DeltaTableError};
 
+pub use self::commit_coordinator::{
+    CommitCoordinator, CommitCoordinatorLogStore, InMemoryCommitCoordinator, StagedCommit,
+};
 pub use self::config::StorageConfig;
 pub use self::factories::{
     LogStoreFactory, LogStoreFactoryRegistry, ObjectStoreFactory, ObjectStoreFactoryRegistry,
     logstore_factories, object_store_factories, store_for,
 };
+pub use self::locking::{CommitLock, CommitLockGuard, FileCommitLock, LockingLogStore};
 pub use self::storage::utils::commit_uri_from_version;
+#[cfg(feature = "delta-cache")]
+pub use self::storage::{CacheConfig, CachingObjectStore};
 pub use self::storage::{
-    DefaultObjectStoreRegistry, DeltaIOStorageBackend, IORuntime, ObjectStoreRef,
-    ObjectStoreRegistry, ObjectStoreRetryExt, client_options_from_certificate,
+    DefaultObjectStoreRegistry, DeltaIOStorageBackend, IORuntime, IoMetrics, IoStats,
+    LatencyHistogram, MetricsObjectStore, ObjectStoreMetrics, ObjectStoreRef, ObjectStoreRegistry,
+    ObjectStoreRetryExt, RateLimitStore, RequestKind, RequestStats,
+    client_options_from_certificate,
 };
 /// Convenience re-export of the object store crate
 pub use ::object_store;
 
+pub(crate) mod commit_coordinator;
 pub mod config;
 pub(crate) mod default_logstore;
 pub(crate) mod factories;
+pub(crate) mod locking;
 pub(crate) mod storage;
 
 /// Internal trait to handle object store configuration and initialization.
@@ -121,7 +131,9 @@ impl<T: LogStoreFactory + ?Sized> LogStoreFactoryExt for T {
         location: &Url,
         options: &StorageConfig,
     ) -> DeltaResult<LogStoreRef> {
-        let prefixed_store = options.decorate_store(root_store.clone(), location)?;
+        // decorate the root store once, so that limits and metrics are shared with the prefixed store
+        let root_store = options.decorate_layers(root_store, location);
+        let prefixed_store = StorageConfig::decorate_prefix(root_store.clone(), location)?;
         let log_store =
             self.with_options(Arc::new(prefixed_store), root_store, location, options)?;
         Ok(log_store)
@@ -159,7 +171,7 @@ pub type LogStoreRef = Arc<dyn LogStore>;
 static DELTA_LOG_PATH: LazyLock<Path> = LazyLock::new(|| Path::from("_delta_log"));
 
 pub(crate) static DELTA_LOG_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(\d{20})\.(json|checkpoint(\.\d+)?\.parquet)$").unwrap());
+    LazyLock::new(|| Regex::new(r"(\d{20})\.(json|crc|checkpoint(\.\d+)?\.parquet)$").unwrap());
 
 /// Return the [LogStoreRef] for the provided [Url] location
 ///
@@ -473,6 +485,15 @@ pub trait LogStore: Send + Sync + AsAny {
     /// Get configuration representing configured log store.
     fn config(&self) -> &LogStoreConfig;
 
+    /// Request metrics of the object stores used by this log store.
+    ///
+    /// Requests issued through [`object_store`](Self::object_store) or
+    /// [`root_object_store`](Self::root_object_store) with an operation id are additionally
+    /// attributed to that operation.
+    fn io_metrics(&self) -> Arc<ObjectStoreMetrics> {
+        self.config().options().io_metrics.clone()
+    }
+
     #[cfg(feature = "datafusion")]
     /// Generate a unique enough url to identify the store in datafusion.
     /// The DF object store registry only cares about the scheme and the host of the url for
@@ -581,6 +602,10 @@ impl<T: LogStore + ?Sized> LogStore for Arc<T> {
         T::config(self)
     }
 
+    fn io_metrics(&self) -> Arc<ObjectStoreMetrics> {
+        T::io_metrics(self)
+    }
+
     #[cfg(feature = "datafusion")]
     fn object_store_url(&self) -> ObjectStoreUrl {
         T::object_store_url(self)
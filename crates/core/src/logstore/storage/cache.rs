@@ -0,0 +1,656 @@
+//! Local cache for immutable objects
+//!
+//! Most files of a Delta table never change once written: commits, checkpoints, sidecars,
+//! deletion vectors and data files. [`CachingObjectStore`] keeps copies of these objects in a
+//! hybrid memory and disk cache backed by [`foyer`], so repeated snapshot loads and scans do
+//! not download them again.
+//!
+//! Mutable files like `_last_checkpoint` or temporary commit files are never cached, and only
+//! successful reads are stored, so probing for the next commit always reaches the store.
+//!
+//! Cached objects are never revalidated against the store. This relies on the protocol: a
+//! version is committed exactly once, and checkpoints, sidecars, deletion vectors and data
+//! files are written under unique names. A table that is deleted and recreated at the same
+//! location by another process breaks this assumption, and a cache in a persistent
+//! `cache_dir` would serve the old table's log. Use a fresh `cache_dir` in that case.
+//!
+//! Caches are shared by all stores created with the same cache configuration in a process.
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::Range;
+use std::sync::{Arc, LazyLock};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use deltalake_derive::DeltaConfig;
+use foyer::{BlockEngineBuilder, DeviceBuilder, FsDeviceBuilder, HybridCache, HybridCacheBuilder};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use indexmap::IndexSet;
+use object_store::path::Path;
+use object_store::{
+    Attributes, CopyOptions, GetOptions, GetRange, GetResult, GetResultPayload, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions, PutPayload,
+    PutResult, RenameOptions, Result as ObjectStoreResult,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use tracing::*;
+use url::Url;
+
+const DEFAULT_MEMORY_CAPACITY: usize = 64 * 1024 * 1024;
+const DEFAULT_DISK_CAPACITY: usize = 1024 * 1024 * 1024;
+const DEFAULT_MAX_OBJECT_SIZE: usize = 8 * 1024 * 1024;
+/// Number of objects too large for the cache that a store remembers
+const MAX_OVERSIZED_OBJECTS: usize = 4096;
+
+/// Configuration of the local object cache
+///
+/// The cache is enabled as soon as any of the options is set, unless `cache_enabled` is false.
+#[derive(Debug, Clone, Default, DeltaConfig)]
+pub struct CacheConfig {
+    /// Enable or disable the cache.
+    #[delta(alias = "delta_cache_enabled", env = "DELTA_CACHE_ENABLED")]
+    pub cache_enabled: Option<bool>,
+
+    /// Directory of the disk tier. Defaults to a temporary directory.
+    #[delta(alias = "delta_cache_dir", env = "DELTA_CACHE_DIR")]
+    pub cache_dir: Option<String>,
+
+    /// Capacity of the memory tier in bytes. Defaults to 64 MiB.
+    #[delta(
+        alias = "delta_cache_memory_capacity",
+        env = "DELTA_CACHE_MEMORY_CAPACITY"
+    )]
+    pub cache_memory_capacity: Option<usize>,
+
+    /// Capacity of the disk tier in bytes. Defaults to 1 GiB.
+    #[delta(alias = "delta_cache_disk_capacity", env = "DELTA_CACHE_DISK_CAPACITY")]
+    pub cache_disk_capacity: Option<usize>,
+
+    /// Largest object that is cached in bytes. Defaults to 8 MiB.
+    #[delta(
+        alias = "delta_cache_max_object_size",
+        env = "DELTA_CACHE_MAX_OBJECT_SIZE"
+    )]
+    pub cache_max_object_size: Option<usize>,
+
+    /// Cache data files in addition to log files and deletion vectors. Defaults to false.
+    #[delta(alias = "delta_cache_data_files", env = "DELTA_CACHE_DATA_FILES")]
+    pub cache_data_files: Option<bool>,
+}
+
+impl CacheConfig {
+    /// Whether the cache should be applied
+    pub fn is_enabled(&self) -> bool {
+        self.cache_enabled.unwrap_or(true)
+    }
+
+    fn max_object_size(&self) -> u64 {
+        self.cache_max_object_size
+            .unwrap_or(DEFAULT_MAX_OBJECT_SIZE) as u64
+    }
+
+    fn key(&self) -> CacheKey {
+        CacheKey {
+            dir: self.cache_dir.clone(),
+            memory_capacity: self
+                .cache_memory_capacity
+                .unwrap_or(DEFAULT_MEMORY_CAPACITY),
+            disk_capacity: self.cache_disk_capacity.unwrap_or(DEFAULT_DISK_CAPACITY),
+        }
+    }
+}
+
+/// Identity of a cache in the process wide registry
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    dir: Option<String>,
+    memory_capacity: usize,
+    disk_capacity: usize,
+}
+
+static CACHES: LazyLock<Mutex<HashMap<CacheKey, Arc<ObjectCache>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Object as stored in the cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedObject {
+    data: Vec<u8>,
+    last_modified_us: i64,
+    e_tag: Option<String>,
+    version: Option<String>,
+}
+
+impl CachedObject {
+    fn meta(&self, location: &Path) -> ObjectMeta {
+        ObjectMeta {
+            location: location.clone(),
+            last_modified: DateTime::from_timestamp_micros(self.last_modified_us)
+                .unwrap_or(DateTime::<Utc>::UNIX_EPOCH),
+            size: self.data.len() as u64,
+            e_tag: self.e_tag.clone(),
+            version: self.version.clone(),
+        }
+    }
+
+    fn slice(&self, range: &Range<u64>) -> Option<Bytes> {
+        let data = self.data.get(range.start as usize..range.end as usize)?;
+        Some(Bytes::copy_from_slice(data))
+    }
+}
+
+/// Outcome of downloading an object into the cache
+enum Fill {
+    /// The object was downloaded and cached
+    Cached(CachedObject),
+    /// The object is too large to be cached, its payload was not read
+    Oversized(GetResult),
+}
+
+/// Hybrid memory and disk cache, built lazily on first use
+struct ObjectCache {
+    key: CacheKey,
+    cache: OnceCell<Option<HybridCache<String, CachedObject>>>,
+    /// Keeps the default disk tier directory alive for the lifetime of the cache
+    temp_dir: Mutex<Option<tempfile::TempDir>>,
+}
+
+impl Debug for ObjectCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObjectCache")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl ObjectCache {
+    fn for_config(config: &CacheConfig) -> Arc<Self> {
+        let key = config.key();
+        CACHES
+            .lock()
+            .entry(key.clone())
+            .or_insert_with(|| {
+                Arc::new(Self {
+                    key,
+                    cache: OnceCell::new(),
+                    temp_dir: Mutex::new(None),
+                })
+            })
+            .clone()
+    }
+
+    /// The cache, or `None` if it could not be created
+    async fn get(&self) -> Option<&HybridCache<String, CachedObject>> {
+        self.cache
+            .get_or_init(|| async {
+                match self.build().await {
+                    Ok(cache) => Some(cache),
+                    Err(err) => {
+                        warn!(dir = ?self.key.dir, "failed to create object cache: {err}");
+                        None
+                    }
+                }
+            })
+            .await
+            .as_ref()
+    }
+
+    async fn build(
+        &self,
+    ) -> Result<HybridCache<String, CachedObject>, Box<dyn std::error::Error + Send + Sync>> {
+        let dir = match &self.key.dir {
+            Some(dir) => std::path::PathBuf::from(dir),
+            None => {
+                let temp_dir = tempfile::Builder::new().prefix("delta-cache").tempdir()?;
+                let path = temp_dir.path().to_path_buf();
+                *self.temp_dir.lock() = Some(temp_dir);
+                path
+            }
+        };
+        std::fs::create_dir_all(&dir)?;
+        let device = FsDeviceBuilder::new(&dir)
+            .with_capacity(self.key.disk_capacity)
+            .build()?;
+        let cache = HybridCacheBuilder::new()
+            .with_name("delta-cache")
+            .memory(self.key.memory_capacity)
+            .with_weighter(|key: &String, value: &CachedObject| key.len() + value.data.len())
+            .storage()
+            .with_engine_config(BlockEngineBuilder::new(device))
+            .build()
+            .await?;
+        debug!(dir = %dir.display(), "created object cache");
+        Ok(cache)
+    }
+}
+
+/// [`ObjectStore`] serving immutable Delta table objects from a local cache
+#[derive(Debug, Clone)]
+pub struct CachingObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    cache: Arc<ObjectCache>,
+    namespace: String,
+    max_object_size: u64,
+    cache_data_files: bool,
+    /// Objects known to exceed `max_object_size`, these are read from the store directly
+    oversized: Arc<Mutex<IndexSet<Path>>>,
+}
+
+impl CachingObjectStore {
+    /// Wrap `inner` with the cache described by `config`.
+    ///
+    /// `table_root` identifies the storage account or bucket of the store, so that stores
+    /// sharing a cache do not mix up their objects.
+    pub fn new(inner: Arc<dyn ObjectStore>, config: &CacheConfig, table_root: &Url) -> Self {
+        Self {
+            inner,
+            cache: ObjectCache::for_config(config),
+            namespace: format!("{}://{}", table_root.scheme(), table_root.authority()),
+            max_object_size: config.max_object_size(),
+            cache_data_files: config.cache_data_files.unwrap_or(false),
+            oversized: Arc::new(Mutex::new(IndexSet::new())),
+        }
+    }
+
+    fn cache_key(&self, location: &Path) -> String {
+        format!("{}/{}", self.namespace, location)
+    }
+
+    /// Whether the object at `location` is immutable and may be cached
+    fn is_cacheable(&self, location: &Path) -> bool {
+        let Some(filename) = location.filename() else {
+            return false;
+        };
+        if filename.starts_with("deletion_vector_") && filename.ends_with(".bin") {
+            return true;
+        }
+        let mut parts = location.as_ref().rsplit('/').skip(1);
+        match parts.next() {
+            Some("_delta_log") => is_log_file(filename),
+            Some("_sidecars") => {
+                parts.next() == Some("_delta_log") && filename.ends_with(".parquet")
+            }
+            _ => {
+                self.cache_data_files
+                    && filename.ends_with(".parquet")
+                    && !location
+                        .as_ref()
+                        .split('/')
+                        .any(|part| part == "_delta_log")
+            }
+        }
+    }
+
+    fn is_oversized(&self, location: &Path) -> bool {
+        self.oversized.lock().contains(location)
+    }
+
+    fn mark_oversized(&self, location: &Path) {
+        let mut oversized = self.oversized.lock();
+        if oversized.len() >= MAX_OVERSIZED_OBJECTS {
+            oversized.shift_remove_index(0);
+        }
+        oversized.insert(location.clone());
+    }
+
+    fn invalidate(&self, location: &Path) {
+        self.oversized.lock().shift_remove(location);
+        if self.is_cacheable(location)
+            && let Some(Some(cache)) = self.cache.cache.get()
+        {
+            cache.remove(&self.cache_key(location));
+        }
+    }
+
+    async fn lookup(&self, location: &Path) -> Option<CachedObject> {
+        let cache = self.cache.get().await?;
+        match cache.get(&self.cache_key(location)).await {
+            Ok(entry) => entry.map(|entry| entry.value().clone()),
+            Err(err) => {
+                warn!(%location, "failed to read from object cache: {err}");
+                None
+            }
+        }
+    }
+
+    /// Download the whole object and store it in the cache.
+    ///
+    /// Objects that are too large to be cached are remembered, so later reads skip the cache
+    /// instead of issuing this request again. Their response is returned unread, so it can
+    /// still serve a read of the full object.
+    async fn fill(&self, location: &Path) -> ObjectStoreResult<Fill> {
+        let result = self.inner.get_opts(location, GetOptions::default()).await?;
+        if result.meta.size > self.max_object_size {
+            trace!(%location, size = result.meta.size, "object too large for cache");
+            self.mark_oversized(location);
+            return Ok(Fill::Oversized(result));
+        }
+        let meta = result.meta.clone();
+        let data = result.bytes().await?;
+        let object = CachedObject {
+            data: data.to_vec(),
+            last_modified_us: meta.last_modified.timestamp_micros(),
+            e_tag: meta.e_tag,
+            version: meta.version,
+        };
+        if let Some(cache) = self.cache.get().await {
+            trace!(%location, size = meta.size, "caching object");
+            cache.insert(self.cache_key(location), object.clone());
+        }
+        Ok(Fill::Cached(object))
+    }
+
+    async fn cached(&self, location: &Path) -> ObjectStoreResult<Option<CachedObject>> {
+        if let Some(object) = self.lookup(location).await {
+            return Ok(Some(object));
+        }
+        Ok(match self.fill(location).await? {
+            Fill::Cached(object) => Some(object),
+            Fill::Oversized(_) => None,
+        })
+    }
+}
+
+/// Whether `filename` is an immutable file in `_delta_log`, i.e. a commit, checkpoint,
+/// compacted log or checksum file, all of which start with a version.
+fn is_log_file(filename: &str) -> bool {
+    let Some((version, _)) = filename.split_once('.') else {
+        return false;
+    };
+    version.len() == 20
+        && version.bytes().all(|b| b.is_ascii_digit())
+        && [".json", ".parquet", ".crc"]
+            .iter()
+            .any(|suffix| filename.ends_with(suffix))
+}
+
+/// Resolve the requested range against an object of `size` bytes
+fn resolve_range(range: Option<&GetRange>, size: u64) -> Option<Range<u64>> {
+    let range = match range {
+        None => 0..size,
+        Some(GetRange::Bounded(range)) => range.start..range.end.min(size),
+        Some(GetRange::Offset(offset)) => *offset..size,
+        Some(GetRange::Suffix(suffix)) => size.saturating_sub(*suffix)..size,
+    };
+    (range.start <= range.end && (range.start < size || size == 0)).then_some(range)
+}
+
+fn is_unconditional(options: &GetOptions) -> bool {
+    options.if_match.is_none()
+        && options.if_none_match.is_none()
+        && options.if_modified_since.is_none()
+        && options.if_unmodified_since.is_none()
+        && options.version.is_none()
+}
+
+impl Display for CachingObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CachingObjectStore({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for CachingObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.invalidate(location);
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.invalidate(location);
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        if !self.is_cacheable(location)
+            || !is_unconditional(&options)
+            || self.is_oversized(location)
+        {
+            return self.inner.get_opts(location, options).await;
+        }
+        let object = match self.lookup(location).await {
+            Some(object) => Some(object),
+            None if options.head => None,
+            None => match self.fill(location).await? {
+                Fill::Cached(object) => Some(object),
+                // the whole object was requested, which is what the fill just fetched
+                Fill::Oversized(result) if options.range.is_none() => return Ok(result),
+                Fill::Oversized(_) => None,
+            },
+        };
+        let Some(object) = object else {
+            return self.inner.get_opts(location, options).await;
+        };
+        let meta = object.meta(location);
+        let range = match resolve_range(options.range.as_ref(), meta.size) {
+            Some(range) if !options.head => range,
+            Some(_) => 0..0,
+            // let the store report the invalid range
+            None => return self.inner.get_opts(location, options).await,
+        };
+        let data = object.slice(&range).unwrap_or_default();
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(futures::stream::once(async { Ok(data) }).boxed()),
+            meta,
+            range,
+            attributes: Attributes::default(),
+        })
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        if self.is_cacheable(location)
+            && !self.is_oversized(location)
+            && let Some(object) = self.cached(location).await?
+            && let Some(buffers) = ranges.iter().map(|range| object.slice(range)).collect()
+        {
+            return Ok(buffers);
+        }
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        let this = self.clone();
+        self.inner.delete_stream(
+            locations
+                .inspect_ok(move |location| this.invalidate(location))
+                .boxed(),
+        )
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        self.invalidate(to);
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        self.invalidate(from);
+        self.invalidate(to);
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt as _;
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::logstore::storage::{IoMetrics, MetricsObjectStore};
+
+    fn cached_store(config: CacheConfig) -> (CachingObjectStore, Arc<IoMetrics>) {
+        let metrics = Arc::new(IoMetrics::default());
+        let inner = Arc::new(MetricsObjectStore::new(
+            Arc::new(InMemory::new()),
+            metrics.clone(),
+        ));
+        let table_root = Url::parse("memory:///").unwrap();
+        (
+            CachingObjectStore::new(inner, &config, &table_root),
+            metrics,
+        )
+    }
+
+    fn config(dir: &tempfile::TempDir) -> CacheConfig {
+        CacheConfig {
+            cache_dir: Some(dir.path().to_string_lossy().to_string()),
+            cache_memory_capacity: Some(1024 * 1024),
+            cache_disk_capacity: Some(16 * 1024 * 1024),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cacheable_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, _) = cached_store(config(&dir));
+        for path in [
+            "table/_delta_log/00000000000000000001.json",
+            "table/_delta_log/00000000000000000010.checkpoint.parquet",
+            "table/_delta_log/00000000000000000010.checkpoint.0000000001.0000000002.parquet",
+            "table/_delta_log/00000000000000000001.00000000000000000009.compacted.json",
+            "table/_delta_log/00000000000000000001.crc",
+            "table/_delta_log/_sidecars/3a0d65cd-4056-49b8-937b-95f9e3ee90e5.parquet",
+            "table/deletion_vector_a52eda8c-0a57-4636-814b-9c165388f7ca.bin",
+        ] {
+            assert!(store.is_cacheable(&Path::from(path)), "{path}");
+        }
+        for path in [
+            "table/_delta_log/_last_checkpoint",
+            "table/_delta_log/_commit_3a0d65cd.json.tmp",
+            "table/_delta_log/_commit.lock",
+            "table/part-00000-a52eda8c.snappy.parquet",
+        ] {
+            assert!(!store.is_cacheable(&Path::from(path)), "{path}");
+        }
+
+        let (store, _) = cached_store(CacheConfig {
+            cache_data_files: Some(true),
+            ..config(&dir)
+        });
+        assert!(store.is_cacheable(&Path::from("table/part-00000-a52eda8c.snappy.parquet")));
+    }
+
+    #[test]
+    fn test_resolve_range() {
+        assert_eq!(resolve_range(None, 10), Some(0..10));
+        assert_eq!(
+            resolve_range(Some(&GetRange::Bounded(2..4)), 10),
+            Some(2..4)
+        );
+        assert_eq!(
+            resolve_range(Some(&GetRange::Bounded(2..40)), 10),
+            Some(2..10)
+        );
+        assert_eq!(resolve_range(Some(&GetRange::Offset(7)), 10), Some(7..10));
+        assert_eq!(resolve_range(Some(&GetRange::Suffix(3)), 10), Some(7..10));
+        assert_eq!(resolve_range(Some(&GetRange::Offset(10)), 10), None);
+    }
+
+    #[tokio::test]
+    async fn test_serves_immutable_objects_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, metrics) = cached_store(config(&dir));
+
+        let commit = Path::from("table/_delta_log/00000000000000000000.json");
+        let last_checkpoint = Path::from("table/_delta_log/_last_checkpoint");
+        let data = Bytes::from_static(b"{\"commitInfo\":{}}\n");
+        store.put(&commit, data.clone().into()).await.unwrap();
+        store
+            .put(&last_checkpoint, data.clone().into())
+            .await
+            .unwrap();
+
+        for _ in 0..3 {
+            let bytes = store.get(&commit).await.unwrap().bytes().await.unwrap();
+            assert_eq!(bytes, data);
+            store.get(&last_checkpoint).await.unwrap();
+        }
+        assert_eq!(
+            store.get_range(&commit, 2..14).await.unwrap(),
+            data.slice(2..14)
+        );
+        assert_eq!(
+            store.get_ranges(&commit, &[0..1, 5..6]).await.unwrap(),
+            vec![data.slice(0..1), data.slice(5..6)]
+        );
+        assert_eq!(store.head(&commit).await.unwrap().size, data.len() as u64);
+
+        let stats = metrics.snapshot();
+        // one fill for the commit and three reads of _last_checkpoint
+        assert_eq!(stats.get.requests, 4);
+        assert_eq!(stats.head.requests, 0);
+
+        store.delete(&commit).await.unwrap();
+        assert!(store.get(&commit).await.is_err());
+        assert!(store.head(&commit).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_objects_are_read_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, metrics) = cached_store(CacheConfig {
+            cache_max_object_size: Some(8),
+            ..config(&dir)
+        });
+
+        let checkpoint = Path::from("table/_delta_log/00000000000000000010.checkpoint.parquet");
+        let data = Bytes::from(vec![7u8; 32]);
+        store.put(&checkpoint, data.clone().into()).await.unwrap();
+
+        for _ in 0..2 {
+            let bytes = store.get(&checkpoint).await.unwrap().bytes().await.unwrap();
+            assert_eq!(bytes, data);
+            assert_eq!(
+                store.get_range(&checkpoint, 4..12).await.unwrap(),
+                data.slice(4..12)
+            );
+        }
+        // the first read is served by the fill, later reads bypass the cache
+        assert_eq!(metrics.snapshot().get.requests, 4);
+    }
+}
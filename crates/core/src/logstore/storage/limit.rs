@@ -0,0 +1,277 @@
+//! Request rate limiting for object stores
+//!
+//! [`RateLimitStore`] throttles the requests issued to the wrapped store with a token bucket.
+//! The bucket refills at the configured rate and holds at most `burst` tokens, so short
+//! bursts are served immediately while the sustained rate stays below the limit.
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt, stream};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as ObjectStoreResult,
+};
+use parking_lot::Mutex;
+
+/// Token bucket shared by all requests of a store
+///
+/// Tokens are reserved up front, so the level may drop below zero. A request that takes the
+/// bucket into debt sleeps until the refill has paid it back, which keeps waiting requests in
+/// the order they arrived.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: usize) -> Self {
+        let burst = burst.max(1) as f64;
+        Self {
+            rate,
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token and return how long the caller has to wait before using it
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut state = self.state.lock();
+        let elapsed = now.saturating_duration_since(state.updated).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.burst) - 1.0;
+        state.updated = now.max(state.updated);
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        }
+    }
+
+    async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// [`ObjectStore`] limiting the rate of requests issued to the wrapped store
+///
+/// Every request, including each part of a multipart upload and each listing, takes one
+/// token. Deletes take one token per object, even if the wrapped store batches them.
+#[derive(Debug, Clone)]
+pub struct RateLimitStore {
+    inner: Arc<dyn ObjectStore>,
+    bucket: Arc<TokenBucket>,
+}
+
+impl RateLimitStore {
+    /// Create a new [`RateLimitStore`] allowing `requests_per_second` with bursts of up to
+    /// `burst` requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests_per_second` is not a positive number.
+    pub fn new(inner: Arc<dyn ObjectStore>, requests_per_second: f64, burst: usize) -> Self {
+        assert!(
+            requests_per_second.is_finite() && requests_per_second > 0.0,
+            "requests per second must be positive"
+        );
+        Self {
+            inner,
+            bucket: Arc::new(TokenBucket::new(requests_per_second, burst)),
+        }
+    }
+
+    fn throttle_stream<T: Send + 'static>(
+        &self,
+        stream: BoxStream<'static, ObjectStoreResult<T>>,
+    ) -> BoxStream<'static, ObjectStoreResult<T>> {
+        let bucket = self.bucket.clone();
+        stream::once(async move { bucket.acquire().await })
+            .flat_map(move |_| stream)
+            .boxed()
+    }
+}
+
+impl Display for RateLimitStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "RateLimitStore({}/s, {})", self.bucket.rate, self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for RateLimitStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.bucket.acquire().await;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.bucket.acquire().await;
+        let inner = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(RateLimitUpload {
+            inner,
+            bucket: self.bucket.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.bucket.acquire().await;
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        self.bucket.acquire().await;
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        let bucket = self.bucket.clone();
+        let locations = locations
+            .and_then(move |location| {
+                let bucket = bucket.clone();
+                async move {
+                    bucket.acquire().await;
+                    Ok(location)
+                }
+            })
+            .boxed();
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.throttle_stream(self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.throttle_stream(self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.bucket.acquire().await;
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        self.bucket.acquire().await;
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        self.bucket.acquire().await;
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+#[derive(Debug)]
+struct RateLimitUpload {
+    inner: Box<dyn MultipartUpload>,
+    bucket: Arc<TokenBucket>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for RateLimitUpload {
+    fn put_part(&mut self, data: PutPayload) -> object_store::UploadPart {
+        let bucket = self.bucket.clone();
+        let part = self.inner.put_part(data);
+        Box::pin(async move {
+            bucket.acquire().await;
+            part.await
+        })
+    }
+
+    async fn complete(&mut self) -> ObjectStoreResult<PutResult> {
+        self.bucket.acquire().await;
+        self.inner.complete().await
+    }
+
+    async fn abort(&mut self) -> ObjectStoreResult<()> {
+        self.bucket.acquire().await;
+        self.inner.abort().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt as _;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_reserve() {
+        let bucket = TokenBucket::new(10.0, 2);
+        let now = Instant::now();
+        assert_eq!(bucket.reserve(now), Duration::ZERO);
+        assert_eq!(bucket.reserve(now), Duration::ZERO);
+        // the bucket is empty, the next requests queue up behind each other
+        assert_eq!(bucket.reserve(now), Duration::from_millis(100));
+        assert_eq!(bucket.reserve(now), Duration::from_millis(200));
+
+        // once the debt is paid the bucket refills, but only up to the burst
+        let later = now + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert!(!bucket.reserve(later).is_zero());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_store_throttles() {
+        let store = RateLimitStore::new(Arc::new(InMemory::new()), 50.0, 1);
+        let path = Path::from("data");
+        let start = Instant::now();
+        store.put(&path, vec![0u8; 8].into()).await.unwrap();
+        for _ in 0..5 {
+            store.head(&path).await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+}
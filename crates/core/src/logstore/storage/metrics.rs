@@ -0,0 +1,580 @@
+//! Request metrics for object stores
+//!
+//! The [`MetricsObjectStore`] counts the requests issued to the wrapped store and the bytes
+//! transferred, and records request latencies in a histogram. Metrics are collected per
+//! [`LogStore`](crate::logstore::LogStore) in an [`ObjectStoreMetrics`] registry, which also
+//! tracks the I/O of individual operations by their operation id.
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use indexmap::IndexMap;
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as ObjectStoreResult, UploadPart,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::*;
+use uuid::Uuid;
+
+/// Upper bounds of the latency histogram buckets in milliseconds
+const LATENCY_BUCKETS_MS: [u64; 11] = [1, 5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000];
+
+/// Number of operations for which metrics are retained
+const MAX_TRACKED_OPERATIONS: usize = 1024;
+
+/// Kind of request issued to an object store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestKind {
+    /// Object and range reads
+    Get,
+    /// Object writes, including multipart parts
+    Put,
+    /// Listings
+    List,
+    /// Metadata reads
+    Head,
+    /// Deletes
+    Delete,
+    /// Server side copies and renames
+    Copy,
+}
+
+impl RequestKind {
+    const ALL: [RequestKind; 6] = [
+        RequestKind::Get,
+        RequestKind::Put,
+        RequestKind::List,
+        RequestKind::Head,
+        RequestKind::Delete,
+        RequestKind::Copy,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            RequestKind::Get => "GET",
+            RequestKind::Put => "PUT",
+            RequestKind::List => "LIST",
+            RequestKind::Head => "HEAD",
+            RequestKind::Delete => "DELETE",
+            RequestKind::Copy => "COPY",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RequestCounters {
+    requests: AtomicU64,
+    errors: AtomicU64,
+    bytes: AtomicU64,
+    latency_sum_us: AtomicU64,
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+impl RequestCounters {
+    fn record(&self, bytes: u64, latency: Option<Duration>, failed: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        if failed {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Some(latency) = latency {
+            self.latency_sum_us
+                .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+            let millis = latency.as_millis() as u64;
+            let bucket = LATENCY_BUCKETS_MS
+                .iter()
+                .position(|bound| millis <= *bound)
+                .unwrap_or(LATENCY_BUCKETS_MS.len());
+            self.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn snapshot(&self) -> RequestStats {
+        RequestStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            latency: LatencyHistogram {
+                bucket_bounds_ms: LATENCY_BUCKETS_MS.to_vec(),
+                counts: self
+                    .latency_buckets
+                    .iter()
+                    .map(|count| count.load(Ordering::Relaxed))
+                    .collect(),
+                sum_ms: self.latency_sum_us.load(Ordering::Relaxed) as f64 / 1_000.0,
+            },
+        }
+    }
+}
+
+/// Request counters of a single metrics scope
+#[derive(Debug, Default)]
+pub struct IoMetrics {
+    counters: [RequestCounters; RequestKind::ALL.len()],
+}
+
+impl IoMetrics {
+    /// Record a request
+    pub fn record(&self, kind: RequestKind, bytes: u64, latency: Option<Duration>, failed: bool) {
+        self.counters[kind as usize].record(bytes, latency, failed);
+    }
+
+    /// Current values of all counters
+    pub fn snapshot(&self) -> IoStats {
+        let stats = |kind: RequestKind| self.counters[kind as usize].snapshot();
+        IoStats {
+            get: stats(RequestKind::Get),
+            put: stats(RequestKind::Put),
+            list: stats(RequestKind::List),
+            head: stats(RequestKind::Head),
+            delete: stats(RequestKind::Delete),
+            copy: stats(RequestKind::Copy),
+        }
+    }
+}
+
+/// Latency histogram of a request kind
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyHistogram {
+    /// Inclusive upper bounds of the buckets in milliseconds. The last bucket is unbounded.
+    pub bucket_bounds_ms: Vec<u64>,
+    /// Number of requests per bucket, one more than there are bounds
+    pub counts: Vec<u64>,
+    /// Sum of all recorded latencies in milliseconds
+    pub sum_ms: f64,
+}
+
+/// Metrics of a single request kind
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestStats {
+    /// Number of requests issued
+    pub requests: u64,
+    /// Number of failed requests
+    pub errors: u64,
+    /// Number of bytes read or written
+    pub bytes: u64,
+    /// Request latencies
+    pub latency: LatencyHistogram,
+}
+
+/// Snapshot of the requests issued to an object store
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IoStats {
+    /// GET requests
+    pub get: RequestStats,
+    /// PUT requests
+    pub put: RequestStats,
+    /// LIST requests
+    pub list: RequestStats,
+    /// HEAD requests
+    pub head: RequestStats,
+    /// DELETE requests
+    pub delete: RequestStats,
+    /// COPY and RENAME requests
+    pub copy: RequestStats,
+}
+
+impl IoStats {
+    fn all(&self) -> [&RequestStats; 6] {
+        [
+            &self.get,
+            &self.put,
+            &self.list,
+            &self.head,
+            &self.delete,
+            &self.copy,
+        ]
+    }
+
+    /// Total number of requests
+    pub fn total_requests(&self) -> u64 {
+        self.all().iter().map(|stats| stats.requests).sum()
+    }
+
+    /// Total number of bytes read and written
+    pub fn total_bytes(&self) -> u64 {
+        self.all().iter().map(|stats| stats.bytes).sum()
+    }
+}
+
+/// Registry of the I/O metrics of a log store and its operations
+#[derive(Default)]
+pub struct ObjectStoreMetrics {
+    total: Arc<IoMetrics>,
+    operations: Mutex<IndexMap<Uuid, Arc<IoMetrics>>>,
+}
+
+impl Debug for ObjectStoreMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObjectStoreMetrics")
+            .field("requests", &self.snapshot().total_requests())
+            .finish()
+    }
+}
+
+impl ObjectStoreMetrics {
+    /// Metrics of all requests issued through the log store
+    pub fn snapshot(&self) -> IoStats {
+        self.total.snapshot()
+    }
+
+    /// Metrics of the requests issued on behalf of an operation
+    pub fn operation_snapshot(&self, operation_id: &Uuid) -> Option<IoStats> {
+        self.operations
+            .lock()
+            .get(operation_id)
+            .map(|metrics| metrics.snapshot())
+    }
+
+    /// Remove and return the metrics of an operation
+    pub fn take_operation(&self, operation_id: &Uuid) -> Option<IoStats> {
+        self.operations
+            .lock()
+            .shift_remove(operation_id)
+            .map(|metrics| metrics.snapshot())
+    }
+
+    fn operation(&self, operation_id: Uuid) -> Arc<IoMetrics> {
+        let mut operations = self.operations.lock();
+        if !operations.contains_key(&operation_id) && operations.len() >= MAX_TRACKED_OPERATIONS {
+            operations.shift_remove_index(0);
+        }
+        operations.entry(operation_id).or_default().clone()
+    }
+
+    /// Wrap `store` so its requests are recorded in the metrics of all requests
+    pub(crate) fn instrument(&self, store: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        Arc::new(MetricsObjectStore::new(store, self.total.clone()))
+    }
+
+    /// Wrap `store` so its requests are additionally attributed to the given operation
+    pub(crate) fn scope(
+        &self,
+        store: Arc<dyn ObjectStore>,
+        operation_id: Option<Uuid>,
+    ) -> Arc<dyn ObjectStore> {
+        match operation_id {
+            Some(operation_id) => {
+                Arc::new(MetricsObjectStore::new(store, self.operation(operation_id)))
+            }
+            None => store,
+        }
+    }
+}
+
+/// [`ObjectStore`] recording the requests to the wrapped store in [`IoMetrics`]
+#[derive(Debug, Clone)]
+pub struct MetricsObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    metrics: Arc<IoMetrics>,
+}
+
+impl MetricsObjectStore {
+    /// Create a new [`MetricsObjectStore`]
+    pub fn new(inner: Arc<dyn ObjectStore>, metrics: Arc<IoMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+
+    /// The metrics the requests are recorded in
+    pub fn metrics(&self) -> &Arc<IoMetrics> {
+        &self.metrics
+    }
+
+    fn record<T>(
+        &self,
+        kind: RequestKind,
+        location: &Path,
+        bytes: u64,
+        start: Instant,
+        result: &ObjectStoreResult<T>,
+    ) {
+        let latency = start.elapsed();
+        trace!(
+            request = kind.as_str(),
+            %location,
+            bytes,
+            latency_ms = latency.as_millis() as u64,
+            failed = result.is_err(),
+            "object store request"
+        );
+        self.metrics
+            .record(kind, bytes, Some(latency), result.is_err());
+    }
+
+    fn list_stream(
+        &self,
+        stream: BoxStream<'static, ObjectStoreResult<ObjectMeta>>,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        let mut guard = ListGuard {
+            metrics: self.metrics.clone(),
+            start: Instant::now(),
+            failed: false,
+        };
+        stream
+            .map(move |item| {
+                guard.failed |= item.is_err();
+                item
+            })
+            .boxed()
+    }
+}
+
+/// Records a listing once its stream is dropped
+struct ListGuard {
+    metrics: Arc<IoMetrics>,
+    start: Instant,
+    failed: bool,
+}
+
+impl Drop for ListGuard {
+    fn drop(&mut self) {
+        self.metrics.record(
+            RequestKind::List,
+            0,
+            Some(self.start.elapsed()),
+            self.failed,
+        );
+    }
+}
+
+impl Display for MetricsObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "MetricsObjectStore({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for MetricsObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        let bytes = payload.content_length() as u64;
+        let start = Instant::now();
+        let result = self.inner.put_opts(location, payload, opts).await;
+        self.record(RequestKind::Put, location, bytes, start, &result);
+        result
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        let start = Instant::now();
+        let result = self.inner.put_multipart_opts(location, opts).await;
+        self.record(RequestKind::Put, location, 0, start, &result);
+        Ok(Box::new(MetricsMultipartUpload {
+            inner: result?,
+            metrics: self.metrics.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        let kind = if options.head {
+            RequestKind::Head
+        } else {
+            RequestKind::Get
+        };
+        let start = Instant::now();
+        let result = self.inner.get_opts(location, options).await;
+        let bytes = match (&result, kind) {
+            (Ok(result), RequestKind::Get) => result.range.end - result.range.start,
+            _ => 0,
+        };
+        self.record(kind, location, bytes, start, &result);
+        result
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        let start = Instant::now();
+        let result = self.inner.get_ranges(location, ranges).await;
+        let bytes = match &result {
+            Ok(buffers) => buffers.iter().map(|b| b.len() as u64).sum(),
+            Err(_) => 0,
+        };
+        self.record(RequestKind::Get, location, bytes, start, &result);
+        result
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        let metrics = self.metrics.clone();
+        self.inner
+            .delete_stream(locations)
+            .map(move |result| {
+                // Deletes may be batched by the store, so no latency is recorded per object
+                metrics.record(RequestKind::Delete, 0, None, result.is_err());
+                result
+            })
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.list_stream(self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.list_stream(self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        let start = Instant::now();
+        let result = self.inner.list_with_delimiter(prefix).await;
+        let location = prefix.cloned().unwrap_or_default();
+        self.record(RequestKind::List, &location, 0, start, &result);
+        result
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        let start = Instant::now();
+        let result = self.inner.copy_opts(from, to, options).await;
+        self.record(RequestKind::Copy, to, 0, start, &result);
+        result
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        let start = Instant::now();
+        let result = self.inner.rename_opts(from, to, options).await;
+        self.record(RequestKind::Copy, to, 0, start, &result);
+        result
+    }
+}
+
+#[derive(Debug)]
+struct MetricsMultipartUpload {
+    inner: Box<dyn MultipartUpload>,
+    metrics: Arc<IoMetrics>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for MetricsMultipartUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let bytes = data.content_length() as u64;
+        let metrics = self.metrics.clone();
+        let part = self.inner.put_part(data);
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = part.await;
+            metrics.record(
+                RequestKind::Put,
+                bytes,
+                Some(start.elapsed()),
+                result.is_err(),
+            );
+            result
+        })
+    }
+
+    async fn complete(&mut self) -> ObjectStoreResult<PutResult> {
+        let start = Instant::now();
+        let result = self.inner.complete().await;
+        self.metrics
+            .record(RequestKind::Put, 0, Some(start.elapsed()), result.is_err());
+        result
+    }
+
+    async fn abort(&mut self) -> ObjectStoreResult<()> {
+        let start = Instant::now();
+        let result = self.inner.abort().await;
+        self.metrics.record(
+            RequestKind::Delete,
+            0,
+            Some(start.elapsed()),
+            result.is_err(),
+        );
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use object_store::ObjectStoreExt as _;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_records_requests_and_bytes() {
+        let metrics = ObjectStoreMetrics::default();
+        let store = metrics.instrument(Arc::new(InMemory::new()));
+        let operation_id = Uuid::new_v4();
+        let scoped = metrics.scope(store.clone(), Some(operation_id));
+
+        let path = Path::from("a/b.parquet");
+        scoped.put(&path, vec![0u8; 100].into()).await.unwrap();
+        store.get(&path).await.unwrap().bytes().await.unwrap();
+        store.get_range(&path, 10..20).await.unwrap();
+        store.head(&path).await.unwrap();
+        let listed: Vec<_> = store.list(None).try_collect().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        store.delete(&path).await.unwrap();
+        assert!(store.head(&path).await.is_err());
+
+        let stats = metrics.snapshot();
+        assert_eq!(stats.put.requests, 1);
+        assert_eq!(stats.put.bytes, 100);
+        assert_eq!(stats.get.requests, 2);
+        assert_eq!(stats.get.bytes, 110);
+        assert_eq!(stats.head.requests, 2);
+        assert_eq!(stats.head.errors, 1);
+        assert_eq!(stats.list.requests, 1);
+        assert_eq!(stats.delete.requests, 1);
+        assert_eq!(stats.total_requests(), 7);
+        assert_eq!(stats.put.latency.counts.iter().sum::<u64>(), 1);
+
+        let operation = metrics.operation_snapshot(&operation_id).unwrap();
+        assert_eq!(operation.total_requests(), 1);
+        assert_eq!(operation.put.bytes, 100);
+        assert!(metrics.take_operation(&operation_id).is_some());
+        assert!(metrics.operation_snapshot(&operation_id).is_none());
+    }
+
+    #[test]
+    fn test_operations_are_bounded() {
+        let metrics = ObjectStoreMetrics::default();
+        let first = Uuid::new_v4();
+        metrics.operation(first);
+        for _ in 0..MAX_TRACKED_OPERATIONS {
+            metrics.operation(Uuid::new_v4());
+        }
+        assert!(metrics.operation_snapshot(&first).is_none());
+        assert_eq!(metrics.operations.lock().len(), MAX_TRACKED_OPERATIONS);
+    }
+}
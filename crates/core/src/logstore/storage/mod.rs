@@ -11,9 +11,20 @@ use url::Url;
 use crate::table::normalize_table_url;
 use crate::{DeltaResult, DeltaTableError};
 
+#[cfg(feature = "delta-cache")]
+pub use cache::{CacheConfig, CachingObjectStore};
+pub use limit::RateLimitStore;
+pub use metrics::{
+    IoMetrics, IoStats, LatencyHistogram, MetricsObjectStore, ObjectStoreMetrics, RequestKind,
+    RequestStats,
+};
 pub use retry_ext::ObjectStoreRetryExt;
 pub use runtime::{DeltaIOStorageBackend, IORuntime};
 
+#[cfg(feature = "delta-cache")]
+pub(super) mod cache;
+pub(super) mod limit;
+pub(super) mod metrics;
 pub(super) mod retry_ext;
 pub(super) mod runtime;
 pub(super) mod utils;
@@ -97,8 +108,33 @@ impl ObjectStoreRegistry for DefaultObjectStoreRegistry {
 
 #[derive(Debug, Clone, Default, DeltaConfig)]
 pub struct LimitConfig {
+    /// Maximum number of concurrent requests to the object store.
     #[delta(alias = "concurrency_limit", env = "OBJECT_STORE_CONCURRENCY_LIMIT")]
     pub max_concurrency: Option<usize>,
+
+    /// Maximum sustained number of requests per second to the object store.
+    #[delta(alias = "rate_limit", env = "OBJECT_STORE_RATE_LIMIT")]
+    pub max_requests_per_second: Option<f64>,
+
+    /// Number of requests that may be issued at once before the rate limit applies.
+    ///
+    /// Defaults to the rate limit rounded up to a whole request.
+    #[delta(env = "OBJECT_STORE_RATE_LIMIT_BURST")]
+    pub rate_limit_burst: Option<usize>,
+}
+
+impl LimitConfig {
+    /// Check that the configured limits can be applied to a store
+    pub(crate) fn validate(&self) -> DeltaResult<()> {
+        if let Some(rate) = self.max_requests_per_second
+            && !(rate.is_finite() && rate > 0.0)
+        {
+            return Err(DeltaTableError::Generic(format!(
+                "invalid max_requests_per_second {rate}, expected a positive number"
+            )));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Default, DeltaConfig)]
@@ -149,6 +185,13 @@ mod tests {
 
         config.try_update_key("max_concurrency", "20").unwrap();
         assert_eq!(config.max_concurrency, Some(20));
+
+        assert!(config.max_requests_per_second.is_none());
+        config.try_update_key("rate_limit", "12.5").unwrap();
+        assert_eq!(config.max_requests_per_second, Some(12.5));
+
+        config.try_update_key("rate_limit_burst", "25").unwrap();
+        assert_eq!(config.rate_limit_burst, Some(25));
     }
 
     #[rstest]
@@ -1,8 +1,22 @@
 //! Add a new column to a table
+//!
+//! Columns are appended to the top level of the schema with [`AddColumnBuilder::with_fields`].
+//! [`AddColumnBuilder::with_column`] can also add a field inside a nested struct and place it
+//! first or after another field:
+//!
+//! ```rust ignore
+//! let table = table
+//!     .add_columns()
+//!     .with_column(NewColumn::new(zip_field).in_struct("address").after("city"))
+//!     .await?;
+//! ```
+//!
+//! NOT NULL columns can only be added to a table that contains data when they have a default
+//! value, which existing rows are rewritten with.
 
 use std::sync::Arc;
 
-use delta_kernel::schema::StructType;
+use delta_kernel::schema::{DataType, StructType};
 use delta_kernel::table_features::ColumnMappingMode;
 use futures::future::BoxFuture;
 use itertools::Itertools;
@@ -12,19 +26,76 @@ use crate::errors::ColumnMappingOperation;
 use crate::kernel::schema::merge_delta_struct;
 use crate::kernel::transaction::{CommitBuilder, CommitProperties};
 use crate::kernel::{
-    Action, EagerSnapshot, MetadataExt, ProtocolExt as _, SnapshotMetadataRef, StructField,
-    StructTypeExt, resolve_snapshot,
+    Action, COLUMN_DEFAULT_KEY, EagerSnapshot, MetadataExt, ProtocolExt as _, SnapshotMetadataRef,
+    StructField, StructTypeExt, resolve_snapshot,
 };
 use crate::logstore::LogStoreRef;
 use crate::protocol::DeltaOperation;
+use crate::table::ColumnDefault;
 use crate::{DeltaResult, DeltaTable, DeltaTableError};
 
+/// Where a new column is placed among the fields of its parent struct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnPosition {
+    /// Place the column before all other fields
+    First,
+    /// Place the column directly after the named field
+    After(String),
+}
+
+/// A column to add with [`AddColumnBuilder::with_column`]
+#[derive(Debug, Clone)]
+pub struct NewColumn {
+    /// Path of the struct the field is added to, empty for the top level
+    parent: Vec<String>,
+    field: StructField,
+    position: Option<ColumnPosition>,
+}
+
+impl NewColumn {
+    /// Add `field` to the top level of the schema, after all existing columns
+    pub fn new(field: StructField) -> Self {
+        Self {
+            parent: vec![],
+            field,
+            position: None,
+        }
+    }
+
+    /// Add the field inside the struct at the dotted `path`, e.g. `address.geo`
+    pub fn in_struct(mut self, path: &str) -> Self {
+        self.parent = path.split('.').map(str::to_string).collect();
+        self
+    }
+
+    /// Place the field before all other fields of its parent
+    pub fn first(mut self) -> Self {
+        self.position = Some(ColumnPosition::First);
+        self
+    }
+
+    /// Place the field directly after `column` in its parent
+    pub fn after(mut self, column: impl Into<String>) -> Self {
+        self.position = Some(ColumnPosition::After(column.into()));
+        self
+    }
+
+    fn path(&self) -> String {
+        self.parent
+            .iter()
+            .chain(std::iter::once(&self.field.name))
+            .join(".")
+    }
+}
+
 /// Add new columns and/or nested fields to a table
 pub struct AddColumnBuilder {
     /// A snapshot of the table's state
     snapshot: Option<EagerSnapshot>,
     /// Fields to add/merge into schema
     fields: Option<Vec<StructField>>,
+    /// Columns to add at a nested path and/or position
+    columns: Vec<NewColumn>,
     /// Delta object store for handling data files
     log_store: LogStoreRef,
     /// Additional information to add to the commit
@@ -48,6 +119,7 @@ impl AddColumnBuilder {
             snapshot,
             log_store,
             fields: None,
+            columns: vec![],
             commit_properties: CommitProperties::default(),
             custom_execute_handler: None,
         }
@@ -58,6 +130,13 @@ impl AddColumnBuilder {
         self.fields = Some(fields.into_iter().collect());
         self
     }
+
+    /// Add a column at a nested path and/or position. Columns are added in the order given.
+    pub fn with_column(mut self, column: NewColumn) -> Self {
+        self.columns.push(column);
+        self
+    }
+
     /// Additional metadata to be added to commit info
     pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
         self.commit_properties = commit_properties;
@@ -71,12 +150,77 @@ impl AddColumnBuilder {
     }
 }
 
+/// Insert `field` into the struct at `parent`, at the requested position.
+fn insert_field(
+    schema: &StructType,
+    parent: &[String],
+    field: &StructField,
+    position: Option<&ColumnPosition>,
+) -> DeltaResult<StructType> {
+    let Some((head, rest)) = parent.split_first() else {
+        if schema.field(field.name()).is_some() {
+            return Err(DeltaTableError::Generic(format!(
+                "Field {} already exists",
+                field.name()
+            )));
+        }
+        let mut fields = schema.fields().cloned().collect_vec();
+        let index = match position {
+            None => fields.len(),
+            Some(ColumnPosition::First) => 0,
+            Some(ColumnPosition::After(name)) => {
+                fields
+                    .iter()
+                    .position(|f| f.name() == name)
+                    .ok_or_else(|| {
+                        DeltaTableError::Generic(format!("Field {name} does not exist"))
+                    })?
+                    + 1
+            }
+        };
+        fields.insert(index, field.clone());
+        return Ok(StructType::try_new(fields)?);
+    };
+
+    let Some(parent_field) = schema.field(head) else {
+        return Err(DeltaTableError::Generic(format!(
+            "Field {head} does not exist"
+        )));
+    };
+    let DataType::Struct(inner) = parent_field.data_type() else {
+        return Err(DeltaTableError::Generic(format!(
+            "Field {head} is not a struct"
+        )));
+    };
+    let inner = insert_field(inner, rest, field, position)?;
+    let parent_field = StructField::new(
+        head.clone(),
+        DataType::Struct(Box::new(inner)),
+        parent_field.is_nullable(),
+    )
+    .with_metadata(parent_field.metadata().clone());
+
+    Ok(StructType::try_new(schema.fields().map(|f| {
+        if f.name() == head {
+            parent_field.clone()
+        } else {
+            f.clone()
+        }
+    }))?)
+}
+
 fn plan_add_column_actions(
     snapshot: SnapshotMetadataRef<'_>,
     fields: Vec<StructField>,
+    columns: &[NewColumn],
 ) -> DeltaResult<(Vec<Action>, DeltaOperation)> {
     let mut metadata = snapshot.metadata.clone();
-    let fields_right = &StructType::try_new(fields.clone())?;
+    let fields_right = &StructType::try_new(
+        fields
+            .iter()
+            .chain(columns.iter().map(|c| &c.field))
+            .cloned(),
+    )?;
 
     if !fields_right
         .get_generated_columns()
@@ -88,8 +232,30 @@ fn plan_add_column_actions(
         ));
     }
 
+    if let Some(column) = columns
+        .iter()
+        .find(|c| !c.parent.is_empty() && c.field.metadata().contains_key(COLUMN_DEFAULT_KEY))
+    {
+        return Err(DeltaTableError::Generic(format!(
+            "Nested field {} cannot have a default value",
+            column.path()
+        )));
+    }
+
     let table_schema = snapshot.table_configuration.logical_schema();
-    let new_table_schema = merge_delta_struct(table_schema.as_ref(), fields_right)?;
+    let mut new_table_schema = if fields.is_empty() {
+        table_schema.as_ref().clone()
+    } else {
+        merge_delta_struct(table_schema.as_ref(), &StructType::try_new(fields.clone())?)?
+    };
+    for column in columns {
+        new_table_schema = insert_field(
+            &new_table_schema,
+            &column.parent,
+            &column.field,
+            column.position.as_ref(),
+        )?;
+    }
 
     let current_protocol = snapshot.protocol;
     let new_protocol = current_protocol
@@ -98,7 +264,7 @@ fn plan_add_column_actions(
         .move_table_properties_into_features(metadata.configuration());
 
     let operation = DeltaOperation::AddColumn {
-        fields: fields.into_iter().collect_vec(),
+        fields: fields_right.fields().cloned().collect_vec(),
     };
 
     metadata = metadata.with_schema(&new_table_schema)?;
@@ -112,6 +278,84 @@ fn plan_add_column_actions(
     Ok((actions, operation))
 }
 
+/// NOT NULL top-level columns with a default value, which existing rows have to be rewritten with.
+fn columns_to_backfill(fields: &[&StructField]) -> DeltaResult<Vec<ColumnDefault>> {
+    let not_null = StructType::try_new(
+        fields
+            .iter()
+            .filter(|f| !f.is_nullable())
+            .map(|f| (*f).clone()),
+    )?;
+    Ok(not_null.get_column_defaults()?)
+}
+
+/// Rewrite all files of the table with the default value of the added NOT NULL columns.
+#[cfg(feature = "datafusion")]
+async fn backfill_column_defaults(
+    log_store: &LogStoreRef,
+    snapshot: &EagerSnapshot,
+    column_defaults: &[ColumnDefault],
+    operation_id: uuid::Uuid,
+) -> DeltaResult<Vec<Action>> {
+    use datafusion::datasource::provider_as_source;
+    use datafusion::logical_expr::LogicalPlanBuilder;
+    use futures::TryStreamExt as _;
+
+    use crate::delta_datafusion::{DeltaScanNext, create_session, update_datafusion_session};
+    use crate::operations::write::WriterStatsConfig;
+    use crate::operations::write::column_defaults::with_column_defaults;
+    use crate::operations::write::execution::write_execution_plan;
+    use crate::parquet_utils::table_writer_properties;
+
+    let removes: Vec<Action> = snapshot
+        .file_views(log_store.as_ref(), None)
+        .map_ok(|file| Action::Remove(file.remove_action(true)))
+        .try_collect()
+        .await?;
+    if removes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let session = create_session().into_inner().state();
+    update_datafusion_session(&session, log_store.as_ref(), Some(operation_id))?;
+
+    let provider = DeltaScanNext::builder()
+        .with_log_store(log_store.clone())
+        .with_eager_snapshot(snapshot.clone())
+        .await?;
+    let scan = LogicalPlanBuilder::scan("target", provider_as_source(provider), None)?.build()?;
+    let plan = with_column_defaults(&session, scan, column_defaults)?;
+    let physical_plan = session.create_physical_plan(&plan).await?;
+
+    let table_configuration = snapshot.table_configuration();
+    let mut actions = write_execution_plan(
+        Some(snapshot),
+        &session,
+        physical_plan,
+        snapshot.metadata().partition_columns().to_vec(),
+        log_store.object_store(Some(operation_id)),
+        Some(snapshot.table_properties().target_file_size()),
+        None,
+        table_writer_properties(None, table_configuration)?,
+        WriterStatsConfig::from_config(table_configuration),
+    )
+    .await?;
+    actions.extend(removes);
+    Ok(actions)
+}
+
+#[cfg(not(feature = "datafusion"))]
+async fn backfill_column_defaults(
+    _log_store: &LogStoreRef,
+    _snapshot: &EagerSnapshot,
+    _column_defaults: &[ColumnDefault],
+    _operation_id: uuid::Uuid,
+) -> DeltaResult<Vec<Action>> {
+    Err(DeltaTableError::Generic(
+        "Adding a NOT NULL column with a default value requires the datafusion feature".to_string(),
+    ))
+}
+
 impl std::future::IntoFuture for AddColumnBuilder {
     type Output = DeltaResult<DeltaTable>;
 
@@ -136,15 +380,70 @@ impl std::future::IntoFuture for AddColumnBuilder {
                 ));
             }
 
-            let fields = match this.fields.clone() {
-                Some(v) => v,
-                None => return Err(DeltaTableError::Generic("No fields provided".to_string())),
-            };
+            let fields = this.fields.clone().unwrap_or_default();
+            if fields.is_empty() && this.columns.is_empty() {
+                return Err(DeltaTableError::Generic("No fields provided".to_string()));
+            }
             let operation_id = this.get_operation_id();
             this.pre_execute(operation_id).await?;
 
-            let (actions, operation) =
-                plan_add_column_actions(snapshot.snapshot().metadata_state(), fields)?;
+            let top_level = fields
+                .iter()
+                .chain(
+                    this.columns
+                        .iter()
+                        .filter(|c| c.parent.is_empty())
+                        .map(|c| &c.field),
+                )
+                .collect_vec();
+            let backfill = columns_to_backfill(&top_level)?;
+            // NOT NULL columns without a value for existing rows
+            let not_null = top_level
+                .iter()
+                .filter(|f| !f.is_nullable() && !backfill.iter().any(|d| &d.name == f.name()))
+                .map(|f| f.name().to_string())
+                .chain(
+                    this.columns
+                        .iter()
+                        .filter(|c| !c.parent.is_empty() && !c.field.is_nullable())
+                        .map(NewColumn::path),
+                )
+                .collect_vec();
+            #[cfg(feature = "datafusion")]
+            crate::operations::write::column_defaults::validate_column_defaults(
+                &StructType::try_new(top_level.iter().map(|f| (*f).clone()))?,
+            )?;
+
+            let (mut actions, operation) = plan_add_column_actions(
+                snapshot.snapshot().metadata_state(),
+                fields,
+                &this.columns,
+            )?;
+
+            let snapshot = if backfill.is_empty() && not_null.is_empty() {
+                snapshot
+            } else {
+                let snapshot =
+                    resolve_snapshot(&this.log_store, Some(snapshot), true, None).await?;
+                if !not_null.is_empty() && snapshot.log_data().num_files() > 0 {
+                    return Err(DeltaTableError::Generic(format!(
+                        "Cannot add NOT NULL columns without a default value to a table with data: {}",
+                        not_null.join(", ")
+                    )));
+                }
+                if !backfill.is_empty() {
+                    actions.extend(
+                        backfill_column_defaults(
+                            &this.log_store,
+                            &snapshot,
+                            &backfill,
+                            operation_id,
+                        )
+                        .await?,
+                    );
+                }
+                snapshot
+            };
 
             let commit = CommitBuilder::from(this.commit_properties.clone())
                 .with_actions(actions)
@@ -201,4 +500,108 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn add_nested_columns_at_position() -> TestResult {
+        let address = StructField::new(
+            "address",
+            DataType::try_struct_type([
+                StructField::new("city", DataType::Primitive(PrimitiveType::String), true),
+                StructField::new("street", DataType::Primitive(PrimitiveType::String), true),
+            ])?,
+            true,
+        );
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns([id_field(), address])
+            .await?;
+
+        let zip = StructField::new("zip", DataType::Primitive(PrimitiveType::String), true);
+        let table = table
+            .add_columns()
+            .with_column(NewColumn::new(zip).in_struct("address").after("city"))
+            .with_column(NewColumn::new(added_field()).first())
+            .await?;
+
+        let schema = table.snapshot()?.schema();
+        assert_eq!(
+            schema.fields().map(|f| f.name().as_str()).collect_vec(),
+            vec!["added", "id", "address"]
+        );
+        let DataType::Struct(address) = schema.field("address").unwrap().data_type() else {
+            panic!("address is not a struct");
+        };
+        assert_eq!(
+            address.fields().map(|f| f.name().as_str()).collect_vec(),
+            vec!["city", "zip", "street"]
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn add_not_null_column_without_default_to_table_with_data_fails() -> TestResult {
+        use arrow_array::{Int32Array, RecordBatch};
+
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns([id_field()])
+            .await?;
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema::Schema::new(vec![arrow_schema::Field::new(
+                "id",
+                arrow_schema::DataType::Int32,
+                true,
+            )])),
+            vec![Arc::new(Int32Array::from(vec![1, 2]))],
+        )?;
+        let table = table.write(vec![batch]).await?;
+
+        let status = StructField::new("status", DataType::Primitive(PrimitiveType::String), false);
+        let result = table.add_columns().with_fields([status]).await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn add_not_null_column_with_default_backfills_existing_rows() -> TestResult {
+        use arrow_array::{Int32Array, RecordBatch};
+        use datafusion::assert_batches_sorted_eq;
+
+        use crate::kernel::{COLUMN_DEFAULT_KEY, MetadataValue};
+        use crate::writer::test_utils::datafusion::get_data;
+
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns([id_field()])
+            .await?;
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema::Schema::new(vec![arrow_schema::Field::new(
+                "id",
+                arrow_schema::DataType::Int32,
+                true,
+            )])),
+            vec![Arc::new(Int32Array::from(vec![1, 2]))],
+        )?;
+        let table = table.write(vec![batch]).await?;
+
+        let status = StructField::new("status", DataType::Primitive(PrimitiveType::String), false)
+            .with_metadata([(
+                COLUMN_DEFAULT_KEY.to_string(),
+                MetadataValue::String("'active'".to_string()),
+            )]);
+        let table = table.add_columns().with_fields([status]).await?;
+
+        let expected = vec![
+            "+----+--------+",
+            "| id | status |",
+            "+----+--------+",
+            "| 1  | active |",
+            "| 2  | active |",
+            "+----+--------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &get_data(&table).await);
+        Ok(())
+    }
 }
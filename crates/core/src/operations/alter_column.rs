@@ -0,0 +1,419 @@
+//! Change the nullability or comment of an existing column
+//!
+//! Nested fields are addressed with a dotted path, e.g. `address.zip`. Making a column NOT NULL
+//! scans the table and fails if any existing row holds a null value for it.
+
+use std::sync::Arc;
+
+use delta_kernel::schema::{DataType, MetadataValue, StructType};
+use futures::future::BoxFuture;
+use itertools::Itertools;
+
+use super::{CustomExecuteHandler, Operation};
+use crate::kernel::transaction::{CommitBuilder, CommitProperties};
+use crate::kernel::{
+    Action, EagerSnapshot, MetadataExt as _, ProtocolExt as _, SnapshotMetadataRef, StructField,
+    resolve_snapshot,
+};
+use crate::logstore::LogStoreRef;
+use crate::protocol::DeltaOperation;
+use crate::{DeltaResult, DeltaTable, DeltaTableError};
+
+/// Field metadata key holding the comment of a column
+const COMMENT_KEY: &str = "comment";
+
+/// Change the nullability or comment of a column, like `ALTER TABLE .. ALTER COLUMN`
+pub struct AlterColumnBuilder {
+    /// A snapshot of the table's state
+    snapshot: Option<EagerSnapshot>,
+    /// Dotted path of the column to change
+    column: Option<String>,
+    /// The nullability to set
+    nullable: Option<bool>,
+    /// The comment to set
+    comment: Option<String>,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Additional information to add to the commit
+    commit_properties: CommitProperties,
+    custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+}
+
+impl Operation for AlterColumnBuilder {
+    fn log_store(&self) -> &LogStoreRef {
+        &self.log_store
+    }
+    fn get_custom_execute_handler(&self) -> Option<Arc<dyn CustomExecuteHandler>> {
+        self.custom_execute_handler.clone()
+    }
+}
+
+impl AlterColumnBuilder {
+    /// Create a new builder
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
+        Self {
+            snapshot,
+            column: None,
+            nullable: None,
+            comment: None,
+            log_store,
+            commit_properties: CommitProperties::default(),
+            custom_execute_handler: None,
+        }
+    }
+
+    /// Specify the column to change, nested fields are addressed with a dotted path
+    pub fn with_column(mut self, column: impl Into<String>) -> Self {
+        self.column = Some(column.into());
+        self
+    }
+
+    /// `SET NOT NULL`: fails if the column contains null values
+    pub fn set_not_null(mut self) -> Self {
+        self.nullable = Some(false);
+        self
+    }
+
+    /// `DROP NOT NULL`: allow null values in the column
+    pub fn drop_not_null(mut self) -> Self {
+        self.nullable = Some(true);
+        self
+    }
+
+    /// `COMMENT`: set the comment of the column
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Additional metadata to be added to commit info
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+
+    /// Set a custom execute handler, for pre and post execution
+    pub fn with_custom_execute_handler(mut self, handler: Arc<dyn CustomExecuteHandler>) -> Self {
+        self.custom_execute_handler = Some(handler);
+        self
+    }
+}
+
+/// Replace the field at `path` with the result of `change`.
+fn change_field(
+    schema: &StructType,
+    path: &[String],
+    change: &dyn Fn(&StructField) -> StructField,
+) -> DeltaResult<StructType> {
+    let (head, rest) = path
+        .split_first()
+        .ok_or_else(|| DeltaTableError::Generic("No column provided".to_string()))?;
+    let Some(field) = schema.field(head) else {
+        return Err(DeltaTableError::Generic(format!(
+            "Field {head} does not exist"
+        )));
+    };
+    let field = if rest.is_empty() {
+        change(field)
+    } else {
+        let DataType::Struct(inner) = field.data_type() else {
+            return Err(DeltaTableError::Generic(format!(
+                "Field {head} is not a struct"
+            )));
+        };
+        StructField::new(
+            head.clone(),
+            DataType::Struct(Box::new(change_field(inner, rest, change)?)),
+            field.is_nullable(),
+        )
+        .with_metadata(field.metadata().clone())
+    };
+
+    Ok(StructType::try_new(schema.fields().map(|f| {
+        if f.name() == head {
+            field.clone()
+        } else {
+            f.clone()
+        }
+    }))?)
+}
+
+fn plan_alter_column_actions(
+    snapshot: SnapshotMetadataRef<'_>,
+    path: &[String],
+    nullable: Option<bool>,
+    comment: Option<&str>,
+) -> DeltaResult<(Vec<Action>, DeltaOperation)> {
+    let table_schema = snapshot.table_configuration.logical_schema();
+
+    let new_table_schema = change_field(table_schema.as_ref(), path, &|field| {
+        let mut field = StructField::new(
+            field.name().clone(),
+            field.data_type().clone(),
+            nullable.unwrap_or(field.is_nullable()),
+        )
+        .with_metadata(field.metadata().clone());
+        if let Some(comment) = comment {
+            field.metadata.insert(
+                COMMENT_KEY.to_string(),
+                MetadataValue::String(comment.to_string()),
+            );
+        }
+        field
+    })?;
+
+    let mut fields = &new_table_schema;
+    let mut field = None;
+    for name in path {
+        if let Some(DataType::Struct(inner)) = field.map(StructField::data_type) {
+            fields = inner.as_ref();
+        }
+        field = fields.field(name);
+    }
+    let field = field
+        .cloned()
+        .ok_or_else(|| DeltaTableError::Generic("Changed field not found".to_string()))?;
+
+    let mut metadata = snapshot.metadata.clone();
+    let current_protocol = snapshot.protocol;
+    let new_protocol = current_protocol
+        .clone()
+        .apply_column_metadata_to_protocol(&new_table_schema)?
+        .move_table_properties_into_features(metadata.configuration());
+
+    let operation = DeltaOperation::ChangeColumn {
+        column: path.iter().join("."),
+        field,
+    };
+
+    metadata = metadata.with_schema(&new_table_schema)?;
+
+    let mut actions = vec![metadata.into()];
+
+    if current_protocol != &new_protocol {
+        actions.push(new_protocol.into())
+    }
+
+    Ok((actions, operation))
+}
+
+/// Check that no row of the table holds a null value in the column at `path`.
+///
+/// Nested fields are only checked where all of their parents are not null.
+#[cfg(feature = "datafusion")]
+async fn validate_no_nulls(
+    log_store: &LogStoreRef,
+    snapshot: &EagerSnapshot,
+    path: &[String],
+) -> DeltaResult<()> {
+    use datafusion::datasource::provider_as_source;
+    use datafusion::functions::core::expr_ext::FieldAccessor as _;
+    use datafusion::logical_expr::{LogicalPlanBuilder, ident, lit};
+    use datafusion::prelude::DataFrame;
+
+    use crate::delta_datafusion::{DeltaScanNext, create_session, update_datafusion_session};
+
+    let session = create_session().into_inner().state();
+    update_datafusion_session(&session, log_store.as_ref(), None)?;
+
+    let provider = DeltaScanNext::builder()
+        .with_log_store(log_store.clone())
+        .with_eager_snapshot(snapshot.clone())
+        .await?;
+
+    let mut value = ident(&path[0]);
+    let mut predicate = lit(true);
+    for name in &path[1..] {
+        predicate = predicate.and(value.clone().is_not_null());
+        value = value.field(name.as_str());
+    }
+    let plan = LogicalPlanBuilder::scan("target", provider_as_source(provider), None)?
+        .filter(predicate.and(value.is_null()))?
+        .limit(0, Some(1))?
+        .build()?;
+
+    if DataFrame::new(session, plan).count().await? > 0 {
+        return Err(DeltaTableError::Generic(format!(
+            "Cannot set column {} to NOT NULL, it contains null values",
+            path.iter().join(".")
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "datafusion"))]
+async fn validate_no_nulls(
+    _log_store: &LogStoreRef,
+    snapshot: &EagerSnapshot,
+    _path: &[String],
+) -> DeltaResult<()> {
+    if snapshot.log_data().num_files() > 0 {
+        return Err(DeltaTableError::Generic(
+            "Setting a column to NOT NULL on a table with data requires the datafusion feature"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl std::future::IntoFuture for AlterColumnBuilder {
+    type Output = DeltaResult<DeltaTable>;
+
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let Some(column) = this.column.clone() else {
+                return Err(DeltaTableError::Generic("No column provided".to_string()));
+            };
+            let path = column.split('.').map(str::to_string).collect_vec();
+
+            let snapshot =
+                resolve_snapshot(&this.log_store, this.snapshot.clone(), false, None).await?;
+
+            let operation_id = this.get_operation_id();
+            this.pre_execute(operation_id).await?;
+
+            let (actions, operation) = plan_alter_column_actions(
+                snapshot.snapshot().metadata_state(),
+                &path,
+                this.nullable,
+                this.comment.as_deref(),
+            )?;
+
+            let snapshot = if this.nullable == Some(false) {
+                let snapshot =
+                    resolve_snapshot(&this.log_store, Some(snapshot), true, None).await?;
+                validate_no_nulls(&this.log_store, &snapshot, &path).await?;
+                snapshot
+            } else {
+                snapshot
+            };
+
+            let commit = CommitBuilder::from(this.commit_properties.clone())
+                .with_actions(actions)
+                .with_operation_id(operation_id)
+                .with_post_commit_hook_handler(this.get_custom_execute_handler())
+                .build(Some(&snapshot), this.log_store.clone(), operation)
+                .await?;
+
+            this.post_execute(operation_id).await?;
+
+            Ok(DeltaTable::new_with_state(
+                this.log_store,
+                commit.snapshot(),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kernel::{DataType, PrimitiveType, StructField};
+    use crate::writer::test_utils::TestResult;
+
+    use super::*;
+
+    fn address_field() -> StructField {
+        StructField::new(
+            "address",
+            DataType::try_struct_type([StructField::new(
+                "zip",
+                DataType::Primitive(PrimitiveType::String),
+                true,
+            )])
+            .unwrap(),
+            true,
+        )
+    }
+
+    #[tokio::test]
+    async fn alter_nested_column_nullability_and_comment() -> TestResult {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns([address_field()])
+            .await?;
+
+        let table = table
+            .alter_column()
+            .with_column("address.zip")
+            .set_not_null()
+            .with_comment("postal code")
+            .await?;
+        let schema = table.snapshot()?.schema();
+        let DataType::Struct(address) = schema.field("address").unwrap().data_type() else {
+            panic!("address is not a struct");
+        };
+        let zip = address.field("zip").unwrap();
+        assert!(!zip.is_nullable());
+        assert_eq!(
+            zip.metadata().get(COMMENT_KEY),
+            Some(&MetadataValue::String("postal code".to_string()))
+        );
+
+        let table = table
+            .alter_column()
+            .with_column("address.zip")
+            .drop_not_null()
+            .await?;
+        let schema = table.snapshot()?.schema();
+        let DataType::Struct(address) = schema.field("address").unwrap().data_type() else {
+            panic!("address is not a struct");
+        };
+        assert!(address.field("zip").unwrap().is_nullable());
+        Ok(())
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn set_not_null_fails_on_existing_nulls() -> TestResult {
+        use arrow_array::{Int32Array, RecordBatch};
+
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns([StructField::new(
+                "id",
+                DataType::Primitive(PrimitiveType::Integer),
+                true,
+            )])
+            .await?;
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema::Schema::new(vec![arrow_schema::Field::new(
+                "id",
+                arrow_schema::DataType::Int32,
+                true,
+            )])),
+            vec![Arc::new(Int32Array::from(vec![Some(1), None]))],
+        )?;
+        let table = table.write(vec![batch]).await?;
+
+        let result = table
+            .clone()
+            .alter_column()
+            .with_column("id")
+            .set_not_null()
+            .await;
+        assert!(result.is_err());
+
+        let table = table
+            .delete()
+            .with_predicate("id IS NULL")
+            .await?
+            .0
+            .alter_column()
+            .with_column("id")
+            .set_not_null()
+            .await?;
+        assert!(
+            !table
+                .snapshot()?
+                .schema()
+                .field("id")
+                .unwrap()
+                .is_nullable()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,809 @@
+//! Continuously read the change data feed of a delta table as new versions are committed.
+//!
+//! A [`ChangeFeedStream`] reads the table one version at a time with a [`CdfLoadBuilder`] and,
+//! once it has caught up with the log, polls for new versions with an exponential backoff
+//! bounded by a maximum interval. Progress is tracked as a [`ChangeFeedOffset`], the next version
+//! to read together with the number of batches of that version already yielded, and persisted
+//! through a [`ChangeFeedOffsetStore`] so that a restarted stream resumes where the previous one
+//! stopped.
+//!
+//! How a version is split into batches depends on the batch size and the target partitions of
+//! the session, so the offset records both and a stream only resumes within a version with the
+//! same configuration.
+//!
+//! The offset of a batch is committed once the next batch is requested, so every batch is
+//! delivered at least once.
+//!
+//! # Example
+//! ```rust ignore
+//! let offsets = ObjectStoreOffsetStore::new(store, Path::from("offsets/orders.json"));
+//! let mut stream = table
+//!     .stream_cdf()
+//!     .with_starting_version(StartingVersion::Latest)
+//!     .with_offset_store(Arc::new(offsets))
+//!     .await?;
+//!
+//! while let Some(change) = stream.try_next().await? {
+//!     handle(change.batch);
+//! }
+//! ```
+
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use arrow_array::RecordBatch;
+use datafusion::catalog::Session;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream, Stream, StreamExt as _};
+use object_store::ObjectStore;
+use object_store::path::Path;
+use serde::{Deserialize, Serialize};
+use tracing::log;
+
+use crate::delta_datafusion::create_session;
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::{Action, EagerSnapshot, StructType, Version, resolve_snapshot};
+use crate::logstore::{LogStoreRef, get_actions};
+use crate::operations::load_cdf::CdfLoadBuilder;
+use crate::table::config::TablePropertiesExt as _;
+
+const DEFAULT_MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Position of a [`ChangeFeedStream`] in the change data feed of a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeFeedOffset {
+    /// The version that is read next
+    pub version: Version,
+    /// Number of batches of `version` that were already consumed
+    pub index: usize,
+    /// Batch configuration the batches counted by `index` were read with, `None` at the start
+    /// of a version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<ChangeFeedBatchLayout>,
+}
+
+impl ChangeFeedOffset {
+    /// The offset at the start of the given version
+    pub fn new(version: Version) -> Self {
+        Self {
+            version,
+            index: 0,
+            layout: None,
+        }
+    }
+}
+
+/// Session configuration that determines how the changes of a version are split into batches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeFeedBatchLayout {
+    /// Maximum number of rows in a batch
+    pub batch_size: usize,
+    /// Number of partitions the scan of a version is planned with
+    pub target_partitions: usize,
+}
+
+impl ChangeFeedBatchLayout {
+    fn from_session(session: &dyn Session) -> Self {
+        Self {
+            batch_size: session.config().batch_size(),
+            target_partitions: session.config().target_partitions(),
+        }
+    }
+}
+
+/// Durable storage for the progress of a [`ChangeFeedStream`].
+#[async_trait::async_trait]
+pub trait ChangeFeedOffsetStore: std::fmt::Debug + Send + Sync {
+    /// Load the last committed offset, `None` if the stream never committed one.
+    async fn load(&self) -> DeltaResult<Option<ChangeFeedOffset>>;
+
+    /// Persist `offset` as the position to resume from.
+    async fn commit(&self, offset: ChangeFeedOffset) -> DeltaResult<()>;
+}
+
+/// Keeps the offset in memory, progress is lost when the process exits.
+#[derive(Debug, Default)]
+pub struct InMemoryOffsetStore {
+    offset: Mutex<Option<ChangeFeedOffset>>,
+}
+
+impl InMemoryOffsetStore {
+    /// Create a new, empty [`InMemoryOffsetStore`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The last committed offset
+    pub fn offset(&self) -> Option<ChangeFeedOffset> {
+        *self.offset.lock().unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl ChangeFeedOffsetStore for InMemoryOffsetStore {
+    async fn load(&self) -> DeltaResult<Option<ChangeFeedOffset>> {
+        Ok(self.offset())
+    }
+
+    async fn commit(&self, offset: ChangeFeedOffset) -> DeltaResult<()> {
+        *self.offset.lock().unwrap() = Some(offset);
+        Ok(())
+    }
+}
+
+/// Stores the offset as a JSON document at a fixed location in an object store.
+#[derive(Debug, Clone)]
+pub struct ObjectStoreOffsetStore {
+    store: Arc<dyn ObjectStore>,
+    location: Path,
+}
+
+impl ObjectStoreOffsetStore {
+    /// Create a new [`ObjectStoreOffsetStore`] writing to `location` in `store`
+    pub fn new(store: Arc<dyn ObjectStore>, location: Path) -> Self {
+        Self { store, location }
+    }
+}
+
+#[async_trait::async_trait]
+impl ChangeFeedOffsetStore for ObjectStoreOffsetStore {
+    async fn load(&self) -> DeltaResult<Option<ChangeFeedOffset>> {
+        match self.store.get(&self.location).await {
+            Ok(result) => Ok(Some(serde_json::from_slice(&result.bytes().await?)?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn commit(&self, offset: ChangeFeedOffset) -> DeltaResult<()> {
+        let bytes = serde_json::to_vec(&offset)?;
+        self.store.put(&self.location, bytes.into()).await?;
+        Ok(())
+    }
+}
+
+/// The version a [`ChangeFeedStream`] starts at when its offset store holds no offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StartingVersion {
+    /// Start with the given version
+    Version(Version),
+    /// Only read versions committed after the stream was created
+    #[default]
+    Latest,
+}
+
+/// A batch of changes yielded by a [`ChangeFeedStream`].
+#[derive(Debug, Clone)]
+pub struct ChangeFeedBatch {
+    /// The changed rows, with the `_change_type`, `_commit_version` and `_commit_timestamp` columns
+    pub batch: RecordBatch,
+    /// The offset to resume from once this batch is processed
+    pub offset: ChangeFeedOffset,
+}
+
+/// Builder for a [`ChangeFeedStream`]
+#[derive(Clone)]
+pub struct ChangeFeedStreamBuilder {
+    /// A snapshot of the table to stream
+    snapshot: Option<EagerSnapshot>,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Where to start if the offset store holds no offset
+    starting_version: StartingVersion,
+    /// Storage for the progress of the stream
+    offset_store: Option<Arc<dyn ChangeFeedOffsetStore>>,
+    /// Initial interval between polls for new versions
+    min_poll_interval: Duration,
+    /// Upper bound of the interval between polls for new versions
+    max_poll_interval: Duration,
+    /// Datafusion session state relevant for executing the scans
+    session: Option<Arc<dyn Session>>,
+}
+
+impl std::fmt::Debug for ChangeFeedStreamBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangeFeedStreamBuilder")
+            .field("snapshot", &self.snapshot)
+            .field("log_store", &self.log_store)
+            .field("starting_version", &self.starting_version)
+            .field("offset_store", &self.offset_store)
+            .field("min_poll_interval", &self.min_poll_interval)
+            .field("max_poll_interval", &self.max_poll_interval)
+            .finish()
+    }
+}
+
+impl ChangeFeedStreamBuilder {
+    /// Create a new [`ChangeFeedStreamBuilder`]
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
+        Self {
+            snapshot,
+            log_store,
+            starting_version: StartingVersion::default(),
+            offset_store: None,
+            min_poll_interval: DEFAULT_MIN_POLL_INTERVAL,
+            max_poll_interval: DEFAULT_MAX_POLL_INTERVAL,
+            session: None,
+        }
+    }
+
+    /// Version to start at when the offset store holds no offset ([`StartingVersion::Latest`]
+    /// if not provided)
+    pub fn with_starting_version(mut self, starting_version: StartingVersion) -> Self {
+        self.starting_version = starting_version;
+        self
+    }
+
+    /// Store to load the starting offset from and commit progress to (in memory if not
+    /// provided)
+    pub fn with_offset_store(mut self, offset_store: Arc<dyn ChangeFeedOffsetStore>) -> Self {
+        self.offset_store = Some(offset_store);
+        self
+    }
+
+    /// Bounds of the interval between polls for new versions. The interval starts at `min`
+    /// and doubles with every poll that finds no new version, up to `max`.
+    pub fn with_poll_interval(mut self, min: Duration, max: Duration) -> Self {
+        self.min_poll_interval = min;
+        self.max_poll_interval = max.max(min);
+        self
+    }
+
+    /// The Datafusion session state to use
+    pub fn with_session_state(mut self, session: Arc<dyn Session>) -> Self {
+        self.session = Some(session);
+        self
+    }
+}
+
+impl std::future::IntoFuture for ChangeFeedStreamBuilder {
+    type Output = DeltaResult<ChangeFeedStream>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let snapshot = resolve_snapshot(&this.log_store, this.snapshot, false, None).await?;
+            let offset_store = this
+                .offset_store
+                .unwrap_or_else(|| Arc::new(InMemoryOffsetStore::new()));
+
+            let offset = match offset_store.load().await? {
+                Some(offset) => offset,
+                None => match this.starting_version {
+                    StartingVersion::Version(version) => ChangeFeedOffset::new(version),
+                    StartingVersion::Latest => ChangeFeedOffset::new(snapshot.version() + 1),
+                },
+            };
+            if !snapshot.table_properties().enable_change_data_feed() {
+                return Err(DeltaTableError::ChangeDataNotEnabled {
+                    version: snapshot.version(),
+                });
+            }
+            if offset.version <= snapshot.version()
+                && this
+                    .log_store
+                    .read_commit_entry(offset.version)
+                    .await?
+                    .is_none()
+            {
+                return Err(DeltaTableError::InvalidVersion(offset.version));
+            }
+
+            let session = this
+                .session
+                .unwrap_or_else(|| Arc::new(create_session().into_inner().state()));
+
+            // The index counts batches, which only identifies the same rows if the version is
+            // split into batches the same way as before the restart.
+            let layout = ChangeFeedBatchLayout::from_session(session.as_ref());
+            if offset.index > 0
+                && let Some(committed) = offset.layout
+                && committed != layout
+            {
+                return Err(DeltaTableError::Generic(format!(
+                    "Cannot resume the change feed within version {}: the offset was committed \
+                     with a batch size of {} and {} target partitions, but the session uses a \
+                     batch size of {} and {} target partitions",
+                    offset.version,
+                    committed.batch_size,
+                    committed.target_partitions,
+                    layout.batch_size,
+                    layout.target_partitions,
+                )));
+            }
+
+            let state = FeedState {
+                log_store: this.log_store,
+                snapshot,
+                session,
+                layout,
+                offset_store,
+                schema: None,
+                offset,
+                pending: None,
+                current: None,
+                min_poll_interval: this.min_poll_interval,
+                max_poll_interval: this.max_poll_interval,
+                poll_interval: this.min_poll_interval,
+            };
+
+            Ok(ChangeFeedStream {
+                inner: stream::try_unfold(state, FeedState::next).boxed(),
+            })
+        })
+    }
+}
+
+/// A never ending stream of the changes committed to a delta table, see the
+/// [module documentation](self).
+pub struct ChangeFeedStream {
+    inner: BoxStream<'static, DeltaResult<ChangeFeedBatch>>,
+}
+
+impl std::fmt::Debug for ChangeFeedStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangeFeedStream").finish_non_exhaustive()
+    }
+}
+
+impl Stream for ChangeFeedStream {
+    type Item = DeltaResult<ChangeFeedBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+/// The batches of the version a [`ChangeFeedStream`] is currently reading.
+struct VersionScan {
+    version: Version,
+    /// Data and change files referenced by the commit
+    files: Vec<String>,
+    batches: BoxStream<'static, datafusion::common::Result<RecordBatch>>,
+    /// Batches of the version read so far, including skipped ones
+    read: usize,
+}
+
+struct FeedState {
+    log_store: LogStoreRef,
+    /// Snapshot at the version currently being read
+    snapshot: EagerSnapshot,
+    session: Arc<dyn Session>,
+    /// How the versions are split into batches by `session`
+    layout: ChangeFeedBatchLayout,
+    offset_store: Arc<dyn ChangeFeedOffsetStore>,
+    /// Schema of the table when the stream started reading
+    schema: Option<Arc<StructType>>,
+    /// Position of the next batch to yield
+    offset: ChangeFeedOffset,
+    /// Offset of the last yielded batch, committed when the next batch is requested
+    pending: Option<ChangeFeedOffset>,
+    current: Option<VersionScan>,
+    min_poll_interval: Duration,
+    max_poll_interval: Duration,
+    poll_interval: Duration,
+}
+
+impl FeedState {
+    async fn next(mut self) -> DeltaResult<Option<(ChangeFeedBatch, Self)>> {
+        if let Some(offset) = self.pending.take() {
+            self.offset_store.commit(offset).await?;
+        }
+
+        loop {
+            let Some(scan) = self.current.as_mut() else {
+                if self.offset.version > self.latest_version().await? {
+                    tokio::time::sleep(self.poll_interval).await;
+                    self.poll_interval = (self.poll_interval * 2).min(self.max_poll_interval);
+                    continue;
+                }
+                self.poll_interval = self.min_poll_interval;
+                self.current = Some(self.scan_version(self.offset.version).await?);
+                continue;
+            };
+
+            match scan.batches.next().await {
+                Some(Ok(batch)) => {
+                    scan.read += 1;
+                    // Skip the batches consumed before the stream was restarted. Empty batches
+                    // still count towards the index, so that it stays stable across restarts.
+                    if scan.read <= self.offset.index || batch.num_rows() == 0 {
+                        continue;
+                    }
+                    self.offset.index = scan.read;
+                    self.offset.layout = Some(self.layout);
+                    self.pending = Some(self.offset);
+                    let change = ChangeFeedBatch {
+                        batch,
+                        offset: self.offset,
+                    };
+                    return Ok(Some((change, self)));
+                }
+                Some(Err(err)) => {
+                    let (version, files) = (scan.version, std::mem::take(&mut scan.files));
+                    return Err(self.scan_error(version, &files, err.into()).await);
+                }
+                None => {
+                    self.current = None;
+                    self.offset = ChangeFeedOffset::new(self.offset.version + 1);
+                    self.offset_store.commit(self.offset).await?;
+                }
+            }
+        }
+    }
+
+    async fn latest_version(&self) -> DeltaResult<Version> {
+        self.log_store
+            .get_latest_version(self.snapshot.version())
+            .await
+    }
+
+    /// Start reading the changes of `version`.
+    async fn scan_version(&mut self, version: Version) -> DeltaResult<VersionScan> {
+        let commit_bytes = self
+            .log_store
+            .read_commit_entry(version)
+            .await?
+            .ok_or(DeltaTableError::InvalidVersion(version))?;
+        let actions = get_actions(version, &commit_bytes)?;
+
+        if let Some(schema) = &self.schema {
+            for action in &actions {
+                if let Action::Metadata(metadata) = action
+                    && metadata.parse_schema()? != **schema
+                {
+                    return Err(DeltaTableError::ChangeDataSchemaChanged { version });
+                }
+            }
+        }
+
+        if version < self.snapshot.version() {
+            self.snapshot = resolve_snapshot(&self.log_store, None, false, Some(version)).await?;
+        } else {
+            self.snapshot.update(&self.log_store, Some(version)).await?;
+        }
+        if self.schema.is_none() {
+            self.schema = Some(self.snapshot.schema());
+        }
+
+        let files: Vec<String> = actions
+            .into_iter()
+            .filter_map(|action| match action {
+                Action::Cdc(cdc) => Some(cdc.path),
+                Action::Add(add) if add.data_change => Some(add.path),
+                Action::Remove(remove) if remove.data_change => Some(remove.path),
+                _ => None,
+            })
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let batches = match self.execute_version(version).await {
+            Ok(batches) => batches,
+            Err(err) => return Err(self.scan_error(version, &files, err).await),
+        };
+
+        log::debug!("change feed: reading version {version}");
+        Ok(VersionScan {
+            version,
+            files,
+            batches: stream::iter(batches).flatten().boxed(),
+            read: 0,
+        })
+    }
+
+    async fn execute_version(
+        &self,
+        version: Version,
+    ) -> DeltaResult<Vec<SendableRecordBatchStream>> {
+        let plan = CdfLoadBuilder::new(self.log_store.clone(), Some(self.snapshot.clone()))
+            .with_starting_version(version)
+            .with_ending_version(version)
+            .build(self.session.as_ref(), None)
+            .await?;
+
+        // Partitions are read one after the other to yield the batches of a version in the same
+        // order whenever it is read.
+        let task_ctx = self.session.task_ctx();
+        let partitions = plan.properties().output_partitioning().partition_count();
+        Ok((0..partitions)
+            .map(|partition| plan.execute(partition, task_ctx.clone()))
+            .collect::<datafusion::common::Result<Vec<_>>>()?)
+    }
+
+    /// Replace the error of a failed scan of `version` with a [`ChangeDataFileNotFound`] error
+    /// if one of the files of the version no longer exists.
+    ///
+    /// [`ChangeDataFileNotFound`]: DeltaTableError::ChangeDataFileNotFound
+    async fn scan_error(
+        &self,
+        version: Version,
+        files: &[String],
+        err: DeltaTableError,
+    ) -> DeltaTableError {
+        let store = self.log_store.object_store(None);
+        for path in files {
+            let Ok(location) = Path::parse(path) else {
+                continue;
+            };
+            if let Err(object_store::Error::NotFound { .. }) = store.head(&location).await {
+                return DeltaTableError::ChangeDataFileNotFound {
+                    version,
+                    path: path.clone(),
+                };
+            }
+        }
+        err
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::{Int32Array, StringArray};
+    use arrow_schema::{DataType, Field, Schema as ArrowSchema};
+    use datafusion::common::assert_batches_sorted_eq;
+    use datafusion::prelude::{SessionConfig, SessionContext};
+    use futures::TryStreamExt as _;
+
+    use crate::kernel::{DataType as DeltaDataType, PrimitiveType, StructField};
+    use crate::writer::test_utils::TestResult;
+    use crate::{DeltaTable, TableProperty};
+
+    async fn setup_cdf_table() -> DeltaTable {
+        DeltaTable::new_in_memory()
+            .create()
+            .with_columns(vec![
+                StructField::nullable("id", DeltaDataType::Primitive(PrimitiveType::Integer)),
+                StructField::nullable("value", DeltaDataType::Primitive(PrimitiveType::String)),
+            ])
+            .with_configuration_property(TableProperty::EnableChangeDataFeed, Some("true"))
+            .await
+            .unwrap()
+    }
+
+    fn batch(ids: Vec<i32>, values: Vec<&str>) -> RecordBatch {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("value", DataType::Utf8, true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(ids)),
+                Arc::new(StringArray::from(values)),
+            ],
+        )
+        .unwrap()
+    }
+
+    async fn next_batches(stream: &mut ChangeFeedStream, n: usize) -> Vec<ChangeFeedBatch> {
+        let mut batches = Vec::with_capacity(n);
+        for _ in 0..n {
+            let batch = tokio::time::timeout(Duration::from_secs(10), stream.try_next())
+                .await
+                .expect("timed out waiting for changes")
+                .unwrap()
+                .unwrap();
+            batches.push(batch);
+        }
+        batches
+    }
+
+    fn position(offset: ChangeFeedOffset) -> (Version, usize) {
+        (offset.version, offset.index)
+    }
+
+    fn polling_builder(table: &DeltaTable) -> ChangeFeedStreamBuilder {
+        table
+            .stream_cdf()
+            .with_poll_interval(Duration::from_millis(10), Duration::from_millis(50))
+    }
+
+    #[tokio::test]
+    async fn test_change_feed_stream_from_version() -> TestResult {
+        let table = setup_cdf_table().await;
+        let table = table.write(vec![batch(vec![1, 2], vec!["a", "b"])]).await?;
+        let offsets = Arc::new(InMemoryOffsetStore::new());
+
+        let mut stream = polling_builder(&table)
+            .with_starting_version(StartingVersion::Version(1))
+            .with_offset_store(offsets.clone())
+            .await?;
+
+        let changes = next_batches(&mut stream, 1).await;
+        assert_eq!(position(changes[0].offset), (1, 1));
+        let batches = changes.into_iter().map(|c| c.batch).collect::<Vec<_>>();
+        let expected = [
+            "+----+-------+--------------+-----------------+",
+            "| id | value | _change_type | _commit_version |",
+            "+----+-------+--------------+-----------------+",
+            "| 1  | a     | insert       | 1               |",
+            "| 2  | b     | insert       | 1               |",
+            "+----+-------+--------------+-----------------+",
+        ];
+        let batches = batches
+            .iter()
+            .map(|b| b.project(&[0, 1, 2, 3]).unwrap())
+            .collect::<Vec<_>>();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // changes committed while the stream is polling are picked up
+        let table = table.write(vec![batch(vec![3], vec!["c"])]).await?;
+        let changes = next_batches(&mut stream, 1).await;
+        assert_eq!(position(changes[0].offset), (2, 1));
+        assert_eq!(changes[0].batch.num_rows(), 1);
+        assert_eq!(offsets.offset(), Some(ChangeFeedOffset::new(2)));
+
+        // a restarted stream resumes after the last committed offset, delivering the batch
+        // that was never acknowledged again
+        drop(stream);
+        let table = table.write(vec![batch(vec![4], vec!["d"])]).await?;
+        let mut stream = polling_builder(&table)
+            .with_offset_store(offsets.clone())
+            .await?;
+        let changes = next_batches(&mut stream, 2).await;
+        let positions = changes
+            .iter()
+            .map(|c| position(c.offset))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 1), (3, 1)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_change_feed_stream_latest() -> TestResult {
+        let table = setup_cdf_table().await;
+        let table = table.write(vec![batch(vec![1], vec!["a"])]).await?;
+        let offsets = Arc::new(InMemoryOffsetStore::new());
+
+        let mut stream = polling_builder(&table)
+            .with_starting_version(StartingVersion::Latest)
+            .with_offset_store(offsets.clone())
+            .await?;
+
+        let table = table.write(vec![batch(vec![2], vec!["b"])]).await?;
+        let changes = next_batches(&mut stream, 1).await;
+        assert_eq!(changes[0].offset.version, 2);
+        let ids = changes[0]
+            .batch
+            .column_by_name("id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap()
+            .values()
+            .to_vec();
+        assert_eq!(ids, vec![2]);
+
+        // the offset of a batch is committed once the next one is requested
+        assert_eq!(offsets.offset(), None);
+        let _ = table.write(vec![batch(vec![3], vec!["c"])]).await?;
+        let changes = next_batches(&mut stream, 1).await;
+        assert_eq!(changes[0].offset.version, 3);
+        assert_eq!(offsets.offset(), Some(ChangeFeedOffset::new(3)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_change_feed_stream_schema_change() -> TestResult {
+        let table = setup_cdf_table().await;
+        let mut stream = polling_builder(&table)
+            .with_starting_version(StartingVersion::Version(0))
+            .await?;
+
+        let table = table
+            .add_columns()
+            .with_fields(vec![StructField::nullable(
+                "extra",
+                DeltaDataType::Primitive(PrimitiveType::Long),
+            )])
+            .await?;
+        assert_eq!(table.version(), Some(1));
+
+        let err = tokio::time::timeout(Duration::from_secs(10), stream.try_next())
+            .await
+            .expect("timed out waiting for changes")
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DeltaTableError::ChangeDataSchemaChanged { version: 1 }
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_change_feed_stream_missing_file() -> TestResult {
+        let table = setup_cdf_table().await;
+        let table = table.write(vec![batch(vec![1], vec!["a"])]).await?;
+        let store = table.log_store().object_store(None);
+        for path in table.get_files_by_partitions(&[]).await? {
+            store.delete(&path).await?;
+        }
+
+        let mut stream = polling_builder(&table)
+            .with_starting_version(StartingVersion::Version(1))
+            .await?;
+        let err = stream.try_next().await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                DeltaTableError::ChangeDataFileNotFound { version: 1, .. }
+            ),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_change_feed_stream_resume_with_other_layout() -> TestResult {
+        let table = setup_cdf_table().await;
+        let table = table.write(vec![batch(vec![1, 2], vec!["a", "b"])]).await?;
+        let offsets = Arc::new(InMemoryOffsetStore::new());
+
+        // the first batch of the version was consumed with another batch size
+        let layout = ChangeFeedBatchLayout {
+            batch_size: 1,
+            target_partitions: 1,
+        };
+        offsets
+            .commit(ChangeFeedOffset {
+                version: 1,
+                index: 1,
+                layout: Some(layout),
+            })
+            .await?;
+        let err = polling_builder(&table)
+            .with_offset_store(offsets.clone())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Cannot resume the change feed within version 1"),
+            "{err}"
+        );
+
+        // the stream resumes with the same configuration
+        let config = SessionConfig::new()
+            .with_batch_size(1)
+            .with_target_partitions(1);
+        let session = SessionContext::new_with_config(config).state();
+        let mut stream = polling_builder(&table)
+            .with_offset_store(offsets.clone())
+            .with_session_state(Arc::new(session))
+            .await?;
+        let changes = next_batches(&mut stream, 1).await;
+        assert_eq!(position(changes[0].offset), (1, 2));
+        assert_eq!(changes[0].offset.layout, Some(layout));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_object_store_offset_store() -> TestResult {
+        let store: Arc<dyn ObjectStore> = Arc::new(object_store::memory::InMemory::new());
+        let offsets = ObjectStoreOffsetStore::new(store, Path::from("offsets/table.json"));
+        assert_eq!(offsets.load().await?, None);
+
+        let offset = ChangeFeedOffset {
+            version: 3,
+            index: 2,
+            layout: Some(ChangeFeedBatchLayout {
+                batch_size: 8192,
+                target_partitions: 4,
+            }),
+        };
+        offsets.commit(offset).await?;
+        assert_eq!(offsets.load().await?, Some(offset));
+        Ok(())
+    }
+}
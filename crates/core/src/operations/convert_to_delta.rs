@@ -1,4 +1,4 @@
-//! Command for converting a Parquet table to a Delta table in place
+//! Command for converting a Parquet or Iceberg table to a Delta table in place
 // https://github.com/delta-io/delta/blob/1d5dd774111395b0c4dc1a69c94abc169b1c83b6/spark/src/main/scala/org/apache/spark/sql/delta/commands/ConvertToDeltaCommand.scala
 use std::collections::{HashMap, HashSet};
 use std::num::TryFromIntError;
@@ -8,6 +8,8 @@ use std::sync::Arc;
 use arrow_schema::{ArrowError, Schema as ArrowSchema};
 use delta_kernel::engine::arrow_conversion::TryIntoKernel as _;
 use delta_kernel::schema::StructType;
+#[cfg(feature = "iceberg")]
+use delta_kernel::table_features::{ColumnMappingMode, TableFeature};
 use futures::TryStreamExt;
 use futures::future::{self, BoxFuture};
 use indexmap::IndexMap;
@@ -19,10 +21,18 @@ use tracing::debug;
 use uuid::Uuid;
 
 use super::{CustomExecuteHandler, Operation};
+#[cfg(feature = "iceberg")]
+use crate::iceberg::convert::{files_have_field_ids, read_iceberg_snapshot};
 use crate::kernel::schema::cast::normalize_for_delta;
 use crate::kernel::transaction::CommitProperties;
+#[cfg(feature = "iceberg")]
+use crate::kernel::{Action, MetadataExt as _, ProtocolInner, transaction::CommitBuilder};
 use crate::logstore::StorageConfig;
+#[cfg(feature = "iceberg")]
+use crate::logstore::get_actions;
 use crate::operations::get_num_idx_cols_and_stats_columns;
+#[cfg(feature = "iceberg")]
+use crate::protocol::DeltaOperation;
 use crate::{
     DeltaResult, DeltaTable, DeltaTableError, NULL_PARTITION_VALUE_DATA_PATH, ObjectStoreError,
     kernel::{Add, DataType, StructField, scalars::ScalarExt},
@@ -34,6 +44,14 @@ use crate::{
     writer::stats::stats_from_parquet_metadata,
 };
 
+/// Commit info key holding the id of the converted Iceberg snapshot
+#[cfg(feature = "iceberg")]
+const ICEBERG_SNAPSHOT_ID_KEY: &str = "icebergSnapshotId";
+
+/// Table property tracking the highest column mapping id
+#[cfg(feature = "iceberg")]
+const COLUMN_MAPPING_MAX_ID_KEY: &str = "delta.columnMapping.maxColumnId";
+
 /// Error converting a Parquet table to a Delta table
 #[derive(Debug, thiserror::Error)]
 enum Error {
@@ -57,6 +75,8 @@ enum Error {
     MissingPartitionSchema,
     #[error("Partition column provided by the user does not exist in the parquet files")]
     PartitionColumnNotExist,
+    #[error("A partition schema cannot be provided with the {0:?} partition strategy")]
+    PartitionSchemaNotSupported(PartitionStrategy),
     #[error("The given location is already a delta table location")]
     DeltaTableAlready,
     #[error("Location must be provided to convert a Parquet table to a Delta table")]
@@ -80,13 +100,18 @@ impl From<Error> for DeltaTableError {
 }
 
 /// The partition strategy used by the Parquet table
-/// Currently only hive-partitioning is supported for Parquet paths
 #[non_exhaustive]
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum PartitionStrategy {
     /// Hive-partitioning
     #[default]
     Hive,
+    /// No partitioning, all Parquet files are data files of a single partition regardless of
+    /// the directories they are stored in
+    Unpartitioned,
+    /// Iceberg table, whose schema, partitioning and data files are read from its metadata
+    #[cfg(feature = "iceberg")]
+    Iceberg,
 }
 
 impl FromStr for PartitionStrategy {
@@ -95,6 +120,9 @@ impl FromStr for PartitionStrategy {
     fn from_str(s: &str) -> DeltaResult<Self> {
         match s.to_ascii_lowercase().as_str() {
             "hive" => Ok(PartitionStrategy::Hive),
+            "none" | "unpartitioned" => Ok(PartitionStrategy::Unpartitioned),
+            #[cfg(feature = "iceberg")]
+            "iceberg" => Ok(PartitionStrategy::Iceberg),
             _ => Err(DeltaTableError::Generic(format!(
                 "Invalid partition strategy provided {s}"
             ))),
@@ -103,6 +131,12 @@ impl FromStr for PartitionStrategy {
 }
 
 /// Build an operation to convert a Parquet table to a [`DeltaTable`] in place
+///
+/// With the [`PartitionStrategy::Iceberg`] strategy, the location holds an Iceberg table. The
+/// Delta table is written next to its `metadata` directory and references the Iceberg data
+/// files without copying them. Converting the same location again adds the changes of the
+/// snapshots committed to the Iceberg table since the last conversion, which fails if the Delta
+/// table was changed since then.
 pub struct ConvertToDeltaBuilder {
     log_store: Option<LogStoreRef>,
     location: Option<String>,
@@ -192,7 +226,6 @@ impl ConvertToDeltaBuilder {
     }
 
     /// Specify the partition strategy of the Parquet table
-    /// Currently only hive-partitioning is supported for Parquet paths
     pub fn with_partition_strategy(mut self, strategy: PartitionStrategy) -> Self {
         self.partition_strategy = strategy;
         self
@@ -251,11 +284,10 @@ impl ConvertToDeltaBuilder {
         self
     }
 
-    /// Consume self into CreateBuilder with corresponding add actions, schemas and operation meta
-    async fn into_create_builder(mut self) -> Result<(CreateBuilder, Uuid), Error> {
-        // Use the specified log store. If a log store is not provided, create a new store from the specified path.
-        // Return an error if neither log store nor path is provided
-        self.log_store = if let Some(log_store) = self.log_store {
+    /// Use the specified log store. If a log store is not provided, create a new store from the specified path.
+    /// Return an error if neither log store nor path is provided
+    fn resolve_log_store(&mut self) -> Result<(), Error> {
+        self.log_store = if let Some(log_store) = self.log_store.take() {
             Some(log_store)
         } else if let Some(location) = self.location.clone() {
             let storage_config =
@@ -268,6 +300,17 @@ impl ConvertToDeltaBuilder {
         } else {
             return Err(Error::MissingLocation);
         };
+        Ok(())
+    }
+
+    /// Consume self into CreateBuilder with corresponding add actions, schemas and operation meta
+    async fn into_create_builder(mut self) -> Result<(CreateBuilder, Uuid), Error> {
+        if self.partition_strategy == PartitionStrategy::Unpartitioned
+            && !self.partition_schema.is_empty()
+        {
+            return Err(Error::PartitionSchemaNotSupported(self.partition_strategy));
+        }
+        self.resolve_log_store()?;
 
         let operation_id = self.get_operation_id();
         self.pre_execute(operation_id).await?;
@@ -321,7 +364,8 @@ impl ConvertToDeltaBuilder {
             let mut subpath = iter.next();
 
             // Get partitions from subpaths. Skip the last subpath
-            while iter.peek().is_some() {
+            // Subdirectories of unpartitioned tables carry no partition values
+            while self.partition_strategy == PartitionStrategy::Hive && iter.peek().is_some() {
                 let curr_path = subpath.unwrap();
                 let (key, value) = curr_path
                     .split_once('=')
@@ -443,6 +487,190 @@ impl ConvertToDeltaBuilder {
         }
         Ok((builder, operation_id))
     }
+
+    /// Convert the Iceberg table at the location, or add the changes of the Iceberg snapshots
+    /// committed since the Delta table at the location was converted
+    #[cfg(feature = "iceberg")]
+    async fn convert_iceberg(mut self) -> DeltaResult<DeltaTable> {
+        if !self.partition_schema.is_empty() {
+            return Err(Error::PartitionSchemaNotSupported(self.partition_strategy).into());
+        }
+        self.resolve_log_store()?;
+        let log_store = self.log_store().clone();
+        let operation_id = self.get_operation_id();
+        self.pre_execute(operation_id).await?;
+
+        let table = if log_store.is_delta_table_location().await? {
+            self.update_from_iceberg(operation_id).await?
+        } else {
+            debug!(
+                "Converting Iceberg table in log store location: {:?}",
+                log_store.root_url()
+            );
+            let iceberg = read_iceberg_snapshot(&log_store, None).await?;
+
+            // Iceberg resolves columns by field id, which is kept by the `id` column mapping mode.
+            // Columns of files written without field ids can only be resolved by name.
+            let mode = if files_have_field_ids(&log_store, &iceberg.files).await? {
+                "id"
+            } else {
+                tracing::warn!(
+                    "Data files of the Iceberg table lack Parquet field ids, converting with the \
+                     `name` column mapping mode"
+                );
+                "name"
+            };
+            let protocol = ProtocolInner::default()
+                .append_reader_features([TableFeature::ColumnMapping])
+                .append_writer_features([TableFeature::ColumnMapping])
+                .as_kernel();
+            let mut configuration = self.configuration;
+            configuration.insert(
+                TableProperty::ColumnMappingMode.as_ref().into(),
+                Some(mode.into()),
+            );
+            configuration.insert(
+                COLUMN_MAPPING_MAX_ID_KEY.into(),
+                Some(iceberg.max_column_id.to_string()),
+            );
+            let mut commit_properties = self.commit_properties;
+            commit_properties
+                .app_metadata
+                .insert(ICEBERG_SNAPSHOT_ID_KEY.into(), iceberg.snapshot_id.into());
+
+            let mut builder = CreateBuilder::new()
+                .with_log_store(log_store.clone())
+                .with_columns(iceberg.schema.fields().cloned())
+                .with_partition_columns(iceberg.partition_columns)
+                .with_actions(
+                    std::iter::once(Action::Protocol(protocol))
+                        .chain(iceberg.files.into_iter().map(Action::Add)),
+                )
+                .with_column_mapping()
+                .with_save_mode(self.mode)
+                .with_configuration(configuration)
+                .with_commit_properties(commit_properties);
+            if let Some(name) = self.name {
+                builder = builder.with_table_name(name);
+            }
+            if let Some(comment) = self.comment {
+                builder = builder.with_comment(comment);
+            }
+            builder.await?
+        };
+
+        if let Some(handler) = self.custom_execute_handler {
+            handler.post_execute(&log_store, operation_id).await?;
+        }
+        Ok(table)
+    }
+
+    /// Commit the difference between the Delta table at the location and the current snapshot
+    /// of the Iceberg table it was converted from
+    #[cfg(feature = "iceberg")]
+    async fn update_from_iceberg(&self, operation_id: Uuid) -> DeltaResult<DeltaTable> {
+        let log_store = self.log_store().clone();
+        let mut table = DeltaTable::new(log_store.clone(), Default::default());
+        table.load().await?;
+        let snapshot = table.snapshot()?;
+        let mode = snapshot
+            .snapshot()
+            .table_configuration()
+            .column_mapping_mode();
+        if !matches!(mode, ColumnMappingMode::Id | ColumnMappingMode::Name) {
+            return Err(Error::DeltaTableAlready.into());
+        }
+
+        // Files missing from the Iceberg snapshot are removed from the Delta table, which would
+        // drop the files added by commits to the Delta table since it was converted.
+        let version = snapshot.version();
+        let commit_bytes = log_store
+            .read_commit_entry(version)
+            .await?
+            .ok_or(DeltaTableError::InvalidVersion(version))?;
+        let commit_info = get_actions(version, &commit_bytes)?
+            .into_iter()
+            .find_map(|action| match action {
+                Action::CommitInfo(info) => Some(info),
+                _ => None,
+            });
+        if !commit_info.is_some_and(|info| info.info.contains_key(ICEBERG_SNAPSHOT_ID_KEY)) {
+            return Err(DeltaTableError::Generic(format!(
+                "Version {version} of the Delta table was not converted from the Iceberg table, \
+                 converting again would drop the changes committed to the Delta table since the \
+                 last conversion"
+            )));
+        }
+
+        let schema = snapshot.schema();
+        let iceberg = read_iceberg_snapshot(&log_store, Some(schema.as_ref())).await?;
+        if iceberg.partition_columns != *snapshot.metadata().partition_columns() {
+            return Err(DeltaTableError::Generic(format!(
+                "The partitioning of the Iceberg table changed to {:?} since it was converted, \
+                 which Delta tables do not support",
+                iceberg.partition_columns
+            )));
+        }
+
+        let mut existing: HashMap<String, _> = snapshot
+            .snapshot()
+            .file_views(&log_store, None)
+            .map_ok(|file| (file.path().to_string(), file))
+            .try_collect()
+            .await?;
+        let added: Vec<_> = iceberg
+            .files
+            .into_iter()
+            .filter(|add| existing.remove(&add.path).is_none())
+            .collect();
+        if mode == ColumnMappingMode::Id && !files_have_field_ids(&log_store, &added).await? {
+            return Err(DeltaTableError::Generic(
+                "New data files of the Iceberg table lack Parquet field ids, which the table \
+                 converted with the `id` column mapping mode cannot read"
+                    .into(),
+            ));
+        }
+        let mut actions: Vec<_> = added.into_iter().map(Action::Add).collect();
+        actions.extend(
+            existing
+                .values()
+                .map(|file| Action::Remove(file.remove_action(true))),
+        );
+        let num_files = actions.len() as i64;
+
+        if iceberg.schema != *schema.as_ref() {
+            let metadata = snapshot
+                .metadata()
+                .clone()
+                .with_schema(&iceberg.schema)?
+                .add_config_key(
+                    COLUMN_MAPPING_MAX_ID_KEY.into(),
+                    iceberg.max_column_id.to_string(),
+                )?;
+            actions.push(Action::Metadata(metadata));
+        }
+        if actions.is_empty() {
+            debug!("The Delta table is up to date with the Iceberg table");
+            return Ok(table);
+        }
+
+        let mut commit_properties = self.commit_properties.clone();
+        commit_properties
+            .app_metadata
+            .insert(ICEBERG_SNAPSHOT_ID_KEY.into(), iceberg.snapshot_id.into());
+        let operation = DeltaOperation::Convert {
+            num_files,
+            partition_by: Some(iceberg.partition_columns).filter(|p| !p.is_empty()),
+            source_format: "iceberg".into(),
+        };
+        let commit = CommitBuilder::from(commit_properties)
+            .with_actions(actions)
+            .with_operation_id(operation_id)
+            .with_post_commit_hook_handler(self.get_custom_execute_handler())
+            .build(Some(snapshot.snapshot()), log_store.clone(), operation)
+            .await?;
+        Ok(DeltaTable::new_with_state(log_store, commit.snapshot()))
+    }
 }
 
 impl std::future::IntoFuture for ConvertToDeltaBuilder {
@@ -453,6 +681,11 @@ impl std::future::IntoFuture for ConvertToDeltaBuilder {
         let this = self;
 
         Box::pin(async move {
+            #[cfg(feature = "iceberg")]
+            if this.partition_strategy == PartitionStrategy::Iceberg {
+                return this.convert_iceberg().await;
+            }
+
             let handler = this.custom_execute_handler.clone();
             let (builder, operation_id) = this
                 .into_create_builder()
@@ -1080,4 +1313,342 @@ mod tests {
         let state = table.snapshot().unwrap();
         assert_eq!(state.log_data().num_files(), 1);
     }
+
+    #[tokio::test]
+    async fn test_convert_unpartitioned_layout() {
+        let root = tempdir().expect("Failed to create a temp directory");
+        let temp_dir = root.path().to_str().unwrap();
+        copy_files(
+            format!(
+                "{}/../test/tests/data/delta-0.8.0-numeric-partition",
+                env!("CARGO_MANIFEST_DIR")
+            ),
+            temp_dir,
+        );
+
+        let table = ConvertToDeltaBuilder::new()
+            .with_log_store(log_store(temp_dir))
+            .with_partition_strategy(PartitionStrategy::Unpartitioned)
+            .await
+            .expect("Failed to convert to Delta table");
+        let state = table.snapshot().unwrap();
+        assert!(state.metadata().partition_columns().is_empty());
+        assert_eq!(state.log_data().num_files(), 2);
+        let files: Vec<_> = state
+            .snapshot()
+            .file_views(&table.log_store(), None)
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(files.iter().all(|f| f.path().contains('=')));
+
+        ConvertToDeltaBuilder::new()
+            .with_location(temp_dir)
+            .with_partition_strategy(PartitionStrategy::Unpartitioned)
+            .with_partition_schema(vec![schema_field("x", PrimitiveType::Long, true)])
+            .await
+            .expect_err("A partition schema cannot be used without partitioning. Should error");
+    }
+
+    #[cfg(feature = "iceberg")]
+    mod iceberg {
+        use apache_avro::types::Value as AvroValue;
+        use apache_avro::{Schema as AvroSchema, Writer};
+        use arrow::array::{Int64Array, StringArray};
+        use delta_kernel::table_features::ColumnMappingMode;
+        use serde_json::json;
+
+        use super::*;
+
+        const MANIFEST_LIST_SCHEMA: &str = r#"{"type": "record", "name": "manifest_file", "fields": [
+            {"name": "manifest_path", "type": "string"},
+            {"name": "manifest_length", "type": "long"},
+            {"name": "partition_spec_id", "type": "int"},
+            {"name": "content", "type": "int"}
+        ]}"#;
+
+        const MANIFEST_SCHEMA: &str = r#"{"type": "record", "name": "manifest_entry", "fields": [
+            {"name": "status", "type": "int"},
+            {"name": "snapshot_id", "type": ["null", "long"]},
+            {"name": "data_file", "type": {"type": "record", "name": "r2", "fields": [
+                {"name": "content", "type": "int"},
+                {"name": "file_path", "type": "string"},
+                {"name": "file_format", "type": "string"},
+                {"name": "partition", "type": {"type": "record", "name": "r102", "fields": [
+                    {"name": "region", "type": ["null", "string"]}
+                ]}},
+                {"name": "record_count", "type": "long"},
+                {"name": "file_size_in_bytes", "type": "long"},
+                {"name": "lower_bounds", "type": ["null", {"type": "array", "items": {
+                    "type": "record", "name": "k126_v127", "fields": [
+                        {"name": "key", "type": "int"},
+                        {"name": "value", "type": "bytes"}
+                    ]
+                }}]}
+            ]}}
+        ]}"#;
+
+        fn write_avro(path: &std::path::Path, schema: &str, values: Vec<AvroValue>) {
+            let schema = AvroSchema::parse_str(schema).unwrap();
+            let mut writer = Writer::new(&schema, Vec::new());
+            for value in values {
+                writer.append(value).unwrap();
+            }
+            fs::write(path, writer.into_inner().unwrap()).unwrap();
+        }
+
+        /// Write a data file of the `region` partition and a manifest adding it, returns the
+        /// manifest path
+        fn write_data_file(
+            root: &std::path::Path,
+            snapshot_id: i64,
+            region: &str,
+            field_ids: bool,
+        ) -> String {
+            let field = |id: i64, name: &str, data_type: ArrowDataType, nullable: bool| {
+                let field = ArrowField::new(name, data_type, nullable);
+                if field_ids {
+                    field.with_metadata(HashMap::from([(
+                        parquet::arrow::PARQUET_FIELD_ID_META_KEY.to_string(),
+                        id.to_string(),
+                    )]))
+                } else {
+                    field
+                }
+            };
+            let schema = Arc::new(ArrowSchema::new(vec![
+                field(1, "id", ArrowDataType::Int64, false),
+                field(2, "region", ArrowDataType::Utf8, true),
+            ]));
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from(vec![snapshot_id, snapshot_id + 1])),
+                    Arc::new(StringArray::from(vec![region, region])),
+                ],
+            )
+            .unwrap();
+            let data_path = root.join(format!("data/region={region}/{snapshot_id}.parquet"));
+            fs::create_dir_all(data_path.parent().unwrap()).unwrap();
+            let mut writer =
+                ArrowWriter::try_new(File::create(&data_path).unwrap(), schema, None).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+
+            let manifest_path = root.join(format!("metadata/{snapshot_id}-m0.avro"));
+            write_avro(
+                &manifest_path,
+                MANIFEST_SCHEMA,
+                vec![AvroValue::Record(vec![
+                    ("status".into(), AvroValue::Int(1)),
+                    (
+                        "snapshot_id".into(),
+                        AvroValue::Union(1, Box::new(AvroValue::Long(snapshot_id))),
+                    ),
+                    (
+                        "data_file".into(),
+                        AvroValue::Record(vec![
+                            ("content".into(), AvroValue::Int(0)),
+                            (
+                                "file_path".into(),
+                                AvroValue::String(data_path.to_str().unwrap().into()),
+                            ),
+                            ("file_format".into(), AvroValue::String("PARQUET".into())),
+                            (
+                                "partition".into(),
+                                AvroValue::Record(vec![(
+                                    "region".into(),
+                                    AvroValue::Union(1, Box::new(AvroValue::String(region.into()))),
+                                )]),
+                            ),
+                            ("record_count".into(), AvroValue::Long(2)),
+                            (
+                                "file_size_in_bytes".into(),
+                                AvroValue::Long(fs::metadata(&data_path).unwrap().len() as i64),
+                            ),
+                            (
+                                "lower_bounds".into(),
+                                AvroValue::Union(
+                                    1,
+                                    Box::new(AvroValue::Array(vec![AvroValue::Record(vec![
+                                        ("key".into(), AvroValue::Int(1)),
+                                        (
+                                            "value".into(),
+                                            AvroValue::Bytes(snapshot_id.to_le_bytes().to_vec()),
+                                        ),
+                                    ])])),
+                                ),
+                            ),
+                        ]),
+                    ),
+                ])],
+            );
+            manifest_path.to_str().unwrap().to_string()
+        }
+
+        /// Commit an Iceberg snapshot listing the given manifests as metadata version `version`
+        fn write_snapshot(
+            root: &std::path::Path,
+            version: i64,
+            snapshot_id: i64,
+            manifests: &[String],
+        ) {
+            let manifest_list = root.join(format!("metadata/snap-{snapshot_id}.avro"));
+            write_avro(
+                &manifest_list,
+                MANIFEST_LIST_SCHEMA,
+                manifests
+                    .iter()
+                    .map(|path| {
+                        AvroValue::Record(vec![
+                            ("manifest_path".into(), AvroValue::String(path.clone())),
+                            ("manifest_length".into(), AvroValue::Long(0)),
+                            ("partition_spec_id".into(), AvroValue::Int(0)),
+                            ("content".into(), AvroValue::Int(0)),
+                        ])
+                    })
+                    .collect(),
+            );
+            let metadata = json!({
+                "format-version": 2,
+                "location": root.to_str().unwrap(),
+                "last-sequence-number": version,
+                "last-updated-ms": 1_700_000_000_000i64 + version,
+                "last-column-id": 2,
+                "schemas": [{"type": "struct", "schema-id": 0, "fields": [
+                    {"id": 1, "name": "id", "required": true, "type": "long"},
+                    {"id": 2, "name": "region", "required": false, "type": "string"}
+                ]}],
+                "current-schema-id": 0,
+                "partition-specs": [{"spec-id": 0, "fields": [
+                    {"source-id": 2, "field-id": 1000, "name": "region", "transform": "identity"}
+                ]}],
+                "default-spec-id": 0,
+                "current-snapshot-id": snapshot_id,
+                "snapshots": [{
+                    "snapshot-id": snapshot_id,
+                    "sequence-number": version,
+                    "timestamp-ms": 1_700_000_000_000i64 + version,
+                    "manifest-list": manifest_list.to_str().unwrap(),
+                    "summary": {"operation": "append"}
+                }]
+            });
+            fs::write(
+                root.join(format!("metadata/v{version}.metadata.json")),
+                serde_json::to_vec(&metadata).unwrap(),
+            )
+            .unwrap();
+            fs::write(root.join("metadata/version-hint.text"), version.to_string()).unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_convert_iceberg_table() {
+            let root = tempdir().expect("Failed to create a temp directory");
+            fs::create_dir_all(root.path().join("metadata")).unwrap();
+            let first = write_data_file(root.path(), 100, "eu", true);
+            write_snapshot(root.path(), 1, 100, std::slice::from_ref(&first));
+
+            let table = ConvertToDeltaBuilder::new()
+                .with_log_store(log_store(root.path().to_str().unwrap()))
+                .with_partition_strategy(PartitionStrategy::Iceberg)
+                .await
+                .expect("Failed to convert the Iceberg table");
+            let state = table.snapshot().unwrap();
+            assert_eq!(state.version(), 0);
+            assert_eq!(
+                state.metadata().partition_columns(),
+                &vec!["region".to_string()]
+            );
+            assert_eq!(
+                state.snapshot().table_configuration().column_mapping_mode(),
+                ColumnMappingMode::Id
+            );
+            let files: Vec<_> = state
+                .snapshot()
+                .file_views(&table.log_store(), None)
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(files.len(), 1);
+            assert_eq!(files[0].path(), "data/region=eu/100.parquet");
+            assert_eq!(files[0].num_records(), Some(2));
+
+            // converting again without new Iceberg snapshots is a no-op
+            let table = ConvertToDeltaBuilder::new()
+                .with_log_store(log_store(root.path().to_str().unwrap()))
+                .with_partition_strategy(PartitionStrategy::Iceberg)
+                .await
+                .unwrap();
+            assert_eq!(table.version(), Some(0));
+
+            let second = write_data_file(root.path(), 200, "us", true);
+            write_snapshot(root.path(), 2, 200, &[first, second]);
+            let table = ConvertToDeltaBuilder::new()
+                .with_log_store(log_store(root.path().to_str().unwrap()))
+                .with_partition_strategy(PartitionStrategy::Iceberg)
+                .await
+                .expect("Failed to convert the new Iceberg snapshot");
+            assert_eq!(table.version(), Some(1));
+            assert_eq!(table.snapshot().unwrap().log_data().num_files(), 2);
+
+            // files without field ids would be read as nulls in the `id` column mapping mode
+            let third = write_data_file(root.path(), 300, "ap", false);
+            write_snapshot(root.path(), 3, 300, &[third]);
+            let result = ConvertToDeltaBuilder::new()
+                .with_log_store(log_store(root.path().to_str().unwrap()))
+                .with_partition_strategy(PartitionStrategy::Iceberg)
+                .await;
+            assert!(
+                matches!(result, Err(DeltaTableError::Generic(msg)) if msg.contains("field ids"))
+            );
+        }
+
+        #[tokio::test]
+        async fn test_convert_iceberg_table_without_field_ids() {
+            let root = tempdir().expect("Failed to create a temp directory");
+            fs::create_dir_all(root.path().join("metadata")).unwrap();
+            let first = write_data_file(root.path(), 100, "eu", false);
+            write_snapshot(root.path(), 1, 100, std::slice::from_ref(&first));
+
+            let table = ConvertToDeltaBuilder::new()
+                .with_log_store(log_store(root.path().to_str().unwrap()))
+                .with_partition_strategy(PartitionStrategy::Iceberg)
+                .await
+                .expect("Failed to convert the Iceberg table");
+            let state = table.snapshot().unwrap();
+            assert_eq!(
+                state.snapshot().table_configuration().column_mapping_mode(),
+                ColumnMappingMode::Name
+            );
+
+            // files with and without field ids can be added in the `name` mode
+            let second = write_data_file(root.path(), 200, "us", true);
+            write_snapshot(root.path(), 2, 200, &[first, second]);
+            let table = ConvertToDeltaBuilder::new()
+                .with_log_store(log_store(root.path().to_str().unwrap()))
+                .with_partition_strategy(PartitionStrategy::Iceberg)
+                .await
+                .expect("Failed to convert the new Iceberg snapshot");
+            assert_eq!(table.version(), Some(1));
+            assert_eq!(table.snapshot().unwrap().log_data().num_files(), 2);
+
+            // changes committed to the Delta table would be lost by converting again
+            let table = table
+                .set_tbl_properties()
+                .with_properties(HashMap::from([(
+                    "delta.logRetentionDuration".to_string(),
+                    "interval 30 days".to_string(),
+                )]))
+                .await
+                .unwrap();
+            assert_eq!(table.version(), Some(2));
+            let result = ConvertToDeltaBuilder::new()
+                .with_log_store(log_store(root.path().to_str().unwrap()))
+                .with_partition_strategy(PartitionStrategy::Iceberg)
+                .await;
+            assert!(
+                matches!(result, Err(DeltaTableError::Generic(msg)) if msg.contains("Version 2"))
+            );
+        }
+    }
 }
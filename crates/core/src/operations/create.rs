@@ -90,6 +90,8 @@ pub struct CreateBuilder {
     /// Additional information to add to the commit
     commit_properties: CommitProperties,
     raise_if_key_not_exists: bool,
+    /// Whether the columns may carry column mapping metadata
+    allow_column_mapping: bool,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
 }
 
@@ -126,6 +128,7 @@ impl CreateBuilder {
             configuration: Default::default(),
             commit_properties: CommitProperties::default(),
             raise_if_key_not_exists: true,
+            allow_column_mapping: false,
             custom_execute_handler: None,
         }
     }
@@ -263,6 +266,15 @@ impl CreateBuilder {
         self
     }
 
+    /// Allow column mapping, for conversions of existing files that are resolved by field id
+    /// or by name.
+    /// Writers of this crate still refuse to write to the created table.
+    #[cfg(feature = "iceberg")]
+    pub(crate) fn with_column_mapping(mut self) -> Self {
+        self.allow_column_mapping = true;
+        self
+    }
+
     /// Set a custom execute handler, for pre and post execution
     pub fn with_custom_execute_handler(mut self, handler: Arc<dyn CustomExecuteHandler>) -> Self {
         self.custom_execute_handler = Some(handler);
@@ -283,17 +295,19 @@ impl CreateBuilder {
         if self.columns.is_empty() {
             return Err(CreateError::MissingSchema.into());
         }
-        if self
-            .configuration
-            .get(TableProperty::ColumnMappingMode.as_ref())
-            .is_some_and(|value| value.is_some())
+        if !self.allow_column_mapping
+            && self
+                .configuration
+                .get(TableProperty::ColumnMappingMode.as_ref())
+                .is_some_and(|value| value.is_some())
         {
             return Err(DeltaTableError::unsupported_column_mapping(
                 ColumnMappingOperation::Write,
                 "CREATE TABLE with delta.columnMapping.mode",
             ));
         }
-        if self.columns.iter().any(field_has_column_mapping_metadata) {
+        if !self.allow_column_mapping && self.columns.iter().any(field_has_column_mapping_metadata)
+        {
             return Err(DeltaTableError::unsupported_column_mapping(
                 ColumnMappingOperation::Write,
                 "CREATE TABLE with column mapping metadata",
@@ -345,6 +359,8 @@ impl CreateBuilder {
             .unwrap_or_else(|| current_protocol);
 
         let schema = StructType::try_new(self.columns)?;
+        #[cfg(feature = "datafusion")]
+        crate::operations::write::column_defaults::validate_column_defaults(&schema)?;
 
         let protocol = protocol
             .apply_properties_to_protocol(&configuration, self.raise_if_key_not_exists)?
@@ -78,9 +78,10 @@ use crate::kernel::transaction::{CommitBuilder, CommitProperties, PROTOCOL};
 use crate::kernel::{
     Action, ActiveAddOptions, AddStatsPolicy, EagerSnapshot, LogicalFileView, resolve_snapshot,
 };
-use crate::logstore::{LogStore, LogStoreRef};
+use crate::logstore::{IoStats, LogStore, LogStoreRef};
 use crate::operations::CustomExecuteHandler;
 use crate::operations::cdc::CDC_COLUMN_NAME;
+use crate::operations::replan::ReplanPolicy;
 use crate::operations::write::execution::write_exec_plan;
 use crate::protocol::DeltaOperation;
 use crate::table::config::TablePropertiesExt as _;
@@ -108,6 +109,8 @@ pub struct DeleteBuilder {
     /// Commit properties and configuration
     commit_properties: CommitProperties,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    /// Re-plan the operation against the latest snapshot after commit conflicts
+    replan_policy: Option<ReplanPolicy>,
 }
 
 impl std::fmt::Debug for DeleteBuilder {
@@ -145,6 +148,11 @@ pub struct DeleteMetrics {
     pub scan_time_ms: u64,
     /// Time taken to rewrite the matched files
     pub rewrite_time_ms: u64,
+    /// Object store requests issued by the operation before its commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io_stats: Option<IoStats>,
+    /// Number of times the operation was re-planned after a commit conflict
+    pub num_replans: usize,
 }
 
 struct FullFileDeleteResult {
@@ -235,6 +243,7 @@ impl DeleteBuilder {
             commit_properties: CommitProperties::default(),
             writer_properties: None,
             custom_execute_handler: None,
+            replan_policy: None,
         }
     }
 
@@ -272,6 +281,13 @@ impl DeleteBuilder {
         self
     }
 
+    /// Reload the snapshot, re-plan and retry the operation when its commit conflicts with a
+    /// concurrent transaction. See [`ReplanPolicy`] for details.
+    pub fn with_replan_policy(mut self, replan_policy: ReplanPolicy) -> Self {
+        self.replan_policy = Some(replan_policy);
+        self
+    }
+
     /// Writer properties passed to parquet writer for when files are rewritten
     pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
         self.writer_properties = Some(writer_properties);
@@ -285,11 +301,8 @@ impl DeleteBuilder {
     }
 }
 
-impl std::future::IntoFuture for DeleteBuilder {
-    type Output = DeltaResult<(DeltaTable, DeleteMetrics)>;
-    type IntoFuture = BoxFuture<'static, Self::Output>;
-
-    fn into_future(self) -> Self::IntoFuture {
+impl DeleteBuilder {
+    fn execute_once(self) -> BoxFuture<'static, DeltaResult<(DeltaTable, DeleteMetrics)>> {
         let mut this = self;
 
         Box::pin(async move {
@@ -329,7 +342,7 @@ impl std::future::IntoFuture for DeleteBuilder {
                 predicate: predicate.as_ref().map(fmt_expr_to_sql).transpose()?,
             };
 
-            let (actions, metrics) = execute(
+            let (actions, mut metrics) = execute(
                 predicate,
                 this.log_store.clone(),
                 snapshot.clone(),
@@ -337,6 +350,7 @@ impl std::future::IntoFuture for DeleteBuilder {
                 operation_id,
             )
             .await?;
+            metrics.io_stats = super::take_io_stats(&this.log_store, operation_id);
 
             // Do not make a commit when there are zero updates to the state
             if actions.is_empty() {
@@ -375,6 +389,31 @@ impl std::future::IntoFuture for DeleteBuilder {
     }
 }
 
+impl std::future::IntoFuture for DeleteBuilder {
+    type Output = DeltaResult<(DeltaTable, DeleteMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let Some(policy) = self.replan_policy.clone() else {
+            return self.execute_once();
+        };
+
+        Box::pin(async move {
+            let ((table, mut metrics), replans) = policy
+                .execute(|replan| {
+                    let mut builder = self.clone();
+                    if replan > 0 {
+                        builder.snapshot = None;
+                    }
+                    builder.execute_once()
+                })
+                .await?;
+            metrics.num_replans = replans;
+            Ok((table, metrics))
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct DeleteMetricExtensionPlanner {}
 
@@ -707,6 +746,7 @@ mod tests {
     use super::*;
 
     use crate::kernel::DataType as DeltaDataType;
+    use crate::kernel::transaction::{CommitConflictError, TransactionError};
     use crate::operations::collect_sendable_stream;
     use crate::protocol::*;
     use crate::writer::test_utils::datafusion::get_data;
@@ -876,6 +916,75 @@ mod tests {
             .expect_err("Remove action is included when Delta table is append-only. Should error");
     }
 
+    #[tokio::test]
+    async fn test_delete_replans_after_conflict() {
+        let schema = get_arrow_schema(&None);
+        let table = setup_table(None).await;
+
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["A", "B", "A", "A"])),
+                Arc::new(arrow::array::Int32Array::from(vec![1, 10, 10, 100])),
+                Arc::new(arrow::array::StringArray::from(vec![
+                    "2021-02-02",
+                    "2021-02-02",
+                    "2021-02-02",
+                    "2021-02-02",
+                ])),
+            ],
+        )
+        .unwrap();
+        let table = table
+            .write(vec![batch])
+            .with_save_mode(SaveMode::Append)
+            .await
+            .unwrap();
+        let stale = table.clone();
+
+        // a concurrent delete rewrites the only file of the table
+        let (table, _) = table
+            .delete()
+            .with_predicate(col("value").eq(lit(1)))
+            .await
+            .unwrap();
+        assert_eq!(table.version(), Some(2));
+
+        let result = stale
+            .clone()
+            .delete()
+            .with_predicate(col("value").eq(lit(10)))
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeltaTableError::Transaction {
+                source: TransactionError::CommitConflict(CommitConflictError::ConcurrentDeleteRead)
+            })
+        ));
+
+        let (table, metrics) = stale
+            .delete()
+            .with_predicate(col("value").eq(lit(10)))
+            .with_replan_policy(
+                ReplanPolicy::default().with_initial_backoff(std::time::Duration::ZERO),
+            )
+            .await
+            .unwrap();
+        assert_eq!(table.version(), Some(3));
+        assert_eq!(metrics.num_replans, 1);
+        assert_eq!(metrics.num_deleted_rows, Some(2));
+
+        let expected = vec![
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 100   | 2021-02-02 |",
+            "+----+-------+------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
     #[tokio::test]
     async fn test_delete_default() {
         let schema = get_arrow_schema(&None);
@@ -0,0 +1,564 @@
+//! Describe the table-level details of a Delta table
+//!
+//! The [`DescribeDetailBuilder`] collects the information reported by Spark's
+//! `DESCRIBE DETAIL` command - location, size, file count, layout and protocol - into a
+//! single [`TableDetail`]. In addition it reports a histogram of the active file sizes and
+//! the number of files and rows affected by deletion vectors.
+//!
+//! ```rust ignore
+//! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap()).await?;
+//! let detail = table.describe_detail().await?;
+//! println!("{} files, {} bytes", detail.num_files, detail.size_in_bytes);
+//! ```
+use std::collections::BTreeMap;
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{
+    ArrayRef, Int32Array, Int64Array, Int64Builder, ListBuilder, MapBuilder, RecordBatch,
+    StringArray, StringBuilder, StructArray, TimestampMillisecondArray,
+};
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+use delta_kernel::schema::{DataType as KernelDataType, StructType};
+use delta_kernel::table_features::ColumnMappingMode;
+use futures::TryStreamExt as _;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use super::{CustomExecuteHandler, Operation};
+use crate::DeltaResult;
+use crate::kernel::{EagerSnapshot, Snapshot, Version, resolve_snapshot};
+use crate::logstore::{LogStore, LogStoreRef};
+
+/// Domain holding the clustering columns of tables using liquid clustering.
+const CLUSTERING_DOMAIN: &str = "delta.clustering";
+
+/// Lower bounds of the [`FileSizeHistogram`] bins, zero and then doubling from 8 KiB to 4 GiB.
+static FILE_SIZE_BIN_BOUNDARIES: LazyLock<Vec<i64>> = LazyLock::new(|| {
+    std::iter::once(0)
+        .chain((13..=32).map(|exp| 1_i64 << exp))
+        .collect()
+});
+
+/// Histogram of the sizes of the active files of a table.
+///
+/// Bin `i` holds the files with a size in `[sorted_bin_boundaries[i], sorted_bin_boundaries[i + 1])`,
+/// the last bin holds all files larger than the last boundary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSizeHistogram {
+    /// Lower bound of each bin in bytes, starting at zero
+    pub sorted_bin_boundaries: Vec<i64>,
+    /// Number of files in each bin
+    pub file_counts: Vec<i64>,
+    /// Total size of the files in each bin
+    pub total_bytes: Vec<i64>,
+}
+
+impl Default for FileSizeHistogram {
+    fn default() -> Self {
+        let bins = FILE_SIZE_BIN_BOUNDARIES.len();
+        Self {
+            sorted_bin_boundaries: FILE_SIZE_BIN_BOUNDARIES.clone(),
+            file_counts: vec![0; bins],
+            total_bytes: vec![0; bins],
+        }
+    }
+}
+
+impl FileSizeHistogram {
+    /// Add a file of the given size to the histogram
+    pub fn insert(&mut self, size: i64) {
+        let bin = self.bin(size);
+        self.file_counts[bin] += 1;
+        self.total_bytes[bin] += size;
+    }
+
+    /// Remove a file of the given size from the histogram
+    pub fn remove(&mut self, size: i64) {
+        let bin = self.bin(size);
+        self.file_counts[bin] -= 1;
+        self.total_bytes[bin] -= size;
+    }
+
+    fn bin(&self, size: i64) -> usize {
+        self.sorted_bin_boundaries
+            .partition_point(|boundary| *boundary <= size)
+            .saturating_sub(1)
+    }
+}
+
+/// Table-level details of a Delta table at a specific version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableDetail {
+    /// Format of the table, always `delta`
+    pub format: String,
+    /// Unique id of the table
+    pub id: String,
+    /// User-provided name of the table
+    pub name: Option<String>,
+    /// User-provided description of the table
+    pub description: Option<String>,
+    /// Fully qualified location of the table
+    pub location: String,
+    /// Time the table was created
+    pub created_at: Option<DateTime<Utc>>,
+    /// Time of the commit of the described version
+    pub last_modified: Option<DateTime<Utc>>,
+    /// Version of the table described
+    pub version: Version,
+    /// Partition columns of the table
+    pub partition_columns: Vec<String>,
+    /// Liquid clustering columns of the table, nested columns joined by `.`
+    pub clustering_columns: Vec<String>,
+    /// Number of active files
+    pub num_files: i64,
+    /// Total size of the active files in bytes
+    pub size_in_bytes: i64,
+    /// Size of the smallest active file
+    pub min_file_size: Option<i64>,
+    /// Size of the largest active file
+    pub max_file_size: Option<i64>,
+    /// Number of rows, if all active files carry statistics. Rows deleted by deletion
+    /// vectors are not subtracted.
+    pub num_records: Option<i64>,
+    /// Histogram of the sizes of the active files
+    pub file_size_histogram: FileSizeHistogram,
+    /// Table properties
+    pub properties: BTreeMap<String, String>,
+    /// Minimum reader protocol version
+    pub min_reader_version: i32,
+    /// Minimum writer protocol version
+    pub min_writer_version: i32,
+    /// Reader and writer features enabled on the table
+    pub table_features: Vec<String>,
+    /// Number of active files with a deletion vector
+    pub num_deletion_vector_files: i64,
+    /// Number of rows marked as deleted by deletion vectors
+    pub num_deleted_rows: i64,
+}
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+}
+
+fn list_type(data_type: DataType) -> DataType {
+    DataType::List(Arc::new(Field::new_list_field(data_type, true)))
+}
+
+fn histogram_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("sorted_bin_boundaries", list_type(DataType::Int64), false),
+        Field::new("file_counts", list_type(DataType::Int64), false),
+        Field::new("total_bytes", list_type(DataType::Int64), false),
+    ])
+}
+
+static TABLE_DETAIL_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let properties = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new())
+        .finish()
+        .data_type()
+        .clone();
+    Arc::new(Schema::new(vec![
+        Field::new("format", DataType::Utf8, false),
+        Field::new("id", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("description", DataType::Utf8, true),
+        Field::new("location", DataType::Utf8, false),
+        Field::new("created_at", timestamp_type(), true),
+        Field::new("last_modified", timestamp_type(), true),
+        Field::new("version", DataType::Int64, false),
+        Field::new("partition_columns", list_type(DataType::Utf8), false),
+        Field::new("clustering_columns", list_type(DataType::Utf8), false),
+        Field::new("num_files", DataType::Int64, false),
+        Field::new("size_in_bytes", DataType::Int64, false),
+        Field::new("min_file_size", DataType::Int64, true),
+        Field::new("max_file_size", DataType::Int64, true),
+        Field::new("num_records", DataType::Int64, true),
+        Field::new(
+            "file_size_histogram",
+            DataType::Struct(histogram_fields()),
+            false,
+        ),
+        Field::new("properties", properties, false),
+        Field::new("min_reader_version", DataType::Int32, false),
+        Field::new("min_writer_version", DataType::Int32, false),
+        Field::new("table_features", list_type(DataType::Utf8), false),
+        Field::new("num_deletion_vector_files", DataType::Int64, false),
+        Field::new("num_deleted_rows", DataType::Int64, false),
+    ]))
+});
+
+impl TableDetail {
+    /// Collect the details of a table snapshot
+    pub(crate) async fn try_from_snapshot(
+        snapshot: &Snapshot,
+        log_store: &dyn LogStore,
+    ) -> DeltaResult<Self> {
+        let metadata = snapshot.metadata();
+        let protocol = snapshot.protocol();
+
+        let mut detail = Self {
+            format: "delta".to_string(),
+            id: metadata.id().to_string(),
+            name: metadata.name().map(ToString::to_string),
+            description: metadata.description().map(ToString::to_string),
+            location: log_store.root_url().to_string(),
+            created_at: metadata
+                .created_time()
+                .and_then(DateTime::from_timestamp_millis),
+            last_modified: snapshot
+                .version_timestamp(snapshot.version())
+                .and_then(DateTime::from_timestamp_millis),
+            version: snapshot.version(),
+            partition_columns: metadata.partition_columns().clone(),
+            clustering_columns: clustering_columns(snapshot, log_store).await?,
+            num_files: 0,
+            size_in_bytes: 0,
+            min_file_size: None,
+            max_file_size: None,
+            num_records: Some(0),
+            file_size_histogram: FileSizeHistogram::default(),
+            properties: metadata
+                .configuration()
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            min_reader_version: protocol.min_reader_version(),
+            min_writer_version: protocol.min_writer_version(),
+            table_features: protocol
+                .reader_features()
+                .into_iter()
+                .chain(protocol.writer_features())
+                .flatten()
+                .map(ToString::to_string)
+                .collect::<std::collections::BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            num_deletion_vector_files: 0,
+            num_deleted_rows: 0,
+        };
+
+        let mut latest_file_modification = None;
+        let mut files = snapshot.file_views(log_store, None);
+        while let Some(file) = files.try_next().await? {
+            let size = file.size();
+            detail.num_files += 1;
+            detail.size_in_bytes += size;
+            detail.min_file_size = Some(detail.min_file_size.map_or(size, |min| min.min(size)));
+            detail.max_file_size = Some(detail.max_file_size.map_or(size, |max| max.max(size)));
+            detail.file_size_histogram.insert(size);
+            detail.num_records = detail
+                .num_records
+                .zip(file.num_records())
+                .map(|(total, n)| total + n as i64);
+            if let Some(dv) = file.deletion_vector_descriptor() {
+                detail.num_deletion_vector_files += 1;
+                detail.num_deleted_rows += dv.cardinality;
+            }
+            latest_file_modification = latest_file_modification.max(Some(file.modification_time()));
+        }
+
+        // the commit file of the described version is not listed if the snapshot was
+        // loaded from a checkpoint at that version.
+        if detail.last_modified.is_none() {
+            detail.last_modified =
+                latest_file_modification.and_then(DateTime::from_timestamp_millis);
+        }
+        Ok(detail)
+    }
+
+    /// The arrow schema of [`TableDetail::to_record_batch`]
+    pub fn arrow_schema() -> SchemaRef {
+        TABLE_DETAIL_SCHEMA.clone()
+    }
+
+    /// Convert the details into a record batch with a single row
+    pub fn to_record_batch(&self) -> DeltaResult<RecordBatch> {
+        let string_list = |values: &[String]| -> ArrayRef {
+            let mut builder = ListBuilder::new(StringBuilder::new());
+            builder.append_value(values.iter().map(Some));
+            Arc::new(builder.finish())
+        };
+        let int_list = |values: &[i64]| -> ArrayRef {
+            let mut builder = ListBuilder::new(Int64Builder::new());
+            builder.append_value(values.iter().copied().map(Some));
+            Arc::new(builder.finish())
+        };
+        let timestamp = |value: Option<DateTime<Utc>>| -> ArrayRef {
+            Arc::new(
+                TimestampMillisecondArray::from(vec![value.map(|ts| ts.timestamp_millis())])
+                    .with_timezone("UTC"),
+            )
+        };
+
+        let histogram = &self.file_size_histogram;
+        let histogram = StructArray::try_new(
+            histogram_fields(),
+            vec![
+                int_list(&histogram.sorted_bin_boundaries),
+                int_list(&histogram.file_counts),
+                int_list(&histogram.total_bytes),
+            ],
+            None,
+        )?;
+
+        let mut properties = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+        for (key, value) in &self.properties {
+            properties.keys().append_value(key);
+            properties.values().append_value(value);
+        }
+        properties.append(true)?;
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec![self.format.clone()])),
+            Arc::new(StringArray::from(vec![self.id.clone()])),
+            Arc::new(StringArray::from(vec![self.name.clone()])),
+            Arc::new(StringArray::from(vec![self.description.clone()])),
+            Arc::new(StringArray::from(vec![self.location.clone()])),
+            timestamp(self.created_at),
+            timestamp(self.last_modified),
+            Arc::new(Int64Array::from(vec![self.version as i64])),
+            string_list(&self.partition_columns),
+            string_list(&self.clustering_columns),
+            Arc::new(Int64Array::from(vec![self.num_files])),
+            Arc::new(Int64Array::from(vec![self.size_in_bytes])),
+            Arc::new(Int64Array::from(vec![self.min_file_size])),
+            Arc::new(Int64Array::from(vec![self.max_file_size])),
+            Arc::new(Int64Array::from(vec![self.num_records])),
+            Arc::new(histogram),
+            Arc::new(properties.finish()),
+            Arc::new(Int32Array::from(vec![self.min_reader_version])),
+            Arc::new(Int32Array::from(vec![self.min_writer_version])),
+            string_list(&self.table_features),
+            Arc::new(Int64Array::from(vec![self.num_deletion_vector_files])),
+            Arc::new(Int64Array::from(vec![self.num_deleted_rows])),
+        ];
+        Ok(RecordBatch::try_new(Self::arrow_schema(), columns)?)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClusteringDomain {
+    clustering_columns: Vec<Vec<String>>,
+}
+
+/// Read the clustering columns from the clustering domain metadata, as logical column names.
+async fn clustering_columns(
+    snapshot: &Snapshot,
+    log_store: &dyn LogStore,
+) -> DeltaResult<Vec<String>> {
+    let Some(config) = snapshot
+        .domain_metadata(log_store, CLUSTERING_DOMAIN)
+        .await?
+    else {
+        return Ok(Vec::new());
+    };
+    let domain: ClusteringDomain = serde_json::from_str(&config)?;
+    let schema = snapshot.schema();
+    let mode = snapshot.table_configuration().column_mapping_mode();
+    Ok(domain
+        .clustering_columns
+        .iter()
+        .map(|physical_path| logical_column_name(&schema, physical_path, mode))
+        .collect())
+}
+
+/// Resolve a physical column path to the dot-separated logical column name.
+///
+/// Path segments that can not be resolved in the schema are kept as-is.
+fn logical_column_name(
+    schema: &StructType,
+    physical_path: &[String],
+    mode: ColumnMappingMode,
+) -> String {
+    let mut names = Vec::with_capacity(physical_path.len());
+    let mut current = Some(schema);
+    for part in physical_path {
+        let field = current.and_then(|fields| {
+            fields
+                .fields()
+                .find(|field| field.physical_name(mode) == part.as_str())
+        });
+        match field {
+            Some(field) => {
+                names.push(field.name().to_string());
+                current = match field.data_type() {
+                    KernelDataType::Struct(nested) => Some(nested.as_ref()),
+                    _ => None,
+                };
+            }
+            None => {
+                names.push(part.clone());
+                current = None;
+            }
+        }
+    }
+    names.join(".")
+}
+
+/// Builder for collecting the [`TableDetail`] of a table
+#[derive(Clone)]
+pub struct DescribeDetailBuilder {
+    /// A snapshot of the table to describe
+    snapshot: Option<EagerSnapshot>,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Version of the table to describe, defaults to the snapshot version
+    version: Option<Version>,
+    custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+}
+
+impl Operation for DescribeDetailBuilder {
+    fn log_store(&self) -> &LogStoreRef {
+        &self.log_store
+    }
+    fn get_custom_execute_handler(&self) -> Option<Arc<dyn CustomExecuteHandler>> {
+        self.custom_execute_handler.clone()
+    }
+}
+
+impl DescribeDetailBuilder {
+    /// Create a new [`DescribeDetailBuilder`]
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
+        Self {
+            snapshot,
+            log_store,
+            version: None,
+            custom_execute_handler: None,
+        }
+    }
+
+    /// Describe the table at the given version instead of the loaded one
+    pub fn with_version(mut self, version: Version) -> Self {
+        self.version = Some(version);
+        self
+    }
+}
+
+impl std::future::IntoFuture for DescribeDetailBuilder {
+    type Output = DeltaResult<TableDetail>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+        Box::pin(async move {
+            let snapshot = match this.snapshot {
+                Some(snapshot) if this.version.is_none_or(|v| v == snapshot.version()) => snapshot,
+                _ => resolve_snapshot(this.log_store.as_ref(), None, false, this.version).await?,
+            };
+            TableDetail::try_from_snapshot(snapshot.snapshot(), this.log_store.as_ref()).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::AsArray as _;
+    use arrow::datatypes::Int64Type;
+
+    use super::*;
+    use crate::DeltaTable;
+    use crate::kernel::schema::{DataType, PrimitiveType};
+    use crate::kernel::{Action, Add};
+
+    #[test]
+    fn test_file_size_histogram() {
+        let mut histogram = FileSizeHistogram::default();
+        histogram.insert(0);
+        histogram.insert(8191);
+        histogram.insert(8192);
+        histogram.insert(1 << 40);
+
+        assert_eq!(histogram.sorted_bin_boundaries[0], 0);
+        assert_eq!(histogram.sorted_bin_boundaries[1], 8192);
+        assert_eq!(histogram.file_counts[0], 2);
+        assert_eq!(histogram.total_bytes[0], 8191);
+        assert_eq!(histogram.file_counts[1], 1);
+        assert_eq!(histogram.file_counts.last(), Some(&1));
+        assert_eq!(histogram.file_counts.iter().sum::<i64>(), 4);
+
+        histogram.remove(8191);
+        assert_eq!(histogram.file_counts[0], 1);
+        assert_eq!(histogram.total_bytes[0], 0);
+    }
+
+    #[tokio::test]
+    async fn test_describe_detail() -> DeltaResult<()> {
+        let add = |path: &str, size: i64, num_records: i64| {
+            Action::Add(Add {
+                path: path.into(),
+                size,
+                partition_values: [("part".to_string(), Some("a".to_string()))].into(),
+                stats: Some(format!(r#"{{"numRecords":{num_records}}}"#)),
+                data_change: true,
+                ..Default::default()
+            })
+        };
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_column("id", DataType::Primitive(PrimitiveType::Long), true, None)
+            .with_column(
+                "part",
+                DataType::Primitive(PrimitiveType::String),
+                true,
+                None,
+            )
+            .with_partition_columns(["part"])
+            .with_configuration_property(crate::TableProperty::AppendOnly, Some("true"))
+            .with_actions(vec![
+                add("part=a/1.parquet", 100, 10),
+                add("part=a/2.parquet", 20_000, 5),
+            ])
+            .await?;
+
+        let detail = table.describe_detail().await?;
+        assert_eq!(detail.format, "delta");
+        assert_eq!(detail.version, 0);
+        assert_eq!(detail.location, "memory:///");
+        assert!(detail.last_modified.is_some());
+        assert_eq!(detail.partition_columns, vec!["part"]);
+        assert!(detail.clustering_columns.is_empty());
+        assert_eq!(detail.num_files, 2);
+        assert_eq!(detail.size_in_bytes, 20_100);
+        assert_eq!(detail.min_file_size, Some(100));
+        assert_eq!(detail.max_file_size, Some(20_000));
+        assert_eq!(detail.num_records, Some(15));
+        assert_eq!(detail.file_size_histogram.file_counts[0], 1);
+        assert_eq!(detail.file_size_histogram.file_counts[2], 1);
+        assert_eq!(
+            detail
+                .properties
+                .get("delta.appendOnly")
+                .map(String::as_str),
+            Some("true")
+        );
+        assert_eq!(detail.num_deletion_vector_files, 0);
+
+        let batch = detail.to_record_batch()?;
+        assert_eq!(batch.schema(), TableDetail::arrow_schema());
+        assert_eq!(batch.num_rows(), 1);
+        let num_files = batch.column_by_name("num_files").unwrap();
+        assert_eq!(num_files.as_primitive::<Int64Type>().value(0), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_logical_column_name() {
+        let schema: StructType = serde_json::from_str(
+            r#"{"type":"struct","fields":[{"name":"a","type":{"type":"struct","fields":[{"name":"b","type":"long","nullable":true,"metadata":{"delta.columnMapping.physicalName":"col-b"}}]},"nullable":true,"metadata":{"delta.columnMapping.physicalName":"col-a"}}]}"#,
+        )
+        .unwrap();
+        let path = vec!["col-a".to_string(), "col-b".to_string()];
+        assert_eq!(
+            logical_column_name(&schema, &path, ColumnMappingMode::Name),
+            "a.b"
+        );
+        assert_eq!(
+            logical_column_name(&schema, &path, ColumnMappingMode::None),
+            "col-a.col-b"
+        );
+    }
+}
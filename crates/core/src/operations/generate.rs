@@ -69,6 +69,9 @@
 //!         └── day=5
 //!             └── part-00000-c5856301-3439-4032-a6fc-22b7bc92bebb.c000.snappy.parquet
 //! ```
+//!
+//! With the `iceberg` feature, `GenerateMode::IcebergMetadata` writes Iceberg metadata for tables
+//! enabling icebergCompatV2 instead, so Iceberg readers can read the table from its location.
 use bytes::{BufMut, BytesMut};
 use futures::StreamExt as _;
 use futures::future::BoxFuture;
@@ -86,26 +89,47 @@ use crate::logstore::object_store::PutPayload;
 use crate::table::state::DeltaTableState;
 use crate::{DeltaResult, DeltaTable, DeltaTableError};
 
+/// What to generate for external engines
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GenerateMode {
+    /// A `_symlink_format_manifest` listing the data files, per partition for partitioned tables
+    #[default]
+    SymlinkFormatManifest,
+    /// Iceberg metadata in `metadata/`, which requires the table to enable icebergCompatV2.
+    /// Tables with `delta.universalFormat.enabledFormats=iceberg` get it after every commit.
+    #[cfg(feature = "iceberg")]
+    IcebergMetadata,
+}
+
 /// Simple builder to generate the manifest
 #[derive(Clone)]
 pub struct GenerateBuilder {
     /// A snapshot of the table state to be generated
     snapshot: Option<EagerSnapshot>,
     log_store: LogStoreRef,
+    mode: GenerateMode,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
 }
 
 impl GenerateBuilder {
     /// Create a new [GenerateBuilder]
     ///
-    /// Currently only one mode is supported: [GenerateMode::SymlinkFormatManifest]
+    /// Generates a [GenerateMode::SymlinkFormatManifest] unless another mode is set
     pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
         Self {
             snapshot,
             log_store,
+            mode: GenerateMode::default(),
             custom_execute_handler: None,
         }
     }
+
+    /// Set what to generate
+    pub fn with_mode(mut self, mode: GenerateMode) -> Self {
+        self.mode = mode;
+        self
+    }
 }
 
 impl super::Operation for GenerateBuilder {
@@ -126,50 +150,20 @@ impl std::future::IntoFuture for GenerateBuilder {
         Box::pin(async move {
             let snapshot =
                 resolve_snapshot(this.log_store(), this.snapshot.clone(), true, None).await?;
-            let mut payloads = HashMap::new();
-            let manifest_part = PathPart::parse("manifest").expect("This is not possible");
-
-            let mut file_stream = snapshot.file_views(&this.log_store, None);
-            while let Some(add) = file_stream.next().await {
-                let add = add?;
-                let path = add.object_store_path();
-                // The output_path is more or less the tree structure as the original file, just
-                // inside the _symlink_format_manifest directory. This makes it easier to avoid
-                // messing with partition values on the action
-                let output_path = Path::from_iter(
-                    std::iter::once(PathPart::parse("_symlink_format_manifest").map_err(|e| {
-                        DeltaTableError::GenericError {
-                            source: Box::new(e),
-                        }
-                    })?)
-                    .chain(path.parts().filter(|p| path.filename() != Some(p.as_ref())))
-                    .chain(std::iter::once(manifest_part.clone())),
-                );
-                trace!("Computed output path for add action: {output_path:?}");
-                if !payloads.contains_key(&output_path) {
-                    payloads.insert(output_path.clone(), BytesMut::new());
+            match this.mode {
+                GenerateMode::SymlinkFormatManifest => {
+                    generate_symlink_format_manifest(this.log_store(), &snapshot).await?
                 }
-
-                if let Some(payload) = payloads.get_mut(&output_path) {
-                    let uri = this.log_store().to_uri(&path);
-                    trace!("Prepare {uri} for the symlink_format_manifest");
-                    payload.put(uri.as_bytes());
-                    payload.put_u8(b'\n');
+                #[cfg(feature = "iceberg")]
+                GenerateMode::IcebergMetadata => {
+                    crate::iceberg::generate::write_iceberg_metadata(
+                        this.log_store(),
+                        &snapshot,
+                        None,
+                    )
+                    .await?
                 }
             }
-            debug!("Total of {} manifest files prepared", payloads.len());
-            for (path, payload) in payloads.drain() {
-                debug!(
-                    "Generated manifest for {:?} is {} bytes",
-                    path,
-                    payload.len()
-                );
-                let payload = PutPayload::from(payload.freeze());
-                this.log_store()
-                    .object_store(None)
-                    .put(&path, payload)
-                    .await?;
-            }
             Ok(DeltaTable::new_with_state(
                 this.log_store().clone(),
                 DeltaTableState::new(snapshot.clone()),
@@ -178,6 +172,54 @@ impl std::future::IntoFuture for GenerateBuilder {
     }
 }
 
+async fn generate_symlink_format_manifest(
+    log_store: &LogStoreRef,
+    snapshot: &EagerSnapshot,
+) -> DeltaResult<()> {
+    let mut payloads = HashMap::new();
+    let manifest_part = PathPart::parse("manifest").expect("This is not possible");
+
+    let mut file_stream = snapshot.file_views(log_store, None);
+    while let Some(add) = file_stream.next().await {
+        let add = add?;
+        let path = add.object_store_path();
+        // The output_path is more or less the tree structure as the original file, just
+        // inside the _symlink_format_manifest directory. This makes it easier to avoid
+        // messing with partition values on the action
+        let output_path = Path::from_iter(
+            std::iter::once(PathPart::parse("_symlink_format_manifest").map_err(|e| {
+                DeltaTableError::GenericError {
+                    source: Box::new(e),
+                }
+            })?)
+            .chain(path.parts().filter(|p| path.filename() != Some(p.as_ref())))
+            .chain(std::iter::once(manifest_part.clone())),
+        );
+        trace!("Computed output path for add action: {output_path:?}");
+        if !payloads.contains_key(&output_path) {
+            payloads.insert(output_path.clone(), BytesMut::new());
+        }
+
+        if let Some(payload) = payloads.get_mut(&output_path) {
+            let uri = log_store.to_uri(&path);
+            trace!("Prepare {uri} for the symlink_format_manifest");
+            payload.put(uri.as_bytes());
+            payload.put_u8(b'\n');
+        }
+    }
+    debug!("Total of {} manifest files prepared", payloads.len());
+    for (path, payload) in payloads.drain() {
+        debug!(
+            "Generated manifest for {:?} is {} bytes",
+            path,
+            payload.len()
+        );
+        let payload = PutPayload::from(payload.freeze());
+        log_store.object_store(None).put(&path, payload).await?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
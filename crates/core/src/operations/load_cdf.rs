@@ -10,29 +10,32 @@
 //! let provider = DeltaCdfTableProvider::try_new(builder)?;
 //! let df = ctx.read_table(provider).await?;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::SystemTime;
 
-use arrow_schema::{ArrowError, Field, Schema};
+use arrow_schema::{ArrowError, Field, FieldRef, Schema};
 use chrono::{DateTime, Utc};
 use datafusion::catalog::Session;
-use datafusion::common::DFSchema;
-use datafusion::common::ScalarValue;
+use datafusion::common::{Column, DFSchema, JoinType, ScalarValue};
 use datafusion::config::TableParquetOptions;
+use datafusion::datasource::listing::PartitionedFile;
 use datafusion::datasource::memory::DataSourceExec;
 use datafusion::datasource::physical_plan::{FileGroup, FileScanConfigBuilder, ParquetSource};
+use datafusion::datasource::provider_as_source;
 use datafusion::datasource::table_schema::TableSchema;
-use datafusion::logical_expr::Expr;
+use datafusion::logical_expr::{Expr, LogicalPlan, LogicalPlanBuilder, cast, lit};
 use datafusion::physical_expr::{PhysicalExpr, expressions};
 use datafusion::physical_plan::ExecutionPlan;
 use datafusion::physical_plan::projection::ProjectionExec;
 use datafusion::physical_plan::union::UnionExec;
 use delta_kernel::table_features::ColumnMappingMode;
+use itertools::Itertools as _;
 use tracing::log;
 
 use crate::DeltaTableError;
 use crate::delta_datafusion::{
-    DataFusionMixins, DeltaSessionExt, extract_partition_only_predicate,
+    DataFusionMixins, DeltaScanNext, DeltaSessionExt, extract_partition_only_predicate,
 };
 use crate::errors::{ColumnMappingOperation, DeltaResult};
 use crate::kernel::transaction::PROTOCOL;
@@ -42,6 +45,36 @@ use crate::kernel::{
 use crate::logstore::{LogStoreRef, get_actions};
 use crate::{delta_datafusion::cdf::*, kernel::Remove};
 
+/// How the change rows of commits without change data files are reconstructed, see
+/// [`CdfLoadBuilder::with_inferred_change_data`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InferredChangeData {
+    /// Compare whole rows. Rows only found in the removed files are reported as `delete`, rows
+    /// only found in the added files as `insert`.
+    FullRow,
+    /// Match the changed rows by the given key columns. Keys found in both the removed and the
+    /// added files are reported as `update_preimage` and `update_postimage`, the remaining rows
+    /// as `delete` and `insert`.
+    Key(Vec<String>),
+}
+
+/// A commit without change data files whose changes are inferred from its data files.
+struct InferredChangeSpec {
+    version: Version,
+    timestamp: i64,
+    removed: Vec<String>,
+    added: Vec<String>,
+}
+
+/// The actions of the commits in the version range of a read.
+#[derive(Default)]
+struct FilesToRead {
+    change_files: Vec<CdcDataSpec<AddCDCFile>>,
+    add_files: Vec<CdcDataSpec<Add>>,
+    remove_files: Vec<CdcDataSpec<Remove>>,
+    inferred: Vec<InferredChangeSpec>,
+}
+
 /// Builder for create a read of change data feeds for delta tables
 #[derive(Clone)]
 pub struct CdfLoadBuilder {
@@ -66,6 +99,8 @@ pub struct CdfLoadBuilder {
     /// conjuncts are ignored here and row-level correctness must be enforced by a
     /// separate `FilterExec` wrapped around the resulting plan.
     filter: Option<Expr>,
+    /// Reconstruct the changes of commits that rewrote files without change data files
+    inferred_change_data: Option<InferredChangeData>,
 }
 
 impl std::fmt::Debug for CdfLoadBuilder {
@@ -78,6 +113,7 @@ impl std::fmt::Debug for CdfLoadBuilder {
             .field("starting_timestamp", &self.starting_timestamp)
             .field("ending_timestamp", &self.ending_timestamp)
             .field("allow_out_of_range", &self.allow_out_of_range)
+            .field("inferred_change_data", &self.inferred_change_data)
             .finish()
     }
 }
@@ -95,6 +131,7 @@ impl CdfLoadBuilder {
             allow_out_of_range: false,
             session: None,
             filter: None,
+            inferred_change_data: None,
         }
     }
 
@@ -134,6 +171,19 @@ impl CdfLoadBuilder {
         self
     }
 
+    /// Infer the changes of commits that removed and added files without writing change data
+    /// files, e.g. commits of writers that do not support the change data feed or made while it
+    /// was disabled, by diffing the rows of the removed and the added files.
+    ///
+    /// Reads no longer fail for versions where the change data feed was not enabled. Rows are
+    /// compared as multisets (`EXCEPT ALL`): a row is reported as often as its number of
+    /// occurrences on one side exceeds those on the other, so rows that were only moved between
+    /// files are not reported.
+    pub fn with_inferred_change_data(mut self, mode: InferredChangeData) -> Self {
+        self.inferred_change_data = Some(mode);
+        self
+    }
+
     /// A logical predicate used ONLY to prune files by their partition values.
     ///
     /// This does NOT apply a row-level filter: only the conjuncts that reference
@@ -172,11 +222,7 @@ impl CdfLoadBuilder {
         &self,
         snapshot: &EagerSnapshot,
         partition_pruning: Option<&PartitionPruningPredicate>,
-    ) -> DeltaResult<(
-        Vec<CdcDataSpec<AddCDCFile>>,
-        Vec<CdcDataSpec<Add>>,
-        Vec<CdcDataSpec<Remove>>,
-    )> {
+    ) -> DeltaResult<FilesToRead> {
         if self.starting_version.is_none() && self.starting_timestamp.is_none() {
             return Err(DeltaTableError::NoStartingVersionOrTimestamp);
         }
@@ -189,12 +235,13 @@ impl CdfLoadBuilder {
         let mut change_files: Vec<CdcDataSpec<AddCDCFile>> = vec![];
         let mut add_files: Vec<CdcDataSpec<Add>> = vec![];
         let mut remove_files: Vec<CdcDataSpec<Remove>> = vec![];
+        let mut inferred = vec![];
 
         // Start from 0 since if start > latest commit, the returned commit is not a valid commit
         let latest_version = match self.log_store.get_latest_version(start).await {
             Ok(latest_version) => latest_version,
             Err(DeltaTableError::InvalidVersion(_)) if self.allow_out_of_range => {
-                return Ok((change_files, add_files, remove_files));
+                return Ok(FilesToRead::default());
             }
             Err(e) => return Err(e),
         };
@@ -207,14 +254,14 @@ impl CdfLoadBuilder {
 
         if end < start {
             return if self.allow_out_of_range {
-                Ok((change_files, add_files, remove_files))
+                Ok(FilesToRead::default())
             } else {
                 Err(DeltaTableError::ChangeDataInvalidVersionRange { start, end })
             };
         }
         if start > latest_version {
             return if self.allow_out_of_range {
-                Ok((change_files, add_files, remove_files))
+                Ok(FilesToRead::default())
             } else {
                 Err(DeltaTableError::InvalidVersion(start))
             };
@@ -245,7 +292,7 @@ impl CdfLoadBuilder {
             && starting_timestamp.timestamp_millis() > *latest_timestamp
         {
             return if self.allow_out_of_range {
-                Ok((change_files, add_files, remove_files))
+                Ok(FilesToRead::default())
             } else {
                 Err(DeltaTableError::ChangeDataTimestampGreaterThanCommit { ending_timestamp })
             };
@@ -288,7 +335,9 @@ impl CdfLoadBuilder {
             for action in &version_actions {
                 match action {
                     Action::Cdc(f) => cdc_actions.push(f.clone()),
-                    Action::Metadata(md) => {
+                    // Changes of commits without change data files are inferred regardless of
+                    // whether the change data feed was enabled when they were written.
+                    Action::Metadata(md) if self.inferred_change_data.is_none() => {
                         log::info!("Metadata: {md:?}");
                         if let Some(key) = &md.configuration().get("delta.enableChangeDataFeed") {
                             let key = key.to_lowercase();
@@ -335,6 +384,20 @@ impl CdfLoadBuilder {
                     })
                     .collect::<Vec<Remove>>();
 
+                if self.inferred_change_data.is_some()
+                    && !add_actions.is_empty()
+                    && !remove_actions.is_empty()
+                {
+                    log::debug!("Inferring changes of version: {version}");
+                    inferred.push(InferredChangeSpec {
+                        version,
+                        timestamp: ts,
+                        removed: remove_actions.into_iter().map(|r| r.path).collect(),
+                        added: add_actions.into_iter().map(|a| a.path).collect(),
+                    });
+                    continue;
+                }
+
                 if !add_actions.is_empty() {
                     log::debug!(
                         "Located {} cdf actions for version: {version}",
@@ -359,7 +422,12 @@ impl CdfLoadBuilder {
             remove_files = prune_specs_by_partition(remove_files, partition_pruning)?;
         }
 
-        Ok((change_files, add_files, remove_files))
+        Ok(FilesToRead {
+            change_files,
+            add_files,
+            remove_files,
+            inferred,
+        })
     }
 
     #[inline]
@@ -452,7 +520,10 @@ impl CdfLoadBuilder {
         session: &dyn Session,
         filters: Option<&Arc<dyn PhysicalExpr>>,
     ) -> DeltaResult<Arc<dyn ExecutionPlan>> {
-        let snapshot = resolve_snapshot(&self.log_store, self.snapshot.clone(), true, None).await?;
+        // Only the schema and metadata of the snapshot are used, the files to read come from the
+        // commits in the version range.
+        let snapshot =
+            resolve_snapshot(&self.log_store, self.snapshot.clone(), false, None).await?;
         PROTOCOL.can_read_from(&snapshot)?;
         if snapshot.table_configuration().column_mapping_mode() != ColumnMappingMode::None {
             return Err(DeltaTableError::unsupported_column_mapping(
@@ -466,7 +537,12 @@ impl CdfLoadBuilder {
 
         let partition_pruning =
             self.partition_pruning_predicate(session, &schema, partition_values)?;
-        let (cdc, add, remove) = self
+        let FilesToRead {
+            change_files: cdc,
+            add_files: add,
+            remove_files: remove,
+            inferred,
+        } = self
             .determine_files_to_read(&snapshot, partition_pruning.as_ref())
             .await?;
         session.ensure_log_store_registered(self.log_store.as_ref())?;
@@ -549,24 +625,19 @@ impl CdfLoadBuilder {
 
         let cdc_scan: Arc<dyn ExecutionPlan> = DataSourceExec::from_data_source(
             FileScanConfigBuilder::new(self.log_store.object_store_url(), Arc::new(cdc_source))
-                .with_file_groups(cdc_file_groups.into_values().map(FileGroup::from).collect())
+                .with_file_groups(sorted_file_groups(cdc_file_groups))
                 .build(),
         );
 
         let add_scan: Arc<dyn ExecutionPlan> = DataSourceExec::from_data_source(
             FileScanConfigBuilder::new(self.log_store.object_store_url(), Arc::new(add_source))
-                .with_file_groups(add_file_groups.into_values().map(FileGroup::from).collect())
+                .with_file_groups(sorted_file_groups(add_file_groups))
                 .build(),
         );
 
         let remove_scan: Arc<dyn ExecutionPlan> = DataSourceExec::from_data_source(
             FileScanConfigBuilder::new(self.log_store.object_store_url(), Arc::new(remove_source))
-                .with_file_groups(
-                    remove_file_groups
-                        .into_values()
-                        .map(FileGroup::from)
-                        .collect(),
-                )
+                .with_file_groups(sorted_file_groups(remove_file_groups))
                 .build(),
         );
 
@@ -597,12 +668,191 @@ impl CdfLoadBuilder {
             })
             .collect();
 
-        let scan = Arc::new(ProjectionExec::try_new(expressions, union_scan)?);
+        let scan: Arc<dyn ExecutionPlan> =
+            Arc::new(ProjectionExec::try_new(expressions, union_scan)?);
+
+        match &self.inferred_change_data {
+            Some(mode) if !inferred.is_empty() => {
+                let inferred_scan = self
+                    .inferred_changes_plan(session, mode, &project_schema, inferred)
+                    .await?;
+                Ok(UnionExec::try_new(vec![scan, inferred_scan])?)
+            }
+            _ => Ok(scan),
+        }
+    }
+
+    /// Plan the change rows of commits without change data files, see
+    /// [`Self::with_inferred_change_data`].
+    async fn inferred_changes_plan(
+        &self,
+        session: &dyn Session,
+        mode: &InferredChangeData,
+        output_schema: &Schema,
+        specs: Vec<InferredChangeSpec>,
+    ) -> DeltaResult<Arc<dyn ExecutionPlan>> {
+        let data_fields =
+            &output_schema.fields()[..output_schema.fields().len() - ADD_PARTITION_SCHEMA.len()];
+        if let InferredChangeData::Key(keys) = mode
+            && let Some(missing) = keys
+                .iter()
+                .find(|key| !data_fields.iter().any(|f| f.name() == *key))
+        {
+            return Err(DeltaTableError::SchemaMismatch {
+                msg: format!("Key column '{missing}' for inferring changes does not exist"),
+            });
+        }
+
+        let mut plan: Option<LogicalPlanBuilder> = None;
+        for spec in specs {
+            let changes = self.infer_version_changes(mode, data_fields, spec).await?;
+            plan = Some(match plan {
+                Some(plan) => plan.union(changes)?,
+                None => LogicalPlanBuilder::from(changes),
+            });
+        }
+        let plan = plan
+            .ok_or_else(|| DeltaTableError::generic("No versions to infer changes for"))?
+            .build()?;
+        Ok(session.create_physical_plan(&plan).await?)
+    }
+
+    /// Diff the rows of the files removed and added by a commit.
+    async fn infer_version_changes(
+        &self,
+        mode: &InferredChangeData,
+        data_fields: &[FieldRef],
+        spec: InferredChangeSpec,
+    ) -> DeltaResult<LogicalPlan> {
+        // Removed files are read as of the previous version, so that rows hidden by a deletion
+        // vector replaced in this commit are not reported twice.
+        let removed = self
+            .files_plan(spec.version - 1, spec.removed, data_fields)
+            .await?;
+        let added = self
+            .files_plan(spec.version, spec.added, data_fields)
+            .await?;
+
+        let old = LogicalPlanBuilder::except(removed.clone(), added.clone(), true)?;
+        let old = LogicalPlanBuilder::from(old).alias("old")?.build()?;
+        let new = LogicalPlanBuilder::except(added, removed, true)?;
+        let new = LogicalPlanBuilder::from(new).alias("new")?.build()?;
+
+        let changes = match mode {
+            InferredChangeData::FullRow => vec![(old, "delete"), (new, "insert")],
+            InferredChangeData::Key(keys) => {
+                let key_columns = |relation: &str| {
+                    keys.iter()
+                        .map(|key| Column::new(Some(relation), key))
+                        .collect_vec()
+                };
+                let old_keys = key_columns("old");
+                let new_keys = key_columns("new");
+                let old_rows = |join_type| {
+                    LogicalPlanBuilder::from(old.clone())
+                        .join(
+                            new.clone(),
+                            join_type,
+                            (old_keys.clone(), new_keys.clone()),
+                            None,
+                        )?
+                        .build()
+                };
+                let new_rows = |join_type| {
+                    LogicalPlanBuilder::from(new.clone())
+                        .join(
+                            old.clone(),
+                            join_type,
+                            (new_keys.clone(), old_keys.clone()),
+                            None,
+                        )?
+                        .build()
+                };
+                // Keys present on both sides changed their values, the others were deleted or
+                // inserted.
+                vec![
+                    (old_rows(JoinType::LeftSemi)?, "update_preimage"),
+                    (new_rows(JoinType::LeftSemi)?, "update_postimage"),
+                    (old_rows(JoinType::LeftAnti)?, "delete"),
+                    (new_rows(JoinType::LeftAnti)?, "insert"),
+                ]
+            }
+        };
+
+        let mut plan: Option<LogicalPlanBuilder> = None;
+        for (changes, change_type) in changes {
+            let projection = data_fields
+                .iter()
+                .map(|field| Expr::Column(Column::from_name(field.name())))
+                .chain([
+                    lit(change_type).alias(CHANGE_TYPE_COL),
+                    lit(ScalarValue::UInt64(Some(spec.version as u64))).alias(COMMIT_VERSION_COL),
+                    lit(ScalarValue::TimestampMillisecond(
+                        Some(spec.timestamp),
+                        None,
+                    ))
+                    .alias(COMMIT_TIMESTAMP_COL),
+                ]);
+            let changes = LogicalPlanBuilder::from(changes)
+                .project(projection)?
+                .build()?;
+            plan = Some(match plan {
+                Some(plan) => plan.union(changes)?,
+                None => LogicalPlanBuilder::from(changes),
+            });
+        }
+        Ok(plan.expect("at least one kind of change").build()?)
+    }
 
-        Ok(scan)
+    /// Read the given files of the table as of `version`, cast to the columns of the change
+    /// data feed.
+    async fn files_plan(
+        &self,
+        version: Version,
+        paths: Vec<String>,
+        data_fields: &[FieldRef],
+    ) -> DeltaResult<LogicalPlan> {
+        let provider = DeltaScanNext::builder()
+            .with_log_store(self.log_store.clone())
+            .with_table_version(version)
+            .with_file_paths(paths)
+            .await?;
+        let scan = LogicalPlanBuilder::scan("files", provider_as_source(provider), None)?;
+        let schema = scan.schema().clone();
+
+        // Columns added to the table after this version are read as nulls.
+        let projection = data_fields
+            .iter()
+            .map(|field| {
+                let expr = if schema.has_column_with_unqualified_name(field.name()) {
+                    cast(
+                        Expr::Column(Column::from_name(field.name())),
+                        field.data_type().clone(),
+                    )
+                } else {
+                    lit(ScalarValue::try_from(field.data_type())?)
+                };
+                Ok(expr.alias(field.name()))
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+        Ok(scan.project(projection)?.build()?)
     }
 }
 
+/// Order the file groups by their first file, so that batches of a scan are always produced in the
+/// same order. Change feed streams rely on this to resume within a version.
+fn sorted_file_groups(groups: HashMap<Vec<ScalarValue>, Vec<PartitionedFile>>) -> Vec<FileGroup> {
+    groups
+        .into_values()
+        .sorted_by(|a, b| {
+            let first =
+                |files: &[PartitionedFile]| files.first().map(|f| f.object_meta.location.clone());
+            first(a).cmp(&first(b))
+        })
+        .map(FileGroup::from)
+        .collect()
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -1516,4 +1766,94 @@ pub(crate) mod tests {
         assert!(cdc_actions.is_empty());
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_load_inferred_change_data() -> TestResult {
+        use datafusion::prelude::{col, lit};
+
+        let delta_schema = TestSchemas::simple();
+        let table: DeltaTable = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(delta_schema.fields().cloned())
+            .await?;
+        let schema: Arc<Schema> = Arc::new(delta_schema.try_into_arrow()?);
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(StringArray::from(vec![Some("1"), Some("2"), Some("3")])),
+                Arc::new(Int32Array::from(vec![Some(1), Some(2), Some(3)])),
+                Arc::new(StringArray::from(vec![
+                    Some("yes"),
+                    Some("yes"),
+                    Some("no"),
+                ])),
+            ],
+        )?;
+        let table = table.write(vec![batch]).await?;
+        let (table, _) = table
+            .update()
+            .with_predicate(col("id").eq(lit("2")))
+            .with_update("value", lit(20))
+            .await?;
+        let (table, _) = table
+            .delete()
+            .with_predicate(col("id").eq(lit("3")))
+            .await?;
+        assert_eq!(table.version(), Some(3));
+
+        let ctx = SessionContext::new();
+        let read = |mode| {
+            let table = table.clone();
+            let ctx = &ctx;
+            async move {
+                let cdf_scan = table
+                    .scan_cdf()
+                    .with_starting_version(0)
+                    .with_inferred_change_data(mode)
+                    .build(&ctx.state(), None)
+                    .await?;
+                let mut batches = collect(cdf_scan, ctx.task_ctx()).await?;
+                let _: Vec<_> = batches.iter_mut().map(|b| b.remove_column(5)).collect();
+                DeltaResult::Ok(batches)
+            }
+        };
+
+        let batches = read(InferredChangeData::Key(vec!["id".into()])).await?;
+        assert_batches_sorted_eq! {[
+            "+----+-------+----------+------------------+-----------------+",
+            "| id | value | modified | _change_type     | _commit_version |",
+            "+----+-------+----------+------------------+-----------------+",
+            "| 1  | 1     | yes      | insert           | 1               |",
+            "| 2  | 2     | yes      | insert           | 1               |",
+            "| 2  | 2     | yes      | update_preimage  | 2               |",
+            "| 2  | 20    | yes      | update_postimage | 2               |",
+            "| 3  | 3     | no       | delete           | 3               |",
+            "| 3  | 3     | no       | insert           | 1               |",
+            "+----+-------+----------+------------------+-----------------+",
+        ], &batches }
+
+        let batches = read(InferredChangeData::FullRow).await?;
+        assert_batches_sorted_eq! {[
+            "+----+-------+----------+--------------+-----------------+",
+            "| id | value | modified | _change_type | _commit_version |",
+            "+----+-------+----------+--------------+-----------------+",
+            "| 1  | 1     | yes      | insert       | 1               |",
+            "| 2  | 2     | yes      | delete       | 2               |",
+            "| 2  | 2     | yes      | insert       | 1               |",
+            "| 2  | 20    | yes      | insert       | 2               |",
+            "| 3  | 3     | no       | delete       | 3               |",
+            "| 3  | 3     | no       | insert       | 1               |",
+            "+----+-------+----------+--------------+-----------------+",
+        ], &batches }
+
+        let err = table
+            .scan_cdf()
+            .with_starting_version(0)
+            .with_inferred_change_data(InferredChangeData::Key(vec!["missing".into()]))
+            .build(&ctx.state(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeltaTableError::SchemaMismatch { .. }));
+        Ok(())
+    }
 }
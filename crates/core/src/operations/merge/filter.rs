@@ -2,13 +2,20 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use arrow::compute::concat_batches;
+use arrow::array::{Array, ArrayRef, UInt32Array};
+use arrow::compute::{concat_batches, take};
+use arrow_ord::ord::make_comparator;
+use arrow_schema::SortOptions;
 use datafusion::catalog::Session;
 use datafusion::common::tree_node::{Transformed, TreeNode};
-use datafusion::common::{ScalarValue, TableReference};
+use datafusion::common::{Column, ScalarValue, TableReference};
 use datafusion::functions_aggregate::expr_fn::{max, min};
 use datafusion::logical_expr::expr::{InList, Placeholder};
-use datafusion::logical_expr::{Aggregate, Between, BinaryExpr, Expr, LogicalPlan, Operator, lit};
+use datafusion::logical_expr::utils::split_conjunction;
+use datafusion::logical_expr::{
+    Aggregate, Between, BinaryExpr, Expr, LogicalPlan, LogicalPlanBuilder, Operator, col, lit,
+};
+use datafusion::physical_optimizer::pruning::PruningStatistics;
 use datafusion::physical_plan::ExecutionPlan;
 use either::{Left, Right};
 use futures::TryStreamExt as _;
@@ -395,6 +402,191 @@ pub(crate) async fn try_construct_early_filter(
     }
 }
 
+/// Maximum number of distinct source key combinations collected for dynamic file pruning.
+///
+/// Sources with more distinct keys fall back to the range filter of [`try_construct_early_filter`].
+pub(crate) const DYNAMIC_FILTER_MAX_KEYS: usize = 100_000;
+
+/// Target files and filters derived from the distinct join keys of the source.
+pub(crate) struct DynamicKeyFilter {
+    /// Paths of the target files whose statistics may contain one of the source keys.
+    pub files: Vec<String>,
+    /// `target.key IN (...)` filters with the distinct source keys, one per join key.
+    ///
+    /// These filter rows and may only be applied to scans whose files are never rewritten.
+    pub predicates: Vec<Expr>,
+}
+
+/// Collect the equi-join keys between plain non-partition target columns and source expressions.
+fn equi_join_keys(
+    join_predicate: &Expr,
+    partition_columns: &[String],
+    source_name: &TableReference,
+    target_name: &TableReference,
+) -> Vec<(String, Expr)> {
+    split_conjunction(join_predicate)
+        .into_iter()
+        .filter_map(|expr| match expr {
+            Expr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::Eq,
+                right,
+            }) => [(left, right), (right, left)]
+                .into_iter()
+                .find_map(|(target, source)| match target.as_ref() {
+                    Expr::Column(column)
+                        if column.relation.as_ref() == Some(target_name)
+                            && !partition_columns.contains(&column.name)
+                            && references_table(source, source_name).has_reference()
+                            && !references_table(source, target_name).has_reference() =>
+                    {
+                        Some((column.name.clone(), source.as_ref().clone()))
+                    }
+                    _ => None,
+                }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Distinct non-null values of each of the `keys` in the source, sorted, or `None` when the
+/// source has more than [`DYNAMIC_FILTER_MAX_KEYS`] distinct key combinations.
+///
+/// The source is only executed once, however many join keys there are.
+async fn collect_distinct_keys(
+    session_state: &dyn Session,
+    source: &LogicalPlan,
+    keys: Vec<Expr>,
+) -> DeltaResult<Option<Vec<ArrayRef>>> {
+    let names: Vec<String> = (0..keys.len()).map(|i| format!("key_{i}")).collect();
+    let not_null = names
+        .iter()
+        .map(|name| col(name.as_str()).is_not_null())
+        .reduce(Expr::or)
+        .unwrap_or_else(|| lit(false));
+    let plan = LogicalPlanBuilder::from(source.clone())
+        .aggregate(
+            keys.into_iter()
+                .zip(&names)
+                .map(|(key, name)| key.alias(name)),
+            Vec::<Expr>::new(),
+        )?
+        .filter(not_null)?
+        .limit(0, Some(DYNAMIC_FILTER_MAX_KEYS + 1))?
+        .build()?;
+    let execution_plan = session_state.create_physical_plan(&plan).await?;
+    let batch = execute_plan_to_batch(session_state, execution_plan).await?;
+    if batch.num_rows() > DYNAMIC_FILTER_MAX_KEYS {
+        return Ok(None);
+    }
+    batch
+        .columns()
+        .iter()
+        .map(distinct_sorted)
+        .try_collect()
+        .map(Some)
+}
+
+/// The distinct non-null values of `values` in ascending order.
+fn distinct_sorted(values: &ArrayRef) -> DeltaResult<ArrayRef> {
+    let sorted = arrow_ord::sort::sort(values, None)?;
+    let cmp = make_comparator(sorted.as_ref(), sorted.as_ref(), SortOptions::default())?;
+    let indices: UInt32Array = (0..sorted.len())
+        .filter(|&i| sorted.is_valid(i) && (i == 0 || cmp(i - 1, i).is_ne()))
+        .map(|i| i as u32)
+        .collect();
+    Ok(take(sorted.as_ref(), &indices, None)?)
+}
+
+/// For each file, whether the `[min, max]` range of its statistics contains one of the sorted
+/// `keys`. Files without statistics are kept. Returns `None` if the keys cannot be compared
+/// with the statistics.
+fn files_containing_keys(keys: &ArrayRef, min: &ArrayRef, max: &ArrayRef) -> Option<Vec<bool>> {
+    let keys = arrow_cast::cast(keys, min.data_type()).ok()?;
+    // Keys that cannot be represented in the column type make the comparison meaningless
+    if keys.null_count() > 0 {
+        return None;
+    }
+    let lower = make_comparator(keys.as_ref(), min.as_ref(), SortOptions::default()).ok()?;
+    let upper = make_comparator(keys.as_ref(), max.as_ref(), SortOptions::default()).ok()?;
+    let indices: Vec<usize> = (0..keys.len()).collect();
+    Some(
+        (0..min.len())
+            .map(|file| {
+                if min.is_null(file) || max.is_null(file) {
+                    return true;
+                }
+                let first = indices.partition_point(|&key| lower(key, file).is_lt());
+                first < keys.len() && upper(first, file).is_le()
+            })
+            .collect(),
+    )
+}
+
+/// Dynamic file pruning for merges with equi-join keys on non-partition columns.
+///
+/// A min/max range of the source keys covers most of the table when keys are sparse, e.g.
+/// UUIDs. Instead, the distinct source keys are collected and a target file is only read when
+/// its statistics range for the key column contains at least one of them. The keys are also
+/// returned as `IN` lists, which the parquet reader can check against row group statistics
+/// and bloom filters.
+pub(crate) async fn try_construct_dynamic_key_filter(
+    join_predicate: &Expr,
+    table_snapshot: &EagerSnapshot,
+    session_state: &dyn Session,
+    source: &LogicalPlan,
+    source_name: &TableReference,
+    target_name: &TableReference,
+) -> DeltaResult<Option<DynamicKeyFilter>> {
+    let keys = equi_join_keys(
+        join_predicate,
+        table_snapshot.metadata().partition_columns(),
+        source_name,
+        target_name,
+    );
+    if keys.is_empty() {
+        return Ok(None);
+    }
+
+    let (target_columns, source_exprs): (Vec<_>, Vec<_>) = keys.into_iter().unzip();
+    let Some(distinct_keys) = collect_distinct_keys(session_state, source, source_exprs).await?
+    else {
+        return Ok(None);
+    };
+
+    let mut keep = vec![true; table_snapshot.num_containers()];
+    let mut predicates = Vec::with_capacity(target_columns.len());
+    for (target_column, values) in target_columns.into_iter().zip(distinct_keys) {
+        let column = Column::new_unqualified(target_column.clone());
+        if let (Some(min), Some(max)) = (
+            table_snapshot.min_values(&column),
+            table_snapshot.max_values(&column),
+        ) && let Some(contained) = files_containing_keys(&values, &min, &max)
+        {
+            keep.iter_mut()
+                .zip(contained)
+                .for_each(|(keep, contained)| *keep &= contained);
+        }
+        let list = (0..values.len())
+            .map(|i| ScalarValue::try_from_array(&values, i).map(lit))
+            .try_collect()?;
+        predicates.push(Expr::InList(InList {
+            expr: Box::new(col(Column::new(Some(target_name.clone()), target_column))),
+            list,
+            negated: false,
+        }));
+    }
+
+    let files = table_snapshot
+        .log_data()
+        .iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|(file, _)| file.path().to_string())
+        .collect();
+    Ok(Some(DynamicKeyFilter { files, predicates }))
+}
+
 async fn execute_plan_to_batch(
     state: &dyn Session,
     plan: Arc<dyn ExecutionPlan>,
@@ -418,10 +610,12 @@ async fn execute_plan_to_batch(
 
 #[cfg(test)]
 mod tests {
+    use super::{distinct_sorted, files_containing_keys};
     use crate::operations::merge::tests::setup_table;
     use crate::operations::merge::try_construct_early_filter;
     use crate::writer::test_utils::get_arrow_schema;
 
+    use arrow::array::{ArrayRef, Int32Array, Int64Array};
     use arrow::record_batch::RecordBatch;
 
     use datafusion::datasource::provider_as_source;
@@ -855,6 +1049,34 @@ mod tests {
         assert_eq!(pred.unwrap(), filter);
     }
 
+    #[test]
+    fn test_files_containing_keys() {
+        let keys: ArrayRef = Arc::new(Int32Array::from(vec![3, 42, 97]));
+        let min: ArrayRef = Arc::new(Int64Array::from(vec![Some(0), Some(10), Some(50), None]));
+        let max: ArrayRef = Arc::new(Int64Array::from(vec![Some(5), Some(40), Some(100), None]));
+
+        // Only the second file's range [10, 40] lies between two keys. The last file has no
+        // statistics and is kept.
+        let contained = files_containing_keys(&keys, &min, &max).unwrap();
+        assert_eq!(contained, vec![true, false, true, true]);
+    }
+
+    #[test]
+    fn test_distinct_sorted() {
+        let values: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(7),
+            None,
+            Some(3),
+            Some(7),
+            None,
+            Some(3),
+            Some(1),
+        ]));
+        let distinct = distinct_sorted(&values).unwrap();
+        let expected: ArrayRef = Arc::new(Int32Array::from(vec![1, 3, 7]));
+        assert_eq!(&distinct, &expected);
+    }
+
     /// return a join predicate for the source and target tables
     ///
     /// `source.id = target.id`
@@ -7,6 +7,20 @@
 //! and specify additional predicates for finer control. The order of operations
 //! specified matter.  See [`MergeBuilder`] for more information
 //!
+//! Merges with only `when_not_matched_insert` clauses use a left-anti join of the source
+//! against the target instead. They only add files, so target files are never rewritten and
+//! the commit does not conflict with concurrent compactions.
+//!
+//! Target files are pruned with the distinct source values of equi-join keys: a file is only
+//! read when its statistics range for a key column contains one of the source keys. Insert-only
+//! merges additionally push the keys into the parquet scan, so row groups are skipped using their
+//! statistics and bloom filters.
+//!
+//! [`UpdateBuilder::update_all`] and [`InsertBuilder::insert_all`] assign every source column
+//! to the target column of the same name. With [`MergeBuilder::with_merge_schema`] enabled,
+//! source columns missing from the target, including fields nested in structs and in lists of
+//! structs, are added to the table schema.
+//!
 //! # Example
 //! ```rust ignore
 //! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap())?;
@@ -33,7 +47,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Instant;
 
-use arrow_schema::{DataType, Field, SchemaBuilder};
+use arrow_schema::{DataType, Field, Schema as ArrowSchema, SchemaBuilder};
 use async_trait::async_trait;
 use datafusion::catalog::Session;
 use datafusion::common::tree_node::{Transformed, TreeNode};
@@ -61,7 +75,7 @@ use datafusion::{
 
 use delta_kernel::engine::arrow_conversion::{TryIntoArrow as _, TryIntoKernel as _};
 use delta_kernel::schema::{ColumnMetadataKey, StructType};
-use filter::try_construct_early_filter;
+use filter::{try_construct_dynamic_key_filter, try_construct_early_filter};
 use futures::{TryStreamExt as _, future::BoxFuture};
 use parquet::file::properties::WriterProperties;
 use serde::Serialize;
@@ -90,10 +104,12 @@ use crate::kernel::{
     Action, ActiveAddOptions, AddStatsPolicy, EagerSnapshot, StructTypeExt, new_metadata,
     resolve_snapshot,
 };
-use crate::logstore::{LogStore, LogStoreRef};
+use crate::logstore::{IoStats, LogStore, LogStoreRef};
 use crate::operations::cdc::*;
 use crate::operations::merge::barrier::find_node;
+use crate::operations::replan::ReplanPolicy;
 use crate::operations::write::WriterStatsConfig;
+use crate::operations::write::column_defaults::{column_defaults_enabled, parse_column_defaults};
 use crate::operations::write::execution::write_execution_plan_v2;
 use crate::operations::write::generated_columns::{
     add_generated_columns, add_missing_generated_columns, gc_is_enabled,
@@ -147,6 +163,7 @@ fn merge_cast(expr: Expr, data_type: DataType, safe_cast: bool) -> Expr {
 }
 
 /// Merge records into a Delta Table.
+#[derive(Clone)]
 pub struct MergeBuilder {
     /// The join predicate
     predicate: Expression,
@@ -181,6 +198,8 @@ pub struct MergeBuilder {
     /// By default an error is returned
     safe_cast: bool,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    /// Re-plan the operation against the latest snapshot after commit conflicts
+    replan_policy: Option<ReplanPolicy>,
 }
 
 impl super::Operation for MergeBuilder {
@@ -219,6 +238,7 @@ impl MergeBuilder {
             safe_cast: false,
             streaming: false,
             custom_execute_handler: None,
+            replan_policy: None,
         }
     }
 
@@ -257,7 +277,8 @@ impl MergeBuilder {
     {
         let builder = builder(UpdateBuilder::default());
         let op =
-            MergeOperationConfig::new(builder.predicate, builder.updates, OperationType::Update)?;
+            MergeOperationConfig::new(builder.predicate, builder.updates, OperationType::Update)?
+                .with_all(builder.all);
         self.match_operations.push(op);
         Ok(self)
     }
@@ -322,7 +343,8 @@ impl MergeBuilder {
         F: FnOnce(InsertBuilder) -> InsertBuilder,
     {
         let builder = builder(InsertBuilder::default());
-        let op = MergeOperationConfig::new(builder.predicate, builder.set, OperationType::Insert)?;
+        let op = MergeOperationConfig::new(builder.predicate, builder.set, OperationType::Insert)?
+            .with_all(builder.all);
         self.not_match_operations.push(op);
         Ok(self)
     }
@@ -356,6 +378,12 @@ impl MergeBuilder {
         F: FnOnce(UpdateBuilder) -> UpdateBuilder,
     {
         let builder = builder(UpdateBuilder::default());
+        if builder.all {
+            return Err(DeltaTableError::Generic(
+                "update_all requires a source record and cannot be used when not matched by source"
+                    .into(),
+            ));
+        }
         let op =
             MergeOperationConfig::new(builder.predicate, builder.updates, OperationType::Update)?;
         self.not_match_source_operations.push(op);
@@ -440,6 +468,13 @@ impl MergeBuilder {
         self
     }
 
+    /// Reload the snapshot, re-plan and retry the operation when its commit conflicts with a
+    /// concurrent transaction. See [`ReplanPolicy`] for details.
+    pub fn with_replan_policy(mut self, replan_policy: ReplanPolicy) -> Self {
+        self.replan_policy = Some(replan_policy);
+        self
+    }
+
     /// Writer properties passed to parquet writer for when files are rewritten
     pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
         self.writer_properties = Some(writer_properties);
@@ -481,6 +516,8 @@ pub struct UpdateBuilder {
     predicate: Option<Expression>,
     /// How to update columns in the target table
     updates: HashMap<Column, Expression>,
+    /// Update every target column with the source column of the same name
+    all: bool,
 }
 
 impl UpdateBuilder {
@@ -501,6 +538,18 @@ impl UpdateBuilder {
         self.updates.insert(column.into().into(), expression.into());
         self
     }
+
+    /// Update every target column with the value of the source column that has the same name,
+    /// the equivalent of `UPDATE SET *`.
+    ///
+    /// Target columns without a source counterpart keep their value. Source columns without a
+    /// target counterpart are ignored, unless schema evolution is enabled with
+    /// [`MergeBuilder::with_merge_schema`], in which case they are added to the table. Columns
+    /// given explicitly with [`UpdateBuilder::update`] take precedence.
+    pub fn update_all(mut self) -> Self {
+        self.all = true;
+        self
+    }
 }
 
 /// Builder for insert clauses
@@ -510,6 +559,8 @@ pub struct InsertBuilder {
     predicate: Option<Expression>,
     /// What value each column is inserted with
     set: HashMap<Column, Expression>,
+    /// Insert every source column into the target column of the same name
+    all: bool,
 }
 
 impl InsertBuilder {
@@ -529,6 +580,18 @@ impl InsertBuilder {
         self.set.insert(column.into().into(), expression.into());
         self
     }
+
+    /// Insert the value of every source column into the target column that has the same name,
+    /// the equivalent of `INSERT *`.
+    ///
+    /// Target columns without a source counterpart are inserted as null. Source columns without
+    /// a target counterpart are ignored, unless schema evolution is enabled with
+    /// [`MergeBuilder::with_merge_schema`], in which case they are added to the table. Columns
+    /// given explicitly with [`InsertBuilder::set`] take precedence.
+    pub fn insert_all(mut self) -> Self {
+        self.all = true;
+        self
+    }
 }
 
 /// Builder for delete clauses
@@ -566,12 +629,15 @@ enum MatchParticipationClass {
 }
 
 //Encapsute the User's Merge configuration for later processing
+#[derive(Clone)]
 struct MergeOperationConfig {
     /// Which records to update
     predicate: Option<Expression>,
     /// How to update columns in a record that match the predicate
     operations: HashMap<Column, Expression>,
     r#type: OperationType,
+    /// Assign every source column to the target column of the same name
+    all: bool,
 }
 
 struct MergeOperation {
@@ -668,8 +734,46 @@ impl MergeOperationConfig {
             predicate,
             operations,
             r#type,
+            all: false,
         })
     }
+
+    fn with_all(mut self, all: bool) -> Self {
+        self.all = all;
+        self
+    }
+
+    /// Expand an `UPDATE SET *` or `INSERT *` clause into one assignment per source column.
+    ///
+    /// Only source columns that exist in the target are assigned, unless `merge_schema` is
+    /// enabled. Explicit assignments for a column are kept.
+    fn expand_all(
+        mut self,
+        source_schema: &DFSchema,
+        source_name: &TableReference,
+        target_schema: &ArrowSchema,
+        merge_schema: bool,
+        skip: &[String],
+    ) -> Self {
+        if !self.all {
+            return self;
+        }
+        for field in source_schema.fields() {
+            let name = field.name();
+            if skip.contains(name)
+                || self.operations.keys().any(|column| &column.name == name)
+                || (!merge_schema && target_schema.field_with_name(name).is_err())
+            {
+                continue;
+            }
+            self.operations.insert(
+                Column::new_unqualified(name),
+                Expression::DataFusion(col(Column::new(Some(source_name.clone()), name))),
+            );
+        }
+        self.all = false;
+        self
+    }
 }
 
 #[derive(Default, Serialize, Debug)]
@@ -701,6 +805,11 @@ pub struct MergeMetrics {
     pub scan_time_ms: u64,
     /// Time taken to rewrite the matched files
     pub rewrite_time_ms: u64,
+    /// Number of times the operation was re-planned after a commit conflict
+    pub num_replans: usize,
+    /// Object store requests issued by the operation before its commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io_stats: Option<IoStats>,
 }
 #[derive(Clone, Debug)]
 pub(crate) struct MergeMetricExtensionPlanner {}
@@ -978,10 +1087,38 @@ async fn execute(
 
     debug!("Using target subset filter: {commit_predicate:?}");
 
+    // Keys such as UUIDs are spread across the whole table, so their min/max range rarely skips
+    // any file. Select the target files from the distinct source keys instead.
+    let dynamic_key_filter = if not_match_source_operations.is_empty() && !streaming {
+        try_construct_dynamic_key_filter(
+            &predicate,
+            &snapshot,
+            &state,
+            &source,
+            &source_name,
+            &target_name,
+        )
+        .await?
+    } else {
+        None
+    };
+    if let Some(filter) = &dynamic_key_filter {
+        debug!(
+            "Dynamic file pruning selected {} of {} target files",
+            filter.files.len(),
+            snapshot.log_data().num_files()
+        );
+    }
+
     // Apply the early filter only to file skipping.
     let file_skipping_predicates =
         build_file_skipping_predicates(target_subset_filter, target_alias.as_deref());
     let needs_duplicate_match_validation = !match_operations.is_empty();
+    // Merges that only insert unmatched source rows never modify target rows, so the target
+    // files are only read to find the source rows without a match and are never rewritten.
+    let insert_only = match_operations.is_empty()
+        && not_match_source_operations.is_empty()
+        && !not_match_target_operations.is_empty();
 
     let target_provider = {
         let mut builder = DeltaScanNext::builder()
@@ -998,10 +1135,23 @@ async fn execute(
             builder = builder.with_file_skipping_predicates(file_skipping_predicates);
         }
 
+        if let Some(filter) = &dynamic_key_filter {
+            builder = builder.with_file_paths(filter.files.clone());
+        }
+
         provider_as_source(builder.await?)
     };
 
-    let target = LogicalPlanBuilder::scan(target_name.clone(), target_provider, None)?.build()?;
+    let target = LogicalPlanBuilder::scan(target_name.clone(), target_provider, None)?;
+    // Target rows are only filtered when no target file is rewritten. The key filter is pushed
+    // into the parquet scan, which skips row groups using their statistics and bloom filters.
+    let target = match dynamic_key_filter
+        .and_then(|filter| filter.predicates.into_iter().reduce(Expr::and))
+    {
+        Some(key_filter) if insert_only => target.filter(key_filter)?,
+        _ => target,
+    }
+    .build()?;
 
     let source = DataFrame::new(state.clone(), source.clone());
     let source = source.with_column(SOURCE_COLUMN, lit(true))?;
@@ -1019,8 +1169,40 @@ async fn execute(
     let target = DataFrame::new(state.clone(), target);
     let target = target.with_column(TARGET_COLUMN, lit(true))?;
 
-    let join = source.join(target, JoinType::Full, &[], &[], Some(predicate.clone()))?;
+    let join = source.clone().join(
+        target.clone(),
+        JoinType::Full,
+        &[],
+        &[],
+        Some(predicate.clone()),
+    )?;
     let join_schema_df = join.schema().to_owned();
+    let join = if insert_only {
+        debug!("insert-only merge, using a left-anti join of the source against the target");
+        build_insert_only_join(source, target, predicate.clone(), &join_schema_df)?
+    } else {
+        join
+    };
+
+    // Expand `UPDATE SET *` and `INSERT *` clauses by matching source and target columns by name.
+    // Generated columns the source does not provide are computed after the merge projection.
+    let table_schema = snapshot.input_schema();
+    let skip_columns = missing_generated_col.as_deref().unwrap_or_default();
+    let expand_all = |op: MergeOperationConfig| {
+        op.expand_all(
+            source_schema,
+            &source_name,
+            table_schema.as_ref(),
+            merge_schema,
+            skip_columns,
+        )
+    };
+    let match_operations: Vec<MergeOperationConfig> =
+        match_operations.into_iter().map(expand_all).collect();
+    let not_match_target_operations: Vec<MergeOperationConfig> = not_match_target_operations
+        .into_iter()
+        .map(expand_all)
+        .collect();
 
     let match_operations: Vec<MergeOperation> = match_operations
         .into_iter()
@@ -1228,6 +1410,13 @@ async fn execute(
         snapshot.schema()
     };
 
+    // Target columns that an insert clause does not assign take their default value
+    let column_defaults = if column_defaults_enabled(&snapshot) {
+        parse_column_defaults(&state, &schema.get_column_defaults()?)?
+    } else {
+        HashMap::new()
+    };
+
     for delta_field in schema.fields() {
         let mut when_expr = Vec::with_capacity(operations_size);
         let mut then_expr = Vec::with_capacity(operations_size);
@@ -1256,15 +1445,16 @@ async fn execute(
             .unwrap_or(kernel_arrow_type);
 
         // Receive the correct column reference given that some columns are only in source table
+        let mut evolved = false;
         let column = if let Some(field) = snapshot.schema().field(name) {
-            if field == delta_field {
-                Column::new(qualifier.clone(), name)
-            } else {
-                // when there is a change in the field such as an added column in a nested data types casts will break with the new field data type
-                let col_ref = Column::new(source_qualifier.clone(), name);
-                cast_type = source_schema.data_type(&col_ref)?.to_owned();
-                col_ref
+            if field != delta_field {
+                // Fields added inside a nested type: every branch, including the target values
+                // of rows that are copied unchanged, is cast to the evolved type so the new
+                // nested fields are filled with nulls.
+                cast_type = delta_field.data_type().try_into_arrow()?;
+                evolved = true;
             }
+            Column::new(qualifier.clone(), name)
         } else {
             null_target_column = Some(merge_cast(
                 lit(ScalarValue::Null).alias(name),
@@ -1274,11 +1464,20 @@ async fn execute(
             Column::new(source_qualifier.clone(), name)
         };
 
-        for (idx, (operations, _, _)) in ops.iter().enumerate() {
+        for (idx, (operations, r#type, _)) in ops.iter().enumerate() {
             let op: Expr = operations
                 .get(&column)
                 .map(|expr| expr.to_owned())
+                .or_else(|| match r#type {
+                    OperationType::Insert => column_defaults.get(name).cloned(),
+                    _ => None,
+                })
                 .unwrap_or_else(|| col(column.clone()));
+            let op = if evolved {
+                merge_cast(op, cast_type.clone(), safe_cast)
+            } else {
+                op
+            };
 
             when_expr.push(lit(idx as i32));
             then_expr.push(op);
@@ -1608,7 +1807,7 @@ async fn execute(
 
     let table_root = snapshot.table_configuration().table_root().clone();
 
-    {
+    if !insert_only {
         let mut active_adds = snapshot.snapshot().active_adds(
             log_store.as_ref(),
             ActiveAddOptions {
@@ -1684,6 +1883,7 @@ async fn execute(
         derived
     };
     metrics.execution_time_ms = Instant::now().duration_since(exec_start).as_millis() as u64;
+    metrics.io_stats = super::take_io_stats(&log_store, operation_id);
 
     let app_metadata = &mut commit_properties.app_metadata;
     app_metadata.insert("readVersion".to_owned(), snapshot.version().into());
@@ -1752,6 +1952,34 @@ fn modify_schema(
     Ok(())
 }
 
+/// Left-anti join of the source against the target, used when the merge only inserts source rows
+/// without a match.
+///
+/// The target columns are projected as nulls, so the result has the schema of the full outer
+/// join and the insert clauses resolved against it can be evaluated unchanged.
+fn build_insert_only_join(
+    source: DataFrame,
+    target: DataFrame,
+    predicate: Expr,
+    join_schema: &DFSchema,
+) -> DeltaResult<DataFrame> {
+    let anti_join = source.join(target, JoinType::LeftAnti, &[], &[], Some(predicate))?;
+    let anti_join_schema = anti_join.schema().clone();
+    let projection = join_schema
+        .iter()
+        .map(|(qualifier, field)| {
+            let column = Column::new(qualifier.cloned(), field.name());
+            if anti_join_schema.has_column(&column) {
+                Ok(col(column))
+            } else {
+                Ok(lit(ScalarValue::try_from(field.data_type())?)
+                    .alias_qualified(qualifier.cloned(), field.name()))
+            }
+        })
+        .collect::<DataFusionResult<Vec<_>>>()?;
+    Ok(anti_join.select(projection)?)
+}
+
 fn remove_table_alias(expr: Expr, table_alias: &str) -> Expr {
     expr.transform(&|expr| match expr {
         Expr::Column(c) => match c.relation {
@@ -1845,11 +2073,8 @@ fn should_remove_rewritten_file(
     let full_id = normalize_path_as_file_id(log_path, table_root, "merge remove")?;
     Ok(survivors.contains(full_id.as_str()))
 }
-impl std::future::IntoFuture for MergeBuilder {
-    type Output = DeltaResult<(DeltaTable, MergeMetrics)>;
-    type IntoFuture = BoxFuture<'static, Self::Output>;
-
-    fn into_future(self) -> Self::IntoFuture {
+impl MergeBuilder {
+    fn execute_once(self) -> BoxFuture<'static, DeltaResult<(DeltaTable, MergeMetrics)>> {
         let this = self;
 
         Box::pin(async move {
@@ -1914,6 +2139,31 @@ impl std::future::IntoFuture for MergeBuilder {
     }
 }
 
+impl std::future::IntoFuture for MergeBuilder {
+    type Output = DeltaResult<(DeltaTable, MergeMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let Some(policy) = self.replan_policy.clone() else {
+            return self.execute_once();
+        };
+
+        Box::pin(async move {
+            let ((table, mut metrics), replans) = policy
+                .execute(|replan| {
+                    let mut builder = self.clone();
+                    if replan > 0 {
+                        builder.snapshot = None;
+                    }
+                    builder.execute_once()
+                })
+                .await?;
+            metrics.num_replans = replans;
+            Ok((table, metrics))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::TableProperty;
@@ -2032,6 +2282,124 @@ mod tests {
         assert_batches_sorted_eq!(&expected, &actual);
     }
 
+    async fn setup_sparse_key_table() -> DeltaTable {
+        let schema = get_arrow_schema(&None);
+        let mut table = setup_table(None).await;
+        for ids in [["A", "B"], ["M", "N"], ["Y", "Z"]] {
+            let batch = RecordBatch::try_new(
+                Arc::clone(&schema),
+                vec![
+                    Arc::new(arrow::array::StringArray::from(ids.to_vec())),
+                    Arc::new(arrow::array::Int32Array::from(vec![1, 1])),
+                    Arc::new(arrow::array::StringArray::from(vec!["2021-02-01"; 2])),
+                ],
+            )
+            .unwrap();
+            table = table
+                .write(vec![batch])
+                .with_save_mode(SaveMode::Append)
+                .await
+                .unwrap();
+        }
+        assert_eq!(table.snapshot().unwrap().log_data().num_files(), 3);
+        table
+    }
+
+    #[tokio::test]
+    async fn test_merge_dynamic_file_pruning_with_sparse_keys() {
+        let table = setup_sparse_key_table().await;
+
+        // The range of the source keys covers every file, but only two files contain them.
+        let schema = get_arrow_schema(&None);
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["A", "Z"])),
+                Arc::new(arrow::array::Int32Array::from(vec![2, 2])),
+                Arc::new(arrow::array::StringArray::from(vec!["2021-02-02"; 2])),
+            ],
+        )
+        .unwrap();
+        let source = SessionContext::new().read_batch(batch).unwrap();
+
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_update(|update| update.update("value", col("source.value")))
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(metrics.num_target_files_scanned, 2);
+        assert_eq!(metrics.num_target_files_skipped_during_scan, 1);
+        assert_eq!(metrics.num_target_files_removed, 2);
+        assert_eq!(metrics.num_target_rows_updated, 2);
+        assert_eq!(metrics.num_target_rows_copied, 2);
+
+        let expected = vec![
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 2     | 2021-02-01 |",
+            "| B  | 1     | 2021-02-01 |",
+            "| M  | 1     | 2021-02-01 |",
+            "| N  | 1     | 2021-02-01 |",
+            "| Y  | 1     | 2021-02-01 |",
+            "| Z  | 2     | 2021-02-01 |",
+            "+----+-------+------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
+    #[tokio::test]
+    async fn test_merge_insert_only_dynamic_file_pruning() {
+        let table = setup_sparse_key_table().await;
+
+        let schema = get_arrow_schema(&None);
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["B", "C"])),
+                Arc::new(arrow::array::Int32Array::from(vec![2, 2])),
+                Arc::new(arrow::array::StringArray::from(vec!["2021-02-02"; 2])),
+            ],
+        )
+        .unwrap();
+        let source = SessionContext::new().read_batch(batch).unwrap();
+
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_not_matched_insert(|insert| insert.insert_all())
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(metrics.num_target_files_scanned, 1);
+        assert_eq!(metrics.num_target_files_skipped_during_scan, 2);
+        assert_eq!(metrics.num_target_files_removed, 0);
+        assert_eq!(metrics.num_target_rows_inserted, 1);
+
+        let expected = vec![
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 1     | 2021-02-01 |",
+            "| B  | 1     | 2021-02-01 |",
+            "| C  | 2     | 2021-02-02 |",
+            "| M  | 1     | 2021-02-01 |",
+            "| N  | 1     | 2021-02-01 |",
+            "| Y  | 1     | 2021-02-01 |",
+            "| Z  | 1     | 2021-02-01 |",
+            "+----+-------+------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
     #[tokio::test]
     async fn test_merge_metrics_derive_skipped_files_when_scan_skip_metric_missing() {
         let schema = get_arrow_schema(&None);
@@ -3624,6 +3992,266 @@ mod tests {
         assert_batches_sorted_eq!(&expected, &actual);
     }
 
+    #[tokio::test]
+    async fn test_merge_insert_fills_column_defaults() {
+        use crate::kernel::{COLUMN_DEFAULT_KEY, MetadataValue};
+
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns([
+                StructField::nullable("id", DataType::STRING),
+                StructField::not_null("status", DataType::STRING).with_metadata([(
+                    COLUMN_DEFAULT_KEY.to_string(),
+                    MetadataValue::String("'active'".to_string()),
+                )]),
+            ])
+            .await
+            .unwrap();
+
+        let ctx = SessionContext::new();
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "id",
+                ArrowDataType::Utf8,
+                true,
+            )])),
+            vec![Arc::new(arrow::array::StringArray::from(vec!["A", "B"]))],
+        )
+        .unwrap();
+        let source = ctx.read_batch(batch).unwrap();
+
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_not_matched_insert(|insert| insert.insert_all())
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(metrics.num_target_rows_inserted, 2);
+        let expected = vec![
+            "+----+--------+",
+            "| id | status |",
+            "+----+--------+",
+            "| A  | active |",
+            "| B  | active |",
+            "+----+--------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
+    #[tokio::test]
+    async fn test_merge_update_all_and_insert_all() {
+        let (table, _) = setup().await;
+
+        // Source columns are matched by name, so their order does not matter and columns the
+        // target does not have are ignored without schema evolution.
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("modified", ArrowDataType::Utf8, true),
+            Field::new("extra", ArrowDataType::Utf8, true),
+            Field::new("value", ArrowDataType::Int32, true),
+            Field::new("id", ArrowDataType::Utf8, true),
+        ]));
+        let ctx = SessionContext::new();
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec![
+                    "2021-02-02",
+                    "2023-07-04",
+                    "2023-07-04",
+                ])),
+                Arc::new(arrow::array::StringArray::from(vec!["B1", "C1", "X1"])),
+                Arc::new(arrow::array::Int32Array::from(vec![10, 20, 30])),
+                Arc::new(arrow::array::StringArray::from(vec!["B", "C", "X"])),
+            ],
+        )
+        .unwrap();
+        let source = ctx.read_batch(batch).unwrap();
+
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_update(|update| {
+                update
+                    .update_all()
+                    .update("value", col("source.value").add(lit(1)))
+            })
+            .unwrap()
+            .when_not_matched_insert(|insert| insert.insert_all())
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(metrics.num_target_rows_updated, 2);
+        assert_eq!(metrics.num_target_rows_inserted, 1);
+        assert_latest_commit_has_metadata_action(&table, false).await;
+        let expected = vec![
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 1     | 2021-02-01 |",
+            "| B  | 11    | 2021-02-02 |",
+            "| C  | 21    | 2023-07-04 |",
+            "| D  | 100   | 2021-02-02 |",
+            "| X  | 30    | 2023-07-04 |",
+            "+----+-------+------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+
+        let source = merge_source(get_arrow_schema(&None));
+        let result = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_not_matched_by_source_update(|update| update.update_all());
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_merge_update_all_with_nested_schema_evolution() {
+        let sku = Field::new("sku", ArrowDataType::Utf8, true);
+        let qty = Field::new("qty", ArrowDataType::Int32, true);
+        let count = Field::new("count", ArrowDataType::Int64, true);
+        let name = Field::new("name", ArrowDataType::Utf8, true);
+
+        let items_field = |fields: Vec<Field>| {
+            Arc::new(Field::new(
+                "item",
+                ArrowDataType::Struct(fields.into()),
+                true,
+            ))
+        };
+        let target_item = items_field(vec![sku.clone()]);
+        let source_item = items_field(vec![sku.clone(), qty.clone()]);
+
+        let target_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", ArrowDataType::Utf8, true),
+            Field::new(
+                "nested",
+                ArrowDataType::Struct(vec![count.clone()].into()),
+                true,
+            ),
+            Field::new("items", ArrowDataType::List(target_item.clone()), true),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&target_schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["A", "B"])),
+                Arc::new(arrow::array::StructArray::new(
+                    vec![count.clone()].into(),
+                    vec![Arc::new(arrow::array::Int64Array::from(vec![1, 2]))],
+                    None,
+                )),
+                Arc::new(arrow::array::ListArray::new(
+                    target_item,
+                    arrow::buffer::OffsetBuffer::from_lengths([1, 2]),
+                    Arc::new(arrow::array::StructArray::new(
+                        vec![sku.clone()].into(),
+                        vec![Arc::new(arrow::array::StringArray::from(vec![
+                            "a1", "b1", "b2",
+                        ]))],
+                        None,
+                    )),
+                    None,
+                )),
+            ],
+        )
+        .unwrap();
+        let table = DeltaTable::new_in_memory()
+            .write(vec![batch])
+            .await
+            .unwrap();
+        assert_eq!(table.version(), Some(0));
+
+        let source_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", ArrowDataType::Utf8, true),
+            Field::new(
+                "nested",
+                ArrowDataType::Struct(vec![count.clone(), name.clone()].into()),
+                true,
+            ),
+            Field::new("items", ArrowDataType::List(source_item.clone()), true),
+        ]));
+        let batch = RecordBatch::try_new(
+            source_schema,
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["B", "X"])),
+                Arc::new(arrow::array::StructArray::new(
+                    vec![count, name].into(),
+                    vec![
+                        Arc::new(arrow::array::Int64Array::from(vec![20, 30])),
+                        Arc::new(arrow::array::StringArray::from(vec!["b", "x"])),
+                    ],
+                    None,
+                )),
+                Arc::new(arrow::array::ListArray::new(
+                    source_item,
+                    arrow::buffer::OffsetBuffer::from_lengths([1, 1]),
+                    Arc::new(arrow::array::StructArray::new(
+                        vec![sku, qty].into(),
+                        vec![
+                            Arc::new(arrow::array::StringArray::from(vec!["b3", "x1"])),
+                            Arc::new(arrow::array::Int32Array::from(vec![3, 1])),
+                        ],
+                        None,
+                    )),
+                    None,
+                )),
+            ],
+        )
+        .unwrap();
+        let source = SessionContext::new().read_batch(batch).unwrap();
+
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .with_merge_schema(true)
+            .when_matched_update(|update| update.update_all())
+            .unwrap()
+            .when_not_matched_insert(|insert| insert.insert_all())
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(metrics.num_target_rows_updated, 1);
+        assert_eq!(metrics.num_target_rows_inserted, 1);
+        assert_eq!(metrics.num_target_rows_copied, 1);
+        assert_latest_commit_has_metadata_action(&table, true).await;
+
+        let schema = table.snapshot().unwrap().schema();
+        let Some(DataType::Struct(nested)) = schema.field("nested").map(|f| f.data_type()) else {
+            panic!("nested must be a struct");
+        };
+        assert!(nested.field("name").is_some());
+        let Some(DataType::Array(items)) = schema.field("items").map(|f| f.data_type()) else {
+            panic!("items must be an array");
+        };
+        let DataType::Struct(item) = items.element_type() else {
+            panic!("items must contain structs");
+        };
+        assert!(item.field("qty").is_some());
+
+        // The unmatched target row is copied into the rewritten file with nulls for the new
+        // nested fields.
+        let expected = vec![
+            "+----+----------------------+---------------------+",
+            "| id | nested               | items               |",
+            "+----+----------------------+---------------------+",
+            "| A  | {count: 1, name: }   | [{sku: a1, qty: }]  |",
+            "| B  | {count: 20, name: b} | [{sku: b3, qty: 3}] |",
+            "| X  | {count: 30, name: x} | [{sku: x1, qty: 1}] |",
+            "+----+----------------------+---------------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
     #[tokio::test]
     async fn test_merge_schema_evolution_simple_update() {
         let (table, _) = setup().await;
@@ -3902,6 +4530,78 @@ mod tests {
         assert_batches_sorted_eq!(&expected, &actual);
     }
 
+    #[tokio::test]
+    async fn test_merge_insert_only_does_not_rewrite_target_files() {
+        let (table, source) = setup().await;
+
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_not_matched_insert(|insert| {
+                insert
+                    .set("id", col("source.id"))
+                    .set("value", col("source.value"))
+                    .set("modified", col("source.modified"))
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(table.version(), Some(2));
+        assert_eq!(table.snapshot().unwrap().log_data().num_files(), 2);
+        assert_eq!(metrics.num_target_files_added, 1);
+        assert_eq!(metrics.num_target_files_removed, 0);
+        assert_eq!(metrics.num_target_rows_copied, 0);
+        assert_eq!(metrics.num_target_rows_inserted, 1);
+        assert_eq!(metrics.num_output_rows, 1);
+        assert_eq!(metrics.num_source_rows, 3);
+
+        let expected = vec![
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 1     | 2021-02-01 |",
+            "| B  | 10    | 2021-02-01 |",
+            "| C  | 10    | 2021-02-02 |",
+            "| D  | 100   | 2021-02-02 |",
+            "| X  | 30    | 2023-07-04 |",
+            "+----+-------+------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
+    #[tokio::test]
+    async fn test_merge_insert_only_does_not_conflict_with_optimize() {
+        let schema = get_arrow_schema(&None);
+        let (table, source) = setup().await;
+        let table = write_data(table, &schema).await;
+        assert_eq!(table.snapshot().unwrap().log_data().num_files(), 2);
+
+        let (_, optimize_metrics) = table.clone().optimize().await.unwrap();
+        assert_eq!(optimize_metrics.num_files_removed, 2);
+
+        // the merge reads the version before the compaction
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_not_matched_insert(|insert| {
+                insert
+                    .set("id", col("source.id"))
+                    .set("value", col("source.value"))
+                    .set("modified", col("source.modified"))
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(table.version(), Some(4));
+        assert_eq!(metrics.num_target_rows_inserted, 1);
+        assert_eq!(metrics.num_target_files_removed, 0);
+    }
+
     #[tokio::test]
     async fn test_merge_str() {
         // Validate that users can use string predicates
@@ -21,42 +21,50 @@ use url::Url;
 use uuid::Uuid;
 
 use self::{
-    add_column::AddColumnBuilder, add_feature::AddTableFeatureBuilder, create::CreateBuilder,
+    add_column::AddColumnBuilder, add_feature::AddTableFeatureBuilder,
+    alter_column::AlterColumnBuilder, create::CreateBuilder, describe::DescribeDetailBuilder,
     filesystem_check::FileSystemCheckBuilder, restore::RestoreBuilder,
     set_tbl_properties::SetTablePropertiesBuilder,
     update_field_metadata::UpdateFieldMetadataBuilder,
     update_table_metadata::UpdateTableMetadataBuilder, vacuum::VacuumBuilder,
+    validate::ValidateBuilder,
 };
 #[cfg(feature = "datafusion")]
 use self::{
-    constraints::ConstraintBuilder, delete::DeleteBuilder, drop_constraints::DropConstraintBuilder,
-    load::LoadBuilder, load_cdf::CdfLoadBuilder, merge::MergeBuilder, optimize::OptimizeBuilder,
-    update::UpdateBuilder, write::WriteBuilder,
+    change_feed::ChangeFeedStreamBuilder, constraints::ConstraintBuilder, delete::DeleteBuilder,
+    drop_constraints::DropConstraintBuilder, load::LoadBuilder, load_cdf::CdfLoadBuilder,
+    merge::MergeBuilder, optimize::OptimizeBuilder, update::UpdateBuilder, write::WriteBuilder,
 };
 use crate::DeltaTable;
 #[cfg(feature = "datafusion")]
 use crate::delta_datafusion::Expression;
 use crate::errors::{DeltaResult, DeltaTableError};
-use crate::logstore::LogStoreRef;
+use crate::logstore::{IoStats, LogStoreRef};
 use crate::operations::generate::GenerateBuilder;
 use crate::table::builder::DeltaTableBuilder;
 use crate::table::config::{DEFAULT_NUM_INDEX_COLS, TablePropertiesExt as _};
 
 pub mod add_column;
 pub mod add_feature;
+pub mod alter_column;
 pub mod convert_to_delta;
 pub mod create;
+pub mod describe;
 pub mod drop_constraints;
 pub mod filesystem_check;
 pub mod generate;
+pub mod replan;
 pub mod restore;
 pub mod update_field_metadata;
 pub mod update_table_metadata;
 pub mod vacuum;
+pub mod validate;
 
 #[cfg(feature = "datafusion")]
 mod cdc;
 #[cfg(feature = "datafusion")]
+pub mod change_feed;
+#[cfg(feature = "datafusion")]
 pub mod constraints;
 #[cfg(feature = "datafusion")]
 pub mod delete;
@@ -163,6 +171,12 @@ impl DeltaTable {
         AddColumnBuilder::new(self.log_store(), self.state.clone().map(|s| s.snapshot))
     }
 
+    /// Change the nullability or comment of a column
+    #[must_use]
+    pub fn alter_column(self) -> AlterColumnBuilder {
+        AlterColumnBuilder::new(self.log_store(), self.state.clone().map(|s| s.snapshot))
+    }
+
     /// Update field metadata
     #[must_use]
     pub fn update_field_metadata(self) -> UpdateFieldMetadataBuilder {
@@ -179,6 +193,26 @@ impl DeltaTable {
     pub fn generate(self) -> GenerateBuilder {
         GenerateBuilder::new(self.log_store(), self.state.map(|s| s.snapshot))
     }
+
+    /// Check the active files for inconsistencies between the log and the data without
+    /// modifying the table, returning a [`ValidateBuilder`].
+    #[must_use]
+    pub fn validate(&self) -> ValidateBuilder {
+        ValidateBuilder::new(
+            self.log_store(),
+            self.state.clone().map(|state| state.snapshot),
+        )
+    }
+
+    /// Collect table-level details like size, file count and protocol, returning a
+    /// [`DescribeDetailBuilder`].
+    #[must_use]
+    pub fn describe_detail(&self) -> DescribeDetailBuilder {
+        DescribeDetailBuilder::new(
+            self.log_store(),
+            self.state.clone().map(|state| state.snapshot),
+        )
+    }
 }
 
 #[cfg(feature = "datafusion")]
@@ -198,6 +232,16 @@ impl DeltaTable {
         CdfLoadBuilder::new(self.log_store(), self.state.map(|s| s.snapshot))
     }
 
+    /// Continuously read the change data feed as new versions are committed, returning a
+    /// [`ChangeFeedStreamBuilder`].
+    #[must_use]
+    pub fn stream_cdf(&self) -> ChangeFeedStreamBuilder {
+        ChangeFeedStreamBuilder::new(
+            self.log_store(),
+            self.state.clone().map(|state| state.snapshot),
+        )
+    }
+
     /// Write the given record batches to the table, returning a [`WriteBuilder`].
     #[must_use]
     pub fn write(self, batches: impl IntoIterator<Item = RecordBatch>) -> WriteBuilder {
@@ -527,6 +571,12 @@ impl DeltaOps {
     pub fn update_table_metadata(self) -> UpdateTableMetadataBuilder {
         UpdateTableMetadataBuilder::new(self.0.log_store, self.0.state.map(|s| s.snapshot))
     }
+
+    /// Collect table-level details like size, file count and protocol
+    #[deprecated(note = "Use [`DeltaTable::describe_detail`] instead")]
+    pub fn describe_detail(self) -> DescribeDetailBuilder {
+        DescribeDetailBuilder::new(self.0.log_store, self.0.state.map(|s| s.snapshot))
+    }
 }
 
 #[allow(deprecated)]
@@ -591,6 +641,11 @@ pub fn get_num_idx_cols_and_stats_columns(
     )
 }
 
+/// Remove and return the object store requests issued on behalf of `operation_id`
+pub(crate) fn take_io_stats(log_store: &LogStoreRef, operation_id: Uuid) -> Option<IoStats> {
+    log_store.io_metrics().take_operation(&operation_id)
+}
+
 /// Get the target_file_size from the table configuration in the sates
 /// If table_config does not exist (only can occur in the first write action) it takes
 /// the configuration that was passed to the writerBuilder.
@@ -47,6 +47,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as DeErr
 use tracing::*;
 use uuid::Uuid;
 
+use super::replan::ReplanPolicy;
 use super::write::writer::{PartitionWriter, PartitionWriterConfig};
 use super::{CustomExecuteHandler, Operation};
 use crate::delta_datafusion::{
@@ -57,8 +58,8 @@ use crate::errors::{ColumnMappingOperation, DeltaResult, DeltaTableError};
 use crate::kernel::transaction::{CommitBuilder, CommitProperties, DEFAULT_RETRIES, PROTOCOL};
 use crate::kernel::{Action, Add, DataType, PartitionsExt, Remove, StructType, Version};
 use crate::kernel::{EagerSnapshot, resolve_snapshot};
-use crate::logstore::{LogStore, LogStoreRef, ObjectStoreRef};
-use crate::parquet_utils::default_writer_properties;
+use crate::logstore::{IoStats, LogStore, LogStoreRef, ObjectStoreRef};
+use crate::parquet_utils::{default_writer_properties, with_table_bloom_filters};
 use crate::protocol::DeltaOperation;
 use crate::table::config::TablePropertiesExt as _;
 use crate::table::state::DeltaTableState;
@@ -114,6 +115,12 @@ pub struct Metrics {
     pub preserved_stable_order: bool,
     /// Largest count of adjacent input files in one bin
     pub max_bin_span_files: usize,
+    /// Number of times the operation was re-planned after a commit conflict
+    pub num_replans: usize,
+    /// Object store requests issued by the operation, including its commits. Only reported
+    /// in the returned metrics, not in the commit info.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io_stats: Option<IoStats>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -137,6 +144,10 @@ struct MetricsSerde {
     preserved_stable_order: Option<bool>,
     #[serde(default)]
     max_bin_span_files: usize,
+    #[serde(default)]
+    num_replans: usize,
+    #[serde(default)]
+    io_stats: Option<IoStats>,
 }
 
 impl From<MetricsSerde> for Metrics {
@@ -160,6 +171,8 @@ impl From<MetricsSerde> for Metrics {
             planner_strategy: value.planner_strategy,
             preserved_stable_order,
             max_bin_span_files: value.max_bin_span_files,
+            num_replans: value.num_replans,
+            io_stats: value.io_stats,
         }
     }
 }
@@ -262,7 +275,7 @@ impl Default for MetricDetails {
 }
 
 /// Type of optimization to perform.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum OptimizeType {
     /// Compact files into pre-determined bins
     Compact,
@@ -274,6 +287,7 @@ pub enum OptimizeType {
 ///
 /// If a target file size is not provided then `delta.targetFileSize` from the
 /// table's configuration is read. Otherwise a default value is used.
+#[derive(Clone)]
 pub struct OptimizeBuilder<'a> {
     /// A snapshot of the to-be-optimized table's state
     snapshot: Option<EagerSnapshot>,
@@ -296,6 +310,8 @@ pub struct OptimizeBuilder<'a> {
     session_fallback_policy: SessionFallbackPolicy,
     min_commit_interval: Option<Duration>,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    /// Re-plan the operation against the latest snapshot after commit conflicts
+    replan_policy: Option<ReplanPolicy>,
 }
 
 impl super::Operation for OptimizeBuilder<'_> {
@@ -323,6 +339,7 @@ impl<'a> OptimizeBuilder<'a> {
             session: None,
             session_fallback_policy: SessionFallbackPolicy::default(),
             custom_execute_handler: None,
+            replan_policy: None,
         }
     }
 
@@ -356,6 +373,13 @@ impl<'a> OptimizeBuilder<'a> {
         self
     }
 
+    /// Reload the snapshot, re-plan and retry the operation when its commit conflicts with a
+    /// concurrent transaction. See [`ReplanPolicy`] for details.
+    pub fn with_replan_policy(mut self, replan_policy: ReplanPolicy) -> Self {
+        self.replan_policy = Some(replan_policy);
+        self
+    }
+
     /// Deprecated. This setting has no effect.
     #[deprecated(
         since = "0.32.0",
@@ -406,11 +430,8 @@ impl<'a> OptimizeBuilder<'a> {
     }
 }
 
-impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
-    type Output = DeltaResult<(DeltaTable, Metrics)>;
-    type IntoFuture = BoxFuture<'a, Self::Output>;
-
-    fn into_future(self) -> Self::IntoFuture {
+impl<'a> OptimizeBuilder<'a> {
+    fn execute_once(self) -> BoxFuture<'a, DeltaResult<(DeltaTable, Metrics)>> {
         let this = self;
 
         Box::pin(async move {
@@ -430,6 +451,8 @@ impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
             let writer_properties = this.writer_properties.unwrap_or_else(|| {
                 default_writer_properties(Compression::ZSTD(ZstdLevel::try_new(4).unwrap()))
             });
+            let writer_properties =
+                with_table_bloom_filters(writer_properties, snapshot.table_configuration())?;
             let (session, _) = resolve_session_state(
                 this.session.as_deref(),
                 this.session_fallback_policy,
@@ -474,6 +497,31 @@ impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
     }
 }
 
+impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
+    type Output = DeltaResult<(DeltaTable, Metrics)>;
+    type IntoFuture = BoxFuture<'a, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let Some(policy) = self.replan_policy.clone() else {
+            return self.execute_once();
+        };
+
+        Box::pin(async move {
+            let ((table, mut metrics), replans) = policy
+                .execute(|replan| {
+                    let mut builder = self.clone();
+                    if replan > 0 {
+                        builder.snapshot = None;
+                    }
+                    builder.execute_once()
+                })
+                .await?;
+            metrics.num_replans = replans;
+            Ok((table, metrics))
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 struct OptimizeInput {
     target_size: NonZeroU64,
@@ -991,6 +1039,7 @@ impl MergePlan {
             }
         }
 
+        total_metrics.io_stats = super::take_io_stats(&log_store, operation_id);
         if total_metrics.num_files_added == 0 {
             total_metrics.files_added.min = 0;
         }
@@ -0,0 +1,265 @@
+//! Re-plan table operations after commit conflicts
+//!
+//! The commit loop in [`CommitBuilder`](crate::kernel::transaction::CommitBuilder) retries a
+//! commit only when the conflict checker decides the winning commits do not affect it. When a
+//! real conflict is detected (e.g. [`ConcurrentDeleteRead`](CommitConflictError::ConcurrentDeleteRead))
+//! the actions computed by the operation are stale and have to be recomputed against the new
+//! table state.
+//!
+//! A [`ReplanPolicy`] attached to a DML builder (delete, update, merge or optimize) makes the
+//! builder do this automatically: it reloads the latest snapshot, re-plans the operation and
+//! tries to commit again, sleeping with exponential backoff and jitter between attempts.
+//!
+//! # Example
+//! ```rust ignore
+//! let policy = ReplanPolicy::default().with_max_replans(5);
+//! let (table, metrics) = DeltaOps(table)
+//!     .delete()
+//!     .with_predicate(col("id").eq(lit(1)))
+//!     .with_replan_policy(policy)
+//!     .await?;
+//! println!("re-planned {} times", metrics.num_replans);
+//! ```
+//!
+//! Re-planning executes the whole operation again, so any input to it must be re-readable.
+//! For merge this means the source `DataFrame` must produce the same data each time it is
+//! executed.
+
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use rand::RngExt;
+use tracing::*;
+
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::transaction::{CommitConflictError, TransactionError};
+
+const DEFAULT_MAX_REPLANS: usize = 3;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Policy controlling how often and how fast an operation is re-planned after a commit conflict
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplanPolicy {
+    max_replans: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+}
+
+impl Default for ReplanPolicy {
+    fn default() -> Self {
+        Self {
+            max_replans: DEFAULT_MAX_REPLANS,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            jitter: true,
+        }
+    }
+}
+
+impl ReplanPolicy {
+    /// Create a new [`ReplanPolicy`] with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of times the operation is re-planned before the conflict is returned
+    pub fn with_max_replans(mut self, max_replans: usize) -> Self {
+        self.max_replans = max_replans;
+        self
+    }
+
+    /// Backoff before the first re-plan. Doubled for every subsequent re-plan.
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Upper bound for the backoff between two re-plans
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Randomize the backoff so concurrent writers do not re-plan in lockstep
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Maximum number of re-plans
+    pub fn max_replans(&self) -> usize {
+        self.max_replans
+    }
+
+    /// Backoff before the given re-plan, starting at `1` for the first re-plan.
+    ///
+    /// With jitter enabled the result is drawn uniformly from the upper half of the
+    /// exponential backoff, so it never drops below half of the configured delay.
+    pub fn backoff(&self, replan: usize) -> Duration {
+        let exponent = replan.saturating_sub(1).min(u32::MAX as usize) as u32;
+        let backoff = self
+            .initial_backoff
+            .checked_mul(2u32.saturating_pow(exponent))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if !self.jitter || backoff.is_zero() {
+            return backoff;
+        }
+        let half = backoff / 2;
+        half + half.mul_f64(rand::rng().random_range(0.0..=1.0))
+    }
+
+    /// Run `attempt` until it succeeds, fails with an error that cannot be resolved by
+    /// re-planning, or the maximum number of re-plans is exhausted.
+    ///
+    /// `attempt` receives the number of re-plans done so far and must load a fresh snapshot
+    /// whenever that number is non-zero. Returns the result with the number of re-plans.
+    pub(crate) async fn execute<'a, T>(
+        &self,
+        mut attempt: impl FnMut(usize) -> BoxFuture<'a, DeltaResult<T>>,
+    ) -> DeltaResult<(T, usize)> {
+        let mut replans = 0;
+        loop {
+            match attempt(replans).await {
+                Ok(result) => return Ok((result, replans)),
+                Err(err) if replans < self.max_replans && is_replannable(&err) => {
+                    replans += 1;
+                    let backoff = self.backoff(replans);
+                    debug!(
+                        replans,
+                        backoff_ms = backoff.as_millis() as u64,
+                        "commit conflict, re-planning operation: {err}"
+                    );
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Whether re-running an operation against a newer snapshot may resolve the error
+pub(crate) fn is_replannable(err: &DeltaTableError) -> bool {
+    match err {
+        DeltaTableError::VersionAlreadyExists(_) => true,
+        DeltaTableError::Transaction { source } => match source {
+            TransactionError::MaxCommitAttempts(_) => true,
+            TransactionError::CommitConflict(conflict) => !matches!(
+                conflict,
+                CommitConflictError::ConcurrentTransaction
+                    | CommitConflictError::UnsupportedReaderVersion(_)
+                    | CommitConflictError::UnsupportedWriterVersion(_)
+                    | CommitConflictError::CorruptedState { .. }
+                    | CommitConflictError::Predicate { .. }
+                    | CommitConflictError::NoMetadata
+            ),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::FutureExt;
+
+    use super::*;
+
+    fn conflict() -> DeltaTableError {
+        TransactionError::CommitConflict(CommitConflictError::ConcurrentDeleteRead).into()
+    }
+
+    #[test]
+    fn test_backoff_is_bounded() {
+        let policy = ReplanPolicy::default()
+            .with_initial_backoff(Duration::from_millis(10))
+            .with_max_backoff(Duration::from_millis(50))
+            .with_jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(10));
+        assert_eq!(policy.backoff(2), Duration::from_millis(20));
+        assert_eq!(policy.backoff(3), Duration::from_millis(40));
+        assert_eq!(policy.backoff(4), Duration::from_millis(50));
+        assert_eq!(policy.backoff(100), Duration::from_millis(50));
+
+        let policy = policy.with_jitter(true);
+        for replan in 1..10 {
+            let backoff = policy.backoff(replan);
+            let expected = policy.clone().with_jitter(false).backoff(replan);
+            assert!(backoff >= expected / 2 && backoff <= expected);
+        }
+    }
+
+    #[test]
+    fn test_replannable_errors() {
+        assert!(is_replannable(&conflict()));
+        assert!(is_replannable(
+            &TransactionError::MaxCommitAttempts(3).into()
+        ));
+        assert!(!is_replannable(&DeltaTableError::Generic("boom".into())));
+        assert!(!is_replannable(
+            &TransactionError::CommitConflict(CommitConflictError::CorruptedState {
+                source: Box::new(std::io::Error::other("corrupt")),
+            })
+            .into()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_replans_until_success() {
+        let policy = ReplanPolicy::default()
+            .with_initial_backoff(Duration::ZERO)
+            .with_max_replans(3);
+        let attempts = AtomicUsize::new(0);
+        let (result, replans) = policy
+            .execute(|replan| {
+                assert_eq!(replan, attempts.fetch_add(1, Ordering::SeqCst));
+                async move {
+                    if replan < 2 {
+                        Err(conflict())
+                    } else {
+                        Ok(replan)
+                    }
+                }
+                .boxed()
+            })
+            .await
+            .unwrap();
+        assert_eq!(result, 2);
+        assert_eq!(replans, 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_gives_up() {
+        let policy = ReplanPolicy::default()
+            .with_initial_backoff(Duration::ZERO)
+            .with_max_replans(2);
+        let attempts = AtomicUsize::new(0);
+        let result = policy
+            .execute(|_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>(conflict()) }.boxed()
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeltaTableError::Transaction {
+                source: TransactionError::CommitConflict(_)
+            })
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        attempts.store(0, Ordering::SeqCst);
+        let result = policy
+            .execute(|_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>(DeltaTableError::Generic("boom".into())) }.boxed()
+            })
+            .await;
+        assert!(matches!(result, Err(DeltaTableError::Generic(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -51,8 +51,9 @@ use crate::delta_datafusion::{
     DeltaScanConfig, Expression, scan_files_where_matches, update_datafusion_session,
 };
 use crate::kernel::resolve_snapshot;
-use crate::logstore::LogStoreRef;
+use crate::logstore::{IoStats, LogStoreRef};
 use crate::operations::cdc::*;
+use crate::operations::replan::ReplanPolicy;
 use crate::protocol::DeltaOperation;
 use crate::table::state::DeltaTableState;
 use crate::{DeltaResult, DeltaTable, DeltaTableError};
@@ -83,6 +84,7 @@ const COPIED_ROW_COUNT: &str = "num_copied_rows";
 
 /// Updates records in the Delta Table.
 /// See this module's documentation for more information
+#[derive(Clone)]
 pub struct UpdateBuilder {
     /// Which records to update
     predicate: Option<Expression>,
@@ -103,6 +105,8 @@ pub struct UpdateBuilder {
     /// By default an error is returned
     safe_cast: bool,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    /// Re-plan the operation against the latest snapshot after commit conflicts
+    replan_policy: Option<ReplanPolicy>,
 }
 
 #[derive(Default, Serialize, Debug)]
@@ -120,6 +124,11 @@ pub struct UpdateMetrics {
     pub execution_time_ms: u64,
     /// Time taken to scan the files for matches.
     pub scan_time_ms: u64,
+    /// Number of times the operation was re-planned after a commit conflict.
+    pub num_replans: usize,
+    /// Object store requests issued by the operation before its commit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io_stats: Option<IoStats>,
 }
 
 impl super::Operation for UpdateBuilder {
@@ -145,6 +154,7 @@ impl UpdateBuilder {
             commit_properties: CommitProperties::default(),
             safe_cast: false,
             custom_execute_handler: None,
+            replan_policy: None,
         }
     }
 
@@ -192,6 +202,13 @@ impl UpdateBuilder {
         self
     }
 
+    /// Reload the snapshot, re-plan and retry the operation when its commit conflicts with a
+    /// concurrent transaction. See [`ReplanPolicy`] for details.
+    pub fn with_replan_policy(mut self, replan_policy: ReplanPolicy) -> Self {
+        self.replan_policy = Some(replan_policy);
+        self
+    }
+
     /// Writer properties passed to parquet writer for when files are rewritten
     pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
         self.writer_properties = Some(writer_properties);
@@ -454,11 +471,8 @@ async fn execute(
     Ok((actions, metrics))
 }
 
-impl std::future::IntoFuture for UpdateBuilder {
-    type Output = DeltaResult<(DeltaTable, UpdateMetrics)>;
-    type IntoFuture = BoxFuture<'static, Self::Output>;
-
-    fn into_future(self) -> Self::IntoFuture {
+impl UpdateBuilder {
+    fn execute_once(self) -> BoxFuture<'static, DeltaResult<(DeltaTable, UpdateMetrics)>> {
         let mut this = self;
 
         Box::pin(async move {
@@ -506,7 +520,7 @@ impl std::future::IntoFuture for UpdateBuilder {
                 predicate: Some(fmt_expr_to_sql(&predicate)?),
             };
 
-            let (actions, metrics) = execute(
+            let (actions, mut metrics) = execute(
                 predicate,
                 this.updates,
                 this.log_store.clone(),
@@ -517,6 +531,7 @@ impl std::future::IntoFuture for UpdateBuilder {
                 this.safe_cast,
             )
             .await?;
+            metrics.io_stats = super::take_io_stats(&this.log_store, operation_id);
 
             // if no files were re-written, we can skip the commit.
             if actions.is_empty() {
@@ -552,3 +567,28 @@ impl std::future::IntoFuture for UpdateBuilder {
         })
     }
 }
+
+impl std::future::IntoFuture for UpdateBuilder {
+    type Output = DeltaResult<(DeltaTable, UpdateMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let Some(policy) = self.replan_policy.clone() else {
+            return self.execute_once();
+        };
+
+        Box::pin(async move {
+            let ((table, mut metrics), replans) = policy
+                .execute(|replan| {
+                    let mut builder = self.clone();
+                    if replan > 0 {
+                        builder.snapshot = None;
+                    }
+                    builder.execute_once()
+                })
+                .await?;
+            metrics.num_replans = replans;
+            Ok((table, metrics))
+        })
+    }
+}
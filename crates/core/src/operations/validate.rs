@@ -0,0 +1,1273 @@
+//! Validate the active files of a Delta table against the data in storage.
+//!
+//! Unlike the [filesystem check](super::filesystem_check), which only removes log entries of
+//! missing files, validation is read-only and inspects every active file for inconsistencies
+//! between the log and the data. It reads the Parquet footer of each file, but not the data
+//! itself, so statistics are compared against the footer statistics of the file.
+//!
+//! The following checks are available, see [`ValidationCheck`]:
+//! - the size recorded in the `add` action matches the object size
+//! - the Parquet footer can be read and the row count matches `numRecords`
+//! - `minValues`, `maxValues` and `nullCount` are consistent with the footer statistics
+//! - partition values match the file path and, if the file contains the partition columns, the data
+//! - deletion vectors exist, pass their checksum and match their cardinality
+//! - the schema of the file is compatible with the table schema
+//! - the version checksum (`.crc`) file, if present, matches the replayed log
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap()).await?;
+//! let report = table.validate().await?;
+//! println!("{}", serde_json::to_string_pretty(&report)?);
+//! ```
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use arrow_schema::{DataType, Schema};
+use chrono::{DateTime, NaiveDateTime};
+use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
+use delta_kernel::table_properties::DataSkippingNumIndexedCols;
+use futures::future::BoxFuture;
+use futures::{StreamExt as _, TryStreamExt as _};
+use indexmap::IndexMap;
+use object_store::ObjectStoreExt as _;
+use object_store::path::Path;
+use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+use parquet::file::metadata::ParquetMetaData;
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::debug;
+
+use super::describe::FileSizeHistogram;
+use super::{CustomExecuteHandler, Operation};
+use crate::kernel::transaction::deletion_vector::read_deletion_vector;
+use crate::kernel::{
+    DeletionVectorDescriptor, EagerSnapshot, StructType, Version, resolve_snapshot,
+};
+use crate::logstore::LogStoreRef;
+use crate::protocol::checksum::{checksum_path, read_version_checksum};
+use crate::writer::stats::stats_from_parquet_metadata;
+use crate::{DeltaResult, DeltaTableError};
+
+/// Partition value used in paths for `null` partition values
+const NULL_PARTITION_VALUE: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// A check performed by the [`ValidateBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationCheck {
+    /// The size recorded in the log matches the object size
+    FileSize,
+    /// The Parquet footer can be read and its row count matches `numRecords`
+    Footer,
+    /// The file statistics in the log are consistent with the Parquet footer statistics
+    Stats,
+    /// The partition values match the file path and the data
+    Partitions,
+    /// Deletion vectors exist, pass their checksum and match their cardinality
+    DeletionVectors,
+    /// The schema of the data file is compatible with the table schema
+    Schema,
+    /// The version checksum file, if present, matches the replayed log
+    Checksum,
+}
+
+impl ValidationCheck {
+    /// All available checks
+    pub const ALL: [ValidationCheck; 7] = [
+        ValidationCheck::FileSize,
+        ValidationCheck::Footer,
+        ValidationCheck::Stats,
+        ValidationCheck::Partitions,
+        ValidationCheck::DeletionVectors,
+        ValidationCheck::Schema,
+        ValidationCheck::Checksum,
+    ];
+}
+
+/// The kind of inconsistency found by a validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationIssueKind {
+    /// The data file does not exist
+    MissingFile,
+    /// The object size differs from the size in the log
+    SizeMismatch,
+    /// The Parquet footer could not be read
+    InvalidFooter,
+    /// The row count of the file differs from `numRecords`
+    RecordCountMismatch,
+    /// The statistics in the log are not consistent with the data
+    StatsMismatch,
+    /// The partition values differ from the file path or the data
+    PartitionMismatch,
+    /// The deletion vector file does not exist
+    MissingDeletionVector,
+    /// The deletion vector could not be read or its checksum does not match
+    InvalidDeletionVector,
+    /// The number of deleted rows differs from the cardinality in the log
+    DeletionVectorCardinalityMismatch,
+    /// The schema of the file is not compatible with the table schema
+    SchemaMismatch,
+    /// The version checksum differs from the replayed log
+    ChecksumMismatch,
+}
+
+/// An inconsistency found in an active file or the version checksum of the table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    /// Path of the data file as recorded in the log, or of the checksum file
+    pub path: String,
+    /// Kind of the inconsistency
+    pub kind: ValidationIssueKind,
+    /// Details on the inconsistency
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn new(path: &str, kind: ValidationIssueKind, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+/// Result of validating a Delta table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    /// Version of the table validated
+    pub version: Version,
+    /// Checks that were performed
+    pub checks: Vec<ValidationCheck>,
+    /// Number of active files validated
+    pub num_files_checked: usize,
+    /// Number of deletion vectors validated
+    pub num_deletion_vectors_checked: usize,
+    /// Inconsistencies found, ordered by path
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Whether no inconsistencies were found
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Validate the active files of a table without modifying it.
+/// See this module's documentation for more information
+pub struct ValidateBuilder {
+    /// A snapshot of the to-be-validated table's state
+    snapshot: Option<EagerSnapshot>,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Checks to perform
+    checks: Vec<ValidationCheck>,
+    /// Max number of files validated concurrently
+    max_concurrent_tasks: usize,
+    custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+}
+
+impl Operation for ValidateBuilder {
+    fn log_store(&self) -> &LogStoreRef {
+        &self.log_store
+    }
+    fn get_custom_execute_handler(&self) -> Option<Arc<dyn CustomExecuteHandler>> {
+        self.custom_execute_handler.clone()
+    }
+}
+
+impl ValidateBuilder {
+    /// Create a new [`ValidateBuilder`]
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
+        Self {
+            snapshot,
+            log_store,
+            checks: ValidationCheck::ALL.to_vec(),
+            max_concurrent_tasks: num_cpus::get(),
+            custom_execute_handler: None,
+        }
+    }
+
+    /// Only perform the given checks, defaults to all checks
+    pub fn with_checks(mut self, checks: impl IntoIterator<Item = ValidationCheck>) -> Self {
+        self.checks = checks.into_iter().collect();
+        self
+    }
+
+    /// Max number of files validated concurrently, defaults to the number of CPUs
+    pub fn with_max_concurrent_tasks(mut self, max_concurrent_tasks: usize) -> Self {
+        self.max_concurrent_tasks = max_concurrent_tasks.max(1);
+        self
+    }
+}
+
+/// An active file of the table, detached from the log batch it was read from
+struct FileToValidate {
+    path: String,
+    location: Path,
+    size: i64,
+    num_records: Option<i64>,
+    stats: Option<String>,
+    partition_values: HashMap<String, Option<String>>,
+    deletion_vector: Option<DeletionVectorDescriptor>,
+}
+
+struct ValidationContext {
+    log_store: LogStoreRef,
+    checks: HashSet<ValidationCheck>,
+    /// Physical schema of the data files, without partition columns
+    data_schema: Schema,
+    /// Physical names of the partition columns
+    partition_columns: Vec<String>,
+}
+
+impl ValidationContext {
+    fn enabled(&self, check: ValidationCheck) -> bool {
+        self.checks.contains(&check)
+    }
+
+    fn requires_footer(&self) -> bool {
+        [
+            ValidationCheck::Footer,
+            ValidationCheck::Stats,
+            ValidationCheck::Partitions,
+            ValidationCheck::Schema,
+        ]
+        .iter()
+        .any(|check| self.enabled(*check))
+    }
+}
+
+impl std::future::IntoFuture for ValidateBuilder {
+    type Output = DeltaResult<ValidationReport>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+        Box::pin(async move {
+            let snapshot =
+                resolve_snapshot(this.log_store.as_ref(), this.snapshot.clone(), true, None)
+                    .await?;
+            let mode = snapshot.table_configuration().column_mapping_mode();
+            let schema = snapshot.schema();
+            let partition_columns = snapshot
+                .metadata()
+                .partition_columns()
+                .iter()
+                .map(|name| {
+                    schema
+                        .field(name)
+                        .map(|field| field.physical_name(mode).to_string())
+                        .ok_or_else(|| {
+                            DeltaTableError::Generic(format!(
+                                "partition column '{name}' not found in the table schema"
+                            ))
+                        })
+                })
+                .collect::<DeltaResult<Vec<_>>>()?;
+            let data_fields = schema
+                .fields()
+                .filter(|field| {
+                    !snapshot
+                        .metadata()
+                        .partition_columns()
+                        .contains(field.name())
+                })
+                .map(|field| field.make_physical(mode))
+                .collect::<Result<Vec<_>, _>>()?;
+            let data_schema: Schema = StructType::try_new(data_fields)?.try_into_arrow()?;
+
+            let ctx = Arc::new(ValidationContext {
+                log_store: this.log_store.clone(),
+                checks: this.checks.iter().copied().collect(),
+                data_schema,
+                partition_columns,
+            });
+
+            let files: Vec<FileToValidate> = snapshot
+                .file_views(this.log_store.as_ref(), None)
+                .map_ok(|file| FileToValidate {
+                    path: file.path().to_string(),
+                    location: file.object_store_path(),
+                    size: file.size(),
+                    num_records: file.num_records().map(|n| n as i64),
+                    stats: file.stats(),
+                    partition_values: file.partition_values_map(),
+                    deletion_vector: file.deletion_vector_descriptor(),
+                })
+                .try_collect()
+                .await?;
+
+            let mut report = ValidationReport {
+                version: snapshot.version(),
+                checks: this.checks.clone(),
+                num_files_checked: files.len(),
+                num_deletion_vectors_checked: 0,
+                issues: Vec::new(),
+            };
+            if ctx.enabled(ValidationCheck::DeletionVectors) {
+                report.num_deletion_vectors_checked = files
+                    .iter()
+                    .filter(|file| file.deletion_vector.is_some())
+                    .count();
+            }
+
+            let mut checksum_issues = Vec::new();
+            if ctx.enabled(ValidationCheck::Checksum) {
+                checksum_issues = check_checksum(&ctx, &snapshot, &files).await?;
+            }
+
+            let issues: Vec<Vec<ValidationIssue>> = futures::stream::iter(files)
+                .map(|file| {
+                    let ctx = ctx.clone();
+                    async move { validate_file(&ctx, file).await }
+                })
+                .buffer_unordered(this.max_concurrent_tasks)
+                .try_collect()
+                .await?;
+            report.issues = issues
+                .into_iter()
+                .flatten()
+                .chain(checksum_issues)
+                .collect();
+            report
+                .issues
+                .sort_by(|a, b| a.path.cmp(&b.path).then(a.kind.cmp(&b.kind)));
+            debug!(
+                files = report.num_files_checked,
+                issues = report.issues.len(),
+                "table validation completed"
+            );
+            Ok(report)
+        })
+    }
+}
+
+async fn validate_file(
+    ctx: &ValidationContext,
+    file: FileToValidate,
+) -> DeltaResult<Vec<ValidationIssue>> {
+    let mut issues = Vec::new();
+    if ctx.enabled(ValidationCheck::Partitions) {
+        issues.extend(check_partition_path(ctx, &file));
+    }
+    if ctx.enabled(ValidationCheck::DeletionVectors)
+        && let Some(dv) = &file.deletion_vector
+    {
+        issues.extend(check_deletion_vector(ctx, &file, dv).await?);
+    }
+
+    let store = ctx.log_store.object_store(None);
+    let meta = match store.head(&file.location).await {
+        Ok(meta) => meta,
+        Err(object_store::Error::NotFound { .. }) => {
+            issues.push(ValidationIssue::new(
+                &file.path,
+                ValidationIssueKind::MissingFile,
+                "file does not exist",
+            ));
+            return Ok(issues);
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if ctx.enabled(ValidationCheck::FileSize) && meta.size != file.size as u64 {
+        issues.push(ValidationIssue::new(
+            &file.path,
+            ValidationIssueKind::SizeMismatch,
+            format!(
+                "file has {} bytes, but the log records {} bytes",
+                meta.size, file.size
+            ),
+        ));
+    }
+    if !ctx.requires_footer() {
+        return Ok(issues);
+    }
+
+    let reader = ParquetObjectReader::new(store, file.location.clone()).with_file_size(meta.size);
+    let builder = match ParquetRecordBatchStreamBuilder::new(reader).await {
+        Ok(builder) => builder,
+        Err(err) => {
+            issues.push(ValidationIssue::new(
+                &file.path,
+                ValidationIssueKind::InvalidFooter,
+                format!("failed to read parquet footer: {err}"),
+            ));
+            return Ok(issues);
+        }
+    };
+    let metadata = builder.metadata().as_ref();
+
+    let num_rows = metadata.file_metadata().num_rows();
+    if ctx.enabled(ValidationCheck::Footer)
+        && let Some(num_records) = file.num_records
+        && num_records != num_rows
+    {
+        issues.push(ValidationIssue::new(
+            &file.path,
+            ValidationIssueKind::RecordCountMismatch,
+            format!("file has {num_rows} rows, but the log records {num_records}"),
+        ));
+    }
+
+    if ctx.enabled(ValidationCheck::Stats) || ctx.enabled(ValidationCheck::Partitions) {
+        let footer_stats = footer_stats(metadata);
+        if ctx.enabled(ValidationCheck::Stats) {
+            issues.extend(check_stats(&file, &footer_stats));
+        }
+        if ctx.enabled(ValidationCheck::Partitions) {
+            issues.extend(check_partition_data(ctx, &file, &footer_stats, num_rows));
+        }
+    }
+
+    if ctx.enabled(ValidationCheck::Schema) {
+        issues.extend(check_schema(ctx, &file, builder.schema()));
+    }
+    Ok(issues)
+}
+
+/// Compare the version checksum of the snapshot, if it exists, with the replayed log.
+async fn check_checksum(
+    ctx: &ValidationContext,
+    snapshot: &EagerSnapshot,
+    files: &[FileToValidate],
+) -> DeltaResult<Vec<ValidationIssue>> {
+    let log_store = ctx.log_store.as_ref();
+    let Some(checksum) = read_version_checksum(log_store, snapshot.version()).await? else {
+        return Ok(Vec::new());
+    };
+    let path = checksum_path(log_store, snapshot.version()).to_string();
+    let mut issues = Vec::new();
+    let mut mismatch = |message: String| {
+        issues.push(ValidationIssue::new(
+            &path,
+            ValidationIssueKind::ChecksumMismatch,
+            message,
+        ))
+    };
+
+    let num_files = files.len() as i64;
+    if checksum.num_files != num_files {
+        mismatch(format!(
+            "checksum records {} files, but the log has {num_files}",
+            checksum.num_files
+        ));
+    }
+    let table_size_bytes: i64 = files.iter().map(|file| file.size).sum();
+    if checksum.table_size_bytes != table_size_bytes {
+        mismatch(format!(
+            "checksum records {} bytes, but the log has {table_size_bytes}",
+            checksum.table_size_bytes
+        ));
+    }
+    let deletion_vectors: Vec<_> = files
+        .iter()
+        .filter_map(|file| file.deletion_vector.as_ref())
+        .collect();
+    if let Some(num_deletion_vectors) = checksum.num_deletion_vectors_opt
+        && num_deletion_vectors != deletion_vectors.len() as i64
+    {
+        mismatch(format!(
+            "checksum records {num_deletion_vectors} deletion vectors, but the log has {}",
+            deletion_vectors.len()
+        ));
+    }
+    let num_deleted_records: i64 = deletion_vectors.iter().map(|dv| dv.cardinality).sum();
+    if let Some(num_deleted) = checksum.num_deleted_records_opt
+        && num_deleted != num_deleted_records
+    {
+        mismatch(format!(
+            "checksum records {num_deleted} deleted rows, but the log has {num_deleted_records}"
+        ));
+    }
+    if let Some(histogram) = &checksum.file_size_histogram {
+        let mut expected = FileSizeHistogram {
+            sorted_bin_boundaries: histogram.sorted_bin_boundaries.clone(),
+            file_counts: vec![0; histogram.sorted_bin_boundaries.len()],
+            total_bytes: vec![0; histogram.sorted_bin_boundaries.len()],
+        };
+        files.iter().for_each(|file| expected.insert(file.size));
+        if &expected != histogram {
+            mismatch("file size histogram does not match the active files".to_string());
+        }
+    }
+    if &checksum.metadata != snapshot.metadata() {
+        mismatch("metadata does not match the log".to_string());
+    }
+    if &checksum.protocol != snapshot.protocol() {
+        mismatch("protocol does not match the log".to_string());
+    }
+    Ok(issues)
+}
+
+/// Compare the hive style `key=value` segments of the file path with the partition values.
+fn check_partition_path(ctx: &ValidationContext, file: &FileToValidate) -> Vec<ValidationIssue> {
+    let directories = file.path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    directories
+        .split('/')
+        .filter_map(|segment| segment.split_once('='))
+        .filter(|(key, _)| ctx.partition_columns.iter().any(|col| col == key))
+        .filter_map(|(key, value)| {
+            let value = percent_decode_str(value).decode_utf8_lossy();
+            let expected = file.partition_values.get(key).cloned().flatten();
+            let matches = match &expected {
+                Some(expected) => value == expected.as_str(),
+                None => value == NULL_PARTITION_VALUE,
+            };
+            (!matches).then(|| {
+                ValidationIssue::new(
+                    &file.path,
+                    ValidationIssueKind::PartitionMismatch,
+                    format!(
+                        "path has {key}={value}, but the log records {}",
+                        expected.as_deref().unwrap_or("null")
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+async fn check_deletion_vector(
+    ctx: &ValidationContext,
+    file: &FileToValidate,
+    dv: &DeletionVectorDescriptor,
+) -> DeltaResult<Vec<ValidationIssue>> {
+    let rows = match read_deletion_vector(ctx.log_store.as_ref(), dv).await {
+        Ok(rows) => rows,
+        Err(DeltaTableError::ObjectStore {
+            source: object_store::Error::NotFound { .. },
+        }) => {
+            return Ok(vec![ValidationIssue::new(
+                &file.path,
+                ValidationIssueKind::MissingDeletionVector,
+                format!("deletion vector {} does not exist", dv.path_or_inline_dv),
+            )]);
+        }
+        Err(err @ DeltaTableError::ObjectStore { .. }) => return Err(err),
+        Err(err) => {
+            return Ok(vec![ValidationIssue::new(
+                &file.path,
+                ValidationIssueKind::InvalidDeletionVector,
+                err.to_string(),
+            )]);
+        }
+    };
+
+    let mut issues = Vec::new();
+    if rows.len() as i64 != dv.cardinality {
+        issues.push(ValidationIssue::new(
+            &file.path,
+            ValidationIssueKind::DeletionVectorCardinalityMismatch,
+            format!(
+                "deletion vector marks {} rows, but the log records a cardinality of {}",
+                rows.len(),
+                dv.cardinality
+            ),
+        ));
+    }
+    if let (Some(num_records), Some(max_row)) = (file.num_records, rows.max())
+        && max_row >= num_records as u64
+    {
+        issues.push(ValidationIssue::new(
+            &file.path,
+            ValidationIssueKind::InvalidDeletionVector,
+            format!("deletion vector marks row {max_row}, but the file has {num_records} rows"),
+        ));
+    }
+    Ok(issues)
+}
+
+/// Statistics derived from a Parquet footer, flattened to dot-separated physical column paths
+#[derive(Default)]
+struct FooterStats {
+    min_values: BTreeMap<String, Value>,
+    max_values: BTreeMap<String, Value>,
+    /// Null counts, only if every column chunk of the file records them
+    null_count: Option<BTreeMap<String, Value>>,
+    /// Paths of columns that are not nested in a list or map
+    comparable_columns: HashSet<String>,
+}
+
+fn footer_stats(metadata: &ParquetMetaData) -> FooterStats {
+    let stats = stats_from_parquet_metadata(
+        &IndexMap::new(),
+        metadata,
+        DataSkippingNumIndexedCols::AllColumns,
+        &None,
+    )
+    .map_err(DeltaTableError::from)
+    .and_then(|stats| Ok(serde_json::to_value(stats)?));
+    let stats = match stats {
+        Ok(stats) => stats,
+        Err(err) => {
+            debug!("failed to derive statistics from parquet footer: {err}");
+            return FooterStats::default();
+        }
+    };
+
+    let has_null_counts = metadata.row_groups().iter().all(|row_group| {
+        row_group.columns().iter().all(|column| {
+            column
+                .statistics()
+                .is_some_and(|stats| stats.null_count_opt().is_some())
+        })
+    });
+    FooterStats {
+        min_values: flatten_stats(&stats["minValues"]),
+        max_values: flatten_stats(&stats["maxValues"]),
+        null_count: has_null_counts.then(|| flatten_stats(&stats["nullCount"])),
+        comparable_columns: metadata
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .filter(|column| column.max_rep_level() == 0)
+            .map(|column| column.path().string())
+            .collect(),
+    }
+}
+
+fn check_stats(file: &FileToValidate, footer: &FooterStats) -> Vec<ValidationIssue> {
+    let Some(stats) = file.stats.as_deref() else {
+        return Vec::new();
+    };
+    let recorded: Value = match serde_json::from_str(stats) {
+        Ok(recorded) => recorded,
+        Err(err) => {
+            return vec![ValidationIssue::new(
+                &file.path,
+                ValidationIssueKind::StatsMismatch,
+                format!("failed to parse statistics: {err}"),
+            )];
+        }
+    };
+
+    let mut issues = Vec::new();
+    let mut mismatch = |message: String| {
+        issues.push(ValidationIssue::new(
+            &file.path,
+            ValidationIssueKind::StatsMismatch,
+            message,
+        ))
+    };
+    let comparable = |column: &String| footer.comparable_columns.contains(column);
+
+    for (column, recorded) in flatten_stats(&recorded["minValues"]) {
+        if let Some(actual) = footer
+            .min_values
+            .get(&column)
+            .filter(|_| comparable(&column))
+            && compare_stat_values(&recorded, actual) == Some(Ordering::Greater)
+            && !is_truncation_of(&recorded, actual)
+        {
+            mismatch(format!(
+                "minValues.{column} is {recorded}, but the file contains {actual}"
+            ));
+        }
+    }
+    for (column, recorded) in flatten_stats(&recorded["maxValues"]) {
+        if let Some(actual) = footer
+            .max_values
+            .get(&column)
+            .filter(|_| comparable(&column))
+            && compare_stat_values(&recorded, actual) == Some(Ordering::Less)
+            && !is_truncation_of(&recorded, actual)
+        {
+            mismatch(format!(
+                "maxValues.{column} is {recorded}, but the file contains {actual}"
+            ));
+        }
+    }
+    if let Some(null_count) = &footer.null_count {
+        for (column, recorded) in flatten_stats(&recorded["nullCount"]) {
+            if let Some(actual) = null_count.get(&column).filter(|_| comparable(&column))
+                && actual != &recorded
+            {
+                mismatch(format!(
+                    "nullCount.{column} is {recorded}, but the file contains {actual} nulls"
+                ));
+            }
+        }
+    }
+    issues
+}
+
+/// Compare partition values with the data of partition columns stored in the file.
+fn check_partition_data(
+    ctx: &ValidationContext,
+    file: &FileToValidate,
+    footer: &FooterStats,
+    num_rows: i64,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for column in &ctx.partition_columns {
+        if !footer.comparable_columns.contains(column) {
+            continue;
+        }
+        let expected = file.partition_values.get(column).cloned().flatten();
+        let matches = match &expected {
+            Some(expected) => [&footer.min_values, &footer.max_values]
+                .iter()
+                .all(|values| {
+                    values
+                        .get(column)
+                        .is_none_or(|value| stat_matches_partition_value(value, expected))
+                }),
+            None => footer
+                .null_count
+                .as_ref()
+                .and_then(|null_count| null_count.get(column))
+                .is_none_or(|count| count.as_i64() == Some(num_rows)),
+        };
+        if !matches {
+            issues.push(ValidationIssue::new(
+                &file.path,
+                ValidationIssueKind::PartitionMismatch,
+                format!(
+                    "file contains values of {column} other than the partition value {}",
+                    expected.as_deref().unwrap_or("null")
+                ),
+            ));
+        }
+    }
+    issues
+}
+
+fn check_schema(
+    ctx: &ValidationContext,
+    file: &FileToValidate,
+    file_schema: &Schema,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut mismatch = |message: String| {
+        issues.push(ValidationIssue::new(
+            &file.path,
+            ValidationIssueKind::SchemaMismatch,
+            message,
+        ))
+    };
+    for field in file_schema.fields() {
+        if ctx.partition_columns.contains(field.name()) {
+            continue;
+        }
+        match ctx.data_schema.field_with_name(field.name()) {
+            Ok(table_field) if !is_compatible(field.data_type(), table_field.data_type()) => {
+                mismatch(format!(
+                    "column {} has type {} in the file, but {} in the table",
+                    field.name(),
+                    field.data_type(),
+                    table_field.data_type()
+                ))
+            }
+            Ok(_) => {}
+            Err(_) => mismatch(format!(
+                "column {} is not part of the table schema",
+                field.name()
+            )),
+        }
+    }
+    for field in ctx.data_schema.fields() {
+        if !field.is_nullable() && file_schema.field_with_name(field.name()).is_err() {
+            mismatch(format!(
+                "non-nullable column {} is missing in the file",
+                field.name()
+            ));
+        }
+    }
+    issues
+}
+
+/// Whether data of type `file` can be read as type `table`, allowing for type widening and
+/// the different physical representations of the same logical type in Parquet.
+fn is_compatible(file: &DataType, table: &DataType) -> bool {
+    use DataType::*;
+    match (file, table) {
+        (a, b) if a == b => true,
+        (Utf8 | LargeUtf8 | Utf8View, Utf8 | LargeUtf8 | Utf8View) => true,
+        (Binary | LargeBinary | BinaryView, Binary | LargeBinary | BinaryView) => true,
+        (Timestamp(_, _), Timestamp(_, _)) => true,
+        (Int8, Int16 | Int32 | Int64) | (Int16, Int32 | Int64) | (Int32, Int64) => true,
+        (Float32, Float64) | (Int8 | Int16 | Int32, Float64) => true,
+        (Date32, Timestamp(_, None)) => true,
+        (Decimal128(p1, s1), Decimal128(p2, s2)) => {
+            s1 <= s2 && (*p1 as i16 - *s1 as i16) <= (*p2 as i16 - *s2 as i16)
+        }
+        (Int8 | Int16 | Int32 | Int64, Decimal128(_, _)) => true,
+        (Struct(file_fields), Struct(table_fields)) => file_fields.iter().all(|field| {
+            table_fields
+                .find(field.name())
+                .is_some_and(|(_, table)| is_compatible(field.data_type(), table.data_type()))
+        }),
+        (List(a) | LargeList(a) | ListView(a), List(b) | LargeList(b) | ListView(b)) => {
+            is_compatible(a.data_type(), b.data_type())
+        }
+        (Map(a, _), Map(b, _)) => is_compatible(a.data_type(), b.data_type()),
+        _ => false,
+    }
+}
+
+/// Flatten nested statistics into dot-separated column paths
+fn flatten_stats(value: &Value) -> BTreeMap<String, Value> {
+    fn flatten(prefix: Option<&str>, value: &Value, out: &mut BTreeMap<String, Value>) {
+        match value {
+            Value::Object(fields) => {
+                for (name, value) in fields {
+                    let path = match prefix {
+                        Some(prefix) => format!("{prefix}.{name}"),
+                        None => name.clone(),
+                    };
+                    flatten(Some(&path), value, out);
+                }
+            }
+            Value::Null => {}
+            value => {
+                if let Some(prefix) = prefix {
+                    out.insert(prefix.to_string(), value.clone());
+                }
+            }
+        }
+    }
+    let mut out = BTreeMap::new();
+    flatten(None, value, &mut out);
+    out
+}
+
+/// Compare two statistics values of the same column, `None` if they are not comparable.
+///
+/// Timestamps are compared at millisecond precision, which is the precision of the statistics.
+fn compare_stat_values(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        },
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::String(b)) => match (parse_timestamp(a), parse_timestamp(b)) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => Some(a.cmp(b)),
+        },
+        _ => None,
+    }
+}
+
+/// Whether one of the string values is a prefix of the other, as writers truncate long strings
+fn is_truncation_of(a: &Value, b: &Value) -> bool {
+    matches!((a, b), (Value::String(a), Value::String(b)) if a.starts_with(b.as_str()) || b.starts_with(a.as_str()))
+}
+
+fn stat_matches_partition_value(stat: &Value, partition_value: &str) -> bool {
+    match stat {
+        Value::String(value) => {
+            value == partition_value
+                || parse_timestamp(value)
+                    .is_some_and(|ts| Some(ts) == parse_timestamp(partition_value))
+        }
+        Value::Number(value) => match (value.as_i64(), partition_value.parse::<i64>()) {
+            (Some(a), Ok(b)) => a == b,
+            _ => value.as_f64() == partition_value.parse::<f64>().ok(),
+        },
+        Value::Bool(value) => partition_value.parse::<bool>().ok() == Some(*value),
+        _ => true,
+    }
+}
+
+/// Parse a timestamp statistic, returning milliseconds since the epoch
+fn parse_timestamp(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|ts| ts.timestamp_millis())
+        .ok()
+        .or_else(|| {
+            ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+                .map(|ts| ts.and_utc().timestamp_millis())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, RecordBatch, StringArray};
+    use arrow_schema::{DataType as ArrowDataType, Field};
+    use object_store::ObjectStoreExt as _;
+    use parquet::arrow::ArrowWriter;
+    use serde_json::json;
+
+    use roaring::RoaringTreemap;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::kernel::schema::{DataType as DeltaDataType, PrimitiveType};
+    use crate::kernel::transaction::deletion_vector::inline_deletion_vector;
+    use crate::kernel::{Action, Add, StorageType};
+    use crate::{DeltaTable, TableProperty};
+
+    fn parquet_file(ids: Vec<i64>, names: Vec<Option<&str>>) -> Vec<u8> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", ArrowDataType::Int64, false),
+            Field::new("name", ArrowDataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(ids)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )
+        .unwrap();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        buffer
+    }
+
+    fn add(path: &str, size: usize, stats: Value) -> Action {
+        Action::Add(Add {
+            path: path.into(),
+            size: size as i64,
+            partition_values: [("part".to_string(), Some("a".to_string()))].into(),
+            stats: Some(stats.to_string()),
+            data_change: true,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_validate() -> DeltaResult<()> {
+        let valid = parquet_file(vec![1, 2, 3], vec![Some("a"), None, Some("c")]);
+        let corrupt = parquet_file(vec![10, 20], vec![Some("x"), Some("y")]);
+        let valid_stats = json!({
+            "numRecords": 3,
+            "minValues": {"id": 1, "name": "a"},
+            "maxValues": {"id": 3, "name": "c"},
+            "nullCount": {"id": 0, "name": 1},
+        });
+        let corrupt_stats = json!({
+            "numRecords": 3,
+            "minValues": {"id": 15},
+            "maxValues": {"id": 20},
+            "nullCount": {"id": 0, "name": 0},
+        });
+
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_column(
+                "id",
+                DeltaDataType::Primitive(PrimitiveType::Long),
+                false,
+                None,
+            )
+            .with_column(
+                "name",
+                DeltaDataType::Primitive(PrimitiveType::String),
+                true,
+                None,
+            )
+            .with_column(
+                "part",
+                DeltaDataType::Primitive(PrimitiveType::String),
+                true,
+                None,
+            )
+            .with_partition_columns(["part"])
+            .with_actions(vec![
+                add("part=a/valid.parquet", valid.len(), valid_stats),
+                add("part=b/corrupt.parquet", corrupt.len() + 1, corrupt_stats),
+                add("part=a/missing.parquet", 10, json!({"numRecords": 1})),
+            ])
+            .await?;
+        let store = table.log_store().object_store(None);
+        store
+            .put(&Path::from("part=a/valid.parquet"), valid.into())
+            .await?;
+        store
+            .put(&Path::from("part=b/corrupt.parquet"), corrupt.into())
+            .await?;
+
+        let report = table.validate().await?;
+        assert!(!report.is_valid());
+        assert_eq!(report.version, 0);
+        assert_eq!(report.num_files_checked, 3);
+
+        let issues: Vec<_> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.path.as_str(), issue.kind))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                ("part=a/missing.parquet", ValidationIssueKind::MissingFile),
+                ("part=b/corrupt.parquet", ValidationIssueKind::SizeMismatch),
+                (
+                    "part=b/corrupt.parquet",
+                    ValidationIssueKind::RecordCountMismatch
+                ),
+                ("part=b/corrupt.parquet", ValidationIssueKind::StatsMismatch),
+                (
+                    "part=b/corrupt.parquet",
+                    ValidationIssueKind::PartitionMismatch
+                ),
+            ]
+        );
+
+        let report = table
+            .validate()
+            .with_checks([ValidationCheck::FileSize])
+            .await?;
+        assert_eq!(report.issues.len(), 2);
+        assert!(serde_json::to_value(&report)?["issues"][0]["kind"] == "missingFile");
+
+        let log_store = table.log_store();
+        let report = table
+            .validate()
+            .with_checks([ValidationCheck::Checksum])
+            .await?;
+        assert!(report.is_valid());
+
+        let mut checksum = read_version_checksum(log_store.as_ref(), 0)
+            .await?
+            .expect("checksum of version 0");
+        checksum.num_files += 1;
+        store
+            .put(
+                &checksum_path(log_store.as_ref(), 0),
+                serde_json::to_vec(&checksum)?.into(),
+            )
+            .await?;
+        let report = table
+            .validate()
+            .with_checks([ValidationCheck::Checksum])
+            .await?;
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].path, "_delta_log/00000000000000000000.crc");
+        assert_eq!(report.issues[0].kind, ValidationIssueKind::ChecksumMismatch);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_deletion_vectors_and_schema() -> DeltaResult<()> {
+        let data = parquet_file(vec![1, 2, 3], vec![Some("a"), Some("b"), Some("c")]);
+        let stats = json!({"numRecords": 3});
+        let with_dv =
+            |path: &str, dv: DeletionVectorDescriptor| match add(path, data.len(), stats.clone()) {
+                Action::Add(add) => Action::Add(Add {
+                    deletion_vector: Some(dv),
+                    ..add
+                }),
+                _ => unreachable!(),
+            };
+        let stored_dv = |id: Uuid, size: i32| DeletionVectorDescriptor {
+            storage_type: StorageType::UuidRelativePath,
+            path_or_inline_dv: z85::encode(id.as_bytes()),
+            offset: Some(1),
+            size_in_bytes: size,
+            cardinality: 1,
+        };
+
+        let corrupt_id = Uuid::new_v4();
+        let mut wrong_cardinality = inline_deletion_vector(&RoaringTreemap::from_iter([0, 1]));
+        wrong_cardinality.cardinality = 3;
+
+        // a file whose id column is a string and which has a column unknown to the table
+        let mismatched_schema = Arc::new(Schema::new(vec![
+            Field::new("id", ArrowDataType::Utf8, false),
+            Field::new("extra", ArrowDataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            mismatched_schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["1"])),
+                Arc::new(Int64Array::from(vec![1])),
+            ],
+        )
+        .unwrap();
+        let mut mismatched = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut mismatched, mismatched_schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_column(
+                "id",
+                DeltaDataType::Primitive(PrimitiveType::Long),
+                false,
+                None,
+            )
+            .with_column(
+                "name",
+                DeltaDataType::Primitive(PrimitiveType::String),
+                true,
+                None,
+            )
+            .with_column(
+                "part",
+                DeltaDataType::Primitive(PrimitiveType::String),
+                true,
+                None,
+            )
+            .with_partition_columns(["part"])
+            .with_configuration_property(TableProperty::EnableDeletionVectors, Some("true"))
+            .with_actions(vec![
+                with_dv(
+                    "part=a/a_valid.parquet",
+                    inline_deletion_vector(&RoaringTreemap::from_iter([0])),
+                ),
+                with_dv("part=a/b_missing.parquet", stored_dv(Uuid::new_v4(), 8)),
+                with_dv("part=a/c_corrupt.parquet", stored_dv(corrupt_id, 7)),
+                with_dv("part=a/d_cardinality.parquet", wrong_cardinality),
+                with_dv(
+                    "part=a/e_out_of_range.parquet",
+                    inline_deletion_vector(&RoaringTreemap::from_iter([7])),
+                ),
+                add("part=a/f_schema.parquet", mismatched.len(), stats.clone()),
+            ])
+            .await?;
+
+        let store = table.log_store().object_store(None);
+        for name in [
+            "a_valid",
+            "b_missing",
+            "c_corrupt",
+            "d_cardinality",
+            "e_out_of_range",
+        ] {
+            store
+                .put(
+                    &Path::from(format!("part=a/{name}.parquet")),
+                    data.clone().into(),
+                )
+                .await?;
+        }
+        store
+            .put(&Path::from("part=a/f_schema.parquet"), mismatched.into())
+            .await?;
+        // a deletion vector file whose checksum does not match its content
+        let mut corrupt = vec![1u8];
+        corrupt.extend(7u32.to_be_bytes());
+        corrupt.extend(b"garbage");
+        corrupt.extend(0u32.to_be_bytes());
+        store
+            .put(
+                &Path::from(format!("deletion_vector_{corrupt_id}.bin")),
+                corrupt.into(),
+            )
+            .await?;
+
+        let report = table
+            .validate()
+            .with_checks([ValidationCheck::DeletionVectors, ValidationCheck::Schema])
+            .await?;
+        assert_eq!(report.num_files_checked, 6);
+        assert_eq!(report.num_deletion_vectors_checked, 5);
+
+        let issues: Vec<_> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.path.as_str(), issue.kind))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "part=a/b_missing.parquet",
+                    ValidationIssueKind::MissingDeletionVector
+                ),
+                (
+                    "part=a/c_corrupt.parquet",
+                    ValidationIssueKind::InvalidDeletionVector
+                ),
+                (
+                    "part=a/d_cardinality.parquet",
+                    ValidationIssueKind::DeletionVectorCardinalityMismatch
+                ),
+                (
+                    "part=a/e_out_of_range.parquet",
+                    ValidationIssueKind::InvalidDeletionVector
+                ),
+                (
+                    "part=a/f_schema.parquet",
+                    ValidationIssueKind::SchemaMismatch
+                ),
+                (
+                    "part=a/f_schema.parquet",
+                    ValidationIssueKind::SchemaMismatch
+                ),
+            ]
+        );
+        let schema_messages: Vec<_> = report
+            .issues
+            .iter()
+            .filter(|issue| issue.kind == ValidationIssueKind::SchemaMismatch)
+            .map(|issue| issue.message.as_str())
+            .collect();
+        assert!(
+            schema_messages
+                .contains(&"column id has type Utf8 in the file, but Int64 in the table")
+        );
+        assert!(schema_messages.contains(&"column extra is not part of the table schema"));
+        assert!(
+            report.issues[1].message.contains("checksum mismatch"),
+            "{}",
+            report.issues[1].message
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_stat_values() {
+        assert_eq!(
+            compare_stat_values(&json!(1), &json!(2)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_stat_values(&json!(1.5), &json!(1)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_stat_values(
+                &json!("2024-01-01T00:00:00.000Z"),
+                &json!("2024-01-01T00:00:00.000999+00:00")
+            ),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(compare_stat_values(&json!("b"), &json!(1)), None);
+        assert!(is_truncation_of(&json!("abc"), &json!("abcdef")));
+        assert!(!is_truncation_of(&json!("abd"), &json!("abcdef")));
+    }
+
+    #[test]
+    fn test_is_compatible() {
+        use arrow_schema::TimeUnit;
+        assert!(is_compatible(&ArrowDataType::Int32, &ArrowDataType::Int64));
+        assert!(!is_compatible(&ArrowDataType::Int64, &ArrowDataType::Int32));
+        assert!(is_compatible(
+            &ArrowDataType::Utf8View,
+            &ArrowDataType::Utf8
+        ));
+        assert!(is_compatible(
+            &ArrowDataType::Timestamp(TimeUnit::Nanosecond, None),
+            &ArrowDataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        ));
+        assert!(is_compatible(
+            &ArrowDataType::Decimal128(5, 2),
+            &ArrowDataType::Decimal128(10, 4)
+        ));
+        assert!(!is_compatible(
+            &ArrowDataType::Decimal128(10, 2),
+            &ArrowDataType::Decimal128(10, 4)
+        ));
+        assert!(!is_compatible(&ArrowDataType::Utf8, &ArrowDataType::Int64));
+    }
+}
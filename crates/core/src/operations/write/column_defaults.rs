@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, RecordBatch, RecordBatchOptions};
+use arrow_schema::Schema;
+use datafusion::catalog::Session;
+use datafusion::common::DFSchema;
+use datafusion::logical_expr::{Expr, LogicalPlan, LogicalPlanBuilder, col};
+use datafusion::prelude::SessionContext;
+use delta_kernel::table_features::TableFeature;
+use tracing::debug;
+
+use crate::{
+    DeltaResult, DeltaTableError,
+    delta_datafusion::expr::parse_column_default_expression,
+    kernel::{EagerSnapshot, StructType, StructTypeExt, TableFeatures},
+    table::ColumnDefault,
+};
+
+/// check if the table protocol lists the `allowColumnDefaults` writer feature
+#[inline]
+pub fn column_defaults_enabled(snapshot: &EagerSnapshot) -> bool {
+    TableFeature::try_from(&TableFeatures::AllowColumnDefaults).is_ok_and(|feature| {
+        snapshot
+            .protocol()
+            .writer_features()
+            .is_some_and(|features| features.contains(&feature))
+    })
+}
+
+/// Parse the default expression of every column, keyed by column name.
+///
+/// Default values cannot reference other columns, so they are resolved against an empty schema.
+pub fn parse_column_defaults(
+    session: &dyn Session,
+    column_defaults: &[ColumnDefault],
+) -> DeltaResult<HashMap<String, Expr>> {
+    let schema = DFSchema::empty();
+    column_defaults
+        .iter()
+        .map(|column_default| {
+            let expr = parse_column_default_expression(&schema, column_default, session)?;
+            Ok((column_default.name.clone(), expr))
+        })
+        .collect()
+}
+
+/// Add the columns that are missing from the plan but have a default value in the table schema.
+pub fn with_column_defaults(
+    session: &dyn Session,
+    plan: LogicalPlan,
+    column_defaults: &[ColumnDefault],
+) -> DeltaResult<LogicalPlan> {
+    let missing = column_defaults
+        .iter()
+        .filter(|d| plan.schema().field_with_unqualified_name(&d.name).is_err())
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(plan);
+    }
+
+    let mut projection: Vec<_> = plan
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| col(f.name()))
+        .collect();
+    let schema = DFSchema::empty();
+    for column_default in missing {
+        debug!(
+            "Filling missing column {} with its default value.",
+            column_default.name
+        );
+        let expr = parse_column_default_expression(&schema, column_default, session)?;
+        projection.push(expr.alias(&column_default.name));
+    }
+
+    Ok(LogicalPlanBuilder::new(plan).project(projection)?.build()?)
+}
+
+/// Check that the default expressions of a schema can be evaluated to the column type.
+pub(crate) fn validate_column_defaults(schema: &StructType) -> DeltaResult<()> {
+    let generated = schema.get_generated_columns()?;
+    let column_defaults = schema.get_column_defaults()?;
+    if let Some(column) = column_defaults
+        .iter()
+        .find(|d| generated.iter().any(|g| g.name == d.name))
+    {
+        return Err(DeltaTableError::Generic(format!(
+            "Column {} cannot have both a default value and a generation expression",
+            column.name
+        )));
+    }
+    evaluate_column_defaults(&column_defaults).map(|_| ())
+}
+
+/// Evaluate the default value of every column into a single row array.
+///
+/// Used by writers that operate on record batches rather than logical plans. Defaults are not
+/// necessarily deterministic (e.g. `current_timestamp()`), so they are evaluated for every write.
+pub(crate) fn evaluate_column_defaults(
+    column_defaults: &[ColumnDefault],
+) -> DeltaResult<Vec<(String, ArrayRef)>> {
+    if column_defaults.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let session = SessionContext::new().state();
+    let df_schema = DFSchema::empty();
+    let row = RecordBatch::try_new_with_options(
+        Arc::new(Schema::empty()),
+        vec![],
+        &RecordBatchOptions::new().with_row_count(Some(1)),
+    )?;
+    column_defaults
+        .iter()
+        .map(|column_default| {
+            let expr = parse_column_default_expression(&df_schema, column_default, &session)?;
+            let physical = session.create_physical_expr(expr, &df_schema)?;
+            let value = physical.evaluate(&row)?.into_array(1)?;
+            Ok((column_default.name.clone(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow_schema::{DataType as ArrowDataType, Field as ArrowField};
+    use datafusion::catalog::MemTable;
+    use datafusion::datasource::provider_as_source;
+    use delta_kernel::schema::DataType as KernelDataType;
+
+    fn create_test_plan() -> LogicalPlan {
+        let schema = Arc::new(Schema::new(vec![ArrowField::new(
+            "id",
+            ArrowDataType::Int32,
+            false,
+        )]));
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2]))]).unwrap();
+        let source = provider_as_source(Arc::new(
+            MemTable::try_new(batch.schema(), vec![vec![batch]]).unwrap(),
+        ));
+        LogicalPlanBuilder::scan("test", source, None)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_with_column_defaults_fills_missing_columns() {
+        let ctx = SessionContext::new();
+        let column_defaults = vec![
+            ColumnDefault::new("id", "0", &KernelDataType::INTEGER),
+            ColumnDefault::new("status", "concat('act', 'ive')", &KernelDataType::STRING),
+        ];
+
+        let plan =
+            with_column_defaults(&ctx.state(), create_test_plan(), &column_defaults).unwrap();
+        assert_eq!(plan.schema().fields().len(), 2);
+
+        let batches = ctx
+            .execute_logical_plan(plan)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        datafusion::assert_batches_eq!(
+            &[
+                "+----+--------+",
+                "| id | status |",
+                "+----+--------+",
+                "| 1  | active |",
+                "| 2  | active |",
+                "+----+--------+",
+            ],
+            &batches
+        );
+    }
+
+    #[test]
+    fn test_evaluate_column_defaults() {
+        let schema: StructType = serde_json::from_value(serde_json::json!({
+            "type": "struct",
+            "fields": [
+                {"name": "id", "type": "integer", "nullable": false, "metadata": {}},
+                {"name": "status", "type": "string", "nullable": false, "metadata": {"CURRENT_DEFAULT": "'active'"}},
+                {"name": "score", "type": "long", "nullable": false, "metadata": {"CURRENT_DEFAULT": "1 + 1"}}
+            ]
+        }))
+        .unwrap();
+
+        let defaults = evaluate_column_defaults(&schema.get_column_defaults().unwrap()).unwrap();
+        assert_eq!(defaults.len(), 2);
+        let (name, status) = &defaults[0];
+        assert_eq!(name, "status");
+        assert_eq!(status.len(), 1);
+        assert_eq!(
+            status
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap()
+                .value(0),
+            "active"
+        );
+        assert_eq!(defaults[1].1.data_type(), &ArrowDataType::Int64);
+    }
+}
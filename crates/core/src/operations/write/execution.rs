@@ -35,6 +35,7 @@ use crate::kernel::{Action, Add, AddCDCFile, EagerSnapshot, StructType, StructTy
 use crate::logstore::{LogStore, ObjectStoreRef};
 use crate::operations::cdc::CDC_COLUMN_NAME;
 use crate::operations::write::WriterStatsConfig;
+use crate::parquet_utils::table_writer_properties;
 
 const DEFAULT_WRITER_BATCH_CHANNEL_SIZE: usize = 10;
 const WRITER_TASK_CLOSED_UNEXPECTEDLY_MSG: &str = "Writer task closed unexpectedly";
@@ -436,6 +437,13 @@ pub(crate) async fn write_execution_plan_v2(
         plan = drop_internal_column(plan, insert_marker_column)?;
     }
 
+    let writer_properties = match snapshot {
+        Some(snapshot) => {
+            table_writer_properties(writer_properties, snapshot.table_configuration())?
+        }
+        None => writer_properties,
+    };
+
     let sink_config = WriteSinkConfig {
         partition_columns,
         object_store,
@@ -492,6 +500,7 @@ pub(crate) async fn write_exec_plan(
         .parquet
         .into_writer_properties_builder()?
         .build();
+    let writer_properties = table_writer_properties(Some(writer_properties), table_config)?;
     let stats_config = WriterStatsConfig::from_config(table_config);
     let object_store = log_store.object_store(operation_id);
     let sink_config = WriteSinkConfig {
@@ -499,7 +508,7 @@ pub(crate) async fn write_exec_plan(
         object_store,
         target_file_size,
         write_batch_size: None,
-        writer_properties: Some(writer_properties),
+        writer_properties,
         writer_stats_config: stats_config,
         column_mapping: ColumnMappingState::from_table_config(table_config),
     };
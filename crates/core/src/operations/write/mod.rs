@@ -61,9 +61,10 @@ use crate::errors::{DeltaResult, DeltaTableError};
 use crate::kernel::schema::cast::normalize_for_delta;
 use crate::kernel::transaction::{CommitBuilder, CommitProperties, PROTOCOL, TableReference};
 use crate::kernel::{Action, EagerSnapshot, StructType};
-use crate::logstore::LogStoreRef;
+use crate::logstore::{IoStats, LogStoreRef};
 use crate::protocol::{DeltaOperation, SaveMode};
 
+pub(crate) mod column_defaults;
 /// Configuration types controlling how data and statistics are written.
 pub mod configs;
 pub(crate) mod execution;
@@ -176,6 +177,9 @@ pub struct WriteMetrics {
     pub num_added_rows: usize,
     /// Time taken to execute the entire operation
     pub execution_time_ms: u64,
+    /// Object store requests issued by the operation before its commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io_stats: Option<IoStats>,
 }
 
 impl super::Operation for WriteBuilder {
@@ -598,6 +602,7 @@ impl std::future::IntoFuture for WriteBuilder {
 
                 metrics.execution_time_ms =
                     Instant::now().duration_since(exec_start).as_millis() as u64;
+                metrics.io_stats = super::take_io_stats(&this.log_store, operation_id);
 
                 let operation = DeltaOperation::Write {
                     mode: this.mode,
@@ -801,6 +806,125 @@ mod tests {
             .expect_err("Remove action is included when Delta table is append-only. Should error");
     }
 
+    /// Sum of the metric over all nodes of the plan
+    fn sum_plan_metric(
+        plan: &Arc<dyn datafusion::physical_plan::ExecutionPlan>,
+        name: &str,
+    ) -> usize {
+        plan.metrics()
+            .map_or(0, |metrics| get_metric(&metrics, name))
+            + plan
+                .children()
+                .into_iter()
+                .map(|child| sum_plan_metric(child, name))
+                .sum::<usize>()
+    }
+
+    #[tokio::test]
+    async fn test_write_bloom_filter_columns() -> TestResult {
+        use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+
+        let table =
+            setup_table_with_configuration(TableProperty::BloomFilterColumns, Some("id:fpp=0.01"))
+                .await;
+        let table = write_batch(table, get_record_batch(None, false)).await;
+
+        let store = table.log_store().object_store(None);
+        let files = table.get_files_by_partitions(&[]).await?;
+        assert_eq!(files.len(), 1);
+        for file in files {
+            let reader = ParquetObjectReader::new(store.clone(), file);
+            let builder = ParquetRecordBatchStreamBuilder::new(reader).await?;
+            for row_group in builder.metadata().row_groups() {
+                let id = row_group.column(0);
+                assert_eq!(id.column_path().string(), "id");
+                assert!(id.bloom_filter_offset().is_some());
+                assert!(row_group.column(1).bloom_filter_offset().is_none());
+            }
+        }
+
+        // do not rely on the default of DataFusion for reading bloom filters
+        let ctx = SessionContext::new_with_config(
+            SessionConfig::new()
+                .set_bool("datafusion.execution.parquet.bloom_filter_on_read", true),
+        );
+        ctx.register_table("test", table.table_provider().await?)?;
+
+        // 'AA' lies between the min and max of the row group, only the bloom filter prunes it
+        let plan = ctx
+            .sql("SELECT id FROM test WHERE id = 'AA'")
+            .await?
+            .create_physical_plan()
+            .await?;
+        let batches = collect(plan.clone(), ctx.task_ctx()).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+        assert_eq!(sum_plan_metric(&plan, "row_groups_pruned_bloom_filter"), 1);
+
+        let batches = ctx
+            .sql("SELECT id, value FROM test WHERE id IN ('B', 'C') ORDER BY value")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+----+-------+",
+            "| id | value |",
+            "+----+-------+",
+            "| B  | 2     |",
+            "| B  | 4     |",
+            "| B  | 8     |",
+            "| B  | 9     |",
+            "+----+-------+",
+        ];
+        assert_batches_eq!(&expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_fills_column_defaults() -> TestResult {
+        use crate::kernel::TableFeatures;
+        use crate::kernel::{COLUMN_DEFAULT_KEY, DataType as DeltaDataType, StructField};
+        use delta_kernel::table_features::TableFeature;
+
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns([
+                StructField::not_null("id", DeltaDataType::INTEGER),
+                StructField::not_null("status", DeltaDataType::STRING).with_metadata([(
+                    COLUMN_DEFAULT_KEY.to_string(),
+                    MetadataValue::String("'active'".to_string()),
+                )]),
+            ])
+            .await?;
+        let feature = TableFeature::try_from(&TableFeatures::AllowColumnDefaults)?;
+        assert!(
+            table
+                .snapshot()?
+                .protocol()
+                .writer_features()
+                .is_some_and(|features| features.contains(&feature))
+        );
+
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "id",
+            DataType::Int32,
+            false,
+        )]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2]))])?;
+        let table = table.write(vec![batch]).await?;
+
+        let batches = query_table(&table, "SELECT id, status FROM test ORDER BY id").await?;
+        let expected = vec![
+            "+----+--------+",
+            "| id | status |",
+            "+----+--------+",
+            "| 1  | active |",
+            "| 2  | active |",
+            "+----+--------+",
+        ];
+        assert_batches_eq!(&expected, &batches);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_write() {
         let table_schema = get_delta_schema();
@@ -830,6 +954,10 @@ mod tests {
             write_metrics.num_added_files,
             table.snapshot().unwrap().log_data().num_files()
         );
+        // the data file is written through the operation's store
+        let io_stats = write_metrics.io_stats.as_ref().unwrap();
+        assert!(io_stats.put.requests >= 1);
+        assert!(io_stats.put.bytes > 0);
         assert_common_write_metrics(write_metrics);
 
         table.load().await.unwrap();
@@ -17,11 +17,14 @@ use datafusion::logical_expr::{
 };
 use datafusion::prelude::col;
 use delta_kernel::engine::arrow_conversion::TryIntoKernel as _;
+use delta_kernel::table_features::ColumnMappingMode;
 use futures::TryStreamExt as _;
 use itertools::Itertools as _;
+use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
 use uuid::Uuid;
 
+use super::column_defaults::{column_defaults_enabled, with_column_defaults};
 use super::configs::WriterStatsConfig;
 use super::generated_columns::{gc_is_enabled, with_generated_columns};
 use super::metrics::SOURCE_COUNT_ID;
@@ -40,7 +43,9 @@ use crate::kernel::{
 use crate::logstore::LogStoreRef;
 use crate::operations::cdc::{CDC_COLUMN_NAME, should_write_cdc};
 use crate::operations::{get_num_idx_cols_and_stats_columns, get_target_file_size};
+use crate::parquet_utils::{default_writer_properties, with_bloom_filter_columns};
 use crate::protocol::SaveMode;
+use crate::table::config::{TableProperty, parse_bloom_filter_columns};
 
 /// Schema and protocol actions required before the sink executes the write.
 #[derive(Default)]
@@ -287,6 +292,12 @@ pub(super) fn prepare_write(input: WritePreparationInput<'_>) -> DeltaResult<Pre
         normalize_for_delta(source.schema().inner())
     };
 
+    if let Some(snapshot) = snapshot
+        && column_defaults_enabled(snapshot)
+    {
+        source = with_column_defaults(session, source, &snapshot.schema().get_column_defaults()?)?;
+    }
+
     if let Some(snapshot) = snapshot
         && gc_is_enabled(snapshot)
     {
@@ -414,7 +425,7 @@ pub(super) fn prepare_write(input: WritePreparationInput<'_>) -> DeltaResult<Pre
             write_batch_size,
             writer_properties,
             configuration,
-        ),
+        )?,
     })
 }
 
@@ -692,14 +703,31 @@ fn build_exec_options(
     write_batch_size: Option<usize>,
     writer_properties: Option<WriterProperties>,
     configuration: &HashMap<String, Option<String>>,
-) -> WriteExecOptions {
+) -> DeltaResult<WriteExecOptions> {
     let config = snapshot.map(|snapshot| snapshot.table_properties());
+    // Writes to existing tables pick up the table's bloom filter columns when the plan is
+    // executed. A first write takes them from the configuration of the table it creates.
+    let writer_properties = match configuration
+        .get(TableProperty::BloomFilterColumns.as_ref())
+        .and_then(Option::as_deref)
+    {
+        Some(value) if snapshot.is_none() => {
+            let columns = parse_bloom_filter_columns(value).map_err(DeltaTableError::generic)?;
+            Some(with_bloom_filter_columns(
+                writer_properties.unwrap_or_else(|| default_writer_properties(Compression::SNAPPY)),
+                &columns,
+                None,
+                ColumnMappingMode::None,
+            ))
+        }
+        _ => writer_properties,
+    };
     let target_file_size =
         target_file_size.unwrap_or_else(|| Some(get_target_file_size(config, configuration)));
     let (num_indexed_cols, stats_columns) =
         get_num_idx_cols_and_stats_columns(config, configuration.clone());
 
-    WriteExecOptions {
+    Ok(WriteExecOptions {
         partition_columns,
         target_file_size,
         write_batch_size,
@@ -708,7 +736,7 @@ fn build_exec_options(
             num_indexed_cols,
             stats_columns,
         },
-    }
+    })
 }
 
 fn resolve_exact_validation(
@@ -1,5 +1,12 @@
+use delta_kernel::schema::{DataType, StructType};
+use delta_kernel::table_configuration::TableConfiguration;
+use delta_kernel::table_features::ColumnMappingMode;
 use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
+use parquet::schema::types::ColumnPath;
+
+use crate::table::config::{BloomFilterColumn, TablePropertiesExt as _, TableProperty};
+use crate::{DeltaResult, DeltaTableError};
 
 pub(crate) fn default_writer_properties(compression: Compression) -> WriterProperties {
     WriterProperties::builder()
@@ -8,10 +15,112 @@ pub(crate) fn default_writer_properties(compression: Compression) -> WriterPrope
         .build()
 }
 
+/// Enable bloom filters for `columns` on top of `writer_properties`.
+///
+/// Columns are resolved against `schema` to their physical parquet path. Columns that are not in
+/// the schema are skipped, as are columns the writer properties already configure a bloom filter
+/// for.
+pub(crate) fn with_bloom_filter_columns(
+    writer_properties: WriterProperties,
+    columns: &[BloomFilterColumn],
+    schema: Option<&StructType>,
+    column_mapping_mode: ColumnMappingMode,
+) -> WriterProperties {
+    let paths: Vec<_> = columns
+        .iter()
+        .filter_map(|column| {
+            let path = match schema {
+                Some(schema) => physical_column_path(schema, &column.name, column_mapping_mode)?,
+                None => ColumnPath::new(column.name.split('.').map(String::from).collect()),
+            };
+            writer_properties
+                .bloom_filter_properties(&path)
+                .is_none()
+                .then_some((path, column))
+        })
+        .collect();
+    if paths.is_empty() {
+        return writer_properties;
+    }
+
+    let mut builder = writer_properties.into_builder();
+    for (path, column) in paths {
+        builder = builder.set_column_bloom_filter_enabled(path.clone(), true);
+        if let Some(fpp) = column.fpp {
+            builder = builder.set_column_bloom_filter_fpp(path.clone(), fpp);
+        }
+        if let Some(ndv) = column.ndv {
+            builder = builder.set_column_bloom_filter_ndv(path, ndv);
+        }
+    }
+    builder.build()
+}
+
+/// Enable the bloom filters configured in the `delta.bloomFilter.columns` property of a table
+/// on top of `writer_properties`.
+pub(crate) fn with_table_bloom_filters(
+    writer_properties: WriterProperties,
+    table_config: &TableConfiguration,
+) -> DeltaResult<WriterProperties> {
+    let columns = table_config
+        .table_properties()
+        .bloom_filter_columns()
+        .map_err(DeltaTableError::generic)?;
+    Ok(with_bloom_filter_columns(
+        writer_properties,
+        &columns,
+        Some(table_config.logical_schema().as_ref()),
+        table_config.column_mapping_mode(),
+    ))
+}
+
+/// Writer properties for data files of a table, with the bloom filters configured in its
+/// `delta.bloomFilter.columns` property.
+///
+/// Returns `writer_properties` unchanged when the table has no bloom filter columns, so that
+/// writers keep applying their own defaults when none were given.
+pub(crate) fn table_writer_properties(
+    writer_properties: Option<WriterProperties>,
+    table_config: &TableConfiguration,
+) -> DeltaResult<Option<WriterProperties>> {
+    if table_config
+        .table_properties()
+        .unknown_properties
+        .contains_key(TableProperty::BloomFilterColumns.as_ref())
+    {
+        let writer_properties =
+            writer_properties.unwrap_or_else(|| default_writer_properties(Compression::SNAPPY));
+        return with_table_bloom_filters(writer_properties, table_config).map(Some);
+    }
+    Ok(writer_properties)
+}
+
+/// The parquet path of a possibly nested column given by its dot separated logical name.
+fn physical_column_path(
+    schema: &StructType,
+    name: &str,
+    column_mapping_mode: ColumnMappingMode,
+) -> Option<ColumnPath> {
+    let mut parts = Vec::new();
+    let mut current = schema;
+    let mut names = name.split('.').peekable();
+    while let Some(part) = names.next() {
+        let field = current.field(part)?;
+        parts.push(field.physical_name(column_mapping_mode).to_string());
+        if names.peek().is_some() {
+            let DataType::Struct(inner) = field.data_type() else {
+                return None;
+            };
+            current = inner.as_ref();
+        }
+    }
+    Some(ColumnPath::new(parts))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use parquet::schema::types::ColumnPath;
+    use delta_kernel::schema::{PrimitiveType, StructField};
 
     #[test]
     fn default_writer_properties_sets_created_by_and_compression() {
@@ -26,4 +135,48 @@ mod tests {
             Compression::SNAPPY
         );
     }
+
+    #[test]
+    fn with_bloom_filter_columns_resolves_nested_columns() {
+        let schema = StructType::try_new(vec![
+            StructField::nullable("id", PrimitiveType::Long),
+            StructField::nullable(
+                "user",
+                StructType::try_new(vec![StructField::nullable("email", PrimitiveType::String)])
+                    .unwrap(),
+            ),
+        ])
+        .unwrap();
+        let columns = crate::table::config::parse_bloom_filter_columns(
+            "id:fpp=0.01:ndv=100,user.email,missing",
+        )
+        .unwrap();
+
+        let writer_properties = with_bloom_filter_columns(
+            default_writer_properties(Compression::SNAPPY),
+            &columns,
+            Some(&schema),
+            ColumnMappingMode::None,
+        );
+
+        let id = writer_properties
+            .bloom_filter_properties(&ColumnPath::from("id"))
+            .unwrap();
+        assert_eq!(id.fpp, 0.01);
+        assert_eq!(id.ndv, 100);
+        assert!(
+            writer_properties
+                .bloom_filter_properties(&ColumnPath::new(vec!["user".into(), "email".into()]))
+                .is_some()
+        );
+        assert!(
+            writer_properties
+                .bloom_filter_properties(&ColumnPath::from("missing"))
+                .is_none()
+        );
+        assert_eq!(
+            writer_properties.created_by(),
+            format!("delta-rs version {}", crate::crate_version())
+        );
+    }
 }
@@ -523,16 +523,23 @@ mod tests {
             )
             .await
             .unwrap();
-            assert_eq!(count, 1);
+            // the commit and its version checksum
+            assert_eq!(count, 2);
 
             let log_store = table.log_store();
 
+            for file in ["00000000000000000000.json", "00000000000000000000.crc"] {
+                let path = log_store.log_path().clone().join(file);
+                let res = table.log_store().object_store(None).get(&path).await;
+                assert!(res.is_err(), "{file}");
+            }
+
             let path = log_store
                 .log_path()
                 .clone()
-                .join("00000000000000000000.json");
+                .join("00000000000000000001.crc");
             let res = table.log_store().object_store(None).get(&path).await;
-            assert!(res.is_err());
+            assert!(res.is_ok());
 
             let path = log_store
                 .log_path()
@@ -0,0 +1,398 @@
+//! Reading and writing version checksum files.
+//!
+//! A version checksum (`_delta_log/<version>.crc`) summarizes the state of a table at a
+//! version: its size, number of files, metadata, protocol and a histogram of the file sizes.
+//! Checksums are written after a commit, from the files of the post-commit snapshot if they
+//! are loaded, otherwise incrementally from the checksum of the previous version and the
+//! actions of the commit. [`Snapshot::try_new`] takes the protocol and metadata from the
+//! checksum of the loaded version, if it exists, instead of replaying the log.
+use object_store::path::Path;
+use object_store::{Error as ObjectStoreError, ObjectStoreExt as _};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::kernel::{
+    Action, CommitInfo, DomainMetadata, LogDataHandler, Metadata, Protocol, Snapshot, Transaction,
+    Version,
+};
+use crate::logstore::LogStore;
+use crate::operations::describe::FileSizeHistogram;
+use crate::{DeltaResult, DeltaTableConfig, DeltaTableError};
+
+/// Contents of a version checksum file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionChecksum {
+    /// Id of the transaction that committed the version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txn_id: Option<String>,
+    /// Total size of the active files in bytes
+    pub table_size_bytes: i64,
+    /// Number of active files
+    pub num_files: i64,
+    /// Number of metadata actions, always 1
+    pub num_metadata: i64,
+    /// Number of protocol actions, always 1
+    pub num_protocol: i64,
+    /// In-commit timestamp of the version, if enabled on the table
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_commit_timestamp_opt: Option<i64>,
+    /// Active application transactions, if tracked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_transactions: Option<Vec<Transaction>>,
+    /// Active domain metadata, if tracked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain_metadata: Option<Vec<DomainMetadata>>,
+    /// Metadata of the table
+    pub metadata: Metadata,
+    /// Protocol of the table
+    pub protocol: Protocol,
+    /// Histogram of the sizes of the active files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size_histogram: Option<FileSizeHistogram>,
+    /// Number of rows marked as deleted by deletion vectors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_deleted_records_opt: Option<i64>,
+    /// Number of active files with a deletion vector
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_deletion_vectors_opt: Option<i64>,
+}
+
+impl VersionChecksum {
+    /// Compute the checksum of the version committing `actions` from the files of its
+    /// in-memory snapshot
+    pub(crate) fn from_log_data(log_data: LogDataHandler<'_>, actions: &[Action]) -> Self {
+        let mut checksum = Self::empty(log_data.metadata().clone(), log_data.protocol().clone());
+        for file in log_data.iter() {
+            checksum.add_file(
+                file.size(),
+                file.deletion_vector_descriptor().map(|dv| dv.cardinality),
+            );
+        }
+        for action in actions {
+            if let Action::CommitInfo(info) = action {
+                checksum.set_commit_info(info);
+            }
+        }
+        checksum
+    }
+
+    /// Compute the checksum of the version committing `actions` from the checksum of the
+    /// previous version.
+    ///
+    /// Without a previous checksum, the actions must create the table. Returns `None` if the
+    /// checksum cannot be derived, e.g. because a `remove` action does not record the file size.
+    pub(crate) fn try_apply(previous: Option<&Self>, actions: &[Action]) -> Option<Self> {
+        let mut checksum = match previous {
+            Some(previous) => Self {
+                txn_id: None,
+                in_commit_timestamp_opt: None,
+                ..previous.clone()
+            },
+            None => {
+                let metadata = actions.iter().find_map(|action| match action {
+                    Action::Metadata(metadata) => Some(metadata.clone()),
+                    _ => None,
+                })?;
+                let protocol = actions.iter().find_map(|action| match action {
+                    Action::Protocol(protocol) => Some(protocol.clone()),
+                    _ => None,
+                })?;
+                let mut checksum = Self::empty(metadata, protocol);
+                checksum.set_transactions = Some(Vec::new());
+                checksum.domain_metadata = Some(Vec::new());
+                checksum
+            }
+        };
+
+        for action in actions {
+            match action {
+                Action::Add(add) => checksum.add_file(
+                    add.size,
+                    add.deletion_vector.as_ref().map(|dv| dv.cardinality),
+                ),
+                Action::Remove(remove) => checksum.remove_file(
+                    remove.size?,
+                    remove.deletion_vector.as_ref().map(|dv| dv.cardinality),
+                ),
+                Action::Metadata(metadata) => checksum.metadata = metadata.clone(),
+                Action::Protocol(protocol) => checksum.protocol = protocol.clone(),
+                Action::Txn(txn) => {
+                    if let Some(txns) = checksum.set_transactions.as_mut() {
+                        txns.retain(|t| t.app_id != txn.app_id);
+                        txns.push(txn.clone());
+                    }
+                }
+                Action::DomainMetadata(domain) => {
+                    if let Some(domains) = checksum.domain_metadata.as_mut() {
+                        domains.retain(|d| d.domain != domain.domain);
+                        if !domain.removed {
+                            domains.push(domain.clone());
+                        }
+                    }
+                }
+                Action::CommitInfo(info) => checksum.set_commit_info(info),
+                Action::Cdc(_) => {}
+            }
+        }
+        Some(checksum)
+    }
+
+    fn empty(metadata: Metadata, protocol: Protocol) -> Self {
+        Self {
+            txn_id: None,
+            table_size_bytes: 0,
+            num_files: 0,
+            num_metadata: 1,
+            num_protocol: 1,
+            in_commit_timestamp_opt: None,
+            set_transactions: None,
+            domain_metadata: None,
+            metadata,
+            protocol,
+            file_size_histogram: Some(FileSizeHistogram::default()),
+            num_deleted_records_opt: Some(0),
+            num_deletion_vectors_opt: Some(0),
+        }
+    }
+
+    fn set_commit_info(&mut self, info: &CommitInfo) {
+        self.txn_id = info
+            .info
+            .get("txnId")
+            .and_then(|v| v.as_str())
+            .map(ToString::to_string);
+        self.in_commit_timestamp_opt = info.info.get("inCommitTimestamp").and_then(|v| v.as_i64());
+    }
+
+    fn add_file(&mut self, size: i64, deleted_rows: Option<i64>) {
+        self.num_files += 1;
+        self.table_size_bytes += size;
+        if let Some(histogram) = self.file_size_histogram.as_mut() {
+            histogram.insert(size);
+        }
+        if let Some(deleted_rows) = deleted_rows {
+            self.num_deletion_vectors_opt = self.num_deletion_vectors_opt.map(|n| n + 1);
+            self.num_deleted_records_opt = self.num_deleted_records_opt.map(|n| n + deleted_rows);
+        }
+    }
+
+    fn remove_file(&mut self, size: i64, deleted_rows: Option<i64>) {
+        self.num_files -= 1;
+        self.table_size_bytes -= size;
+        if let Some(histogram) = self.file_size_histogram.as_mut() {
+            histogram.remove(size);
+        }
+        if let Some(deleted_rows) = deleted_rows {
+            self.num_deletion_vectors_opt = self.num_deletion_vectors_opt.map(|n| n - 1);
+            self.num_deleted_records_opt = self.num_deleted_records_opt.map(|n| n - deleted_rows);
+        }
+    }
+}
+
+/// Path of the checksum file of the given version
+pub(crate) fn checksum_path(log_store: &dyn LogStore, version: Version) -> Path {
+    log_store.log_path().child(format!("{version:020}.crc"))
+}
+
+/// Read the checksum file of the given version, `None` if the version has no checksum
+pub async fn read_version_checksum(
+    log_store: &dyn LogStore,
+    version: Version,
+) -> DeltaResult<Option<VersionChecksum>> {
+    let path = checksum_path(log_store, version);
+    let bytes = match log_store.object_store(None).get(&path).await {
+        Ok(result) => result.bytes().await?,
+        Err(ObjectStoreError::NotFound { .. }) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let checksum =
+        serde_json::from_slice(&bytes).map_err(|json_err| DeltaTableError::InvalidJsonLog {
+            json_err,
+            line: String::from_utf8_lossy(&bytes).to_string(),
+            version,
+        })?;
+    Ok(Some(checksum))
+}
+
+/// Write the checksum file of the given version
+pub(crate) async fn write_version_checksum(
+    log_store: &dyn LogStore,
+    version: Version,
+    checksum: &VersionChecksum,
+) -> DeltaResult<()> {
+    let bytes = serde_json::to_vec(checksum)
+        .map_err(|json_err| DeltaTableError::SerializeLogJson { json_err })?;
+    log_store
+        .object_store(None)
+        .put(&checksum_path(log_store, version), bytes.into())
+        .await?;
+    debug!(version, "version checksum written");
+    Ok(())
+}
+
+/// Load the protocol and metadata of a table version, latest if `None`.
+///
+/// The protocol and metadata are taken from the checksum file of the version if it exists,
+/// otherwise they are loaded by replaying the log.
+pub async fn load_protocol_and_metadata(
+    log_store: &dyn LogStore,
+    version: Option<Version>,
+) -> DeltaResult<(Version, Protocol, Metadata)> {
+    let snapshot = Snapshot::try_new(
+        log_store,
+        DeltaTableConfig {
+            require_files: false,
+            ..Default::default()
+        },
+        version,
+    )
+    .await?;
+    Ok((
+        snapshot.version(),
+        snapshot.protocol().clone(),
+        snapshot.metadata().clone(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeltaTable;
+    use crate::kernel::transaction::{CommitBuilder, CommitProperties};
+    use crate::kernel::{Add, DataType, MetadataExt as _, PrimitiveType, Remove};
+    use crate::protocol::{DeltaOperation, SaveMode};
+
+    fn add(path: &str, size: i64) -> Action {
+        Action::Add(Add {
+            path: path.into(),
+            size,
+            data_change: true,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_version_checksum_written_on_commit() -> DeltaResult<()> {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_column("id", DataType::Primitive(PrimitiveType::Long), false, None)
+            .with_actions(vec![add("a.parquet", 100), add("b.parquet", 10_000)])
+            .await?;
+        let log_store = table.log_store();
+
+        let checksum = read_version_checksum(log_store.as_ref(), 0)
+            .await?
+            .expect("checksum of version 0");
+        assert_eq!(checksum.num_files, 2);
+        assert_eq!(checksum.table_size_bytes, 10_100);
+        assert_eq!(checksum.metadata.id(), table.snapshot()?.metadata().id());
+        let histogram = checksum.file_size_histogram.as_ref().unwrap();
+        assert_eq!(histogram.file_counts[0], 1);
+        assert_eq!(histogram.file_counts[1], 1);
+
+        let actions = vec![
+            Action::Remove(Remove {
+                path: "a.parquet".into(),
+                size: Some(100),
+                data_change: true,
+                deletion_timestamp: Some(0),
+                ..Default::default()
+            }),
+            add("c.parquet", 200),
+        ];
+        let operation = DeltaOperation::Write {
+            mode: SaveMode::Append,
+            partition_by: None,
+            predicate: None,
+        };
+        let commit = CommitBuilder::from(CommitProperties::default())
+            .with_actions(actions.clone())
+            .build(Some(table.snapshot()?), log_store.clone(), operation)
+            .await?;
+
+        let checksum = read_version_checksum(log_store.as_ref(), commit.version)
+            .await?
+            .expect("checksum of version 1");
+        // the checksum is computed from the in-memory snapshot, the incremental checksum agrees
+        let expected = VersionChecksum::try_apply(
+            read_version_checksum(log_store.as_ref(), 0).await?.as_ref(),
+            &actions,
+        )
+        .unwrap();
+        assert_eq!(checksum.num_files, 2);
+        assert_eq!(checksum.table_size_bytes, 10_200);
+        assert_eq!(checksum.file_size_histogram, expected.file_size_histogram);
+        assert_eq!(checksum.num_files, expected.num_files);
+
+        let (version, protocol, metadata) =
+            load_protocol_and_metadata(log_store.as_ref(), None).await?;
+        assert_eq!(version, 1);
+        assert_eq!(&protocol, table.snapshot()?.protocol());
+        assert_eq!(metadata.id(), checksum.metadata.id());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_takes_protocol_and_metadata_from_checksum() -> DeltaResult<()> {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_column("id", DataType::Primitive(PrimitiveType::Long), false, None)
+            .with_actions(vec![add("a.parquet", 100)])
+            .await?;
+        let log_store = table.log_store();
+
+        // a description only present in the checksum shows that the log was not replayed
+        let mut checksum = read_version_checksum(log_store.as_ref(), 0)
+            .await?
+            .expect("checksum of version 0");
+        checksum.metadata = checksum
+            .metadata
+            .with_description("from checksum".to_string())?;
+        write_version_checksum(log_store.as_ref(), 0, &checksum).await?;
+
+        let snapshot = Snapshot::try_new(log_store.as_ref(), Default::default(), None).await?;
+        assert_eq!(snapshot.metadata().description(), Some("from checksum"));
+
+        log_store
+            .object_store(None)
+            .delete(&checksum_path(log_store.as_ref(), 0))
+            .await?;
+        let snapshot = Snapshot::try_new(log_store.as_ref(), Default::default(), None).await?;
+        assert_eq!(snapshot.metadata().description(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_checksum_requires_remove_size() {
+        let previous = serde_json::from_value::<VersionChecksum>(serde_json::json!({
+            "tableSizeBytes": 100,
+            "numFiles": 1,
+            "numMetadata": 1,
+            "numProtocol": 1,
+            "metadata": {
+                "id": "test",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": "{\"type\":\"struct\",\"fields\":[]}",
+                "partitionColumns": [],
+                "configuration": {}
+            },
+            "protocol": {"minReaderVersion": 1, "minWriterVersion": 2}
+        }))
+        .unwrap();
+        assert!(previous.file_size_histogram.is_none());
+
+        let remove = Action::Remove(Remove {
+            path: "a.parquet".into(),
+            data_change: true,
+            ..Default::default()
+        });
+        assert!(VersionChecksum::try_apply(Some(&previous), &[remove]).is_none());
+
+        let next = VersionChecksum::try_apply(Some(&previous), &[add("b.parquet", 50)]).unwrap();
+        assert_eq!(next.num_files, 2);
+        assert_eq!(next.table_size_bytes, 150);
+        assert!(next.file_size_histogram.is_none());
+        assert!(next.num_deleted_records_opt.is_none());
+    }
+}
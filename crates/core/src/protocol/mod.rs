@@ -24,6 +24,7 @@ use crate::kernel::{
 };
 
 pub mod checkpoints;
+pub mod checksum;
 pub mod log_compaction;
 
 pub(crate) use checkpoints::{cleanup_expired_logs_for, create_checkpoint_for};
@@ -237,6 +238,15 @@ pub enum DeltaOperation {
         fields: Vec<StructField>,
     },
 
+    /// Represents a Delta `Change Column` operation.
+    /// Used to change the nullability or comment of an existing column
+    ChangeColumn {
+        /// Dotted path of the changed column
+        column: String,
+        /// The column after the change
+        field: StructField,
+    },
+
     /// Represents a Delta `Create` operation.
     /// Would usually only create the table, if also data is written,
     /// a `Write` operations is more appropriate
@@ -372,6 +382,16 @@ pub enum DeltaOperation {
         /// Fields added to existing schema
         fields: Vec<StructField>,
     },
+    /// Represents a `Convert` operation, adding the files of a table in another format
+    #[serde(rename_all = "camelCase")]
+    Convert {
+        /// Number of files added or removed by the conversion
+        num_files: i64,
+        /// The columns the table is partitioned by
+        partition_by: Option<Vec<String>>,
+        /// The format of the converted table
+        source_format: String,
+    },
     /// Update table metadata operations
     #[serde(rename_all = "camelCase")]
     UpdateTableMetadata {
@@ -386,11 +406,13 @@ impl DeltaOperation {
         // operation names taken from https://learn.microsoft.com/en-us/azure/databricks/delta/history#--operation-metrics-keys
         match &self {
             DeltaOperation::AddColumn { .. } => "ADD COLUMN",
+            DeltaOperation::ChangeColumn { .. } => "CHANGE COLUMN",
             DeltaOperation::Create {
                 mode: SaveMode::Overwrite,
                 ..
             } => "CREATE OR REPLACE TABLE",
             DeltaOperation::Create { .. } => "CREATE TABLE",
+            DeltaOperation::Convert { .. } => "CONVERT",
             DeltaOperation::Write { .. } => "WRITE",
             DeltaOperation::Delete { .. } => "DELETE",
             DeltaOperation::Update { .. } => "UPDATE",
@@ -436,12 +458,14 @@ impl DeltaOperation {
             | Self::UpdateTableMetadata { .. }
             | Self::SetTableProperties { .. }
             | Self::AddColumn { .. }
+            | Self::ChangeColumn { .. }
             | Self::AddFeature { .. }
             | Self::VacuumStart { .. }
             | Self::VacuumEnd { .. }
             | Self::AddConstraint { .. }
             | Self::DropConstraint { .. } => false,
             Self::Create { .. }
+            | Self::Convert { .. }
             | Self::FileSystemCheck {}
             | Self::StreamingUpdate { .. }
             | Self::Write { .. }
@@ -480,6 +504,10 @@ impl DeltaOperation {
         match self {
             // Predicate is none -> Merge operation had to join full source and target
             Self::Merge { predicate, .. } if predicate.is_none() => true,
+            // NOT NULL columns are backfilled by rewriting every file of the table
+            Self::AddColumn { fields } => fields.iter().any(|field| !field.is_nullable()),
+            // Existing rows are validated before a column is made NOT NULL
+            Self::ChangeColumn { field, .. } => !field.is_nullable(),
             _ => false,
         }
     }
@@ -1,4 +1,4 @@
-//! Constraints and generated column mappings
+//! Constraints, generated column and column default mappings
 use serde::{Deserialize, Serialize};
 
 use crate::kernel::DataType;
@@ -81,3 +81,30 @@ impl DataCheck for GeneratedColumn {
         self
     }
 }
+
+/// A column whose value is filled from a default expression when a write omits it
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct ColumnDefault {
+    /// The full path to the field.
+    pub name: String,
+    /// The SQL string that produces the default value.
+    pub default_expr: String,
+    /// Data Type
+    pub data_type: DataType,
+}
+
+impl ColumnDefault {
+    /// Create a new column default
+    pub fn new(field_name: &str, default_expr: &str, data_type: &DataType) -> Self {
+        Self {
+            name: field_name.to_string(),
+            default_expr: default_expr.to_string(),
+            data_type: data_type.clone(),
+        }
+    }
+
+    /// Returns the SQL expression used to fill this column's values.
+    pub fn get_default_expression(&self) -> &str {
+        &self.default_expr
+    }
+}
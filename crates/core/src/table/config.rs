@@ -26,6 +26,11 @@ pub enum TableProperty {
     /// true for Delta Lake to automatically optimize the layout of the files for this Delta table during writes.
     AutoOptimizeOptimizeWrite,
 
+    /// A comma-separated list of columns for which parquet bloom filters are written, each
+    /// optionally followed by `:fpp=<false positive probability>` and `:ndv=<distinct values>`,
+    /// e.g. `user_id:fpp=0.01,email`.
+    BloomFilterColumns,
+
     /// Interval (number of commits) after which a new checkpoint should be created
     CheckpointInterval,
 
@@ -74,6 +79,10 @@ pub enum TableProperty {
     /// true to enable deletion vectors and predictive I/O for updates.
     EnableDeletionVectors,
 
+    /// true to restrict the table to what Iceberg readers support, which is required to generate
+    /// Iceberg metadata for it. Requires column mapping and excludes deletion vectors.
+    EnableIcebergCompatV2,
+
     /// The degree to which a transaction must be isolated from modifications made by concurrent transactions.
     ///
     /// Valid values are `Serializable` and `WriteSerializable`.
@@ -119,6 +128,10 @@ pub enum TableProperty {
 
     /// 'classic' for classic Delta Lake checkpoints. 'v2' for v2 checkpoints.
     CheckpointPolicy,
+
+    /// A comma-separated list of formats whose metadata is generated after every commit.
+    /// Only `iceberg` is supported, which requires [EnableIcebergCompatV2](Self::EnableIcebergCompatV2).
+    UniversalFormatEnabledFormats,
 }
 
 impl AsRef<str> for TableProperty {
@@ -128,6 +141,7 @@ impl AsRef<str> for TableProperty {
             Self::CheckpointInterval => "delta.checkpointInterval",
             Self::AutoOptimizeAutoCompact => "delta.autoOptimize.autoCompact",
             Self::AutoOptimizeOptimizeWrite => "delta.autoOptimize.optimizeWrite",
+            Self::BloomFilterColumns => "delta.bloomFilter.columns",
             Self::CheckpointWriteStatsAsJson => "delta.checkpoint.writeStatsAsJson",
             Self::CheckpointWriteStatsAsStruct => "delta.checkpoint.writeStatsAsStruct",
             Self::CheckpointUseRunLengthEncoding => "delta-rs.checkpoint.useRunLengthEncoding",
@@ -138,6 +152,7 @@ impl AsRef<str> for TableProperty {
             Self::DeletedFileRetentionDuration => "delta.deletedFileRetentionDuration",
             Self::EnableChangeDataFeed => "delta.enableChangeDataFeed",
             Self::EnableDeletionVectors => "delta.enableDeletionVectors",
+            Self::EnableIcebergCompatV2 => "delta.enableIcebergCompatV2",
             Self::IsolationLevel => "delta.isolationLevel",
             Self::LogRetentionDuration => "delta.logRetentionDuration",
             Self::EnableExpiredLogCleanup => "delta.enableExpiredLogCleanup",
@@ -148,6 +163,7 @@ impl AsRef<str> for TableProperty {
             Self::SetTransactionRetentionDuration => "delta.setTransactionRetentionDuration",
             Self::TargetFileSize => "delta.targetFileSize",
             Self::TuneFileSizesForRewrites => "delta.tuneFileSizesForRewrites",
+            Self::UniversalFormatEnabledFormats => "delta.universalFormat.enabledFormats",
         }
     }
 }
@@ -161,6 +177,7 @@ impl FromStr for TableProperty {
             "delta.checkpointInterval" => Ok(Self::CheckpointInterval),
             "delta.autoOptimize.autoCompact" => Ok(Self::AutoOptimizeAutoCompact),
             "delta.autoOptimize.optimizeWrite" => Ok(Self::AutoOptimizeOptimizeWrite),
+            "delta.bloomFilter.columns" => Ok(Self::BloomFilterColumns),
             "delta.checkpoint.writeStatsAsJson" => Ok(Self::CheckpointWriteStatsAsJson),
             "delta.checkpoint.writeStatsAsStruct" => Ok(Self::CheckpointWriteStatsAsStruct),
             "delta-rs.checkpoint.useRunLengthEncoding" => Ok(Self::CheckpointUseRunLengthEncoding),
@@ -173,6 +190,7 @@ impl FromStr for TableProperty {
             }
             "delta.enableChangeDataFeed" => Ok(Self::EnableChangeDataFeed),
             "delta.enableDeletionVectors" => Ok(Self::EnableDeletionVectors),
+            "delta.enableIcebergCompatV2" => Ok(Self::EnableIcebergCompatV2),
             "delta.isolationLevel" => Ok(Self::IsolationLevel),
             "delta.logRetentionDuration" | "logRetentionDuration" => Ok(Self::LogRetentionDuration),
             "delta.enableExpiredLogCleanup" | "enableExpiredLogCleanup" => {
@@ -185,6 +203,7 @@ impl FromStr for TableProperty {
             "delta.setTransactionRetentionDuration" => Ok(Self::SetTransactionRetentionDuration),
             "delta.targetFileSize" => Ok(Self::TargetFileSize),
             "delta.tuneFileSizesForRewrites" => Ok(Self::TuneFileSizesForRewrites),
+            "delta.universalFormat.enabledFormats" => Ok(Self::UniversalFormatEnabledFormats),
             _ => Err(DeltaTableError::Generic("unknown config key".into())),
         }
     }
@@ -198,6 +217,55 @@ pub enum DeltaConfigError {
     Validation(String),
 }
 
+/// A column for which parquet bloom filters are written, see
+/// [`TableProperty::BloomFilterColumns`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomFilterColumn {
+    /// Column name, nested struct fields are separated by dots
+    pub name: String,
+    /// False positive probability of the bloom filter
+    pub fpp: Option<f64>,
+    /// Expected number of distinct values in a row group
+    pub ndv: Option<u64>,
+}
+
+/// Parse the value of the `delta.bloomFilter.columns` table property.
+pub fn parse_bloom_filter_columns(value: &str) -> Result<Vec<BloomFilterColumn>, DeltaConfigError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let mut parts = entry.split(':').map(str::trim);
+            let mut column = BloomFilterColumn {
+                name: parts.next().unwrap_or_default().to_string(),
+                fpp: None,
+                ndv: None,
+            };
+            for option in parts {
+                let invalid = || {
+                    DeltaConfigError::Validation(format!(
+                        "Invalid bloom filter option '{option}' for column '{}'",
+                        column.name
+                    ))
+                };
+                match option.split_once('=').ok_or_else(invalid)? {
+                    ("fpp", fpp) => {
+                        let fpp = fpp.parse::<f64>().map_err(|_| invalid())?;
+                        if !(fpp > 0.0 && fpp < 1.0) {
+                            return Err(invalid());
+                        }
+                        column.fpp = Some(fpp);
+                    }
+                    ("ndv", ndv) => column.ndv = Some(ndv.parse().map_err(|_| invalid())?),
+                    _ => return Err(invalid()),
+                }
+            }
+            Ok(column)
+        })
+        .collect()
+}
+
 /// Default num index cols
 pub const DEFAULT_NUM_INDEX_COLS: u64 = 32;
 /// Default target file size
@@ -245,6 +313,9 @@ pub trait TablePropertiesExt {
 
     /// The list of constraints (e.g. CHECK constraints) declared on the table.
     fn get_constraints(&self) -> Vec<Constraint>;
+
+    /// Columns for which parquet bloom filters are written.
+    fn bloom_filter_columns(&self) -> Result<Vec<BloomFilterColumn>, DeltaConfigError>;
 }
 
 impl TablePropertiesExt for TableProperties {
@@ -309,6 +380,14 @@ impl TablePropertiesExt for TableProperties {
             })
             .collect()
     }
+
+    fn bloom_filter_columns(&self) -> Result<Vec<BloomFilterColumn>, DeltaConfigError> {
+        self.unknown_properties
+            .get(TableProperty::BloomFilterColumns.as_ref())
+            .map(|value| parse_bloom_filter_columns(value))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
 }
 
 const SECONDS_PER_MINUTE: u64 = 60;
@@ -361,6 +440,35 @@ fn parse_int(value: &str) -> Result<i64, DeltaConfigError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_bloom_filter_columns_test() {
+        assert_eq!(
+            parse_bloom_filter_columns("user_id:fpp=0.01, nested.email:ndv=1000:fpp=0.1,id")
+                .unwrap(),
+            vec![
+                BloomFilterColumn {
+                    name: "user_id".into(),
+                    fpp: Some(0.01),
+                    ndv: None,
+                },
+                BloomFilterColumn {
+                    name: "nested.email".into(),
+                    fpp: Some(0.1),
+                    ndv: Some(1000),
+                },
+                BloomFilterColumn {
+                    name: "id".into(),
+                    fpp: None,
+                    ndv: None,
+                },
+            ]
+        );
+        assert!(parse_bloom_filter_columns("").unwrap().is_empty());
+        assert!(parse_bloom_filter_columns("id:fpp=2").is_err());
+        assert!(parse_bloom_filter_columns("id:size=2").is_err());
+        assert!(parse_bloom_filter_columns("id:ndv").is_err());
+    }
+
     #[test]
     fn parse_interval_test() {
         assert_eq!(
@@ -278,6 +278,27 @@ impl DeltaTable {
         Ok(infos.into_iter().flatten())
     }
 
+    /// Like [`DeltaTable::history`], but pairs each commit info with the version of its commit.
+    ///
+    /// The versions are taken from the names of the commit files, so they are correct for tables
+    /// loaded at an older version and for logs whose older commits were cleaned up.
+    pub async fn history_with_versions(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<impl Iterator<Item = (Version, CommitInfo)> + use<>, DeltaTableError> {
+        let infos = self
+            .snapshot()?
+            .snapshot()
+            .snapshot()
+            .versioned_commit_infos(&self.log_store(), limit)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(infos
+            .into_iter()
+            .filter_map(|(version, info)| info.map(|info| (version, info))))
+    }
+
     #[cfg(test)]
     /// We have enough internal tests that just need to check the last commit of the table.
     ///
@@ -0,0 +1,199 @@
+//! Handling of records which the [`JsonWriter`](super::JsonWriter) cannot write.
+//!
+//! The [`BadRecordPolicy`] decides whether a record which can not be decoded or encoded fails the
+//! write, is skipped, or is sent to a [`DeadLetterSink`] together with the reason it was rejected.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, RecordBatch, StringArray, new_null_array};
+use async_trait::async_trait;
+use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use super::{DeltaWriter, RecordBatchWriter};
+use crate::DeltaTable;
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::{DataType, PrimitiveType, StructField};
+
+/// A JSON value which was rejected by the writer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedValue {
+    /// The rejected value as it was passed to the writer
+    pub value: Value,
+    /// Why the value was rejected
+    pub reason: String,
+}
+
+impl RejectedValue {
+    /// Create a new [`RejectedValue`]
+    pub fn new(value: Value, reason: impl Into<String>) -> Self {
+        Self {
+            value,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Destination for values rejected by the writer.
+#[async_trait]
+pub trait DeadLetterSink: Send + Sync + Debug {
+    /// Deliver the rejected values.
+    ///
+    /// If this returns an error, the write which rejected the values fails as well.
+    async fn send(&self, rejected: Vec<RejectedValue>) -> DeltaResult<()>;
+}
+
+/// What the writer does with records it cannot write.
+#[derive(Debug, Clone, Default)]
+pub enum BadRecordPolicy {
+    /// Fail the write, returning the rejected records in the error where possible.
+    #[default]
+    Fail,
+    /// Drop rejected records, they are only counted in the write report.
+    Skip,
+    /// Send rejected records to a [`DeadLetterSink`].
+    DeadLetter(Arc<dyn DeadLetterSink>),
+}
+
+/// A [`DeadLetterSink`] which appends rejected values to a Delta table.
+///
+/// The table must have the string columns [`RAW_COLUMN`](Self::RAW_COLUMN) and
+/// [`ERROR_COLUMN`](Self::ERROR_COLUMN), any other column must be nullable. Every call to
+/// [`send`](DeadLetterSink::send) commits a new version to the table.
+#[derive(Debug)]
+pub struct DeltaTableDeadLetterSink {
+    table: Mutex<DeltaTable>,
+}
+
+impl DeltaTableDeadLetterSink {
+    /// Column holding the rejected value serialized as JSON
+    pub const RAW_COLUMN: &'static str = "_raw";
+    /// Column holding the reason the value was rejected
+    pub const ERROR_COLUMN: &'static str = "_error";
+
+    /// Create a sink writing to an existing table.
+    pub fn try_new(table: DeltaTable) -> DeltaResult<Self> {
+        let schema = table.snapshot()?.schema();
+        for name in [Self::RAW_COLUMN, Self::ERROR_COLUMN] {
+            match schema.field(name) {
+                Some(field) if field.data_type() == &DataType::Primitive(PrimitiveType::String) => {
+                }
+                _ => {
+                    return Err(DeltaTableError::Generic(format!(
+                        "dead letter table must have a string column '{name}'"
+                    )));
+                }
+            }
+        }
+        if let Some(field) = schema.fields().find(|f| {
+            !f.is_nullable() && f.name() != Self::RAW_COLUMN && f.name() != Self::ERROR_COLUMN
+        }) {
+            return Err(DeltaTableError::Generic(format!(
+                "dead letter table column '{}' must be nullable",
+                field.name()
+            )));
+        }
+        Ok(Self {
+            table: Mutex::new(table),
+        })
+    }
+
+    /// Columns for creating a dead letter table.
+    pub fn columns() -> Vec<StructField> {
+        vec![
+            StructField::new(Self::RAW_COLUMN, PrimitiveType::String, false),
+            StructField::new(Self::ERROR_COLUMN, PrimitiveType::String, false),
+        ]
+    }
+}
+
+#[async_trait]
+impl DeadLetterSink for DeltaTableDeadLetterSink {
+    async fn send(&self, rejected: Vec<RejectedValue>) -> DeltaResult<()> {
+        if rejected.is_empty() {
+            return Ok(());
+        }
+        let mut table = self.table.lock().await;
+        let arrow_schema: arrow_schema::Schema =
+            table.snapshot()?.schema().as_ref().try_into_arrow()?;
+        let arrow_schema = Arc::new(arrow_schema);
+
+        let columns = arrow_schema
+            .fields()
+            .iter()
+            .map(|field| -> DeltaResult<ArrayRef> {
+                Ok(match field.name().as_str() {
+                    Self::RAW_COLUMN => Arc::new(StringArray::from_iter_values(
+                        rejected
+                            .iter()
+                            .map(|r| serde_json::to_string(&r.value))
+                            .collect::<Result<Vec<_>, _>>()?,
+                    )),
+                    Self::ERROR_COLUMN => Arc::new(StringArray::from_iter_values(
+                        rejected.iter().map(|r| r.reason.as_str()),
+                    )),
+                    _ => new_null_array(field.data_type(), rejected.len()),
+                })
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+        let batch = RecordBatch::try_new(arrow_schema, columns)?;
+
+        let mut writer = RecordBatchWriter::for_table(&table)?;
+        writer.write(batch).await?;
+        writer.flush_and_commit(&mut table).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::create::CreateBuilder;
+
+    #[tokio::test]
+    async fn test_delta_table_dead_letter_sink() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = CreateBuilder::new()
+            .with_location(table_dir.path().to_str().unwrap())
+            .with_columns(DeltaTableDeadLetterSink::columns())
+            .await
+            .unwrap();
+        let sink = DeltaTableDeadLetterSink::try_new(table).unwrap();
+
+        sink.send(vec![
+            RejectedValue::new(serde_json::json!({"id": "A"}), "bad value"),
+            RejectedValue::new(serde_json::json!(42), "not an object"),
+        ])
+        .await
+        .unwrap();
+
+        let table = sink.table.lock().await;
+        assert_eq!(table.version(), Some(1));
+        let num_records: usize = table
+            .snapshot()
+            .unwrap()
+            .log_data()
+            .iter()
+            .map(|f| f.num_records().unwrap())
+            .sum();
+        assert_eq!(num_records, 2);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_table_requires_columns() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = CreateBuilder::new()
+            .with_location(table_dir.path().to_str().unwrap())
+            .with_column(
+                "_raw",
+                DataType::Primitive(PrimitiveType::String),
+                true,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(DeltaTableDeadLetterSink::try_new(table).is_err());
+    }
+}
@@ -1,31 +1,30 @@
 //! Main writer API to write json messages to delta table
 use std::collections::HashMap;
+use std::ops::AddAssign;
 use std::sync::Arc;
 
 use arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
 use arrow::record_batch::*;
-use bytes::Bytes;
 use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
 use delta_kernel::expressions::Scalar;
 use indexmap::IndexMap;
 use itertools::Itertools;
-use object_store::path::Path;
+use object_store::ObjectStore;
 use parquet::{arrow::ArrowWriter, errors::ParquetError, file::properties::WriterProperties};
 use serde_json::Value;
 use tracing::*;
 use url::Url;
-use uuid::Uuid;
 
+use super::dead_letter::{BadRecordPolicy, RejectedValue};
 use super::stats::create_add;
+use super::streaming::{DataFileSink, StreamingUploadConfig};
 use super::utils::{
-    arrow_schema_without_partitions, next_data_path, record_batch_from_message,
-    record_batch_without_partitions,
+    arrow_schema_without_partitions, record_batch_from_message, record_batch_without_partitions,
 };
 use super::{DeltaWriter, DeltaWriterError, WriteMode, ensure_legacy_writer_supports_table};
 use crate::DeltaTable;
 use crate::errors::DeltaTableError;
-use crate::kernel::{Add, PartitionsExt, scalars::ScalarExt};
-use crate::logstore::ObjectStoreRetryExt;
+use crate::kernel::{Add, scalars::ScalarExt};
 use crate::parquet_utils::default_writer_properties;
 use crate::table::builder::DeltaTableBuilder;
 use crate::table::config::TablePropertiesExt as _;
@@ -42,6 +41,25 @@ pub struct JsonWriter {
     writer_properties: WriterProperties,
     partition_columns: Vec<String>,
     arrow_writers: HashMap<String, DataArrowWriter>,
+    streaming: Option<StreamingUploadConfig>,
+    bad_record_policy: BadRecordPolicy,
+    report: JsonWriteReport,
+}
+
+/// Number of values accepted and rejected by a [`JsonWriter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonWriteReport {
+    /// Values written to the internal buffers
+    pub accepted: usize,
+    /// Values rejected according to the [`BadRecordPolicy`]
+    pub rejected: usize,
+}
+
+impl AddAssign for JsonWriteReport {
+    fn add_assign(&mut self, other: Self) {
+        self.accepted += other.accepted;
+        self.rejected += other.rejected;
+    }
 }
 
 /// Writes messages to an underlying arrow buffer.
@@ -49,8 +67,7 @@ pub struct JsonWriter {
 pub(crate) struct DataArrowWriter {
     arrow_schema: Arc<ArrowSchema>,
     writer_properties: WriterProperties,
-    buffer: ShareableBuffer,
-    arrow_writer: ArrowWriter<ShareableBuffer>,
+    sink: DataFileSink,
     partition_values: IndexMap<String, Scalar>,
     buffered_record_batch_count: usize,
 }
@@ -60,12 +77,32 @@ impl DataArrowWriter {
     /// This method buffers the write stream internally so it can be invoked for many json buffers and flushed after the appropriate number of bytes has been written.
     async fn write_values(
         &mut self,
+        object_store: &Arc<dyn ObjectStore>,
         partition_columns: &[String],
         arrow_schema: Arc<ArrowSchema>,
         json_buffer: Vec<Value>,
     ) -> Result<(), DeltaWriterError> {
         let record_batch = record_batch_from_message(arrow_schema.clone(), json_buffer.as_slice())?;
+        self.write_decoded(
+            object_store,
+            partition_columns,
+            arrow_schema,
+            json_buffer,
+            record_batch,
+        )
+        .await
+    }
 
+    /// Writes a record batch which was decoded from the given JSON buffer, quarantining the
+    /// values which fail to encode.
+    async fn write_decoded(
+        &mut self,
+        object_store: &Arc<dyn ObjectStore>,
+        partition_columns: &[String],
+        arrow_schema: Arc<ArrowSchema>,
+        json_buffer: Vec<Value>,
+        record_batch: RecordBatch,
+    ) -> Result<(), DeltaWriterError> {
         if record_batch.schema() != arrow_schema {
             return Err(DeltaWriterError::SchemaMismatch {
                 record_batch_schema: record_batch.schema(),
@@ -74,12 +111,18 @@ impl DataArrowWriter {
         }
 
         let result = self
-            .write_record_batch(partition_columns, record_batch)
+            .write_record_batch(object_store, partition_columns, record_batch)
             .await;
 
         if let Err(DeltaWriterError::Parquet { source }) = result {
-            self.write_partial(partition_columns, arrow_schema, json_buffer, source)
-                .await
+            self.write_partial(
+                object_store,
+                partition_columns,
+                arrow_schema,
+                json_buffer,
+                source,
+            )
+            .await
         } else {
             result
         }
@@ -87,6 +130,7 @@ impl DataArrowWriter {
 
     async fn write_partial(
         &mut self,
+        object_store: &Arc<dyn ObjectStore>,
         partition_columns: &[String],
         arrow_schema: Arc<ArrowSchema>,
         json_buffer: Vec<Value>,
@@ -97,7 +141,7 @@ impl DataArrowWriter {
         );
         let (good, bad) = quarantine_failed_parquet_rows(arrow_schema.clone(), json_buffer)?;
         let record_batch = record_batch_from_message(arrow_schema, good.as_slice())?;
-        self.write_record_batch(partition_columns, record_batch)
+        self.write_record_batch(object_store, partition_columns, record_batch)
             .await?;
         info!(
             "Wrote {} good records to record batch and quarantined {} bad records.",
@@ -114,6 +158,7 @@ impl DataArrowWriter {
     /// This method buffers the write stream internally so it can be invoked for many record batches and flushed after the appropriate number of bytes has been written.
     async fn write_record_batch(
         &mut self,
+        object_store: &Arc<dyn ObjectStore>,
         partition_columns: &[String],
         record_batch: RecordBatch,
     ) -> Result<(), DeltaWriterError> {
@@ -122,30 +167,27 @@ impl DataArrowWriter {
             self.partition_values = partition_values;
         }
 
-        // Copy current buffered bytes so we can recover from failures
-        let buffer_bytes = self.buffer.to_vec();
-
         let record_batch = record_batch_without_partitions(&record_batch, partition_columns)?;
-        let result = self.arrow_writer.write(&record_batch);
+        let result = self
+            .sink
+            .write(
+                object_store,
+                &self.arrow_schema,
+                &self.writer_properties,
+                &self.partition_values,
+                &record_batch,
+            )
+            .await;
 
         match result {
             Ok(_) => {
                 self.buffered_record_batch_count += 1;
                 Ok(())
             }
-            // If a write fails we need to reset the state of the DeltaArrowWriter
+            // The sink restores its state on failure, only the partition values need a reset
             Err(e) => {
-                let new_buffer = ShareableBuffer::from_bytes(buffer_bytes.as_slice());
-                let _ = std::mem::replace(&mut self.buffer, new_buffer.clone());
-                let arrow_writer = Self::new_underlying_writer(
-                    new_buffer,
-                    self.arrow_schema.clone(),
-                    self.writer_properties.clone(),
-                )?;
-                let _ = std::mem::replace(&mut self.arrow_writer, arrow_writer);
                 self.partition_values.clear();
-
-                Err(e.into())
+                Err(e)
             }
         }
     }
@@ -153,13 +195,10 @@ impl DataArrowWriter {
     fn new(
         arrow_schema: Arc<ArrowSchema>,
         writer_properties: WriterProperties,
+        streaming: Option<StreamingUploadConfig>,
     ) -> Result<Self, ParquetError> {
-        let buffer = ShareableBuffer::default();
-        let arrow_writer = Self::new_underlying_writer(
-            buffer.clone(),
-            arrow_schema.clone(),
-            writer_properties.clone(),
-        )?;
+        let sink =
+            DataFileSink::try_new(arrow_schema.clone(), writer_properties.clone(), streaming)?;
 
         let partition_values = IndexMap::new();
         let buffered_record_batch_count = 0;
@@ -167,20 +206,11 @@ impl DataArrowWriter {
         Ok(Self {
             arrow_schema,
             writer_properties,
-            buffer,
-            arrow_writer,
+            sink,
             partition_values,
             buffered_record_batch_count,
         })
     }
-
-    fn new_underlying_writer(
-        buffer: ShareableBuffer,
-        arrow_schema: Arc<ArrowSchema>,
-        writer_properties: WriterProperties,
-    ) -> Result<ArrowWriter<ShareableBuffer>, ParquetError> {
-        ArrowWriter::try_new(buffer, arrow_schema, Some(writer_properties))
-    }
 }
 
 impl JsonWriter {
@@ -206,6 +236,9 @@ impl JsonWriter {
             writer_properties,
             partition_columns: partition_columns.unwrap_or_default(),
             arrow_writers: HashMap::new(),
+            streaming: None,
+            bad_record_policy: BadRecordPolicy::default(),
+            report: JsonWriteReport::default(),
         })
     }
 
@@ -226,13 +259,84 @@ impl JsonWriter {
             partition_columns,
             schema_ref: None,
             arrow_writers: HashMap::new(),
+            streaming: None,
+            bad_record_policy: BadRecordPolicy::default(),
+            report: JsonWriteReport::default(),
         })
     }
 
+    /// Stream data files to the object store while they are written instead of buffering each
+    /// file in memory until [`DeltaWriter::flush`] is called.
+    ///
+    /// See [`StreamingUploadConfig`] for how the memory used by uploads is bounded.
+    pub fn with_streaming_upload(mut self, config: StreamingUploadConfig) -> Self {
+        self.streaming = Some(config);
+        self
+    }
+
+    /// Decide what happens to values which can not be written, see [`BadRecordPolicy`].
+    ///
+    /// Defaults to [`BadRecordPolicy::Fail`].
+    pub fn with_bad_record_policy(mut self, policy: BadRecordPolicy) -> Self {
+        self.bad_record_policy = policy;
+        self
+    }
+
+    /// Returns the number of values accepted and rejected since the writer was created.
+    pub fn report(&self) -> JsonWriteReport {
+        self.report
+    }
+
+    /// Writes the given values to internal parquet buffers for each represented partition and
+    /// reports how many of them were accepted.
+    ///
+    /// Values which can not be written are handled according to the configured
+    /// [`BadRecordPolicy`]. With [`BadRecordPolicy::Fail`] the first failure is returned as error.
+    pub async fn write_with_report(
+        &mut self,
+        values: Vec<Value>,
+    ) -> Result<JsonWriteReport, DeltaTableError> {
+        let total = values.len();
+        let rejected = match self.bad_record_policy.clone() {
+            BadRecordPolicy::Fail => {
+                self.write_strict(values).await?;
+                0
+            }
+            BadRecordPolicy::Skip => {
+                let rejected = self.write_lenient(values).await?;
+                if !rejected.is_empty() {
+                    warn!(
+                        count = rejected.len(),
+                        "skipped values which could not be written"
+                    );
+                }
+                rejected.len()
+            }
+            BadRecordPolicy::DeadLetter(sink) => {
+                let rejected = self.write_lenient(values).await?;
+                let count = rejected.len();
+                if count > 0 {
+                    debug!(count, "sending rejected values to dead letter sink");
+                    sink.send(rejected).await?;
+                }
+                count
+            }
+        };
+
+        let report = JsonWriteReport {
+            accepted: total - rejected,
+            rejected,
+        };
+        self.report += report;
+        Ok(report)
+    }
+
     /// Returns the current byte length of the in memory buffer.
     /// This may be used by the caller to decide when to finalize the file write.
+    ///
+    /// With streaming uploads this includes the bytes of the open files already uploaded.
     pub fn buffer_len(&self) -> usize {
-        self.arrow_writers.values().map(|w| w.buffer.len()).sum()
+        self.arrow_writers.values().map(|w| w.sink.len()).sum()
     }
 
     /// Returns the number of records held in the current buffer.
@@ -268,6 +372,161 @@ impl JsonWriter {
         )
     }
 
+    /// Writes the values, failing on the first value which can not be written.
+    async fn write_strict(&mut self, values: Vec<Value>) -> Result<(), DeltaTableError> {
+        let mut partial_writes: Vec<(Value, ParquetError)> = Vec::new();
+        let arrow_schema = self.arrow_schema();
+        let divided = self.divide_by_partition_values(values)?;
+        let partition_columns = self.partition_columns.clone();
+        let writer_properties = self.writer_properties.clone();
+        let object_store = self.table.object_store();
+
+        for (key, values) in divided {
+            match self.arrow_writers.get_mut(&key) {
+                Some(writer) => {
+                    let result = writer
+                        .write_values(
+                            &object_store,
+                            &partition_columns,
+                            arrow_schema.clone(),
+                            values,
+                        )
+                        .await;
+                    collect_partial_write_failure(&mut partial_writes, result)?;
+                }
+                None => {
+                    let schema = arrow_schema_without_partitions(&arrow_schema, &partition_columns);
+                    let mut writer = DataArrowWriter::new(
+                        schema,
+                        writer_properties.clone(),
+                        self.streaming.clone(),
+                    )?;
+                    let result = writer
+                        .write_values(
+                            &object_store,
+                            &partition_columns,
+                            arrow_schema.clone(),
+                            values,
+                        )
+                        .await;
+                    collect_partial_write_failure(&mut partial_writes, result)?;
+                    self.arrow_writers.insert(key, writer);
+                }
+            }
+        }
+
+        if !partial_writes.is_empty() {
+            return Err(DeltaWriterError::PartialParquetWrite {
+                sample_error: match &partial_writes[0].1 {
+                    ParquetError::General(msg) => ParquetError::General(msg.to_owned()),
+                    ParquetError::ArrowError(msg) => ParquetError::ArrowError(msg.to_owned()),
+                    ParquetError::EOF(msg) => ParquetError::EOF(msg.to_owned()),
+                    ParquetError::External(err) => ParquetError::General(err.to_string()),
+                    ParquetError::IndexOutOfBound(u, v) => {
+                        ParquetError::IndexOutOfBound(u.to_owned(), v.to_owned())
+                    }
+                    ParquetError::NYI(msg) => ParquetError::NYI(msg.to_owned()),
+                    // ParquetError is non exhaustive, so have a fallback
+                    e => ParquetError::General(e.to_string()),
+                },
+                skipped_values: partial_writes,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Writes the values which can be decoded and encoded, returning the rejected ones.
+    async fn write_lenient(
+        &mut self,
+        values: Vec<Value>,
+    ) -> Result<Vec<RejectedValue>, DeltaTableError> {
+        let mut rejected = Vec::new();
+        let arrow_schema = self.arrow_schema();
+        let partition_columns = self.partition_columns.clone();
+        let writer_properties = self.writer_properties.clone();
+        let object_store = self.table.object_store();
+
+        let mut divided: HashMap<String, Vec<Value>> = HashMap::new();
+        for value in values {
+            match self.json_to_partition_values(&value) {
+                Ok(key) => divided.entry(key).or_default().push(value),
+                Err(e) => rejected.push(RejectedValue::new(value, e.to_string())),
+            }
+        }
+
+        for (key, values) in divided {
+            let (values, record_batch) =
+                match record_batch_from_message(arrow_schema.clone(), &values) {
+                    Ok(record_batch) => (values, record_batch),
+                    Err(_) => {
+                        let good = quarantine_undecodable_values(
+                            arrow_schema.clone(),
+                            values,
+                            &mut rejected,
+                        );
+                        if good.is_empty() {
+                            continue;
+                        }
+                        let record_batch = record_batch_from_message(arrow_schema.clone(), &good)?;
+                        (good, record_batch)
+                    }
+                };
+
+            let result = match self.arrow_writers.get_mut(&key) {
+                Some(writer) => {
+                    writer
+                        .write_decoded(
+                            &object_store,
+                            &partition_columns,
+                            arrow_schema.clone(),
+                            values,
+                            record_batch,
+                        )
+                        .await
+                }
+                None => {
+                    let schema = arrow_schema_without_partitions(&arrow_schema, &partition_columns);
+                    let mut writer = DataArrowWriter::new(
+                        schema,
+                        writer_properties.clone(),
+                        self.streaming.clone(),
+                    )?;
+                    let result = writer
+                        .write_decoded(
+                            &object_store,
+                            &partition_columns,
+                            arrow_schema.clone(),
+                            values,
+                            record_batch,
+                        )
+                        .await;
+                    if matches!(
+                        result,
+                        Ok(_) | Err(DeltaWriterError::PartialParquetWrite { .. })
+                    ) {
+                        self.arrow_writers.insert(key, writer);
+                    }
+                    result
+                }
+            };
+
+            match result {
+                Ok(_) => {}
+                Err(DeltaWriterError::PartialParquetWrite { skipped_values, .. }) => rejected
+                    .extend(
+                        skipped_values
+                            .into_iter()
+                            .map(|(value, e)| RejectedValue::new(value, e.to_string())),
+                    ),
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(rejected)
+    }
+
     fn divide_by_partition_values(
         &self,
         records: Vec<Value>,
@@ -319,51 +578,7 @@ impl DeltaWriter<Vec<Value>> for JsonWriter {
                 "The JsonWriter does not currently support non-default write modes, falling back to default mode"
             );
         }
-        let mut partial_writes: Vec<(Value, ParquetError)> = Vec::new();
-        let arrow_schema = self.arrow_schema();
-        let divided = self.divide_by_partition_values(values)?;
-        let partition_columns = self.partition_columns.clone();
-        let writer_properties = self.writer_properties.clone();
-
-        for (key, values) in divided {
-            match self.arrow_writers.get_mut(&key) {
-                Some(writer) => {
-                    let result = writer
-                        .write_values(&partition_columns, arrow_schema.clone(), values)
-                        .await;
-                    collect_partial_write_failure(&mut partial_writes, result)?;
-                }
-                None => {
-                    let schema = arrow_schema_without_partitions(&arrow_schema, &partition_columns);
-                    let mut writer = DataArrowWriter::new(schema, writer_properties.clone())?;
-                    let result = writer
-                        .write_values(&partition_columns, arrow_schema.clone(), values)
-                        .await;
-                    collect_partial_write_failure(&mut partial_writes, result)?;
-                    self.arrow_writers.insert(key, writer);
-                }
-            }
-        }
-
-        if !partial_writes.is_empty() {
-            return Err(DeltaWriterError::PartialParquetWrite {
-                sample_error: match &partial_writes[0].1 {
-                    ParquetError::General(msg) => ParquetError::General(msg.to_owned()),
-                    ParquetError::ArrowError(msg) => ParquetError::ArrowError(msg.to_owned()),
-                    ParquetError::EOF(msg) => ParquetError::EOF(msg.to_owned()),
-                    ParquetError::External(err) => ParquetError::General(err.to_string()),
-                    ParquetError::IndexOutOfBound(u, v) => {
-                        ParquetError::IndexOutOfBound(u.to_owned(), v.to_owned())
-                    }
-                    ParquetError::NYI(msg) => ParquetError::NYI(msg.to_owned()),
-                    // ParquetError is non exhaustive, so have a fallback
-                    e => ParquetError::General(e.to_string()),
-                },
-                skipped_values: partial_writes,
-            }
-            .into());
-        }
-
+        self.write_with_report(values).await?;
         Ok(())
     }
 
@@ -379,22 +594,20 @@ impl DeltaWriter<Vec<Value>> for JsonWriter {
 
         Span::current().record("writer_count", writers.len());
 
+        let object_store = self.table.object_store();
         for (_, writer) in writers {
-            let metadata = writer.arrow_writer.close()?;
-            let prefix = writer.partition_values.hive_partition_path();
-            let prefix = Path::parse(prefix)?;
-            let uuid = Uuid::new_v4();
-
-            let path = next_data_path(&prefix, 0, &uuid, &writer.writer_properties);
-            let obj_bytes = Bytes::from(writer.buffer.to_vec());
-            let file_size = obj_bytes.len() as i64;
-
-            debug!(path = %path, size = file_size, rows = metadata.file_metadata().num_rows(), "writing data file");
-
-            self.table
-                .object_store()
-                .put_with_retries(&path, obj_bytes.into(), 15)
+            let (path, file_size, metadata) = writer
+                .sink
+                .close(
+                    &object_store,
+                    &writer.arrow_schema,
+                    &writer.writer_properties,
+                    &writer.partition_values,
+                )
                 .await?;
+            let file_size = file_size as i64;
+
+            debug!(path = %path, size = file_size, rows = metadata.file_metadata().num_rows(), "wrote data file");
 
             let table_config = self.table.snapshot()?.table_config();
 
@@ -428,6 +641,22 @@ fn collect_partial_write_failure(
     }
 }
 
+/// Decodes each value on its own, moving the values which fail to decode into `rejected`.
+fn quarantine_undecodable_values(
+    arrow_schema: Arc<ArrowSchema>,
+    values: Vec<Value>,
+    rejected: &mut Vec<RejectedValue>,
+) -> Vec<Value> {
+    let mut good = Vec::with_capacity(values.len());
+    for value in values {
+        match record_batch_from_message(arrow_schema.clone(), std::slice::from_ref(&value)) {
+            Ok(_) => good.push(value),
+            Err(e) => rejected.push(RejectedValue::new(value, e.to_string())),
+        }
+    }
+    good
+}
+
 fn quarantine_failed_parquet_rows(
     arrow_schema: Arc<ArrowSchema>,
     values: Vec<Value>,
@@ -543,6 +772,113 @@ mod tests {
         assert_eq!(columns, vec!["id".to_string(), "value".to_string()]);
     }
 
+    #[tokio::test]
+    async fn test_json_write_streaming_upload() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = get_test_table(&table_dir).await;
+        let mut writer = JsonWriter::for_table(&table)
+            .unwrap()
+            .with_streaming_upload(StreamingUploadConfig::new(64, 256));
+
+        for chunk in 0..10 {
+            let data = (0..100)
+                .map(|i| {
+                    serde_json::json!({
+                        "id": format!("id-{chunk}-{i}"),
+                        "value": i,
+                        "modified": "2021-02-01"
+                    })
+                })
+                .collect();
+            writer.write(data).await.unwrap();
+        }
+        assert!(writer.buffer_len() > 0);
+
+        let add_actions = writer.flush().await.unwrap();
+        assert_eq!(add_actions.len(), 1);
+        let add = &add_actions[0];
+        let path = table_dir.path().join(&add.path);
+        assert_eq!(std::fs::metadata(&path).unwrap().len() as i64, add.size);
+
+        let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 1000);
+    }
+
+    #[tokio::test]
+    async fn test_json_write_skip_bad_records() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = get_test_table(&table_dir).await;
+        let mut writer = JsonWriter::for_table(&table)
+            .unwrap()
+            .with_bad_record_policy(BadRecordPolicy::Skip);
+
+        let data = vec![
+            serde_json::json!({"id": "A", "value": 1, "modified": "2021-02-01"}),
+            serde_json::json!({"id": "B", "value": "abc", "modified": "2021-02-01"}),
+            serde_json::json!({"id": "C", "value": 3, "modified": "2021-02-01"}),
+            serde_json::json!(42),
+        ];
+        let report = writer.write_with_report(data).await.unwrap();
+        assert_eq!(
+            report,
+            JsonWriteReport {
+                accepted: 2,
+                rejected: 2
+            }
+        );
+        assert_eq!(writer.report(), report);
+
+        let add_actions = writer.flush().await.unwrap();
+        let num_records: i64 = add_actions
+            .iter()
+            .map(|add| {
+                let path = table_dir.path().join(&add.path);
+                let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+                reader.metadata().file_metadata().num_rows()
+            })
+            .sum();
+        assert_eq!(num_records, 2);
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingSink {
+        rejected: std::sync::Mutex<Vec<RejectedValue>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::writer::DeadLetterSink for RecordingSink {
+        async fn send(&self, rejected: Vec<RejectedValue>) -> crate::DeltaResult<()> {
+            self.rejected.lock().unwrap().extend(rejected);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_json_write_dead_letter() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = get_test_table(&table_dir).await;
+        let sink = Arc::new(RecordingSink::default());
+        let mut writer = JsonWriter::for_table(&table)
+            .unwrap()
+            .with_bad_record_policy(BadRecordPolicy::DeadLetter(sink.clone()));
+
+        let bad = serde_json::json!({"id": "B", "value": "abc", "modified": "2021-02-01"});
+        let report = writer
+            .write_with_report(vec![
+                serde_json::json!({"id": "A", "value": 1, "modified": "2021-02-01"}),
+                bad.clone(),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(report.accepted, 1);
+        assert_eq!(report.rejected, 1);
+
+        let rejected = sink.rejected.lock().unwrap();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].value, bad);
+        assert!(!rejected[0].reason.is_empty());
+    }
+
     #[tokio::test]
     async fn test_json_writer_for_table_defaults_include_delta_rs_created_by() {
         let table_dir = tempfile::tempdir().unwrap();
@@ -13,12 +13,16 @@ use crate::kernel::transaction::{CommitBuilder, CommitProperties};
 use crate::kernel::{Action, Add, Version};
 use crate::protocol::{ColumnCountStat, DeltaOperation, SaveMode};
 
-pub use json::JsonWriter;
+pub use dead_letter::{BadRecordPolicy, DeadLetterSink, DeltaTableDeadLetterSink, RejectedValue};
+pub use json::{JsonWriteReport, JsonWriter};
 pub use record_batch::RecordBatchWriter;
+pub use streaming::StreamingUploadConfig;
 
+pub mod dead_letter;
 pub mod json;
 pub mod record_batch;
 pub(crate) mod stats;
+pub mod streaming;
 pub mod utils;
 
 #[cfg(test)]
@@ -2,8 +2,9 @@
 //!
 //! Writes Arrow record batches to a Delta Table, handling partitioning and file statistics.
 //! Each Parquet file is buffered in-memory and only written once `flush()` is called on
-//! the writer. Once written, add actions are returned by the writer. It's the users responsibility
-//! to create the transaction using those actions.
+//! the writer, unless streaming uploads are enabled with
+//! [`RecordBatchWriter::with_streaming_upload`]. Once written, add actions are returned by the
+//! writer. It's the users responsibility to create the transaction using those actions.
 
 use std::{collections::HashMap, sync::Arc};
 
@@ -12,21 +13,19 @@ use arrow_ord::partition::partition;
 use arrow_row::{RowConverter, SortField};
 use arrow_schema::{ArrowError, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
 use arrow_select::take::take;
-use bytes::Bytes;
 use delta_kernel::engine::arrow_conversion::{TryIntoArrow, TryIntoKernel};
 use delta_kernel::expressions::Scalar;
+use delta_kernel::schema::StructType;
+use delta_kernel::table_features::ColumnMappingMode;
 use delta_kernel::table_properties::DataSkippingNumIndexedCols;
 use indexmap::IndexMap;
-use object_store::{ObjectStore, path::Path};
-use parquet::{arrow::ArrowWriter, errors::ParquetError, file::properties::WriterProperties};
+use object_store::ObjectStore;
+use parquet::{errors::ParquetError, file::properties::WriterProperties};
 use tracing::log::*;
-use uuid::Uuid;
 
 use super::stats::create_add;
-use super::utils::{
-    ShareableBuffer, arrow_schema_without_partitions, next_data_path,
-    record_batch_without_partitions,
-};
+use super::streaming::{DataFileSink, StreamingUploadConfig};
+use super::utils::{arrow_schema_without_partitions, record_batch_without_partitions};
 use super::{DeltaWriter, DeltaWriterError, WriteMode, ensure_legacy_writer_supports_table};
 use crate::DeltaTable;
 use crate::errors::DeltaTableError;
@@ -35,10 +34,10 @@ use crate::kernel::schema::merge_arrow_schema;
 use crate::kernel::transaction::CommitProperties;
 use crate::kernel::{Action, Add, PartitionsExt, scalars::ScalarExt};
 use crate::kernel::{MetadataExt as _, Version};
-use crate::logstore::ObjectStoreRetryExt;
-use crate::parquet_utils::default_writer_properties;
+use crate::parquet_utils::{default_writer_properties, with_bloom_filter_columns};
+use crate::table::ColumnDefault;
 use crate::table::builder::DeltaTableBuilder;
-use crate::table::config::DEFAULT_NUM_INDEX_COLS;
+use crate::table::config::{DEFAULT_NUM_INDEX_COLS, TableProperty, parse_bloom_filter_columns};
 
 /// Writes messages to a delta lake table.
 pub struct RecordBatchWriter {
@@ -52,6 +51,9 @@ pub struct RecordBatchWriter {
     num_indexed_cols: DataSkippingNumIndexedCols,
     stats_columns: Option<Vec<String>>,
     commit_properties: Option<CommitProperties>,
+    streaming: Option<StreamingUploadConfig>,
+    /// Columns with a `CURRENT_DEFAULT`, evaluated for every batch that omits one of them
+    column_defaults: Vec<ColumnDefault>,
 }
 
 impl std::fmt::Debug for RecordBatchWriter {
@@ -73,22 +75,26 @@ impl RecordBatchWriter {
         let delta_table = DeltaTableBuilder::from_url(table_url)?
             .with_storage_options(storage_options.unwrap_or_default())
             .build()?;
-        // Initialize writer properties for the underlying arrow writer
-        let writer_properties = default_writer_properties(parquet::basic::Compression::SNAPPY);
-
         // if metadata fails to load, use an empty hashmap and default values for num_indexed_cols and stats_columns
         let configuration = delta_table.snapshot().map_or_else(
             |_| HashMap::new(),
             |snapshot| snapshot.metadata().configuration().clone(),
         );
 
-        Ok(Self::new_with_table(
+        // Initialize writer properties for the underlying arrow writer
+        let writer_properties = with_configured_bloom_filters(
+            default_writer_properties(parquet::basic::Compression::SNAPPY),
+            &configuration,
+            None,
+        )?;
+
+        Self::new_with_table(
             delta_table,
             schema,
             partition_columns,
             configuration,
             writer_properties,
-        ))
+        )
     }
 
     /// Create a new [`RecordBatchWriter`] for an existing table after validating table metadata.
@@ -107,16 +113,20 @@ impl RecordBatchWriter {
         ensure_legacy_writer_supports_table(&delta_table, "RecordBatchWriter")?;
 
         // Initialize writer properties for the underlying arrow writer
-        let writer_properties = default_writer_properties(parquet::basic::Compression::SNAPPY);
         let configuration = delta_table.snapshot()?.metadata().configuration().clone();
+        let writer_properties = with_configured_bloom_filters(
+            default_writer_properties(parquet::basic::Compression::SNAPPY),
+            &configuration,
+            None,
+        )?;
 
-        Ok(Self::new_with_table(
+        Self::new_with_table(
             delta_table,
             schema,
             partition_columns,
             configuration,
             writer_properties,
-        ))
+        )
     }
 
     /// Add the [CommitProperties] to the [RecordBatchWriter] to be used when the writer flushes
@@ -135,13 +145,18 @@ impl RecordBatchWriter {
 
         // Initialize an arrow schema ref from the delta table schema
         let metadata = table.snapshot()?.metadata();
-        let arrow_schema: ArrowSchema = (&metadata.parse_schema()?).try_into_arrow()?;
+        let schema = metadata.parse_schema()?;
+        let arrow_schema: ArrowSchema = (&schema).try_into_arrow()?;
         let arrow_schema_ref = Arc::new(arrow_schema);
         let partition_columns = metadata.partition_columns().into();
 
         // Initialize writer properties for the underlying arrow writer
-        let writer_properties = default_writer_properties(parquet::basic::Compression::SNAPPY);
         let configuration = table.snapshot()?.metadata().configuration().clone();
+        let writer_properties = with_configured_bloom_filters(
+            default_writer_properties(parquet::basic::Compression::SNAPPY),
+            &configuration,
+            Some(&schema),
+        )?;
 
         Ok(Self {
             storage: table.object_store(),
@@ -165,6 +180,8 @@ impl RecordBatchWriter {
                 .get("delta.dataSkippingStatsColumns")
                 .map(|v| v.split(',').map(|s| s.to_string()).collect()),
             commit_properties: None,
+            streaming: None,
+            column_defaults: writer_column_defaults(Some(&schema))?,
         })
     }
 
@@ -178,14 +195,19 @@ impl RecordBatchWriter {
         table: &crate::table::BlindDeltaTable,
     ) -> Result<Self, DeltaTableError> {
         let metadata = table.metadata();
-        let arrow_schema: ArrowSchema = (&metadata.parse_schema()?).try_into_arrow()?;
+        let schema = metadata.parse_schema()?;
+        let arrow_schema: ArrowSchema = (&schema).try_into_arrow()?;
         let arrow_schema_ref = Arc::new(arrow_schema);
         let partition_columns = metadata.partition_columns().to_vec();
 
-        let writer_properties = WriterProperties::builder()
-            .set_compression(parquet::basic::Compression::SNAPPY)
-            .build();
         let configuration = metadata.configuration().clone();
+        let writer_properties = with_configured_bloom_filters(
+            WriterProperties::builder()
+                .set_compression(parquet::basic::Compression::SNAPPY)
+                .build(),
+            &configuration,
+            Some(&schema),
+        )?;
 
         Ok(Self {
             storage: table.object_store(),
@@ -209,6 +231,8 @@ impl RecordBatchWriter {
                 .get("delta.dataSkippingStatsColumns")
                 .map(|v| v.split(',').map(|s| s.to_string()).collect()),
             commit_properties: None,
+            streaming: None,
+            column_defaults: writer_column_defaults(Some(&schema))?,
         })
     }
 
@@ -218,10 +242,15 @@ impl RecordBatchWriter {
         partition_columns: Option<Vec<String>>,
         configuration: HashMap<String, String>,
         writer_properties: WriterProperties,
-    ) -> Self {
+    ) -> Result<Self, DeltaTableError> {
         let schema = normalize_for_delta(&schema);
+        let table_schema = delta_table
+            .snapshot()
+            .ok()
+            .map(|snapshot| snapshot.schema());
+        let column_defaults = writer_column_defaults(table_schema.as_deref())?;
 
-        Self {
+        Ok(Self {
             storage: delta_table.object_store(),
             arrow_schema_ref: schema.clone(),
             original_schema_ref: schema,
@@ -243,11 +272,24 @@ impl RecordBatchWriter {
                 .get("delta.dataSkippingStatsColumns")
                 .map(|v| v.split(',').map(|s| s.to_string()).collect()),
             commit_properties: None,
-        }
+            streaming: None,
+            column_defaults,
+        })
+    }
+
+    /// Stream data files to the object store while they are written instead of buffering each
+    /// file in memory until [`DeltaWriter::flush`] is called.
+    ///
+    /// See [`StreamingUploadConfig`] for how the memory used by uploads is bounded.
+    pub fn with_streaming_upload(mut self, config: StreamingUploadConfig) -> Self {
+        self.streaming = Some(config);
+        self
     }
 
     /// Returns the current byte length of the in memory buffer.
     /// This may be used by the caller to decide when to finalize the file write.
+    ///
+    /// With streaming uploads this includes the bytes of the open files already uploaded.
     pub fn buffer_len(&self) -> usize {
         self.arrow_writers.values().map(|w| w.buffer_len()).sum()
     }
@@ -265,6 +307,56 @@ impl RecordBatchWriter {
         self.arrow_writers.clear();
     }
 
+    /// Fill the columns the record batch omits with their default value, keeping the column order
+    /// of the writer schema.
+    fn with_column_defaults(&self, values: RecordBatch) -> Result<RecordBatch, DeltaTableError> {
+        let input_schema = values.schema();
+        let missing = self
+            .column_defaults
+            .iter()
+            .filter(|d| input_schema.column_with_name(&d.name).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return Ok(values);
+        }
+        let defaults = evaluated_column_defaults(&missing)?;
+
+        let indices = UInt32Array::from(vec![0u32; values.num_rows()]);
+        let mut fields = Vec::with_capacity(self.arrow_schema_ref.fields().len());
+        let mut columns = Vec::with_capacity(self.arrow_schema_ref.fields().len());
+        for field in self.arrow_schema_ref.fields() {
+            if let Some((idx, input_field)) = input_schema.column_with_name(field.name()) {
+                fields.push(Arc::new(input_field.clone()));
+                columns.push(values.column(idx).clone());
+            } else if let Some((_, default)) =
+                defaults.iter().find(|(name, _)| name == field.name())
+            {
+                fields.push(field.clone());
+                columns.push(take(default.as_ref(), &indices, None)?);
+            }
+        }
+        // Columns unknown to the writer schema are kept for schema evolution.
+        for (idx, field) in input_schema.fields().iter().enumerate() {
+            if self
+                .arrow_schema_ref
+                .column_with_name(field.name())
+                .is_none()
+            {
+                fields.push(field.clone());
+                columns.push(values.column(idx).clone());
+            }
+        }
+
+        Ok(RecordBatch::try_new(
+            Arc::new(ArrowSchema::new_with_metadata(
+                fields,
+                self.arrow_schema_ref.metadata().clone(),
+            )),
+            columns,
+        )?)
+    }
+
     /// Returns the arrow schema representation of the delta table schema defined for the wrapped
     /// table.
     pub fn arrow_schema(&self) -> ArrowSchemaRef {
@@ -283,7 +375,7 @@ impl RecordBatchWriter {
         let record_batch = record_batch_without_partitions(&record_batch, &self.partition_columns)?;
 
         let written_schema = match self.arrow_writers.get_mut(&partition_key) {
-            Some(writer) => writer.write(&record_batch, mode)?,
+            Some(writer) => writer.write(&self.storage, &record_batch, mode).await?,
             None => {
                 let mut writer = PartitionWriter::new(
                     arrow_schema_without_partitions(
@@ -292,8 +384,9 @@ impl RecordBatchWriter {
                     ),
                     partition_values.clone(),
                     self.writer_properties.clone(),
+                    self.streaming.clone(),
                 )?;
-                let schema = writer.write(&record_batch, mode)?;
+                let schema = writer.write(&self.storage, &record_batch, mode).await?;
                 // Currently schema evolution is not supported with partition columns which means
                 // the schema returned here is equivalent to `arrow_schema_without_partitions`
                 // which can cause problems see #3783
@@ -351,11 +444,12 @@ impl DeltaWriter<RecordBatch> for RecordBatchWriter {
 
         let values = if values.schema() != self.arrow_schema_ref {
             let normalized = normalize_for_delta(&values.schema());
-            if normalized != values.schema() {
+            let values = if normalized != values.schema() {
                 crate::kernel::schema::cast::cast_record_batch(&values, normalized, true, false)?
             } else {
                 values
-            }
+            };
+            self.with_column_defaults(values)?
         } else {
             values
         };
@@ -375,20 +469,20 @@ impl DeltaWriter<RecordBatch> for RecordBatchWriter {
         let mut actions = Vec::with_capacity(writers.len());
 
         for (_, writer) in writers {
-            let metadata = writer.arrow_writer.close()?;
-            let prefix = Path::parse(writer.partition_values.hive_partition_path())?;
-            let uuid = Uuid::new_v4();
-            let path = next_data_path(&prefix, 0, &uuid, &writer.writer_properties);
-            let obj_bytes = Bytes::from(writer.buffer.to_vec());
-            let file_size = obj_bytes.len() as i64;
-            self.storage
-                .put_with_retries(&path, obj_bytes.into(), 15)
+            let (path, file_size, metadata) = writer
+                .sink
+                .close(
+                    &self.storage,
+                    &writer.arrow_schema,
+                    &writer.writer_properties,
+                    &writer.partition_values,
+                )
                 .await?;
 
             actions.push(create_add(
                 &writer.partition_values,
                 path.to_string(),
-                file_size,
+                file_size as i64,
                 &metadata,
                 self.num_indexed_cols,
                 &self.stats_columns,
@@ -437,8 +531,7 @@ pub struct PartitionResult {
 struct PartitionWriter {
     arrow_schema: ArrowSchemaRef,
     writer_properties: WriterProperties,
-    pub(super) buffer: ShareableBuffer,
-    pub(super) arrow_writer: ArrowWriter<ShareableBuffer>,
+    pub(super) sink: DataFileSink,
     pub(super) partition_values: IndexMap<String, Scalar>,
     pub(super) buffered_record_batch_count: usize,
 }
@@ -448,21 +541,17 @@ impl PartitionWriter {
         arrow_schema: ArrowSchemaRef,
         partition_values: IndexMap<String, Scalar>,
         writer_properties: WriterProperties,
+        streaming: Option<StreamingUploadConfig>,
     ) -> Result<Self, ParquetError> {
-        let buffer = ShareableBuffer::default();
-        let arrow_writer = ArrowWriter::try_new(
-            buffer.clone(),
-            arrow_schema.clone(),
-            Some(writer_properties.clone()),
-        )?;
+        let sink =
+            DataFileSink::try_new(arrow_schema.clone(), writer_properties.clone(), streaming)?;
 
         let buffered_record_batch_count = 0;
 
         Ok(Self {
             arrow_schema,
             writer_properties,
-            buffer,
-            arrow_writer,
+            sink,
             partition_values,
             buffered_record_batch_count,
         })
@@ -474,8 +563,9 @@ impl PartitionWriter {
     ///
     /// Returns the schema which was written by the write which can be used to understand if a
     /// schema evolution has happened
-    pub fn write(
+    pub async fn write(
         &mut self,
+        object_store: &Arc<dyn ObjectStore>,
         record_batch: &RecordBatch,
         mode: WriteMode,
     ) -> Result<ArrowSchemaRef, DeltaWriterError> {
@@ -515,34 +605,24 @@ impl PartitionWriter {
             None
         };
 
-        // Copy current cursor bytes so we can recover from failures
-        let buffer_bytes = self.buffer.to_vec();
         let record_batch = merged_batch.as_ref().unwrap_or(record_batch);
-
-        match self.arrow_writer.write(record_batch) {
-            Ok(_) => {
-                self.buffered_record_batch_count += 1;
-                Ok(self.arrow_schema.clone())
-            }
-            // If a write fails we need to reset the state of the PartitionWriter
-            Err(e) => {
-                let new_buffer = ShareableBuffer::from_bytes(buffer_bytes.as_slice());
-                let _ = std::mem::replace(&mut self.buffer, new_buffer.clone());
-                let arrow_writer = ArrowWriter::try_new(
-                    new_buffer,
-                    self.arrow_schema.clone(),
-                    Some(self.writer_properties.clone()),
-                )?;
-                let _ = std::mem::replace(&mut self.arrow_writer, arrow_writer);
-                Err(e.into())
-            }
-        }
+        self.sink
+            .write(
+                object_store,
+                &self.arrow_schema,
+                &self.writer_properties,
+                &self.partition_values,
+                record_batch,
+            )
+            .await?;
+        self.buffered_record_batch_count += 1;
+        Ok(self.arrow_schema.clone())
     }
 
     /// Returns the current byte length of the in memory buffer.
     /// This may be used by the caller to decide when to finalize the file write.
     pub fn buffer_len(&self) -> usize {
-        self.buffer.len() + self.arrow_writer.in_progress_size()
+        self.sink.len()
     }
 }
 
@@ -626,6 +706,64 @@ fn lexsort_to_indices(arrays: &[ArrayRef]) -> UInt32Array {
     UInt32Array::from_iter_values(sort.iter().map(|(i, _)| *i as u32))
 }
 
+/// The columns of the table schema with a `CURRENT_DEFAULT` expression, checked to evaluate to
+/// the column type.
+///
+/// Default expressions are Spark SQL and need DataFusion to be evaluated.
+#[cfg(feature = "datafusion")]
+fn writer_column_defaults(
+    schema: Option<&StructType>,
+) -> Result<Vec<ColumnDefault>, DeltaTableError> {
+    use crate::kernel::StructTypeExt as _;
+
+    let Some(schema) = schema else {
+        return Ok(vec![]);
+    };
+    let column_defaults = schema.get_column_defaults()?;
+    evaluated_column_defaults(&column_defaults)?;
+    Ok(column_defaults)
+}
+
+#[cfg(not(feature = "datafusion"))]
+fn writer_column_defaults(
+    _schema: Option<&StructType>,
+) -> Result<Vec<ColumnDefault>, DeltaTableError> {
+    Ok(vec![])
+}
+
+/// Evaluate the `CURRENT_DEFAULT` expressions into single row arrays.
+#[cfg(feature = "datafusion")]
+fn evaluated_column_defaults(
+    column_defaults: &[ColumnDefault],
+) -> Result<Vec<(String, ArrayRef)>, DeltaTableError> {
+    crate::operations::write::column_defaults::evaluate_column_defaults(column_defaults)
+}
+
+#[cfg(not(feature = "datafusion"))]
+fn evaluated_column_defaults(
+    _column_defaults: &[ColumnDefault],
+) -> Result<Vec<(String, ArrayRef)>, DeltaTableError> {
+    Ok(vec![])
+}
+
+/// Enable the bloom filters configured in the `delta.bloomFilter.columns` property of a table.
+fn with_configured_bloom_filters(
+    writer_properties: WriterProperties,
+    configuration: &HashMap<String, String>,
+    schema: Option<&StructType>,
+) -> Result<WriterProperties, DeltaTableError> {
+    let Some(value) = configuration.get(TableProperty::BloomFilterColumns.as_ref()) else {
+        return Ok(writer_properties);
+    };
+    let columns = parse_bloom_filter_columns(value).map_err(DeltaTableError::generic)?;
+    Ok(with_bloom_filter_columns(
+        writer_properties,
+        &columns,
+        schema,
+        ColumnMappingMode::None,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use arrow::json::ReaderBuilder;
@@ -924,6 +1062,42 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_write_multiple_partitions_streaming() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_path = table_dir.path().to_str().unwrap();
+        let batch = get_record_batch(None, false);
+        let partition_cols = vec!["modified".to_string()];
+        let mut table = create_initialized_table(table_path, &partition_cols).await;
+        let mut writer = RecordBatchWriter::for_table(&table)
+            .unwrap()
+            .with_streaming_upload(StreamingUploadConfig::new(64, 256));
+
+        writer.write(batch.clone()).await.unwrap();
+        writer.write(batch).await.unwrap();
+        assert!(writer.buffer_len() > 0);
+        let version = writer.flush_and_commit(&mut table).await.unwrap();
+        assert_eq!(version, 1);
+
+        let adds: Vec<_> = table.snapshot().unwrap().log_data().iter().collect();
+        assert_eq!(adds.len(), 2);
+        let table_dir = table
+            .table_url()
+            .to_file_path()
+            .expect("Failed to turn table URL back into file path");
+        let mut num_records = 0;
+        for add in adds {
+            let file = table_dir.join(add.path().as_ref());
+            assert_eq!(
+                std::fs::metadata(&file).unwrap().len() as i64,
+                add.size(),
+                "{file:?} size does not match its add action"
+            );
+            num_records += add.num_records().unwrap();
+        }
+        assert_eq!(num_records, 22);
+    }
+
     fn validate_partition_map(partitions: Vec<PartitionResult>, expected_keys: Vec<String>) {
         assert_eq!(partitions.len(), expected_keys.len());
         for result in partitions {
@@ -1285,6 +1459,107 @@ mod tests {
 
         use futures::TryStreamExt;
 
+        #[tokio::test]
+        async fn test_write_fills_column_defaults() {
+            use arrow_array::Int32Array;
+            use datafusion::assert_batches_sorted_eq;
+
+            use crate::kernel::{COLUMN_DEFAULT_KEY, DataType, MetadataValue, StructField};
+            use crate::writer::test_utils::datafusion::get_data;
+
+            let mut table = DeltaTable::new_in_memory()
+                .create()
+                .with_columns([
+                    StructField::not_null("id", DataType::INTEGER),
+                    StructField::not_null("status", DataType::STRING).with_metadata([(
+                        COLUMN_DEFAULT_KEY.to_string(),
+                        MetadataValue::String("'active'".to_string()),
+                    )]),
+                ])
+                .await
+                .unwrap();
+
+            let batch = RecordBatch::try_new(
+                Arc::new(ArrowSchema::new(vec![arrow_schema::Field::new(
+                    "id",
+                    arrow_schema::DataType::Int32,
+                    false,
+                )])),
+                vec![Arc::new(Int32Array::from(vec![1, 2]))],
+            )
+            .unwrap();
+
+            let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+            writer.write(batch).await.unwrap();
+            writer.flush_and_commit(&mut table).await.unwrap();
+
+            let expected = vec![
+                "+----+--------+",
+                "| id | status |",
+                "+----+--------+",
+                "| 1  | active |",
+                "| 2  | active |",
+                "+----+--------+",
+            ];
+            assert_batches_sorted_eq!(&expected, &get_data(&table).await);
+        }
+
+        #[tokio::test]
+        async fn test_write_evaluates_column_defaults_per_batch() {
+            use arrow_array::{Int32Array, TimestampMicrosecondArray};
+
+            use crate::kernel::{COLUMN_DEFAULT_KEY, DataType, MetadataValue, StructField};
+            use crate::writer::test_utils::datafusion::get_data;
+
+            let mut table = DeltaTable::new_in_memory()
+                .create()
+                .with_columns([
+                    StructField::not_null("id", DataType::INTEGER),
+                    StructField::not_null("created_at", DataType::TIMESTAMP).with_metadata([(
+                        COLUMN_DEFAULT_KEY.to_string(),
+                        MetadataValue::String("current_timestamp()".to_string()),
+                    )]),
+                ])
+                .await
+                .unwrap();
+
+            let batch = |id: i32| {
+                RecordBatch::try_new(
+                    Arc::new(ArrowSchema::new(vec![arrow_schema::Field::new(
+                        "id",
+                        arrow_schema::DataType::Int32,
+                        false,
+                    )])),
+                    vec![Arc::new(Int32Array::from(vec![id]))],
+                )
+                .unwrap()
+            };
+
+            let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+            writer.write(batch(1)).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            writer.write(batch(2)).await.unwrap();
+            writer.flush_and_commit(&mut table).await.unwrap();
+
+            let mut created_at = get_data(&table)
+                .await
+                .iter()
+                .flat_map(|batch| {
+                    batch
+                        .column_by_name("created_at")
+                        .unwrap()
+                        .as_any()
+                        .downcast_ref::<TimestampMicrosecondArray>()
+                        .unwrap()
+                        .values()
+                        .to_vec()
+                })
+                .collect::<Vec<_>>();
+            created_at.sort();
+            created_at.dedup();
+            assert_eq!(created_at.len(), 2);
+        }
+
         #[tokio::test]
         async fn test_write_data_skipping_stats_columns() {
             let batch = get_record_batch(None, false);
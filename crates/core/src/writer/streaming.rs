@@ -0,0 +1,785 @@
+//! Bounded-memory streaming of Parquet data files to object storage.
+//!
+//! By default the [`RecordBatchWriter`](super::RecordBatchWriter) and
+//! [`JsonWriter`](super::JsonWriter) buffer each data file in memory until it is flushed. With a
+//! [`StreamingUploadConfig`] the writers instead encode row groups as they fill and upload the
+//! encoded bytes through [`ObjectStore::put_multipart`], so that memory no longer scales with the
+//! file size times the number of open partitions.
+//!
+//! Memory held by a writer is bounded by roughly one part per open partition, plus the open row
+//! group, plus the in-flight budget shared by all uploads of the writers using the same config.
+//! Row groups are sized by the writer properties as for buffered files, independently of the
+//! part size, so a closed row group may be uploaded as a part larger than the part size.
+//!
+//! Multipart uploads which are not completed, because writing failed or the writer was dropped,
+//! are aborted so that the object store does not keep the uploaded parts around.
+
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use bytes::Bytes;
+use delta_kernel::expressions::Scalar;
+use indexmap::IndexMap;
+use object_store::path::Path;
+use object_store::{MultipartUpload, ObjectStore, ObjectStoreExt as _};
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_writer::{ArrowColumnWriter, ArrowLeafColumn, compute_leaves};
+use parquet::errors::ParquetError;
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::WriterProperties;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
+use tracing::*;
+use uuid::Uuid;
+
+use super::DeltaWriterError;
+use super::utils::{ShareableBuffer, next_data_path};
+use crate::errors::DeltaTableError;
+use crate::kernel::PartitionsExt;
+use crate::logstore::ObjectStoreRetryExt;
+
+/// Default size of the parts uploaded to the object store, the minimum part size of S3 and GCS.
+const DEFAULT_PART_SIZE: usize = 5 * 1024 * 1024;
+/// Default number of parts which may be uploading at the same time.
+const DEFAULT_MAX_IN_FLIGHT_PARTS: usize = 10;
+/// Permits of the in-flight budget are counted in KiB to fit large budgets into `u32` permits.
+const PERMIT_SIZE: usize = 1024;
+/// Attempts made to start and to complete a multipart upload, matching the single put of a file.
+const MAX_RETRIES: usize = 15;
+
+/// Configuration for streaming data files to the object store while they are written.
+///
+/// Clones of a config share the same in-flight budget, so a single config can cap the upload
+/// memory of several writers.
+#[derive(Debug, Clone)]
+pub struct StreamingUploadConfig {
+    part_size: usize,
+    max_in_flight_bytes: usize,
+    budget: Arc<Semaphore>,
+}
+
+impl Default for StreamingUploadConfig {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_PART_SIZE,
+            DEFAULT_PART_SIZE * DEFAULT_MAX_IN_FLIGHT_PARTS,
+        )
+    }
+}
+
+impl StreamingUploadConfig {
+    /// Create a new config uploading parts of `part_size` bytes, with at most
+    /// `max_in_flight_bytes` uploading at any time.
+    ///
+    /// Object stores such as S3 and GCS require all but the last part to be at least 5 MiB.
+    pub fn new(part_size: usize, max_in_flight_bytes: usize) -> Self {
+        let part_size = part_size.max(1);
+        let max_in_flight_bytes = max_in_flight_bytes.max(part_size);
+        Self {
+            part_size,
+            max_in_flight_bytes,
+            budget: Arc::new(Semaphore::new(max_in_flight_bytes.div_ceil(PERMIT_SIZE))),
+        }
+    }
+
+    /// Size of the parts uploaded to the object store.
+    pub fn part_size(&self) -> usize {
+        self.part_size
+    }
+
+    /// Maximum number of bytes uploading at the same time.
+    pub fn max_in_flight_bytes(&self) -> usize {
+        self.max_in_flight_bytes
+    }
+
+    /// Wait until `len` bytes fit into the in-flight budget.
+    async fn reserve(&self, len: usize) -> OwnedSemaphorePermit {
+        let permits = len
+            .div_ceil(PERMIT_SIZE)
+            .min(self.max_in_flight_bytes.div_ceil(PERMIT_SIZE));
+        self.budget
+            .clone()
+            .acquire_many_owned(permits as u32)
+            .await
+            .expect("in-flight budget semaphore is never closed")
+    }
+}
+
+/// Row group which is being encoded.
+///
+/// Batches are validated before any of their columns is encoded, so a rejected batch leaves the
+/// row group untouched without keeping the accepted batches around.
+struct OpenRowGroup {
+    writers: Vec<ArrowColumnWriter>,
+    num_rows: usize,
+}
+
+/// Writes a single Parquet data file, uploading completed row groups while the file is written.
+pub(crate) struct StreamingFileWriter {
+    object_store: Arc<dyn ObjectStore>,
+    path: Path,
+    arrow_schema: ArrowSchemaRef,
+    config: StreamingUploadConfig,
+    max_row_group_rows: usize,
+    buffer: ShareableBuffer,
+    arrow_writer: ArrowWriter<ShareableBuffer>,
+    row_group: Option<OpenRowGroup>,
+    upload: Option<Box<dyn MultipartUpload>>,
+    in_flight: JoinSet<object_store::Result<()>>,
+    bytes_uploaded: usize,
+    /// Set once encoding failed part way through a batch, the data file can only be discarded.
+    poisoned: bool,
+}
+
+impl std::fmt::Debug for StreamingFileWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamingFileWriter")
+            .field("path", &self.path)
+            .field("bytes_uploaded", &self.bytes_uploaded)
+            .finish()
+    }
+}
+
+impl StreamingFileWriter {
+    pub(crate) fn try_new(
+        object_store: Arc<dyn ObjectStore>,
+        path: Path,
+        arrow_schema: ArrowSchemaRef,
+        writer_properties: WriterProperties,
+        config: StreamingUploadConfig,
+    ) -> Result<Self, DeltaWriterError> {
+        let buffer = ShareableBuffer::default();
+        let max_row_group_rows = writer_properties
+            .max_row_group_row_count()
+            .unwrap_or(usize::MAX)
+            .max(1);
+        let arrow_writer = ArrowWriter::try_new(
+            buffer.clone(),
+            arrow_schema.clone(),
+            Some(writer_properties),
+        )?;
+        Ok(Self {
+            object_store,
+            path,
+            arrow_schema,
+            config,
+            max_row_group_rows,
+            buffer,
+            arrow_writer,
+            row_group: None,
+            upload: None,
+            in_flight: JoinSet::new(),
+            bytes_uploaded: 0,
+            poisoned: false,
+        })
+    }
+
+    /// Number of bytes of the data file so far, including bytes already uploaded.
+    pub(crate) fn bytes_written(&self) -> usize {
+        self.bytes_uploaded + self.buffer.len() + self.in_progress_size()
+    }
+
+    fn in_progress_size(&self) -> usize {
+        self.row_group.as_ref().map_or(0, |rg| {
+            rg.writers
+                .iter()
+                .map(|w| w.get_estimated_total_bytes())
+                .sum()
+        })
+    }
+
+    /// Encode the batch into the open row group and upload any completed parts.
+    ///
+    /// A batch which does not match the schema is rejected before it is encoded, so that the
+    /// writer remains usable. A batch is never split across row groups.
+    pub(crate) async fn write(&mut self, batch: &RecordBatch) -> Result<(), DeltaWriterError> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let open_rows = self.row_group.as_ref().map_or(0, |rg| rg.num_rows);
+        if open_rows > 0 && open_rows + batch.num_rows() > self.max_row_group_rows {
+            self.close_row_group()?;
+            self.upload_buffered(self.config.part_size).await?;
+        }
+
+        self.encode(batch)?;
+
+        // row groups are sized by the writer properties only, parts are uploaded from the
+        // encoded row groups once enough bytes are buffered
+        let open_rows = self.row_group.as_ref().map_or(0, |rg| rg.num_rows);
+        if open_rows >= self.max_row_group_rows {
+            self.close_row_group()?;
+            self.upload_buffered(self.config.part_size).await?;
+        }
+        Ok(())
+    }
+
+    fn encode(&mut self, batch: &RecordBatch) -> Result<(), DeltaWriterError> {
+        if self.poisoned {
+            return Err(poisoned());
+        }
+        let leaves = batch_leaves(&self.arrow_schema, batch)?;
+        if self.row_group.is_none() {
+            self.row_group = Some(OpenRowGroup {
+                writers: self.arrow_writer.get_column_writers()?,
+                num_rows: 0,
+            });
+        }
+        let row_group = self.row_group.as_mut().expect("row group was opened");
+        if row_group.writers.len() != leaves.len() {
+            return Err(DeltaWriterError::SchemaMismatch {
+                record_batch_schema: batch.schema(),
+                expected_schema: self.arrow_schema.clone(),
+            });
+        }
+        for (writer, leaf) in row_group.writers.iter_mut().zip(&leaves) {
+            if let Err(err) = writer.write(leaf) {
+                // the column writers hold part of the batch and cannot be rolled back
+                self.poisoned = true;
+                return Err(err.into());
+            }
+        }
+        row_group.num_rows += batch.num_rows();
+        Ok(())
+    }
+
+    fn close_row_group(&mut self) -> Result<(), DeltaWriterError> {
+        if let Some(row_group) = self.row_group.take() {
+            let chunks = row_group
+                .writers
+                .into_iter()
+                .map(|w| w.close())
+                .collect::<Result<Vec<_>, _>>()?;
+            self.arrow_writer.append_row_group(chunks)?;
+        }
+        Ok(())
+    }
+
+    /// Upload the buffered bytes as a new part once at least `min_len` bytes are buffered.
+    async fn upload_buffered(&mut self, min_len: usize) -> Result<(), DeltaWriterError> {
+        if self.buffer.is_empty() || self.buffer.len() < min_len {
+            return Ok(());
+        }
+        // surface failures of earlier parts before queueing more work
+        while let Some(result) = self.in_flight.try_join_next() {
+            join_result(result)?;
+        }
+
+        let bytes = Bytes::from(self.buffer.take());
+        let len = bytes.len();
+        if self.upload.is_none() {
+            debug!(path = %self.path, "starting multipart upload");
+            let mut attempt = 1;
+            let upload = loop {
+                match self.object_store.put_multipart(&self.path).await {
+                    Err(err) if should_retry(&err, attempt, "start multipart upload") => {
+                        attempt += 1
+                    }
+                    result => break result?,
+                }
+            };
+            self.upload = Some(upload);
+        }
+        let permit = self.config.reserve(len).await;
+        let part = self
+            .upload
+            .as_mut()
+            .expect("multipart upload was started")
+            .put_part(bytes.into());
+        self.in_flight.spawn(async move {
+            let result = part.await;
+            drop(permit);
+            result
+        });
+        self.bytes_uploaded += len;
+        Ok(())
+    }
+
+    /// Finish the data file and wait for all uploads to complete.
+    ///
+    /// Returns the size of the file and its Parquet metadata.
+    pub(crate) async fn close(mut self) -> Result<(usize, ParquetMetaData), DeltaWriterError> {
+        match self.finish().await {
+            Ok(result) => Ok(result),
+            Err(err) => {
+                self.abort().await;
+                Err(err)
+            }
+        }
+    }
+
+    /// Discard the data file, aborting the multipart upload if one was started.
+    pub(crate) async fn abort(&mut self) {
+        self.in_flight.abort_all();
+        if let Some(mut upload) = self.upload.take()
+            && let Err(err) = upload.abort().await
+        {
+            warn!(path = %self.path, "failed to abort multipart upload: {err}");
+        }
+    }
+
+    async fn finish(&mut self) -> Result<(usize, ParquetMetaData), DeltaWriterError> {
+        if self.poisoned {
+            return Err(poisoned());
+        }
+        self.close_row_group()?;
+        let metadata = self.arrow_writer.finish()?;
+
+        if self.upload.is_none() {
+            // the whole file fits into a single part, avoid the multipart round trips
+            let bytes = Bytes::from(self.buffer.take());
+            let len = bytes.len();
+            self.object_store
+                .put_with_retries(&self.path, bytes.into(), 15)
+                .await?;
+            return Ok((len, metadata));
+        }
+
+        self.upload_buffered(0).await?;
+        while let Some(result) = self.in_flight.join_next().await {
+            join_result(result)?;
+        }
+        let upload = self.upload.as_mut().expect("multipart upload was started");
+        let mut attempt = 1;
+        loop {
+            match upload.complete().await {
+                Err(err) if should_retry(&err, attempt, "complete multipart upload") => {
+                    attempt += 1
+                }
+                result => {
+                    result?;
+                    break;
+                }
+            }
+        }
+        self.upload = None;
+        debug!(path = %self.path, size = self.bytes_uploaded, "multipart upload completed");
+        Ok((self.bytes_uploaded, metadata))
+    }
+}
+
+impl Drop for StreamingFileWriter {
+    fn drop(&mut self) {
+        self.in_flight.abort_all();
+        let Some(mut upload) = self.upload.take() else {
+            return;
+        };
+        // the writer was dropped before the data file was completed
+        let path = self.path.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(err) = upload.abort().await {
+                        warn!(%path, "failed to abort multipart upload: {err}");
+                    }
+                });
+            }
+            Err(_) => warn!(%path, "multipart upload dropped outside of a runtime, not aborted"),
+        }
+    }
+}
+
+/// Destination of the encoded Parquet bytes of a partition writer.
+pub(crate) enum DataFileSink {
+    /// Buffer the whole data file in memory until the writer is flushed.
+    Buffered {
+        buffer: ShareableBuffer,
+        arrow_writer: ArrowWriter<ShareableBuffer>,
+    },
+    /// Upload row groups while the data file is written, the file is created on the first write.
+    Streaming {
+        config: StreamingUploadConfig,
+        writer: Option<StreamingFileWriter>,
+    },
+}
+
+impl std::fmt::Debug for DataFileSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Buffered { buffer, .. } => f
+                .debug_struct("Buffered")
+                .field("len", &buffer.len())
+                .finish(),
+            Self::Streaming { writer, .. } => {
+                f.debug_struct("Streaming").field("writer", writer).finish()
+            }
+        }
+    }
+}
+
+impl DataFileSink {
+    /// Create a sink for a data file with the given schema.
+    ///
+    /// Files are streamed if a [`StreamingUploadConfig`] is given and buffered otherwise.
+    pub(crate) fn try_new(
+        arrow_schema: ArrowSchemaRef,
+        writer_properties: WriterProperties,
+        streaming: Option<StreamingUploadConfig>,
+    ) -> Result<Self, ParquetError> {
+        Ok(match streaming {
+            Some(config) => Self::Streaming {
+                config,
+                writer: None,
+            },
+            None => {
+                let buffer = ShareableBuffer::default();
+                let arrow_writer =
+                    ArrowWriter::try_new(buffer.clone(), arrow_schema, Some(writer_properties))?;
+                Self::Buffered {
+                    buffer,
+                    arrow_writer,
+                }
+            }
+        })
+    }
+
+    /// Number of bytes of the data file so far.
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Buffered {
+                buffer,
+                arrow_writer,
+            } => buffer.len() + arrow_writer.in_progress_size(),
+            Self::Streaming { writer, .. } => writer
+                .as_ref()
+                .map_or(0, StreamingFileWriter::bytes_written),
+        }
+    }
+
+    /// Write the batch into the data file.
+    ///
+    /// A failed write leaves the data file as it was before the call.
+    pub(crate) async fn write(
+        &mut self,
+        object_store: &Arc<dyn ObjectStore>,
+        arrow_schema: &ArrowSchemaRef,
+        writer_properties: &WriterProperties,
+        partition_values: &IndexMap<String, Scalar>,
+        batch: &RecordBatch,
+    ) -> Result<(), DeltaWriterError> {
+        match self {
+            Self::Buffered {
+                buffer,
+                arrow_writer,
+            } => {
+                // Copy current buffered bytes so we can recover from failures
+                let buffer_bytes = buffer.to_vec();
+                if let Err(e) = arrow_writer.write(batch) {
+                    let new_buffer = ShareableBuffer::from_bytes(buffer_bytes.as_slice());
+                    *arrow_writer = ArrowWriter::try_new(
+                        new_buffer.clone(),
+                        arrow_schema.clone(),
+                        Some(writer_properties.clone()),
+                    )?;
+                    *buffer = new_buffer;
+                    return Err(e.into());
+                }
+                Ok(())
+            }
+            Self::Streaming { config, writer } => {
+                let writer = match writer {
+                    Some(writer) => writer,
+                    None => writer.insert(StreamingFileWriter::try_new(
+                        object_store.clone(),
+                        data_file_path(partition_values, writer_properties)?,
+                        arrow_schema.clone(),
+                        writer_properties.clone(),
+                        config.clone(),
+                    )?),
+                };
+                writer.write(batch).await
+            }
+        }
+    }
+
+    /// Finish the data file and make sure it is stored in the object store.
+    ///
+    /// Returns the path and size of the data file along with its Parquet metadata.
+    pub(crate) async fn close(
+        self,
+        object_store: &Arc<dyn ObjectStore>,
+        arrow_schema: &ArrowSchemaRef,
+        writer_properties: &WriterProperties,
+        partition_values: &IndexMap<String, Scalar>,
+    ) -> Result<(Path, usize, ParquetMetaData), DeltaWriterError> {
+        match self {
+            Self::Buffered {
+                buffer,
+                arrow_writer,
+            } => {
+                let metadata = arrow_writer.close()?;
+                let path = data_file_path(partition_values, writer_properties)?;
+                let obj_bytes = Bytes::from(buffer.to_vec());
+                let file_size = obj_bytes.len();
+                object_store
+                    .put_with_retries(&path, obj_bytes.into(), 15)
+                    .await?;
+                Ok((path, file_size, metadata))
+            }
+            Self::Streaming { config, writer } => {
+                let writer = match writer {
+                    Some(writer) => writer,
+                    None => StreamingFileWriter::try_new(
+                        object_store.clone(),
+                        data_file_path(partition_values, writer_properties)?,
+                        arrow_schema.clone(),
+                        writer_properties.clone(),
+                        config,
+                    )?,
+                };
+                let path = writer.path.clone();
+                let (file_size, metadata) = writer.close().await?;
+                Ok((path, file_size, metadata))
+            }
+        }
+    }
+}
+
+fn data_file_path(
+    partition_values: &IndexMap<String, Scalar>,
+    writer_properties: &WriterProperties,
+) -> Result<Path, DeltaWriterError> {
+    let prefix =
+        Path::parse(partition_values.hive_partition_path()).map_err(DeltaTableError::from)?;
+    Ok(next_data_path(
+        &prefix,
+        0,
+        &Uuid::new_v4(),
+        writer_properties,
+    ))
+}
+
+/// Split the batch into the Parquet leaf columns of the schema, rejecting batches which do not
+/// match the schema before anything is encoded.
+fn batch_leaves(
+    arrow_schema: &ArrowSchemaRef,
+    batch: &RecordBatch,
+) -> Result<Vec<ArrowLeafColumn>, DeltaWriterError> {
+    let mismatch = || DeltaWriterError::SchemaMismatch {
+        record_batch_schema: batch.schema(),
+        expected_schema: arrow_schema.clone(),
+    };
+    if batch.num_columns() != arrow_schema.fields().len() {
+        return Err(mismatch());
+    }
+    let mut leaves = Vec::new();
+    for (field, column) in arrow_schema.fields().iter().zip(batch.columns()) {
+        if field.data_type() != column.data_type() {
+            return Err(mismatch());
+        }
+        leaves.extend(compute_leaves(field, column)?);
+    }
+    Ok(leaves)
+}
+
+fn poisoned() -> DeltaWriterError {
+    ParquetError::General(
+        "a previous write failed part way, the data file must be discarded".into(),
+    )
+    .into()
+}
+
+/// Whether a failed multipart upload request should be retried, generic errors are retried like
+/// in [`put_with_retries`](ObjectStoreRetryExt::put_with_retries).
+///
+/// Parts cannot be retried this way, as every call to [`MultipartUpload::put_part`] claims the
+/// next part number. They rely on the retries of the object store client instead.
+fn should_retry(err: &object_store::Error, attempt: usize, operation: &str) -> bool {
+    let retry = attempt < MAX_RETRIES && matches!(err, object_store::Error::Generic { .. });
+    if retry {
+        debug!("{operation} attempt {attempt} failed: {err}");
+    }
+    retry
+}
+
+fn join_result(
+    result: Result<object_store::Result<()>, tokio::task::JoinError>,
+) -> Result<(), DeltaWriterError> {
+    result.map_err(|err| DeltaTableError::GenericError {
+        source: Box::new(err),
+    })??;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+    use bytes::Bytes;
+    use object_store::ObjectStoreExt as _;
+    use object_store::memory::InMemory;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+
+    fn batch(schema: &ArrowSchemaRef, start: i32, len: i32) -> RecordBatch {
+        let ids = Int32Array::from_iter_values(start..start + len);
+        let values = StringArray::from_iter_values((start..start + len).map(|i| format!("v{i}")));
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(ids), Arc::new(values)]).unwrap()
+    }
+
+    fn schema() -> ArrowSchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("value", DataType::Utf8, true),
+        ]))
+    }
+
+    async fn read_back(store: &InMemory, path: &Path) -> Vec<RecordBatch> {
+        let bytes: Bytes = store.get(path).await.unwrap().bytes().await.unwrap();
+        ParquetRecordBatchReaderBuilder::try_new(bytes)
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_streams_row_groups_as_parts() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("part-00000.parquet");
+        let schema = schema();
+        let props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(1000))
+            .build();
+        let config = StreamingUploadConfig::new(1024, 4096);
+        let mut writer = StreamingFileWriter::try_new(
+            store.clone(),
+            path.clone(),
+            schema.clone(),
+            props,
+            config.clone(),
+        )
+        .unwrap();
+
+        for i in 0..10 {
+            writer.write(&batch(&schema, i * 700, 700)).await.unwrap();
+        }
+        assert!(writer.bytes_uploaded > 0, "expected parts to be uploaded");
+        assert!(writer.buffer.len() < config.part_size());
+
+        let (size, metadata) = writer.close().await.unwrap();
+        assert_eq!(metadata.file_metadata().num_rows(), 7000);
+        assert!(metadata.row_groups().iter().all(|rg| rg.num_rows() <= 1000));
+
+        let meta = store.head(&path).await.unwrap();
+        assert_eq!(meta.size as usize, size);
+        let rows: usize = read_back(&store, &path)
+            .await
+            .iter()
+            .map(|b| b.num_rows())
+            .sum();
+        assert_eq!(rows, 7000);
+        // all permits are returned once the uploads completed
+        assert_eq!(config.budget.available_permits(), 4096 / PERMIT_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_row_groups_are_not_sized_by_parts() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("part-00001.parquet");
+        let schema = schema();
+        let props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(5000))
+            .build();
+        let mut writer = StreamingFileWriter::try_new(
+            store.clone(),
+            path.clone(),
+            schema.clone(),
+            props,
+            StreamingUploadConfig::new(1024, 4096),
+        )
+        .unwrap();
+
+        for i in 0..10 {
+            writer.write(&batch(&schema, i * 1000, 1000)).await.unwrap();
+        }
+        assert!(writer.bytes_uploaded > 0, "expected parts to be uploaded");
+
+        let (_, metadata) = writer.close().await.unwrap();
+        let row_groups: Vec<_> = metadata
+            .row_groups()
+            .iter()
+            .map(|rg| rg.num_rows())
+            .collect();
+        assert_eq!(row_groups, vec![5000, 5000]);
+    }
+
+    #[tokio::test]
+    async fn test_small_file_uses_single_put() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("small.parquet");
+        let schema = schema();
+        let mut writer = StreamingFileWriter::try_new(
+            store.clone(),
+            path.clone(),
+            schema.clone(),
+            WriterProperties::default(),
+            StreamingUploadConfig::default(),
+        )
+        .unwrap();
+        writer.write(&batch(&schema, 0, 3)).await.unwrap();
+        assert!(writer.upload.is_none());
+
+        let (size, metadata) = writer.close().await.unwrap();
+        assert_eq!(metadata.file_metadata().num_rows(), 3);
+        assert_eq!(store.head(&path).await.unwrap().size as usize, size);
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_keeps_accepted_rows() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("rollback.parquet");
+        let schema = schema();
+        let mut writer = StreamingFileWriter::try_new(
+            store.clone(),
+            path.clone(),
+            schema.clone(),
+            WriterProperties::default(),
+            StreamingUploadConfig::default(),
+        )
+        .unwrap();
+        writer.write(&batch(&schema, 0, 5)).await.unwrap();
+
+        let wrong = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)])),
+            vec![Arc::new(Int32Array::from(vec![1, 2]))],
+        )
+        .unwrap();
+        assert!(writer.write(&wrong).await.is_err());
+
+        let wrong_type = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("id", DataType::Int32, false),
+                Field::new("value", DataType::Int32, true),
+            ])),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(Int32Array::from(vec![1, 2])),
+            ],
+        )
+        .unwrap();
+        assert!(writer.write(&wrong_type).await.is_err());
+
+        writer.write(&batch(&schema, 5, 5)).await.unwrap();
+        let (_, metadata) = writer.close().await.unwrap();
+        assert_eq!(metadata.file_metadata().num_rows(), 10);
+        let ids: Vec<i32> = read_back(&store, &path)
+            .await
+            .iter()
+            .flat_map(|b| {
+                b.column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        assert_eq!(ids, (0..10).collect::<Vec<_>>());
+    }
+}
@@ -123,6 +123,12 @@ impl ShareableBuffer {
         (*inner).to_vec()
     }
 
+    /// Takes the bytes written so far, leaving the underlying buffer empty.
+    pub(crate) fn take(&self) -> Vec<u8> {
+        let mut inner = self.buffer.write();
+        std::mem::take(&mut *inner)
+    }
+
     /// Returns the number of bytes in the underlying buffer.
     pub fn len(&self) -> usize {
         let inner = self.buffer.read();
@@ -1269,6 +1269,9 @@ async fn test_idempotent_metrics() -> Result<(), Box<dyn Error>> {
         planner_strategy: PlannerStrategy::PreserveLocality,
         preserved_stable_order: true,
         max_bin_span_files: 0,
+        num_replans: 0,
+        // the requests depend on the store, they are covered by the unit tests
+        io_stats: metrics.io_stats.clone(),
         files_added: expected_metric_details.clone(),
         files_removed: expected_metric_details,
     };
@@ -11,12 +11,16 @@ pub enum MountConfigKey {
     /// If set to "true", allows creating commits without concurrent writer protection.
     /// Only safe if there is one writer to a given table.
     AllowUnsafeRename,
+    /// Serialize commits through a lock, making concurrent writers safe on mounts without
+    /// atomic put-if-absent. Currently only "flock" (advisory lock file) is supported.
+    CommitLock,
 }
 
 impl AsRef<str> for MountConfigKey {
     fn as_ref(&self) -> &str {
         match self {
             Self::AllowUnsafeRename => "mount_allow_unsafe_rename",
+            Self::CommitLock => "mount_commit_lock",
         }
     }
 }
@@ -27,11 +31,32 @@ impl FromStr for MountConfigKey {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "mount_allow_unsafe_rename" | "allow_unsafe_rename" => Ok(Self::AllowUnsafeRename),
+            "mount_commit_lock" | "commit_lock" => Ok(Self::CommitLock),
             _ => Err(Error::UnknownConfigKey(s.to_string())),
         }
     }
 }
 
+/// Lock used to serialize commits, see [`MountConfigKey::CommitLock`]
+#[derive(PartialEq, Eq, Clone, Debug, Copy)]
+pub(crate) enum CommitLockKind {
+    /// Advisory `flock` on `_delta_log/_commit.lock`
+    Flock,
+}
+
+impl FromStr for CommitLockKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "flock" | "file" => Ok(Self::Flock),
+            _ => Err(Error::Parse(format!(
+                "invalid commit lock '{s}', expected 'flock'"
+            ))),
+        }
+    }
+}
+
 /// Helper struct to create full configuration from passed options and environment
 pub(crate) struct MountConfigHelper {
     config: HashMap<MountConfigKey, String>,
@@ -4,7 +4,6 @@ pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[allow(dead_code)]
     #[error("failed to parse config: {0}")]
     Parse(String),
 
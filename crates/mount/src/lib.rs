@@ -1,11 +1,13 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use deltalake_core::logstore::DeltaIOStorageBackend;
 use deltalake_core::logstore::{
-    LogStore, LogStoreFactory, ObjectStoreFactory, ObjectStoreRef, StorageConfig,
-    config::str_is_truthy, default_logstore, logstore_factories, object_store_factories,
+    CommitLock, FileCommitLock, LockingLogStore, LogStore, LogStoreFactory, ObjectStoreFactory,
+    ObjectStoreRef, StorageConfig, config::str_is_truthy, default_logstore, logstore_factories,
+    object_store_factories,
 };
 use deltalake_core::{DeltaResult, DeltaTableError, Path};
 use object_store::DynObjectStore;
@@ -33,6 +35,16 @@ impl MountOptions for HashMap<String, String> {
     }
 }
 
+/// The commit lock configured through [`config::MountConfigKey::CommitLock`], if any
+fn commit_lock_kind(config: &StorageConfig) -> DeltaResult<Option<config::CommitLockKind>> {
+    let mount_config =
+        config::MountConfigHelper::try_new(config.raw.as_mount_options())?.build()?;
+    Ok(mount_config
+        .get(&config::MountConfigKey::CommitLock)
+        .map(|value| value.parse())
+        .transpose()?)
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct MountFactory {}
 
@@ -53,7 +65,9 @@ impl ObjectStoreFactory for MountFactory {
 
         let (mut store, prefix) = match url.scheme() {
             "dbfs" => {
-                if !allow_unsafe_rename {
+                // dbfs is always accessed without atomic renames, which is only safe with a
+                // single writer or when commits are serialized by a commit lock
+                if !allow_unsafe_rename && commit_lock_kind(config)?.is_none() {
                     // Just let the user know that they need to set the allow_unsafe_rename option
                     return Err(error::Error::AllowUnsafeRenameNotSpecified.into());
                 }
@@ -90,12 +104,27 @@ impl LogStoreFactory for MountFactory {
         location: &Url,
         options: &StorageConfig,
     ) -> DeltaResult<Arc<dyn LogStore>> {
-        Ok(default_logstore(
-            prefixed_store,
-            root_store,
-            location,
-            options,
-        ))
+        let Some(kind) = commit_lock_kind(options)? else {
+            return Ok(default_logstore(
+                prefixed_store,
+                root_store,
+                location,
+                options,
+            ));
+        };
+
+        let lock: Arc<dyn CommitLock> = match kind {
+            config::CommitLockKind::Flock => match location.scheme() {
+                "dbfs" => Arc::new(FileCommitLock::new(
+                    PathBuf::from(format!("/dbfs{}", location.path()))
+                        .join("_delta_log")
+                        .join("_commit.lock"),
+                )),
+                _ => Arc::new(FileCommitLock::try_new_for_table(location)?),
+            },
+        };
+        let inner = default_logstore(prefixed_store, root_store, location, options);
+        Ok(Arc::new(LockingLogStore::new(inner, lock)))
     }
 }
 
@@ -725,7 +725,7 @@ class DeltaTable:
             dt = DeltaTable("tmp")
             dt.update(predicate="id = '3'", updates = {"deleted": 'True'})
 
-            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 1, 'num_copied_rows': 2, 'execution_time_ms': ..., 'scan_time_ms': ...}
+            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 1, 'num_copied_rows': 2, 'execution_time_ms': ..., 'scan_time_ms': ..., 'num_replans': 0}
             ```
 
             **Update all row values**
@@ -734,7 +734,7 @@ class DeltaTable:
             ```py
             dt.update(updates = {"deleted": 'True', "id": "concat(id, '_old')"})
 
-            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 3, 'num_copied_rows': 0, 'execution_time_ms': ..., 'scan_time_ms': ...}
+            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 3, 'num_copied_rows': 0, 'execution_time_ms': ..., 'scan_time_ms': ..., 'num_replans': 0}
             ```
 
             **Use Python objects instead of SQL strings**
@@ -744,7 +744,7 @@ class DeltaTable:
             ```py
             dt.update(predicate="id = '1_old'", new_values = {"price": 150.10})
 
-            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 1, 'num_copied_rows': 2, 'execution_time_ms': ..., 'scan_time_ms': ...}
+            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 1, 'num_copied_rows': 2, 'execution_time_ms': ..., 'scan_time_ms': ..., 'num_replans': 0}
             ```
         """
         commit_properties, post_commithook_properties = (
@@ -2245,7 +2245,7 @@ class TableOptimizer:
             dt = DeltaTable("tmp")
             time_delta = timedelta(minutes=10)
             dt.optimize.compact(min_commit_interval=time_delta)
-            {'numFilesAdded': 1, 'numFilesRemoved': 2, 'filesAdded': ..., 'filesRemoved': ..., 'partitionsOptimized': 1, 'numBatches': 2, 'totalConsideredFiles': 2, 'totalFilesSkipped': 0, 'plannerStrategy': 'preserveLocality', 'preservedStableOrder': True, 'preserveInsertionOrder': True, 'maxBinSpanFiles': 2}
+            {'numFilesAdded': 1, 'numFilesRemoved': 2, 'filesAdded': ..., 'filesRemoved': ..., 'partitionsOptimized': 1, 'numBatches': 2, 'totalConsideredFiles': 2, 'totalFilesSkipped': 0, 'plannerStrategy': 'preserveLocality', 'preservedStableOrder': True, 'preserveInsertionOrder': True, 'maxBinSpanFiles': 2, 'numReplans': 0}
             ```
         """
         commit_properties, post_commithook_properties = (
@@ -2327,7 +2327,7 @@ class TableOptimizer:
             dt = DeltaTable("tmp")
             time_delta = timedelta(minutes=10)
             dt.optimize.z_order(["x"], min_commit_interval=time_delta)
-            {'numFilesAdded': 1, 'numFilesRemoved': 2, 'filesAdded': ..., 'filesRemoved': ..., 'partitionsOptimized': 0, 'numBatches': 1, 'totalConsideredFiles': 2, 'totalFilesSkipped': 0, 'plannerStrategy': 'zOrder', 'preservedStableOrder': False, 'preserveInsertionOrder': False, 'maxBinSpanFiles': 2}
+            {'numFilesAdded': 1, 'numFilesRemoved': 2, 'filesAdded': ..., 'filesRemoved': ..., 'partitionsOptimized': 0, 'numBatches': 1, 'totalConsideredFiles': 2, 'totalFilesSkipped': 0, 'plannerStrategy': 'zOrder', 'preservedStableOrder': False, 'preserveInsertionOrder': False, 'maxBinSpanFiles': 2, 'numReplans': 0}
             ```
         """
         commit_properties, post_commithook_properties = (